@@ -0,0 +1,101 @@
+//! Compares the accelerated bulk operations of contiguous memories with the
+//! byte accessor fallbacks.
+
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mem_storage::MemoryStorage;
+
+const SIZE: usize = 64 * 1024;
+
+/// A memory that returns its bytes from `as_slice`, which enables the fast paths.
+struct SliceMemory(Vec<u8>);
+
+impl MemoryStorage for SliceMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.0.get(addr).copied().ok_or(())
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        *self.0.get_mut(addr).ok_or(())? = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(&self.0)
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.0)
+    }
+}
+
+/// The same memory, but only implementing the byte accessors.
+struct ByteMemory(Vec<u8>);
+
+impl MemoryStorage for ByteMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.0.get(addr).copied().ok_or(())
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        *self.0.get_mut(addr).ok_or(())? = byte;
+        Ok(())
+    }
+}
+
+fn bench_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fill");
+    group.throughput(Throughput::Bytes(SIZE as u64));
+
+    let mut fast = SliceMemory(vec![0; SIZE]);
+    group.bench_function("contiguous", |b| {
+        b.iter(|| fast.fill(0..SIZE, black_box(0xAA)))
+    });
+    let mut slow = ByteMemory(vec![0; SIZE]);
+    group.bench_function("bytes", |b| b.iter(|| slow.fill(0..SIZE, black_box(0xAA))));
+    group.finish();
+}
+
+fn bench_copy_within(c: &mut Criterion) {
+    let mut group = c.benchmark_group("copy_within");
+    group.throughput(Throughput::Bytes(SIZE as u64 / 2));
+
+    let mut fast = SliceMemory(vec![0; SIZE]);
+    group.bench_function("contiguous", |b| {
+        b.iter(|| fast.copy_within(0..SIZE / 2, black_box(SIZE / 4)))
+    });
+    let mut slow = ByteMemory(vec![0; SIZE]);
+    group.bench_function("bytes", |b| {
+        b.iter(|| slow.copy_within(0..SIZE / 2, black_box(SIZE / 4)))
+    });
+    group.finish();
+}
+
+fn bench_find(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find");
+    group.throughput(Throughput::Bytes(SIZE as u64));
+
+    let mut data = vec![0; SIZE];
+    data[SIZE - 1] = 0xFF;
+
+    let fast = SliceMemory(data.clone());
+    group.bench_with_input(BenchmarkId::new("contiguous", SIZE), &fast, |b, mem| {
+        b.iter(|| mem.find(0..SIZE, black_box(0xFF)))
+    });
+    group.bench_function(BenchmarkId::new("iter_position", SIZE), |b| {
+        b.iter(|| data.iter().position(|&byte| byte == black_box(0xFF)))
+    });
+    let slow = ByteMemory(data.clone());
+    group.bench_with_input(BenchmarkId::new("bytes", SIZE), &slow, |b, mem| {
+        b.iter(|| mem.find(0..SIZE, black_box(0xFF)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_fill, bench_copy_within, bench_find);
+criterion_main!(benches);
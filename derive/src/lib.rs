@@ -0,0 +1,418 @@
+//! Derive macros for the [`mem_storage`](https://docs.rs/mem_storage) crate.
+//!
+//! This crate should not be used directly. Enable the `derive` feature of `mem_storage`
+//! and use the re-exported macros instead.
+
+#![warn(rust_2018_idioms)]
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, Index, Member};
+
+/// Derives `MemoryStorage` by forwarding every access to one field of the struct.
+///
+/// The field is selected using the `#[memory]` attribute. If the struct only has a
+/// single field, the attribute can be omitted.
+///
+/// ```ignore
+/// #[derive(MemoryStorage)]
+/// struct Console {
+///     cpu: Cpu,
+///     #[memory]
+///     ram: CellMemory,
+/// }
+/// ```
+#[proc_macro_derive(MemoryStorage, attributes(memory))]
+pub fn derive_memory_storage(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let (field, ty) = memory_field(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| syn::parse_quote!(where));
+    where_clause
+        .predicates
+        .push(syn::parse_quote!(#ty: ::mem_storage::MemoryStorage));
+    let alloc_methods = alloc_methods(&field);
+
+    Ok(quote! {
+        impl #impl_generics ::mem_storage::MemoryStorage for #name #ty_generics #where_clause {
+            type Error = <#ty as ::mem_storage::MemoryStorage>::Error;
+
+            fn try_read_byte(&self, addr: usize) -> ::core::result::Result<u8, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_byte(&self.#field, addr)
+            }
+
+            fn try_write_byte(
+                &mut self,
+                addr: usize,
+                byte: u8,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_byte(&mut self.#field, addr, byte)
+            }
+
+            fn as_slice(&self) -> ::core::option::Option<&[u8]> {
+                ::mem_storage::MemoryStorage::as_slice(&self.#field)
+            }
+
+            fn as_mut_slice(&mut self) -> ::core::option::Option<&mut [u8]> {
+                ::mem_storage::MemoryStorage::as_mut_slice(&mut self.#field)
+            }
+
+            fn size(&self) -> ::core::option::Option<usize> {
+                ::mem_storage::MemoryStorage::size(&self.#field)
+            }
+
+            fn addr_range(&self) -> ::core::option::Option<::core::ops::Range<usize>> {
+                ::mem_storage::MemoryStorage::addr_range(&self.#field)
+            }
+
+            fn try_read_into(
+                &self,
+                addr: usize,
+                buf: &mut [u8],
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_into(&self.#field, addr, buf)
+            }
+
+            fn try_write_from(
+                &mut self,
+                addr: usize,
+                buf: &[u8],
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_from(&mut self.#field, addr, buf)
+            }
+
+            fn try_fill(
+                &mut self,
+                range: ::core::ops::Range<usize>,
+                byte: u8,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_fill(&mut self.#field, range, byte)
+            }
+
+            fn try_copy_within(
+                &mut self,
+                src: ::core::ops::Range<usize>,
+                dest: usize,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_copy_within(&mut self.#field, src, dest)
+            }
+
+            fn try_find(
+                &self,
+                range: ::core::ops::Range<usize>,
+                byte: u8,
+            ) -> ::core::result::Result<::core::option::Option<usize>, Self::Error> {
+                ::mem_storage::MemoryStorage::try_find(&self.#field, range, byte)
+            }
+
+            fn fence(&self, order: ::core::sync::atomic::Ordering) {
+                ::mem_storage::MemoryStorage::fence(&self.#field, order)
+            }
+
+            fn try_read_with<__V: ::mem_storage::Value, __E: ::mem_storage::Endianness>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<__V, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_with::<__V, __E>(&self.#field, addr)
+            }
+
+            fn try_write_with<__V: ::mem_storage::Value, __E: ::mem_storage::Endianness>(
+                &mut self,
+                addr: usize,
+                val: __V,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_with::<__V, __E>(&mut self.#field, addr, val)
+            }
+
+            fn try_read<__V: ::mem_storage::Value>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<__V, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read(&self.#field, addr)
+            }
+
+            fn try_read_be<__V: ::mem_storage::Value>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<__V, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_be(&self.#field, addr)
+            }
+
+            fn try_write<__V: ::mem_storage::Value>(
+                &mut self,
+                addr: usize,
+                val: __V,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write(&mut self.#field, addr, val)
+            }
+
+            fn try_write_be<__V: ::mem_storage::Value>(
+                &mut self,
+                addr: usize,
+                val: __V,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_be(&mut self.#field, addr, val)
+            }
+
+            fn try_read_uint<const __N: usize>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<u128, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_uint::<__N>(&self.#field, addr)
+            }
+
+            fn try_read_uint_be<const __N: usize>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<u128, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_uint_be::<__N>(&self.#field, addr)
+            }
+
+            fn try_write_uint<const __N: usize>(
+                &mut self,
+                addr: usize,
+                val: u128,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_uint::<__N>(&mut self.#field, addr, val)
+            }
+
+            fn try_write_uint_be<const __N: usize>(
+                &mut self,
+                addr: usize,
+                val: u128,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_uint_be::<__N>(&mut self.#field, addr, val)
+            }
+
+            fn try_read_signed<__V: ::mem_storage::SignedValue, __W: ::core::convert::From<__V>>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<__W, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_signed::<__V, __W>(&self.#field, addr)
+            }
+
+            fn try_read_signed_be<
+                __V: ::mem_storage::SignedValue,
+                __W: ::core::convert::From<__V>,
+            >(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<__W, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_signed_be::<__V, __W>(&self.#field, addr)
+            }
+
+            fn try_read_bit(
+                &self,
+                addr: usize,
+                bit: usize,
+            ) -> ::core::result::Result<bool, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_bit(&self.#field, addr, bit)
+            }
+
+            fn try_write_bit(
+                &mut self,
+                addr: usize,
+                bit: usize,
+                value: bool,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_bit(&mut self.#field, addr, bit, value)
+            }
+
+            fn try_read_bits(
+                &self,
+                addr: usize,
+                bits: ::core::ops::Range<usize>,
+            ) -> ::core::result::Result<u64, ::mem_storage::RangeError<Self::Error>> {
+                ::mem_storage::MemoryStorage::try_read_bits(&self.#field, addr, bits)
+            }
+
+            fn try_write_bits(
+                &mut self,
+                addr: usize,
+                bits: ::core::ops::Range<usize>,
+                val: u64,
+            ) -> ::core::result::Result<(), ::mem_storage::RangeError<Self::Error>> {
+                ::mem_storage::MemoryStorage::try_write_bits(&mut self.#field, addr, bits, val)
+            }
+
+            fn try_read_bcd<const __N: usize>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<::core::option::Option<u64>, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_bcd::<__N>(&self.#field, addr)
+            }
+
+            fn try_read_bcd_be<const __N: usize>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<::core::option::Option<u64>, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_bcd_be::<__N>(&self.#field, addr)
+            }
+
+            fn try_write_bcd<const __N: usize>(
+                &mut self,
+                addr: usize,
+                val: u64,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_bcd::<__N>(&mut self.#field, addr, val)
+            }
+
+            fn try_write_bcd_be<const __N: usize>(
+                &mut self,
+                addr: usize,
+                val: u64,
+            ) -> ::core::result::Result<(), Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_bcd_be::<__N>(&mut self.#field, addr, val)
+            }
+
+            fn try_increment_bcd<const __N: usize>(
+                &mut self,
+                addr: usize,
+            ) -> ::core::result::Result<bool, Self::Error> {
+                ::mem_storage::MemoryStorage::try_increment_bcd::<__N>(&mut self.#field, addr)
+            }
+
+            fn try_increment_bcd_be<const __N: usize>(
+                &mut self,
+                addr: usize,
+            ) -> ::core::result::Result<bool, Self::Error> {
+                ::mem_storage::MemoryStorage::try_increment_bcd_be::<__N>(&mut self.#field, addr)
+            }
+
+            fn try_read_nonzero<__V: ::mem_storage::NonZeroValue>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<::core::option::Option<__V>, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_nonzero(&self.#field, addr)
+            }
+
+            fn try_read_nonzero_be<__V: ::mem_storage::NonZeroValue>(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<::core::option::Option<__V>, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_nonzero_be(&self.#field, addr)
+            }
+
+            fn try_read_uleb128(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<::core::option::Option<(u64, usize)>, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_uleb128(&self.#field, addr)
+            }
+
+            fn try_read_sleb128(
+                &self,
+                addr: usize,
+            ) -> ::core::result::Result<::core::option::Option<(i64, usize)>, Self::Error> {
+                ::mem_storage::MemoryStorage::try_read_sleb128(&self.#field, addr)
+            }
+
+            fn try_write_uleb128(
+                &mut self,
+                addr: usize,
+                val: u64,
+            ) -> ::core::result::Result<usize, Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_uleb128(&mut self.#field, addr, val)
+            }
+
+            fn try_write_sleb128(
+                &mut self,
+                addr: usize,
+                val: i64,
+            ) -> ::core::result::Result<usize, Self::Error> {
+                ::mem_storage::MemoryStorage::try_write_sleb128(&mut self.#field, addr, val)
+            }
+
+            fn try_swap_endianness<__V: ::mem_storage::Value>(
+                &mut self,
+                range: ::core::ops::Range<usize>,
+            ) -> ::core::result::Result<(), ::mem_storage::RangeError<Self::Error>> {
+                ::mem_storage::MemoryStorage::try_swap_endianness::<__V>(&mut self.#field, range)
+            }
+
+            #alloc_methods
+        }
+    })
+}
+
+/// Forwards the methods that only exist if the `alloc` feature of `mem_storage` is
+/// enabled, which also enables the `alloc` feature of this crate.
+fn alloc_methods(field: &Member) -> TokenStream2 {
+    if !cfg!(feature = "alloc") {
+        return TokenStream2::new();
+    }
+
+    quote! {
+        fn try_read_utf16_str(
+            &self,
+            addr: usize,
+            max_chars: usize,
+        ) -> ::core::result::Result<::mem_storage::__String, Self::Error> {
+            ::mem_storage::MemoryStorage::try_read_utf16_str(&self.#field, addr, max_chars)
+        }
+
+        fn try_read_utf16_str_be(
+            &self,
+            addr: usize,
+            max_chars: usize,
+        ) -> ::core::result::Result<::mem_storage::__String, Self::Error> {
+            ::mem_storage::MemoryStorage::try_read_utf16_str_be(&self.#field, addr, max_chars)
+        }
+    }
+}
+
+/// Finds the field that is marked with `#[memory]`, or the only field of the struct.
+fn memory_field(input: &DeriveInput) -> syn::Result<(Member, &syn::Type)> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`MemoryStorage` can only be derived for structs",
+            ))
+        }
+    };
+
+    let member = |idx: usize, field: &syn::Field| match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(idx)),
+    };
+
+    let mut marked = fields.iter().enumerate().filter(|(_, field)| {
+        field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("memory"))
+    });
+
+    match (marked.next(), marked.next()) {
+        (Some((idx, field)), None) => Ok((member(idx, field), &field.ty)),
+        (Some(_), Some((_, field))) => Err(syn::Error::new(
+            field.span(),
+            "only one field can be marked with `#[memory]`",
+        )),
+        (None, _) => match fields {
+            Fields::Named(_) | Fields::Unnamed(_) if fields.len() == 1 => {
+                let field = fields.iter().next().unwrap();
+                Ok((member(0, field), &field.ty))
+            }
+            _ => Err(syn::Error::new(
+                Span::call_site(),
+                "mark the field that stores the memory with `#[memory]`",
+            )),
+        },
+    }
+}
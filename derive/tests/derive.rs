@@ -0,0 +1,125 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{CellMemory, ContiguousMemory, MemoryStorage};
+use std::ops::Range;
+
+#[derive(MemoryStorage)]
+struct Console {
+    #[allow(dead_code)]
+    cycles: u64,
+    #[memory]
+    ram: CellMemory,
+}
+
+#[derive(MemoryStorage)]
+struct Wrapper<M>(M);
+
+struct SliceMemory(Vec<u8>);
+
+impl MemoryStorage for SliceMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.0.get(addr).copied().ok_or(())
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        *self.0.get_mut(addr).ok_or(())? = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(&self.0)
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.0)
+    }
+}
+
+/// A memory with a bulk fill, which must be used instead of the bytewise default.
+struct BulkFill {
+    bytes: Vec<u8>,
+    byte_writes: usize,
+}
+
+impl MemoryStorage for BulkFill {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.bytes.get(addr).copied().ok_or(())
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.byte_writes += 1;
+        *self.bytes.get_mut(addr).ok_or(())? = byte;
+        Ok(())
+    }
+
+    fn try_fill(&mut self, range: Range<usize>, byte: u8) -> Result<(), Self::Error> {
+        self.bytes.get_mut(range).ok_or(())?.fill(byte);
+        Ok(())
+    }
+}
+
+impl ContiguousMemory for SliceMemory {
+    fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+
+    fn out_of_bounds(&self, _addr: usize) -> Self::Error {}
+}
+
+impl<M: ContiguousMemory> ContiguousMemory for Wrapper<M> {
+    fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.0.as_bytes_mut()
+    }
+
+    fn out_of_bounds(&self, addr: usize) -> Self::Error {
+        self.0.out_of_bounds(addr)
+    }
+}
+
+#[test]
+fn test_derive_named_field() {
+    let mut console = Console {
+        cycles: 0,
+        ram: CellMemory::new(8),
+    };
+
+    console.write::<u32>(2, 0xAABBCCDD);
+    assert_eq!(console.ram.read::<u32>(2), 0xAABBCCDD);
+    assert_eq!(console.read_be::<u16>(2), 0xDDCC);
+    assert!(console.try_read::<u32>(6).is_err());
+    assert!(console.as_slice().is_none());
+}
+
+#[test]
+fn test_derive_generic_tuple() {
+    let mut mem = Wrapper(SliceMemory(vec![0; 4]));
+
+    mem.write::<u16>(1, 0x1234);
+    assert_eq!(mem.get(..), Ok(&[0, 0x34, 0x12, 0][..]));
+    assert_eq!(mem.0.read::<u16>(1), 0x1234);
+}
+
+#[test]
+fn test_derive_forwards_overridden_methods() {
+    let mut mem = Wrapper(BulkFill {
+        bytes: vec![0; 8],
+        byte_writes: 0,
+    });
+
+    mem.fill(2..6, 0xAA);
+    assert_eq!(mem.0.bytes, [0, 0, 0xAA, 0xAA, 0xAA, 0xAA, 0, 0]);
+    assert_eq!(mem.0.byte_writes, 0);
+    assert_eq!(mem.find(0..8, 0xAA), Some(2));
+}
@@ -0,0 +1,111 @@
+use core::fmt;
+
+/// An address inside a memory, with helpers for the alignment and page math that is
+/// needed by emulators.
+///
+/// All alignments and page sizes must be powers of two, otherwise the methods panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Addr(pub usize);
+
+impl Addr {
+    /// Creates a new `Addr` from the raw address.
+    pub const fn new(addr: usize) -> Self {
+        Self(addr)
+    }
+
+    /// Returns the raw address.
+    pub const fn get(self) -> usize {
+        self.0
+    }
+
+    /// Returns `true` if this address is a multiple of `align`.
+    pub fn is_aligned(self, align: usize) -> bool {
+        self.0 & mask(align) == 0
+    }
+
+    /// Rounds this address down to the previous multiple of `align`.
+    pub fn align_down(self, align: usize) -> Self {
+        Self(self.0 & !mask(align))
+    }
+
+    /// Rounds this address up to the next multiple of `align`.
+    ///
+    /// Returns `None` if the result does not fit into a `usize`.
+    pub fn align_up(self, align: usize) -> Option<Self> {
+        let mask = mask(align);
+        self.0.checked_add(mask).map(|addr| Self(addr & !mask))
+    }
+
+    /// Adds `offset` to this address.
+    ///
+    /// Returns `None` if the result does not fit into a `usize`.
+    pub fn checked_add(self, offset: usize) -> Option<Self> {
+        self.0.checked_add(offset).map(Self)
+    }
+
+    /// Subtracts `offset` from this address.
+    ///
+    /// Returns `None` if the result would be negative.
+    pub fn checked_sub(self, offset: usize) -> Option<Self> {
+        self.0.checked_sub(offset).map(Self)
+    }
+
+    /// Returns the distance from `base` to this address.
+    ///
+    /// Returns `None` if this address is below `base`.
+    pub fn offset_from(self, base: Addr) -> Option<usize> {
+        self.0.checked_sub(base.0)
+    }
+
+    /// Returns the index of the page that contains this address.
+    pub fn page(self, page_size: usize) -> usize {
+        self.0 >> mask(page_size).count_ones()
+    }
+
+    /// Returns the offset of this address inside its page.
+    pub fn page_offset(self, page_size: usize) -> usize {
+        self.0 & mask(page_size)
+    }
+
+    /// Splits this address into the index of its page and the offset inside the page.
+    pub fn split(self, page_size: usize) -> (usize, usize) {
+        (self.page(page_size), self.page_offset(page_size))
+    }
+}
+
+/// Returns the mask of the bits below `align`.
+fn mask(align: usize) -> usize {
+    assert!(align.is_power_of_two(), "alignment must be a power of two");
+    align - 1
+}
+
+impl From<usize> for Addr {
+    fn from(addr: usize) -> Self {
+        Self(addr)
+    }
+}
+
+impl From<Addr> for usize {
+    fn from(addr: Addr) -> Self {
+        addr.0
+    }
+}
+
+impl fmt::Display for Addr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl fmt::LowerHex for Addr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::UpperHex for Addr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{ContiguousMemory, CoverageMap, MemoryStorage, OutOfBounds};
+use std::io;
+
+/// The environment variable that AFL uses to pass the id of the coverage map to
+/// the target.
+pub const AFL_SHM_ENV: &str = "__AFL_SHM_ID";
+
+/// The coverage map of an AFL or libAFL fuzzer, which lives inside a System V shared
+/// memory segment.
+///
+/// This allows exposing the map to the guest, or updating it using the
+/// [`CoverageMap`] helpers. The fuzzer only reads the map after the target finished a
+/// testcase, and this memory hands out references to it, so the map must not be accessed
+/// by the fuzzer while it is borrowed.
+#[derive(Debug)]
+pub struct AflSharedMap {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// Safety: the attachment is owned by the memory, and only accessed through it.
+unsafe impl Send for AflSharedMap {}
+
+impl AflSharedMap {
+    /// Attaches the shared memory segment with the given id, which has the size of
+    /// the whole segment.
+    ///
+    /// Returns `Err(x)` if the segment does not exist or could not be attached.
+    pub fn attach(shm_id: i32) -> io::Result<Self> {
+        // Safety: `shmid_ds` is a plain C struct, which is valid if zeroed.
+        let mut stat = unsafe { core::mem::zeroed::<libc::shmid_ds>() };
+        // Safety: `stat` is a valid pointer.
+        if unsafe { libc::shmctl(shm_id, libc::IPC_STAT, &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // Safety: the segment is attached at an address that is chosen by the system.
+        let ptr = unsafe { libc::shmat(shm_id, core::ptr::null(), 0) };
+        if ptr as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            ptr: ptr as *mut u8,
+            len: stat.shm_segsz as usize,
+        })
+    }
+
+    /// Attaches the coverage map whose id is stored inside the [`AFL_SHM_ENV`]
+    /// environment variable, which is set by the fuzzer.
+    ///
+    /// Returns `Err(x)` if the variable is missing or invalid, or the segment could not
+    /// be attached.
+    pub fn from_env() -> io::Result<Self> {
+        let id = std::env::var(AFL_SHM_ENV)
+            .map_err(|_| io::Error::new(io::ErrorKind::NotFound, "AFL shared memory id not set"))?;
+        let id = id.trim().parse().map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidData, "invalid AFL shared memory id")
+        })?;
+        Self::attach(id)
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a [`CoverageMap`] that updates the counters inside this memory.
+    pub fn coverage(&mut self) -> CoverageMap<&mut [u8]> {
+        CoverageMap::new(self.bytes_mut())
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Safety: the attachment is valid for `len` bytes, which are not modified by
+        // the fuzzer while they are borrowed.
+        unsafe { core::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // Safety: the attachment is valid for `len` bytes, which are not accessed by
+        // the fuzzer while they are borrowed.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl MemoryStorage for AflSharedMap {
+    type Error = OutOfBounds;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.bytes().get(addr).copied().ok_or(OutOfBounds { addr })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let cell = self.bytes_mut().get_mut(addr).ok_or(OutOfBounds { addr })?;
+        *cell = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self.bytes())
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(self.bytes_mut())
+    }
+}
+
+impl ContiguousMemory for AflSharedMap {
+    fn as_bytes(&self) -> &[u8] {
+        self.bytes()
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.bytes_mut()
+    }
+
+    fn out_of_bounds(&self, addr: usize) -> Self::Error {
+        OutOfBounds { addr }
+    }
+}
+
+impl Drop for AflSharedMap {
+    fn drop(&mut self) {
+        // Safety: the segment was attached by `attach` and is not used anymore.
+        unsafe { libc::shmdt(self.ptr as *const libc::c_void) };
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    ContiguousMemory, FillPolicy, Global, MemoryStorage, MemoryUsage, OutOfBounds, RawAllocator,
+    ReportUsage, ResizableMemory, ResizeError,
+};
+use alloc::alloc::handle_alloc_error;
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+/// The alignment that is used by [`AlignedMemory::page_aligned`].
+pub const PAGE_ALIGN: usize = 4096;
+
+/// A heap allocated memory, whose first byte is guaranteed to have a given alignment.
+///
+/// This is required when the memory is handed to KVM or used for DMA to host devices,
+/// which need page aligned memory, or by SIMD code that wants aligned loads.
+///
+/// The memory is allocated using a [`RawAllocator`], which is the global allocator
+/// by default.
+pub struct AlignedMemory<A: RawAllocator = Global> {
+    ptr: NonNull<u8>,
+    layout: Layout,
+    alloc: A,
+}
+
+// Safety: the memory uniquely owns its allocation, like a `Box<[u8], A>`.
+unsafe impl<A: RawAllocator + Send> Send for AlignedMemory<A> {}
+// Safety: shared references only allow reading the allocation.
+unsafe impl<A: RawAllocator + Sync> Sync for AlignedMemory<A> {}
+
+impl AlignedMemory {
+    /// Creates a new `AlignedMemory` with `size` zero initialized bytes, whose first byte
+    /// is aligned to `align` bytes.
+    ///
+    /// Panics if `align` is not a power of two, or the size is too large.
+    pub fn new(size: usize, align: usize) -> Self {
+        Self::new_in(size, align, Global)
+    }
+
+    /// Creates a new `AlignedMemory` with `size` zero initialized bytes, whose first byte
+    /// is aligned to a page boundary.
+    pub fn page_aligned(size: usize) -> Self {
+        Self::new(size, PAGE_ALIGN)
+    }
+
+    /// Creates a new `AlignedMemory` with `size` bytes, that are initialized using the
+    /// given [`FillPolicy`], whose first byte is aligned to `align` bytes.
+    ///
+    /// Panics if `align` is not a power of two, or the size is too large.
+    pub fn with_fill(size: usize, align: usize, fill: FillPolicy) -> Self {
+        let mut mem = Self::new(size, align);
+        if fill != FillPolicy::Zero {
+            fill.fill(mem.bytes_mut());
+        }
+        mem
+    }
+
+    /// Tries to create a new `AlignedMemory` with `size` zero initialized bytes, whose
+    /// first byte is aligned to `align` bytes.
+    ///
+    /// Returns `None` if `align` is not a power of two, the size is too large, or the
+    /// memory could not be allocated.
+    pub fn try_new(size: usize, align: usize) -> Option<Self> {
+        Self::try_new_in(size, align, Global)
+    }
+}
+
+impl<A: RawAllocator> AlignedMemory<A> {
+    /// Creates a new `AlignedMemory` with `size` zero initialized bytes, whose first byte
+    /// is aligned to `align` bytes, inside memory that is provided by `alloc`.
+    ///
+    /// Panics if `align` is not a power of two, or the size is too large.
+    pub fn new_in(size: usize, align: usize, alloc: A) -> Self {
+        let layout = Layout::from_size_align(size, align).expect("invalid size or alignment");
+        Self::try_with_layout(layout, alloc).unwrap_or_else(|| handle_alloc_error(layout))
+    }
+
+    /// Tries to create a new `AlignedMemory` with `size` zero initialized bytes, whose
+    /// first byte is aligned to `align` bytes, inside memory that is provided by `alloc`.
+    ///
+    /// Returns `None` if `align` is not a power of two, the size is too large, or the
+    /// memory could not be allocated.
+    pub fn try_new_in(size: usize, align: usize, alloc: A) -> Option<Self> {
+        Self::try_with_layout(Layout::from_size_align(size, align).ok()?, alloc)
+    }
+
+    fn try_with_layout(layout: Layout, alloc: A) -> Option<Self> {
+        let ptr = allocate(&alloc, layout)?;
+        Some(Self { ptr, layout, alloc })
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.layout.size() == 0
+    }
+
+    /// Returns the alignment of the first byte of this memory.
+    pub fn align(&self) -> usize {
+        self.layout.align()
+    }
+
+    /// Returns a reference to the allocator of this memory.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Safety: the pointer is valid for `len` initialized bytes, which are only
+        // modified through `&mut self`.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len()) }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // Safety: the pointer is valid for `len` initialized bytes, which are uniquely
+        // borrowed through `&mut self`.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len()) }
+    }
+}
+
+impl<A: RawAllocator> MemoryStorage for AlignedMemory<A> {
+    type Error = OutOfBounds;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.bytes().get(addr).copied().ok_or(OutOfBounds { addr })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let cell = self.bytes_mut().get_mut(addr).ok_or(OutOfBounds { addr })?;
+        *cell = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self.bytes())
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(self.bytes_mut())
+    }
+}
+
+impl<A: RawAllocator> ContiguousMemory for AlignedMemory<A> {
+    fn as_bytes(&self) -> &[u8] {
+        self.bytes()
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.bytes_mut()
+    }
+
+    fn out_of_bounds(&self, addr: usize) -> Self::Error {
+        OutOfBounds { addr }
+    }
+}
+
+impl<A: RawAllocator> ResizableMemory for AlignedMemory<A> {
+    /// Moves the contents into a new allocation with the same alignment.
+    fn try_resize(&mut self, new_size: usize) -> Result<(), ResizeError> {
+        let layout = Layout::from_size_align(new_size, self.align())
+            .map_err(|_| ResizeError::AllocFailed { size: new_size })?;
+        let ptr =
+            allocate(&self.alloc, layout).ok_or(ResizeError::AllocFailed { size: new_size })?;
+
+        // Safety: both allocations are valid for at least `len` bytes, and do not overlap.
+        let len = self.len().min(new_size);
+        unsafe { core::ptr::copy_nonoverlapping(self.ptr.as_ptr(), ptr.as_ptr(), len) };
+
+        if self.layout.size() != 0 {
+            // Safety: the pointer was allocated by this allocator with this layout.
+            unsafe { self.alloc.deallocate(self.ptr, self.layout) };
+        }
+        self.ptr = ptr;
+        self.layout = layout;
+        Ok(())
+    }
+}
+
+impl<A: RawAllocator + Clone> Clone for AlignedMemory<A> {
+    fn clone(&self) -> Self {
+        let mut mem = Self::try_with_layout(self.layout, self.alloc.clone())
+            .unwrap_or_else(|| handle_alloc_error(self.layout));
+        mem.bytes_mut().copy_from_slice(self.bytes());
+        mem
+    }
+}
+
+impl<A: RawAllocator> Drop for AlignedMemory<A> {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(self);
+
+        if self.layout.size() != 0 {
+            // Safety: the pointer was allocated by this allocator with this layout.
+            unsafe { self.alloc.deallocate(self.ptr, self.layout) };
+        }
+    }
+}
+
+impl<A: RawAllocator> core::fmt::Debug for AlignedMemory<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AlignedMemory")
+            .field("len", &self.len())
+            .field("align", &self.align())
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<A: RawAllocator> zeroize::Zeroize for AlignedMemory<A> {
+    fn zeroize(&mut self) {
+        self.bytes_mut().zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<A: RawAllocator> zeroize::ZeroizeOnDrop for AlignedMemory<A> {}
+
+impl<A: RawAllocator> ReportUsage for AlignedMemory<A> {
+    fn usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            addressable: self.len(),
+            allocated: self.layout.size(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Allocates zero initialized memory for the layout, or returns a dangling pointer that
+/// satisfies the alignment and is never dereferenced, if the layout has a size of zero.
+fn allocate<A: RawAllocator>(alloc: &A, layout: Layout) -> Option<NonNull<u8>> {
+    if layout.size() == 0 {
+        NonNull::new(layout.align() as *mut u8)
+    } else {
+        alloc.allocate_zeroed(layout)
+    }
+}
@@ -0,0 +1,58 @@
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+/// An allocator that provides the storage of an [`AlignedMemory`](crate::AlignedMemory).
+///
+/// This is a minimal version of the unstable `Allocator` trait of the standard library,
+/// which allows to place guest memory inside an arena or a dedicated memory region.
+/// The other heap backed memories, like [`CellMemory`](crate::CellMemory), always use
+/// the global allocator, so an `AlignedMemory` with an alignment of one is the memory
+/// to use for a custom allocator.
+///
+/// # Safety
+///
+/// A pointer that is returned by [`allocate_zeroed`](Self::allocate_zeroed) must be valid
+/// for reads and writes of `layout.size()` bytes, be aligned to `layout.align()`, point to
+/// zero initialized memory and stay valid until it is passed to
+/// [`deallocate`](Self::deallocate).
+pub unsafe trait RawAllocator {
+    /// Allocates zero initialized memory that fits the given non-zero sized layout.
+    ///
+    /// Returns `None` if the memory could not be allocated.
+    fn allocate_zeroed(&self, layout: Layout) -> Option<NonNull<u8>>;
+
+    /// Deallocates memory that was allocated by this allocator.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`allocate_zeroed`](Self::allocate_zeroed) of this
+    /// allocator using the same `layout`, and must not be used afterwards.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+unsafe impl<A: RawAllocator + ?Sized> RawAllocator for &A {
+    fn allocate_zeroed(&self, layout: Layout) -> Option<NonNull<u8>> {
+        (**self).allocate_zeroed(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        (**self).deallocate(ptr, layout)
+    }
+}
+
+/// The global allocator, which is registered using `#[global_allocator]`.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Global;
+
+#[cfg(feature = "alloc")]
+unsafe impl RawAllocator for Global {
+    fn allocate_zeroed(&self, layout: Layout) -> Option<NonNull<u8>> {
+        // Safety: the layout has a non-zero size, as required by the trait.
+        NonNull::new(unsafe { alloc::alloc::alloc_zeroed(layout) })
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        alloc::alloc::dealloc(ptr.as_ptr(), layout)
+    }
+}
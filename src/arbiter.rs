@@ -0,0 +1,230 @@
+use crate::MemoryStorage;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// A handle to an initiator of a [`BusArbiter`], like a CPU or a DMA controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Initiator(usize);
+
+/// The counters of a single initiator of a [`BusArbiter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InitiatorStats {
+    /// The number of accesses that were made by the initiator.
+    pub accesses: u64,
+    /// The number of cycles the initiator had to wait for the bus.
+    pub stall_cycles: u64,
+}
+
+#[derive(Debug)]
+struct InitiatorState {
+    priority: u8,
+    accesses: Cell<u64>,
+    stall_cycles: Cell<u64>,
+}
+
+/// A wrapper that lets multiple initiators, like a CPU, a DMA controller and a video
+/// chip, share the inner memory, and accounts for the cycles every initiator stalls
+/// because the bus is used by another one.
+///
+/// Every initiator accesses the memory through its own [`ArbiterPort`]. Accesses are
+/// forwarded to the inner memory right away and in call order, the arbiter only keeps
+/// track of the time. Every access occupies the bus for
+/// [`access_cycles`](Self::access_cycles), starting at the current time, which is
+/// advanced using [`tick`](Self::tick).
+///
+/// If the bus is still busy, the access stalls until the bus is free. An initiator with
+/// a higher priority than the one that currently holds the bus does not stall, and
+/// instead delays the transfer of the other initiator, which is charged the stall.
+pub struct BusArbiter<M> {
+    inner: M,
+    initiators: Vec<InitiatorState>,
+    access_cycles: u64,
+    now: u64,
+    busy_until: Cell<u64>,
+    owner: Cell<Option<usize>>,
+}
+
+impl<M: MemoryStorage> BusArbiter<M> {
+    /// Creates a new `BusArbiter` without any initiators, where every access takes
+    /// one cycle.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            initiators: Vec::new(),
+            access_cycles: 1,
+            now: 0,
+            busy_until: Cell::new(0),
+            owner: Cell::new(None),
+        }
+    }
+
+    /// Sets the number of cycles every access occupies the bus.
+    pub fn access_cycles(mut self, cycles: u64) -> Self {
+        self.access_cycles = cycles;
+        self
+    }
+
+    /// Adds an initiator with the given priority, where a higher value wins the bus.
+    pub fn add_initiator(&mut self, priority: u8) -> Initiator {
+        self.initiators.push(InitiatorState {
+            priority,
+            accesses: Cell::new(0),
+            stall_cycles: Cell::new(0),
+        });
+        Initiator(self.initiators.len() - 1)
+    }
+
+    /// Returns a port that makes accesses on behalf of the given initiator.
+    ///
+    /// Returns `None` if the initiator was not added to this arbiter.
+    pub fn port(&mut self, initiator: Initiator) -> Option<ArbiterPort<'_, M>> {
+        if initiator.0 >= self.initiators.len() {
+            return None;
+        }
+        Some(ArbiterPort {
+            arbiter: self,
+            initiator: initiator.0,
+        })
+    }
+
+    /// Advances the current time of the bus by the given number of cycles.
+    pub fn tick(&mut self, cycles: u64) {
+        self.now = self.now.saturating_add(cycles);
+    }
+
+    /// Returns the current time of the bus in cycles.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Returns the counters of the given initiator.
+    ///
+    /// Returns `None` if the initiator was not added to this arbiter.
+    pub fn stats(&self, initiator: Initiator) -> Option<InitiatorStats> {
+        self.initiators
+            .get(initiator.0)
+            .map(|state| InitiatorStats {
+                accesses: state.accesses.get(),
+                stall_cycles: state.stall_cycles.get(),
+            })
+    }
+
+    /// Resets the counters of every initiator to zero.
+    pub fn reset_stats(&mut self) {
+        for state in &self.initiators {
+            state.accesses.set(0);
+            state.stall_cycles.set(0);
+        }
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory, whose accesses are not counted.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `BusArbiter` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Accounts for a single access of the given initiator.
+    fn charge(&self, initiator: usize) {
+        let state = &self.initiators[initiator];
+        state.accesses.set(state.accesses.get() + 1);
+
+        let busy_until = self.busy_until.get();
+        if busy_until <= self.now {
+            self.busy_until
+                .set(self.now.saturating_add(self.access_cycles));
+            self.owner.set(Some(initiator));
+            return;
+        }
+
+        match self.owner.get() {
+            Some(owner) if self.initiators[owner].priority < state.priority => {
+                let owner = &self.initiators[owner];
+                owner
+                    .stall_cycles
+                    .set(owner.stall_cycles.get() + self.access_cycles);
+            }
+            _ => {
+                let stall = busy_until - self.now;
+                state.stall_cycles.set(state.stall_cycles.get() + stall);
+                self.owner.set(Some(initiator));
+            }
+        }
+        self.busy_until
+            .set(busy_until.saturating_add(self.access_cycles));
+    }
+}
+
+impl<M> core::fmt::Debug for BusArbiter<M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BusArbiter")
+            .field("initiators", &self.initiators)
+            .field("access_cycles", &self.access_cycles)
+            .field("now", &self.now)
+            .field("busy_until", &self.busy_until.get())
+            .finish()
+    }
+}
+
+/// The view of a [`BusArbiter`] for a single initiator, which is returned by
+/// [`BusArbiter::port`].
+pub struct ArbiterPort<'a, M> {
+    arbiter: &'a mut BusArbiter<M>,
+    initiator: usize,
+}
+
+impl<M: MemoryStorage> MemoryStorage for ArbiterPort<'_, M> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.arbiter.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.arbiter.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.arbiter.charge(self.initiator);
+        self.arbiter.inner.try_read_byte(addr)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.arbiter.charge(self.initiator);
+        self.arbiter.inner.try_write_byte(addr, byte)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.arbiter.charge(self.initiator);
+        self.arbiter.inner.try_read_into(addr, buf)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.arbiter.charge(self.initiator);
+        self.arbiter.inner.try_write_from(addr, buf)
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.arbiter.inner.fence(order)
+    }
+}
+
+impl<M> core::fmt::Debug for ArbiterPort<'_, M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ArbiterPort")
+            .field("initiator", &self.initiator)
+            .finish()
+    }
+}
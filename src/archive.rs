@@ -0,0 +1,92 @@
+#[cfg(feature = "forbid-unsafe")]
+use crate::MemoryStorage;
+use crate::{CellMemory, LengthMismatch};
+use rkyv::rancor::{Fallible, Source};
+use rkyv::ser::{Allocator, Writer};
+use rkyv::util::AlignedVec;
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Deserialize, Place, Serialize};
+
+impl Archive for CellMemory {
+    type Archived = ArchivedVec<u8>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::<u8>::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<S> Serialize<S> for CellMemory
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // Safety: serialization can not write to this memory while the slice is alive.
+        #[cfg(not(feature = "forbid-unsafe"))]
+        let bytes = unsafe { self.bytes_unchecked() };
+        #[cfg(feature = "forbid-unsafe")]
+        let bytes = &self.to_vec();
+        ArchivedVec::<u8>::serialize_from_slice(bytes, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<CellMemory, D> for ArchivedVec<u8> {
+    fn deserialize(&self, _: &mut D) -> Result<CellMemory, D::Error> {
+        Ok(CellMemory::from(self.as_slice().to_vec()))
+    }
+}
+
+impl CellMemory {
+    /// Serializes this memory into an `rkyv` archive.
+    pub fn to_rkyv<E: Source>(&self) -> Result<AlignedVec, E> {
+        rkyv::to_bytes(self)
+    }
+
+    /// Validates an `rkyv` archive that was created using [`to_rkyv`](Self::to_rkyv), and
+    /// returns the archived bytes without deserializing them.
+    ///
+    /// The archive can be memory mapped from a file, and the returned bytes can be inspected
+    /// directly or restored using [`restore_archived`](Self::restore_archived).
+    pub fn access_rkyv<E: Source>(bytes: &[u8]) -> Result<&ArchivedVec<u8>, E> {
+        rkyv::access::<ArchivedVec<u8>, E>(bytes)
+    }
+
+    /// Tries to overwrite the content of this memory with the bytes of an archive, without
+    /// allocating.
+    ///
+    /// Returns `Err(x)` if the archive has a different length than this memory, in which
+    /// case the memory is not modified.
+    pub fn try_restore_archived(
+        &mut self,
+        archived: &ArchivedVec<u8>,
+    ) -> Result<(), LengthMismatch> {
+        let mismatch = LengthMismatch {
+            expected: self.len(),
+            found: archived.len(),
+        };
+        if mismatch.expected != mismatch.found {
+            return Err(mismatch);
+        }
+
+        #[cfg(not(feature = "forbid-unsafe"))]
+        self.bytes_mut().copy_from_slice(archived);
+        #[cfg(feature = "forbid-unsafe")]
+        self.try_write_from(0, archived).map_err(|_| mismatch)?;
+        Ok(())
+    }
+
+    /// Overwrites the content of this memory with the bytes of an archive, without allocating.
+    ///
+    /// Panics if the archive has a different length than this memory.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_restore_archived` instead")
+    )]
+    pub fn restore_archived(&mut self, archived: &ArchivedVec<u8>) {
+        or_panic!(
+            self.try_restore_archived(archived),
+            "restore {} archived bytes",
+            archived.len()
+        )
+    }
+}
@@ -0,0 +1,48 @@
+//! Decoding and encoding of packed binary-coded decimals, where every byte stores
+//! two decimal digits, with the more significant digit in the upper nibble.
+//!
+//! All functions take the bytes with the least significant byte first.
+
+/// The maximum number of bytes of a value, which is the most that fits into a `u64`.
+pub(crate) const MAX_LEN: usize = 8;
+
+/// Decodes the bytes into a value.
+///
+/// Returns `None` if one of the nibbles is not a decimal digit.
+pub(crate) fn decode(bytes: &[u8]) -> Option<u64> {
+    bytes.iter().rev().try_fold(0u64, |value, &byte| {
+        let (high, low) = (byte >> 4, byte & 0xF);
+        if high > 9 || low > 9 {
+            return None;
+        }
+        Some(value * 100 + u64::from(high * 10 + low))
+    })
+}
+
+/// Encodes the lowest `2 * out.len()` digits of `val` into `out`.
+pub(crate) fn encode(mut val: u64, out: &mut [u8]) {
+    for byte in out {
+        let digits = (val % 100) as u8;
+        *byte = ((digits / 10) << 4) | (digits % 10);
+        val /= 100;
+    }
+}
+
+/// Increments the value by one, and returns `true` if it wrapped around to zero.
+///
+/// Nibbles that are not a decimal digit are treated like a nine, so they produce a carry.
+pub(crate) fn increment(bytes: &mut [u8]) -> bool {
+    for byte in bytes {
+        let (high, low) = (*byte >> 4, *byte & 0xF);
+        if low < 9 {
+            *byte = (high << 4) | (low + 1);
+            return false;
+        }
+        if high < 9 {
+            *byte = (high + 1) << 4;
+            return false;
+        }
+        *byte = 0;
+    }
+    true
+}
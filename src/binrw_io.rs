@@ -0,0 +1,132 @@
+use crate::MemoryStorage;
+use binrw::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use core::convert::TryFrom;
+
+/// A cursor that implements the `binrw` I/O traits on top of a memory, so `binrw` types
+/// can be parsed from, or written to, any address of a memory.
+///
+/// Reading requires a `MemoryCursor<&M>` or `MemoryCursor<&mut M>`, and writing
+/// a `MemoryCursor<&mut M>`. Every failed memory access is reported as an
+/// [`ErrorKind::Other`] error. Seeking relative to the end is not supported, since
+/// memories have no known length.
+///
+/// ```
+/// # use mem_storage::{CellMemory, MemoryCursor};
+/// use binrw::{BinRead, BinWrite};
+///
+/// #[derive(BinRead, BinWrite, Debug, PartialEq)]
+/// #[brw(little, magic = b"ROM")]
+/// struct Header {
+///     entry: u32,
+///     banks: u8,
+/// }
+///
+/// let mut rom = CellMemory::new(0x200);
+/// let header = Header { entry: 0x8000, banks: 4 };
+/// header.write(&mut MemoryCursor::new(&mut rom, 0x100)).unwrap();
+///
+/// let parsed = Header::read(&mut MemoryCursor::new(&rom, 0x100)).unwrap();
+/// assert_eq!(parsed, header);
+/// ```
+#[derive(Debug)]
+pub struct MemoryCursor<T> {
+    inner: T,
+    pos: u64,
+}
+
+impl<T> MemoryCursor<T> {
+    /// Creates a new `MemoryCursor` that starts at the given address.
+    pub fn new(inner: T, addr: usize) -> Self {
+        Self {
+            inner,
+            pos: addr as u64,
+        }
+    }
+
+    /// Returns the address the next access starts at.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Moves the cursor to the given address.
+    pub fn set_position(&mut self, addr: u64) {
+        self.pos = addr;
+    }
+
+    /// Returns a reference to the memory of this cursor.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consumes this cursor and returns the memory.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn addr(&self) -> Result<usize> {
+        usize::try_from(self.pos)
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "cursor position is out of range"))
+    }
+
+    fn advance(&mut self, len: usize) -> Result<usize> {
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+fn memory_error<E>(_: E) -> Error {
+    Error::new(ErrorKind::Other, "failed to access memory")
+}
+
+impl<M: MemoryStorage + ?Sized> Read for MemoryCursor<&M> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner
+            .try_read_into(self.addr()?, buf)
+            .map_err(memory_error)?;
+        self.advance(buf.len())
+    }
+}
+
+impl<M: MemoryStorage + ?Sized> Read for MemoryCursor<&mut M> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner
+            .try_read_into(self.addr()?, buf)
+            .map_err(memory_error)?;
+        self.advance(buf.len())
+    }
+}
+
+impl<M: MemoryStorage + ?Sized> Write for MemoryCursor<&mut M> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let addr = self.addr()?;
+        self.inner.try_write_from(addr, buf).map_err(memory_error)?;
+        self.advance(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<T> Seek for MemoryCursor<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "memories can not be seeked from the end",
+                ))
+            }
+        };
+
+        self.pos = pos.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative address",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{AccessKind, MemoryStorage};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt::Write;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// Runs `f` with a wrapper around `mem`, and panics if `f` accessed any byte outside
+/// of `range`.
+///
+/// This makes it easy to prove in tests that a device model or CPU routine never
+/// touches memory it should not. Every access is still forwarded to `mem`, even if it
+/// lies outside of the range, and the panic message lists every offending access.
+///
+/// ```
+/// # use mem_storage::{assert_accesses_within, CellMemory, MemoryStorage};
+/// let mut ram = CellMemory::new(0x100);
+/// assert_accesses_within(&mut ram, 0x10..0x20, |mem| {
+///     mem.write::<u32>(0x10, 0xDEAD_BEEF);
+///     mem.read::<u64>(0x18)
+/// });
+/// ```
+#[track_caller]
+pub fn assert_accesses_within<M, R, F>(mem: &mut M, range: Range<usize>, f: F) -> R
+where
+    M: MemoryStorage + ?Sized,
+    F: FnOnce(&mut AccessGuard<'_, M>) -> R,
+{
+    let mut guard = AccessGuard {
+        inner: mem,
+        allowed: range,
+        violations: RefCell::new(Vec::new()),
+    };
+    let result = f(&mut guard);
+
+    let violations = guard.violations.into_inner();
+    if !violations.is_empty() {
+        let mut msg = String::new();
+        let _ = write!(
+            msg,
+            "{} accesses outside of {:#x}..{:#x}:",
+            violations.len(),
+            guard.allowed.start,
+            guard.allowed.end
+        );
+        for (kind, range) in violations {
+            let kind = match kind {
+                AccessKind::Read => "read",
+                AccessKind::Write => "write",
+            };
+            let _ = write!(msg, "\n  {} of {:#x}..{:#x}", kind, range.start, range.end);
+        }
+        panic!("{}", msg);
+    }
+    result
+}
+
+/// A wrapper that remembers every access to the inner memory, which is not completely
+/// inside an allowed range.
+///
+/// Created by [`assert_accesses_within`].
+pub struct AccessGuard<'a, M: ?Sized> {
+    inner: &'a mut M,
+    allowed: Range<usize>,
+    violations: RefCell<Vec<(AccessKind, Range<usize>)>>,
+}
+
+impl<M: MemoryStorage + ?Sized> AccessGuard<'_, M> {
+    /// Returns the range of addresses that can be accessed.
+    pub fn allowed(&self) -> Range<usize> {
+        self.allowed.clone()
+    }
+
+    /// Returns a reference to the inner memory, whose accesses are not checked.
+    pub fn inner(&self) -> &M {
+        self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory, whose accesses are not checked.
+    pub fn inner_mut(&mut self) -> &mut M {
+        self.inner
+    }
+
+    fn check(&self, kind: AccessKind, addr: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+
+        let end = addr.saturating_add(len);
+        if addr < self.allowed.start || end > self.allowed.end {
+            self.violations.borrow_mut().push((kind, addr..end));
+        }
+    }
+}
+
+impl<M: MemoryStorage + ?Sized> MemoryStorage for AccessGuard<'_, M> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.check(AccessKind::Read, addr, 1);
+        self.inner.try_read_byte(addr)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.check(AccessKind::Write, addr, 1);
+        self.inner.try_write_byte(addr, byte)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.check(AccessKind::Read, addr, buf.len());
+        self.inner.try_read_into(addr, buf)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.check(AccessKind::Write, addr, buf.len());
+        self.inner.try_write_from(addr, buf)
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M: ?Sized> core::fmt::Debug for AccessGuard<'_, M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AccessGuard")
+            .field("allowed", &self.allowed)
+            .field("violations", &self.violations.borrow().len())
+            .finish()
+    }
+}
@@ -0,0 +1,69 @@
+//! Accelerated implementations of the bulk operations of `MemoryStorage`.
+
+/// Returns the index of the first occurrence of `needle` inside `haystack`.
+///
+/// Compares 16 bytes at once using SSE2.
+#[cfg(all(
+    target_arch = "x86_64",
+    target_feature = "sse2",
+    not(feature = "forbid-unsafe")
+))]
+pub(crate) fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    use core::arch::x86_64::{
+        __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8,
+    };
+
+    let mut chunks = haystack.chunks_exact(16);
+    let mut offset = 0;
+    // Safety: SSE2 is enabled for this target, and every chunk is exactly 16 bytes long,
+    // which is the size of one unaligned load.
+    unsafe {
+        let pattern = _mm_set1_epi8(needle as i8);
+        for chunk in &mut chunks {
+            let data = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+            let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(data, pattern));
+            if mask != 0 {
+                return Some(offset + mask.trailing_zeros() as usize);
+            }
+            offset += 16;
+        }
+    }
+
+    find_remainder(chunks.remainder(), needle).map(|idx| offset + idx)
+}
+
+/// Returns the index of the first occurrence of `needle` inside `haystack`.
+///
+/// Compares 8 bytes at once by treating them as one `u64`.
+#[cfg(not(all(
+    target_arch = "x86_64",
+    target_feature = "sse2",
+    not(feature = "forbid-unsafe")
+)))]
+pub(crate) fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    const LO: u64 = 0x0101_0101_0101_0101;
+    const HI: u64 = 0x8080_8080_8080_8080;
+
+    let pattern = LO * needle as u64;
+    let mut chunks = haystack.chunks_exact(8);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(chunk);
+
+        // Every byte that is equal to the needle becomes zero, and the lowest zero byte
+        // is the only one that is guaranteed to have its high bit set here.
+        let word = u64::from_le_bytes(bytes) ^ pattern;
+        let zeros = word.wrapping_sub(LO) & !word & HI;
+        if zeros != 0 {
+            return Some(offset + zeros.trailing_zeros() as usize / 8);
+        }
+        offset += 8;
+    }
+
+    find_remainder(chunks.remainder(), needle).map(|idx| offset + idx)
+}
+
+fn find_remainder(haystack: &[u8], needle: u8) -> Option<usize> {
+    haystack.iter().position(|&b| b == needle)
+}
@@ -0,0 +1,273 @@
+use crate::frames::{Frame, Frames};
+use crate::{MemoryStorage, MemoryUsage, ReportUsage};
+use alloc::vec;
+use core::cell::{Cell, RefCell};
+use core::sync::atomic::Ordering;
+
+/// The hit and miss counters of a [`PageCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CacheStats {
+    /// The number of page accesses that were served from the cache.
+    pub hits: u64,
+    /// The number of page accesses that had to load the page from the inner memory.
+    pub misses: u64,
+    /// The number of modified pages that were written back to the inner memory.
+    pub writebacks: u64,
+}
+
+impl CacheStats {
+    /// Returns the fraction of page accesses that were served from the cache.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// A write-back cache that keeps the most recently used pages of a slow memory,
+/// like a file or remote backend, on the heap.
+///
+/// Every access that misses the cache loads the whole page from the inner memory,
+/// replacing the least recently used page, which is written back if it was modified.
+/// Modified pages are also written back by [`flush`](Self::flush). Reads can load
+/// pages too, so the cache stores the pages and the inner memory inside a [`RefCell`].
+///
+/// The memory is divided into pages of `PAGE_SIZE` bytes, where page `n` starts at
+/// address `n * PAGE_SIZE`. The bytes of an access that are not inside the first `len`
+/// bytes are passed to the inner memory without caching them.
+pub struct PageCache<M, const PAGE_SIZE: usize = 4096> {
+    inner: RefCell<M>,
+    frames: RefCell<Frames>,
+    stats: Cell<CacheStats>,
+    len: usize,
+}
+
+impl<M: MemoryStorage, const PAGE_SIZE: usize> PageCache<M, PAGE_SIZE> {
+    /// Creates a new `PageCache` for the first `len` bytes of the inner memory, which
+    /// keeps at most `capacity` pages.
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(inner: M, len: usize, capacity: usize) -> Self {
+        const { assert!(PAGE_SIZE > 0, "page size must be non-zero") };
+        Self {
+            inner: RefCell::new(inner),
+            frames: RefCell::new(Frames::new(capacity)),
+            stats: Cell::new(CacheStats::default()),
+            len,
+        }
+    }
+
+    /// Returns the number of bytes that are cached.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bytes are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of pages inside the cache.
+    pub fn capacity(&self) -> usize {
+        self.frames.borrow().capacity()
+    }
+
+    /// Returns the number of pages that are currently inside the cache.
+    pub fn cached_pages(&self) -> usize {
+        self.frames.borrow().len()
+    }
+
+    /// Returns the hit and miss counters of this cache.
+    pub fn stats(&self) -> CacheStats {
+        self.stats.get()
+    }
+
+    /// Resets the hit and miss counters of this cache to zero.
+    pub fn reset_stats(&self) {
+        self.stats.set(CacheStats::default());
+    }
+
+    /// Tries to write every modified page back to the inner memory.
+    ///
+    /// The pages stay inside the cache.
+    ///
+    /// Returns `Err(x)` if a page could not be written back.
+    pub fn try_flush(&mut self) -> Result<(), M::Error> {
+        let len = self.len;
+        let inner = self.inner.get_mut();
+        let mut stats = self.stats.get();
+        let result = self
+            .frames
+            .get_mut()
+            .iter_mut()
+            .filter(|frame| frame.dirty)
+            .try_for_each(|frame| {
+                write_back::<M, PAGE_SIZE>(inner, frame, len)?;
+                stats.writebacks += 1;
+                Ok(())
+            });
+        self.stats.set(stats);
+        result
+    }
+
+    /// Writes every modified page back to the inner memory.
+    ///
+    /// The pages stay inside the cache.
+    ///
+    /// Panics if a page could not be written back.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_flush` instead"))]
+    pub fn flush(&mut self) {
+        or_panic!(self.try_flush(), "flush cache of {} bytes", self.len)
+    }
+
+    /// Consumes this `PageCache` and returns the inner memory.
+    ///
+    /// Modified pages that were not [flushed](Self::flush) are lost.
+    pub fn into_inner(self) -> M {
+        self.inner.into_inner()
+    }
+
+    /// Returns the frame of the given page, and loads it first if necessary.
+    fn cached<'a>(&self, frames: &'a mut Frames, page: usize) -> Result<&'a mut Frame, M::Error> {
+        let mut stats = self.stats.get();
+        if frames.get(page).is_some() {
+            stats.hits += 1;
+            self.stats.set(stats);
+            return Ok(frames.get(page).unwrap());
+        }
+
+        let mut inner = self.inner.borrow_mut();
+        let mut data = if frames.is_full() {
+            // The victim stays cached if it can not be written back.
+            let victim = frames.lru().unwrap();
+            if victim.dirty {
+                write_back::<M, PAGE_SIZE>(&mut inner, victim, self.len)?;
+                stats.writebacks += 1;
+            }
+            frames.evict().unwrap().data
+        } else {
+            vec![0u8; PAGE_SIZE].into_boxed_slice()
+        };
+
+        let len = PAGE_SIZE.min(self.len - page * PAGE_SIZE);
+        data[len..].fill(0);
+        inner.try_read_into(page * PAGE_SIZE, &mut data[..len])?;
+
+        stats.misses += 1;
+        self.stats.set(stats);
+        Ok(frames.insert(page, data))
+    }
+
+    /// Calls `f` with the frame and the range inside the frame for every page that is
+    /// covered by the cached part of the access, and returns the length of that part.
+    ///
+    /// The cached part is the start of the access that lies inside the first `len` bytes.
+    fn for_each_page(
+        &self,
+        addr: usize,
+        len: usize,
+        mut f: impl FnMut(&mut Frame, core::ops::Range<usize>, usize),
+    ) -> Result<usize, M::Error> {
+        let cached = self.len.saturating_sub(addr).min(len);
+        let mut frames = self.frames.borrow_mut();
+
+        let mut done = 0;
+        while done < cached {
+            let (page, offset) = ((addr + done) / PAGE_SIZE, (addr + done) % PAGE_SIZE);
+            let count = (PAGE_SIZE - offset).min(cached - done);
+
+            let frame = self.cached(&mut frames, page)?;
+            f(frame, offset..offset + count, done);
+            done += count;
+        }
+        Ok(cached)
+    }
+}
+
+impl<M: MemoryStorage, const PAGE_SIZE: usize> MemoryStorage for PageCache<M, PAGE_SIZE> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let len = buf.len();
+        let cached = self.for_each_page(addr, len, |frame, range, done| {
+            buf[done..done + range.len()].copy_from_slice(&frame.data[range]);
+        })?;
+        if cached == len {
+            return Ok(());
+        }
+        self.inner
+            .borrow()
+            .try_read_into(addr + cached, &mut buf[cached..])
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let cached = self.for_each_page(addr, buf.len(), |frame, range, done| {
+            frame.data[range.clone()].copy_from_slice(&buf[done..done + range.len()]);
+            frame.dirty = true;
+        })?;
+        if cached == buf.len() {
+            return Ok(());
+        }
+        self.inner
+            .get_mut()
+            .try_write_from(addr + cached, &buf[cached..])
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.borrow().fence(order)
+    }
+}
+
+impl<M, const PAGE_SIZE: usize> core::fmt::Debug for PageCache<M, PAGE_SIZE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PageCache")
+            .field("len", &self.len)
+            .field("cached", &self.frames.borrow().len())
+            .field("stats", &self.stats.get())
+            .finish()
+    }
+}
+
+/// Writes a modified page back to the first `len` bytes of the inner memory, and marks
+/// it as clean.
+fn write_back<M: MemoryStorage, const PAGE_SIZE: usize>(
+    inner: &mut M,
+    frame: &mut Frame,
+    len: usize,
+) -> Result<(), M::Error> {
+    let start = frame.page * PAGE_SIZE;
+    let count = PAGE_SIZE.min(len - start);
+    inner.try_write_from(start, &frame.data[..count])?;
+    frame.dirty = false;
+    Ok(())
+}
+
+impl<M: MemoryStorage, const PAGE_SIZE: usize> ReportUsage for PageCache<M, PAGE_SIZE> {
+    fn usage(&self) -> MemoryUsage {
+        let frames = self.frames.borrow();
+        MemoryUsage {
+            addressable: self.len,
+            allocated: frames.len() * PAGE_SIZE,
+            resident_pages: frames.len(),
+            dirty_pages: frames.dirty_pages(),
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use crate::read_ref::gather;
+use crate::{
+    FillPolicy, MemoryStorage, MemoryUsage, OutOfBounds, ReadRef, ReportUsage, ResizableMemory,
+    ResizeError,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
+#[cfg(not(feature = "forbid-unsafe"))]
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::ops::Range;
+
+/// A heap allocated memory that can be written through a shared reference.
+///
+/// This is useful for single-threaded emulators, where passing a `&mut` reference
+/// to the memory through every component is painful.
+///
+/// Besides `CellMemory` itself, `MemoryStorage` is also implemented for `&CellMemory`,
+/// so every method of the trait can write through a shared reference, like
+/// `(&mem).write::<u32>(addr, val)`. It does not implement `ContiguousMemory`, because
+/// it can not hand out references into a storage that can be written through `&self`.
+pub struct CellMemory {
+    cells: Box<[Cell<u8>]>,
+}
+
+impl CellMemory {
+    /// Creates a new `CellMemory` with `size` zero initialized bytes.
+    ///
+    /// The memory is requested as zeroed memory from the allocator, which usually maps
+    /// zero pages of the operating system for large sizes, so creating even multiple GiB
+    /// is cheap, and pages are only backed by physical memory once they are written.
+    pub fn new(size: usize) -> Self {
+        Self::from(vec![0u8; size])
+    }
+
+    /// Tries to create a new `CellMemory` with `size` zero initialized bytes, like
+    /// [`new`](Self::new).
+    ///
+    /// Returns `None` if the memory could not be allocated, instead of aborting.
+    pub fn try_new(size: usize) -> Option<Self> {
+        if size == 0 {
+            return Some(Self::new(0));
+        }
+
+        #[cfg(feature = "forbid-unsafe")]
+        {
+            let mut cells = Vec::new();
+            cells.try_reserve_exact(size).ok()?;
+            cells.resize_with(size, || Cell::new(0));
+            Some(Self {
+                cells: cells.into_boxed_slice(),
+            })
+        }
+
+        #[cfg(not(feature = "forbid-unsafe"))]
+        {
+            let layout = Layout::array::<u8>(size).ok()?;
+            // Safety: the layout has a non-zero size.
+            let ptr = unsafe { alloc::alloc::alloc_zeroed(layout) };
+            if ptr.is_null() {
+                return None;
+            }
+            // Safety: `ptr` was allocated by the global allocator with the layout of a
+            // `[u8]` of `size` initialized bytes, and `Cell<u8>` has the same layout as `u8`.
+            let cells = unsafe {
+                Box::from_raw(core::ptr::slice_from_raw_parts_mut(
+                    ptr as *mut Cell<u8>,
+                    size,
+                ))
+            };
+            Some(Self { cells })
+        }
+    }
+
+    /// Creates a new `CellMemory` with `size` bytes, that are initialized using the given
+    /// [`FillPolicy`].
+    ///
+    /// [`FillPolicy::Zero`] is as cheap as [`new`](Self::new), while the other policies
+    /// have to write every byte.
+    pub fn with_fill(size: usize, fill: FillPolicy) -> Self {
+        let mut bytes = vec![0u8; size];
+        if fill != FillPolicy::Zero {
+            fill.fill(&mut bytes);
+        }
+        Self::from(bytes)
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Copies the content of this memory into a `Vec`.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.cells.iter().map(Cell::get).collect()
+    }
+
+    /// Returns the bytes of this memory as a mutable slice.
+    #[cfg(all(feature = "rkyv", not(feature = "forbid-unsafe")))]
+    pub(crate) fn bytes_mut(&mut self) -> &mut [u8] {
+        let cells = &mut *self.cells;
+        // Safety: `Cell<u8>` has the same memory layout as `u8`, and the mutable
+        // borrow guarantees that nobody else can access the cells.
+        unsafe { core::slice::from_raw_parts_mut(cells.as_mut_ptr() as *mut u8, cells.len()) }
+    }
+
+    /// Returns the bytes of this memory as a slice.
+    ///
+    /// # Safety
+    ///
+    /// The memory must not be written while the returned slice is alive.
+    #[cfg(all(feature = "rkyv", not(feature = "forbid-unsafe")))]
+    pub(crate) unsafe fn bytes_unchecked(&self) -> &[u8] {
+        core::slice::from_raw_parts(self.cells.as_ptr() as *const u8, self.cells.len())
+    }
+
+    fn read_cells(&self, addr: usize, buf: &mut [u8]) -> Result<(), OutOfBounds> {
+        let cells = self.cells(addr, buf.len())?;
+        for (byte, cell) in buf.iter_mut().zip(cells) {
+            *byte = cell.get();
+        }
+        Ok(())
+    }
+
+    fn write_cells(&self, addr: usize, buf: &[u8]) -> Result<(), OutOfBounds> {
+        let cells = self.cells(addr, buf.len())?;
+        for (cell, byte) in cells.iter().zip(buf) {
+            cell.set(*byte);
+        }
+        Ok(())
+    }
+
+    fn cell(&self, addr: usize) -> Result<&Cell<u8>, OutOfBounds> {
+        self.cells.get(addr).ok_or(OutOfBounds { addr })
+    }
+
+    fn cells(&self, addr: usize, len: usize) -> Result<&[Cell<u8>], OutOfBounds> {
+        addr.checked_add(len)
+            .and_then(|end| self.cells.get(addr..end))
+            .ok_or(OutOfBounds { addr })
+    }
+}
+
+impl MemoryStorage for CellMemory {
+    type Error = OutOfBounds;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.cell(addr).map(Cell::get)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.cell(addr).map(|cell| cell.set(byte))
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_cells(addr, buf)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.write_cells(addr, buf)
+    }
+}
+
+/// Allows writing to the memory through a shared reference, e.g. `(&mem).write(addr, val)`.
+impl MemoryStorage for &CellMemory {
+    type Error = OutOfBounds;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.cell(addr).map(Cell::get)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.cell(addr).map(|cell| cell.set(byte))
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_cells(addr, buf)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.write_cells(addr, buf)
+    }
+}
+
+impl ReadRef for CellMemory {
+    type Bytes<'a> = Vec<u8>;
+
+    fn try_read_ref(&self, range: Range<usize>) -> Result<Self::Bytes<'_>, Self::Error> {
+        gather(self, range)
+    }
+}
+
+impl ResizableMemory for CellMemory {
+    fn try_resize(&mut self, new_size: usize) -> Result<(), ResizeError> {
+        let mut cells = core::mem::take(&mut self.cells).into_vec();
+        if let Some(additional) = new_size.checked_sub(cells.len()) {
+            if cells.try_reserve_exact(additional).is_err() {
+                self.cells = cells.into_boxed_slice();
+                return Err(ResizeError::AllocFailed { size: new_size });
+            }
+        }
+        cells.resize_with(new_size, || Cell::new(0));
+        self.cells = cells.into_boxed_slice();
+        Ok(())
+    }
+}
+
+impl From<Vec<u8>> for CellMemory {
+    /// Reuses the allocation of the `Vec`, unless unsafe code is forbidden, in which case
+    /// the bytes are copied.
+    #[cfg(feature = "forbid-unsafe")]
+    fn from(bytes: Vec<u8>) -> Self {
+        Self {
+            cells: bytes.into_iter().map(Cell::new).collect(),
+        }
+    }
+
+    #[cfg(not(feature = "forbid-unsafe"))]
+    fn from(bytes: Vec<u8>) -> Self {
+        let bytes = Box::into_raw(bytes.into_boxed_slice());
+        // Safety: `Cell<u8>` has the same memory layout as `u8`, and the
+        // box is uniquely owned, so nobody else can observe the cast.
+        let cells = unsafe { Box::from_raw(bytes as *mut [Cell<u8>]) };
+        Self { cells }
+    }
+}
+
+impl core::fmt::Debug for CellMemory {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CellMemory")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for CellMemory {
+    fn zeroize(&mut self) {
+        self.cells
+            .iter_mut()
+            .map(Cell::get_mut)
+            .for_each(zeroize::Zeroize::zeroize);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for CellMemory {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for CellMemory {}
+
+impl ReportUsage for CellMemory {
+    fn usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            addressable: self.len(),
+            allocated: self.len(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{ChecksumError, MemoryStorage};
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// A wrapper that keeps a checksum of every page of the inner memory, to catch memory
+/// corruption that did not go through this wrapper, e.g. by a buggy `unsafe` fast path.
+///
+/// The checksums are updated on every write, by reading back the modified pages, and
+/// verified on demand using [`try_verify`](Self::try_verify). In paranoid mode, every
+/// read verifies the pages it touches first. This makes every access a lot slower, so
+/// it is intended for development builds.
+///
+/// Only the first `len` bytes of the inner memory are protected. Accesses beyond them
+/// are passed to the inner memory without checking them.
+pub struct ChecksumMemory<M, const PAGE_SIZE: usize = 4096> {
+    inner: M,
+    sums: Vec<u64>,
+    len: usize,
+    paranoid: bool,
+}
+
+impl<M: MemoryStorage, const PAGE_SIZE: usize> ChecksumMemory<M, PAGE_SIZE> {
+    /// Creates a new `ChecksumMemory` for the first `len` bytes of the inner memory,
+    /// by computing the checksum of every page.
+    ///
+    /// Returns `Err(x)` if one of the pages could not be read.
+    pub fn new(inner: M, len: usize) -> Result<Self, M::Error> {
+        const { assert!(PAGE_SIZE > 0, "page size must be non-zero") };
+        let mut mem = Self {
+            inner,
+            sums: Vec::new(),
+            len,
+            paranoid: false,
+        };
+        mem.sums = (0..len.div_ceil(PAGE_SIZE))
+            .map(|page| mem.checksum(page))
+            .collect::<Result<_, _>>()?;
+        Ok(mem)
+    }
+
+    /// Makes every read verify the checksums of the pages it touches first.
+    pub fn paranoid(mut self) -> Self {
+        self.paranoid = true;
+        self
+    }
+
+    /// Returns the number of bytes that are protected.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bytes are protected.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Consumes this `ChecksumMemory` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Tries to verify the checksum of every page.
+    ///
+    /// Returns `Err(x)` if the content of a page does not match its checksum, or one of
+    /// the pages could not be read.
+    pub fn try_verify(&self) -> Result<(), ChecksumError<M::Error>> {
+        self.verify_pages(0..self.sums.len())
+    }
+
+    /// Verifies the checksum of every page.
+    ///
+    /// Panics if the content of a page does not match its checksum, or one of the pages
+    /// could not be read.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_verify` instead"))]
+    pub fn verify(&self) {
+        or_panic!(self.try_verify(), "verify checksums of {} bytes", self.len)
+    }
+
+    /// Returns the range of protected pages that are touched by the given range.
+    fn pages(&self, addr: usize, len: usize) -> Range<usize> {
+        let end = addr.saturating_add(len).min(self.len);
+        if addr >= end {
+            return 0..0;
+        }
+        addr / PAGE_SIZE..end.div_ceil(PAGE_SIZE)
+    }
+
+    fn verify_pages(&self, pages: Range<usize>) -> Result<(), ChecksumError<M::Error>> {
+        for page in pages {
+            let sum = self.checksum(page).map_err(ChecksumError::Memory)?;
+            if sum != self.sums[page] {
+                trace_event!(warn, page, "checksum mismatch");
+                return Err(ChecksumError::Corrupted { page });
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes the checksum of the given page of the inner memory.
+    fn checksum(&self, page: usize) -> Result<u64, M::Error> {
+        let start = page * PAGE_SIZE;
+        let mut data = [0u8; PAGE_SIZE];
+        let data = &mut data[..(self.len - start).min(PAGE_SIZE)];
+        self.inner.try_read_into(start, data)?;
+        Ok(fnv1a(data))
+    }
+}
+
+impl<M: MemoryStorage, const PAGE_SIZE: usize> MemoryStorage for ChecksumMemory<M, PAGE_SIZE> {
+    type Error = ChecksumError<M::Error>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        if self.paranoid {
+            self.verify_pages(self.pages(addr, buf.len()))?;
+        }
+        self.inner
+            .try_read_into(addr, buf)
+            .map_err(ChecksumError::Memory)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        // Verify first, so a corruption is not hidden by updating the checksum.
+        let pages = self.pages(addr, buf.len());
+        self.verify_pages(pages.clone())?;
+
+        let result = self.inner.try_write_from(addr, buf);
+        // Update the checksums even if the write failed, because it may have been done
+        // partially.
+        for page in pages {
+            self.sums[page] = self.checksum(page).map_err(ChecksumError::Memory)?;
+        }
+        result.map_err(ChecksumError::Memory)
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M, const PAGE_SIZE: usize> core::fmt::Debug for ChecksumMemory<M, PAGE_SIZE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ChecksumMemory")
+            .field("len", &self.len)
+            .field("paranoid", &self.paranoid)
+            .finish()
+    }
+}
+
+/// Computes the 64-bit FNV-1a hash of the given bytes.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
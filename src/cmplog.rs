@@ -0,0 +1,148 @@
+use crate::MemoryStorage;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// A typed read that was reported to a [`CmpLog`] by a [`CmpLogMemory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CmpLogEntry {
+    /// The address of the first byte that was read.
+    pub addr: usize,
+    len: u8,
+    bytes: [u8; 16],
+}
+
+impl CmpLogEntry {
+    /// Returns the width of the read in bytes, which is 2, 4, 8 or 16.
+    pub fn width(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Returns the bytes that were read, in the order they are stored inside the memory.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    /// Returns the value that was read, interpreted as little endian.
+    pub fn value_le(&self) -> u128 {
+        let mut bytes = [0u8; 16];
+        bytes[..self.bytes().len()].copy_from_slice(self.bytes());
+        u128::from_le_bytes(bytes)
+    }
+
+    /// Returns the value that was read, interpreted as big endian.
+    pub fn value_be(&self) -> u128 {
+        let mut bytes = [0u8; 16];
+        bytes[16 - self.bytes().len()..].copy_from_slice(self.bytes());
+        u128::from_be_bytes(bytes)
+    }
+}
+
+/// A comparison log, which receives the typed reads of a [`CmpLogMemory`].
+///
+/// This is implemented for every closure that takes a [`CmpLogEntry`], and for
+/// `RefCell<Vec<CmpLogEntry>>` if the `alloc` feature is enabled.
+pub trait CmpLog {
+    /// Records a typed read.
+    fn log(&self, entry: CmpLogEntry);
+}
+
+impl<F: Fn(CmpLogEntry)> CmpLog for F {
+    fn log(&self, entry: CmpLogEntry) {
+        self(entry)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CmpLog for core::cell::RefCell<alloc::vec::Vec<CmpLogEntry>> {
+    fn log(&self, entry: CmpLogEntry) {
+        self.borrow_mut().push(entry);
+    }
+}
+
+/// A wrapper that reports every successful typed read of the inner memory to a
+/// [`CmpLog`], which enables RedQueen / CmpLog-style input-to-state fuzzing.
+///
+/// A read is reported if it reads 2, 4, 8 or 16 bytes at once, which is what the
+/// `read` family of `MemoryStorage` does for every [`Value`](crate::Value) except `u8`.
+/// Single bytes are not reported, because they are too frequent to be useful.
+///
+/// The wrapper never hands out slices of the inner memory, so every read goes through it.
+pub struct CmpLogMemory<M, L> {
+    inner: M,
+    log: L,
+}
+
+impl<M: MemoryStorage, L: CmpLog> CmpLogMemory<M, L> {
+    /// Creates a new `CmpLogMemory` that reports the reads of `inner` to `log`.
+    pub fn new(inner: M, log: L) -> Self {
+        Self { inner, log }
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the comparison log.
+    pub fn log(&self) -> &L {
+        &self.log
+    }
+
+    /// Returns a mutable reference to the comparison log.
+    pub fn log_mut(&mut self) -> &mut L {
+        &mut self.log
+    }
+
+    /// Consumes this `CmpLogMemory` and returns the inner memory and the comparison log.
+    pub fn into_parts(self) -> (M, L) {
+        (self.inner, self.log)
+    }
+}
+
+impl<M: MemoryStorage, L: CmpLog> MemoryStorage for CmpLogMemory<M, L> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.inner.try_read_byte(addr)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.inner.try_write_byte(addr, byte)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.try_read_into(addr, buf)?;
+        if let 2 | 4 | 8 | 16 = buf.len() {
+            let mut entry = CmpLogEntry {
+                addr,
+                len: buf.len() as u8,
+                bytes: [0; 16],
+            };
+            entry.bytes[..buf.len()].copy_from_slice(buf);
+            self.log.log(entry);
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.inner.try_write_from(addr, buf)
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
@@ -0,0 +1,161 @@
+//! Exporting guest memory as an ELF core file, which can be opened by `gdb` and other
+//! standard tools.
+//!
+//! The core file contains one `PT_LOAD` segment for every region, which stores the bytes
+//! of the memory at the same addresses, and a single `PT_NOTE` segment with the notes
+//! that are supplied by the caller, e.g. the registers of the guest as a `NT_PRSTATUS`
+//! note. Only 64-bit little endian core files are written.
+
+use crate::{CoreDumpError, MemoryStorage};
+use core::convert::TryFrom;
+use std::io::{self, Write};
+use std::vec::Vec;
+
+/// The segment flag that marks a region as executable.
+pub const PF_X: u32 = 1;
+/// The segment flag that marks a region as writable.
+pub const PF_W: u32 = 2;
+/// The segment flag that marks a region as readable.
+pub const PF_R: u32 = 4;
+
+/// The note type that stores the registers of a thread, as `struct elf_prstatus`.
+pub const NT_PRSTATUS: u32 = 1;
+
+const EHDR_SIZE: usize = 64;
+const PHDR_SIZE: usize = 56;
+const PT_LOAD: u32 = 1;
+const PT_NOTE: u32 = 4;
+const ET_CORE: u16 = 4;
+
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    addr: u64,
+    len: u64,
+    flags: u32,
+}
+
+/// A description of the core file, which is written by [`write`](Self::write).
+#[derive(Debug, Clone)]
+pub struct CoreDump {
+    machine: u16,
+    regions: Vec<Region>,
+    notes: Vec<u8>,
+}
+
+impl CoreDump {
+    /// Creates a new `CoreDump` without any regions or notes, for the given `e_machine`
+    /// value of the guest architecture, e.g. `62` for x86-64 or `243` for RISC-V.
+    pub fn new(machine: u16) -> Self {
+        Self {
+            machine,
+            regions: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Adds the `len` bytes starting at `addr` as a region, with the given combination
+    /// of [`PF_R`], [`PF_W`] and [`PF_X`].
+    pub fn region(mut self, addr: u64, len: u64, flags: u32) -> Self {
+        self.regions.push(Region { addr, len, flags });
+        self
+    }
+
+    /// Adds a note with the given owner name, type and content, e.g. the registers
+    /// of the guest as a [`NT_PRSTATUS`] note owned by `CORE`.
+    pub fn note(mut self, name: &str, kind: u32, desc: &[u8]) -> Self {
+        let name_len = name.len() as u32 + 1;
+        self.notes.extend_from_slice(&name_len.to_le_bytes());
+        self.notes
+            .extend_from_slice(&(desc.len() as u32).to_le_bytes());
+        self.notes.extend_from_slice(&kind.to_le_bytes());
+        self.notes.extend_from_slice(name.as_bytes());
+        self.notes.push(0);
+        pad(&mut self.notes, 4);
+        self.notes.extend_from_slice(desc);
+        pad(&mut self.notes, 4);
+        self
+    }
+
+    /// Writes the core file to `out`, by reading every region out of `mem`.
+    ///
+    /// Returns `Err(x)` if one of the regions could not be read, or the file could not
+    /// be written.
+    pub fn write<M, W>(&self, mem: &M, mut out: W) -> Result<(), CoreDumpError<M::Error>>
+    where
+        M: MemoryStorage + ?Sized,
+        W: Write,
+    {
+        let phnum = self.regions.len() + 1;
+        let notes_offset = (EHDR_SIZE + phnum * PHDR_SIZE) as u64;
+
+        let mut header = Vec::with_capacity(notes_offset as usize);
+        header.extend_from_slice(b"\x7fELF");
+        // 64-bit, little endian, version 1, System V ABI.
+        header.extend_from_slice(&[2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        header.extend_from_slice(&ET_CORE.to_le_bytes());
+        header.extend_from_slice(&self.machine.to_le_bytes());
+        header.extend_from_slice(&1u32.to_le_bytes());
+        // The entry point and the section headers are unused.
+        header.extend_from_slice(&0u64.to_le_bytes());
+        header.extend_from_slice(&(EHDR_SIZE as u64).to_le_bytes());
+        header.extend_from_slice(&0u64.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&(EHDR_SIZE as u16).to_le_bytes());
+        header.extend_from_slice(&(PHDR_SIZE as u16).to_le_bytes());
+        header.extend_from_slice(&(phnum as u16).to_le_bytes());
+        header.extend_from_slice(&[0; 6]);
+
+        let notes_len = self.notes.len() as u64;
+        phdr(&mut header, PT_NOTE, 0, notes_offset, 0, notes_len, 4);
+        let mut offset = notes_offset + notes_len;
+        for region in &self.regions {
+            phdr(
+                &mut header,
+                PT_LOAD,
+                region.flags,
+                offset,
+                region.addr,
+                region.len,
+                1,
+            );
+            offset += region.len;
+        }
+
+        out.write_all(&header)?;
+        out.write_all(&self.notes)?;
+
+        let mut buf = [0u8; 4096];
+        for region in &self.regions {
+            let mut done = 0;
+            while done < region.len {
+                let len = (region.len - done).min(buf.len() as u64) as usize;
+                let addr = usize::try_from(region.addr + done).map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "region address is too large")
+                })?;
+                mem.try_read_into(addr, &mut buf[..len])
+                    .map_err(CoreDumpError::Memory)?;
+                out.write_all(&buf[..len])?;
+                done += len as u64;
+            }
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Appends a program header.
+fn phdr(out: &mut Vec<u8>, kind: u32, flags: u32, offset: u64, addr: u64, len: u64, align: u64) {
+    out.extend_from_slice(&kind.to_le_bytes());
+    out.extend_from_slice(&flags.to_le_bytes());
+    out.extend_from_slice(&offset.to_le_bytes());
+    out.extend_from_slice(&addr.to_le_bytes());
+    // The physical address.
+    out.extend_from_slice(&0u64.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&align.to_le_bytes());
+}
+
+fn pad(out: &mut Vec<u8>, align: usize) {
+    out.resize(out.len().next_multiple_of(align), 0);
+}
@@ -0,0 +1,129 @@
+use crate::{ContiguousMemory, MemoryStorage, OutOfBounds};
+
+/// The size of the coverage map that AFL uses by default.
+pub const AFL_MAP_SIZE: usize = 1 << 16;
+
+/// A fixed-size map of hit counters, like the coverage bitmap of AFL-style fuzzers.
+///
+/// Every byte is a counter, which is incremented by [`hit`](Self::hit) and saturates at
+/// 255 instead of wrapping around, so a hot edge never looks as if it was not hit at all.
+///
+/// The map can be kept on the host side and updated by the emulator, or mounted into the
+/// guest address space, because it implements `MemoryStorage` like any other region.
+/// The counters are stored inside `B`, e.g. a `Box<[u8]>` or a `&mut [u8]` that points
+/// into the shared memory of the fuzzer.
+#[derive(Debug, Clone)]
+pub struct CoverageMap<B> {
+    map: B,
+}
+
+#[cfg(feature = "alloc")]
+impl CoverageMap<alloc::boxed::Box<[u8]>> {
+    /// Creates a new, zeroed `CoverageMap` with `size` counters on the heap.
+    pub fn with_size(size: usize) -> Self {
+        Self::new(alloc::vec![0; size].into_boxed_slice())
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> CoverageMap<B> {
+    /// Creates a new `CoverageMap` that stores its counters inside `map`, without
+    /// clearing it.
+    pub fn new(map: B) -> Self {
+        Self { map }
+    }
+
+    /// Returns the number of counters inside this map.
+    pub fn len(&self) -> usize {
+        self.map.as_ref().len()
+    }
+
+    /// Returns `true` if this map has no counters.
+    pub fn is_empty(&self) -> bool {
+        self.map.as_ref().is_empty()
+    }
+
+    /// Increments the counter at `index` modulo the size of the map, saturating at 255.
+    ///
+    /// Panics if the map is empty.
+    pub fn hit(&mut self, index: usize) {
+        let map = self.map.as_mut();
+        let counter = &mut map[index % map.len()];
+        *counter = counter.saturating_add(1);
+    }
+
+    /// Returns the counter at `index` modulo the size of the map.
+    ///
+    /// Panics if the map is empty.
+    pub fn counter(&self, index: usize) -> u8 {
+        let map = self.map.as_ref();
+        map[index % map.len()]
+    }
+
+    /// Sets every counter to zero, e.g. before running the next testcase.
+    pub fn reset(&mut self) {
+        self.map.as_mut().fill(0);
+    }
+
+    /// Returns the number of counters that are not zero.
+    pub fn count(&self) -> usize {
+        self.map
+            .as_ref()
+            .iter()
+            .filter(|&&count| count != 0)
+            .count()
+    }
+
+    /// Returns the counters of this map.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.map.as_ref()
+    }
+
+    /// Consumes this `CoverageMap` and returns the storage of the counters.
+    pub fn into_inner(self) -> B {
+        self.map
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> MemoryStorage for CoverageMap<B> {
+    type Error = OutOfBounds;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.map
+            .as_ref()
+            .get(addr)
+            .copied()
+            .ok_or(OutOfBounds { addr })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let cell = self
+            .map
+            .as_mut()
+            .get_mut(addr)
+            .ok_or(OutOfBounds { addr })?;
+        *cell = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self.map.as_ref())
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(self.map.as_mut())
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> ContiguousMemory for CoverageMap<B> {
+    fn as_bytes(&self) -> &[u8] {
+        self.map.as_ref()
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.map.as_mut()
+    }
+
+    fn out_of_bounds(&self, addr: usize) -> Self::Error {
+        OutOfBounds { addr }
+    }
+}
@@ -0,0 +1,288 @@
+use crate::shm::owned_fd;
+use crate::{ContiguousMemory, MemoryStorage, OutOfBounds};
+use core::ops::Range;
+use std::io;
+use std::os::unix::io::{AsRawFd, OwnedFd};
+
+/// Options that control how the mapping of a [`CowMemory`] is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MapOptions {
+    /// If `true`, every page is faulted in when the memory is created, so the first
+    /// access to a page does not have to wait for the kernel.
+    pub populate: bool,
+    /// If `true`, the kernel is asked to back the memory with transparent hugepages,
+    /// which reduces TLB misses for large memories.
+    ///
+    /// This is only a hint. Whether hugepages are used depends on the
+    /// `transparent_hugepage/shmem_enabled` setting of the system, and creating the
+    /// memory does not fail if the kernel rejects the hint.
+    pub huge_pages: bool,
+}
+
+/// A memory that can be reset to a snapshot almost instantly, which is what snapshot
+/// fuzzers need to run every testcase from the same state.
+///
+/// The snapshot is stored inside an anonymous file created by `memfd_create`, which is
+/// mapped as a private, copy-on-write mapping. Writes only copy the pages they touch,
+/// and [`restore`](Self::restore) discards those copies, so resetting only costs time
+/// for the pages that were modified since the last reset.
+#[derive(Debug)]
+pub struct CowMemory {
+    fd: OwnedFd,
+    ptr: *mut u8,
+    len: usize,
+    map_len: usize,
+}
+
+// Safety: the private mapping is owned by the memory, and only accessed through it.
+unsafe impl Send for CowMemory {}
+// Safety: shared references only allow reading the mapping.
+unsafe impl Sync for CowMemory {}
+
+impl CowMemory {
+    /// Creates a new `CowMemory` with `size` bytes, whose snapshot is zeroed.
+    ///
+    /// Returns `Err(x)` if the memory could not be created.
+    pub fn new(size: usize) -> io::Result<Self> {
+        Self::with_options(size, MapOptions::default())
+    }
+
+    /// Creates a new `CowMemory` with `size` bytes, whose snapshot is zeroed, and whose
+    /// mapping is created using the given options.
+    ///
+    /// Returns `Err(x)` if the memory could not be created.
+    pub fn with_options(size: usize, options: MapOptions) -> io::Result<Self> {
+        // Safety: the name is a valid C string.
+        let name = b"mem_storage_cow\0".as_ptr() as *const libc::c_char;
+        let fd = owned_fd(unsafe { libc::memfd_create(name, libc::MFD_CLOEXEC) })?;
+        // Safety: `fd` is a valid file descriptor.
+        if unsafe { libc::ftruncate(fd.as_raw_fd(), size as libc::off_t) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // The mapping must not be empty, but the byte after the end is never accessed.
+        let map_len = size.max(1);
+        let mut flags = libc::MAP_PRIVATE;
+        if options.populate {
+            flags |= libc::MAP_POPULATE;
+        }
+        // Safety: a new private mapping is created, which does not alias any Rust memory.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                map_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                flags,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mem = Self {
+            fd,
+            ptr: ptr as *mut u8,
+            len: size,
+            map_len,
+        };
+        // The advice is only a hint, so kernels without transparent hugepage support
+        // must not prevent creating the memory.
+        if options.huge_pages && mem.advise(0..map_len, libc::MADV_HUGEPAGE).is_err() {
+            log_warn!("failed to enable hugepages for a copy-on-write memory");
+        }
+        Ok(mem)
+    }
+
+    /// Creates a new `CowMemory`, whose snapshot contains the given bytes.
+    ///
+    /// Returns `Err(x)` if the memory could not be created.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let mut mem = Self::new(bytes.len())?;
+        mem.bytes_mut().copy_from_slice(bytes);
+        mem.snapshot()?;
+        Ok(mem)
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Resets the whole memory to the content of the last snapshot.
+    ///
+    /// Returns `Err(x)` if the modified pages could not be discarded.
+    pub fn restore(&mut self) -> io::Result<()> {
+        self.advise(0..self.map_len, libc::MADV_DONTNEED)
+    }
+
+    /// Resets the given range to the content of the last snapshot.
+    ///
+    /// The modified pages that are fully inside the range are discarded, like
+    /// [`restore`](Self::restore) does, and only the parts of the pages at the edges of
+    /// the range are copied from the snapshot.
+    ///
+    /// Returns `Err(x)` if the range is out of bounds, or the pages could not be
+    /// discarded.
+    pub fn discard(&mut self, range: Range<usize>) -> io::Result<()> {
+        if range.start > range.end || range.end > self.len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "range is outside of the memory",
+            ));
+        }
+
+        let page_size = page_size();
+        let first = range.start.div_ceil(page_size) * page_size;
+        let last = range.end / page_size * page_size;
+        if first >= last {
+            return self.read_snapshot(range);
+        }
+
+        self.read_snapshot(range.start..first)?;
+        self.advise(first..last, libc::MADV_DONTNEED)?;
+        self.read_snapshot(last..range.end)
+    }
+
+    /// Makes the current content of this memory the snapshot, that is restored by
+    /// [`restore`](Self::restore).
+    ///
+    /// This copies the whole memory into the snapshot, so it is as expensive as writing
+    /// every byte.
+    ///
+    /// Returns `Err(x)` if the snapshot could not be written.
+    pub fn snapshot(&mut self) -> io::Result<()> {
+        let mut done = 0;
+        while done < self.len {
+            // Safety: the range is inside the mapping, which is not modified by the call.
+            let written = unsafe {
+                libc::pwrite(
+                    self.fd.as_raw_fd(),
+                    self.ptr.add(done) as *const libc::c_void,
+                    self.len - done,
+                    done as libc::off_t,
+                )
+            };
+            if written < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+                continue;
+            }
+            done += written as usize;
+        }
+        self.restore()
+    }
+
+    /// Copies the given range from the snapshot into the mapping.
+    fn read_snapshot(&mut self, range: Range<usize>) -> io::Result<()> {
+        let mut done = range.start;
+        while done < range.end {
+            // Safety: the range is inside the mapping, which is uniquely borrowed.
+            let read = unsafe {
+                libc::pread(
+                    self.fd.as_raw_fd(),
+                    self.ptr.add(done) as *mut libc::c_void,
+                    range.end - done,
+                    done as libc::off_t,
+                )
+            };
+            if read < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+                continue;
+            }
+            if read == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            done += read as usize;
+        }
+        Ok(())
+    }
+
+    /// Calls `madvise` for the given page aligned range of the mapping.
+    fn advise(&self, range: Range<usize>, advice: libc::c_int) -> io::Result<()> {
+        // Safety: the range is inside the private mapping, and the callers only use
+        // advices that keep the content readable from the snapshot.
+        if unsafe {
+            libc::madvise(
+                self.ptr.add(range.start) as *mut libc::c_void,
+                range.end - range.start,
+                advice,
+            )
+        } != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Safety: the mapping is valid for `len` bytes, which are only modified through
+        // `&mut self`.
+        unsafe { core::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // Safety: the mapping is valid for `len` bytes, which are uniquely borrowed
+        // through `&mut self`.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+fn page_size() -> usize {
+    // Safety: `sysconf` has no preconditions.
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
+
+impl MemoryStorage for CowMemory {
+    type Error = OutOfBounds;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.bytes().get(addr).copied().ok_or(OutOfBounds { addr })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let cell = self.bytes_mut().get_mut(addr).ok_or(OutOfBounds { addr })?;
+        *cell = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self.bytes())
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(self.bytes_mut())
+    }
+}
+
+impl ContiguousMemory for CowMemory {
+    fn as_bytes(&self) -> &[u8] {
+        self.bytes()
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.bytes_mut()
+    }
+
+    fn out_of_bounds(&self, addr: usize) -> Self::Error {
+        OutOfBounds { addr }
+    }
+}
+
+impl Drop for CowMemory {
+    fn drop(&mut self) {
+        // Safety: the mapping was created by `new` and is not used anymore.
+        unsafe { libc::munmap(self.ptr as *mut libc::c_void, self.map_len) };
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{DataBusError, MemoryStorage, Width};
+use core::cell::Cell;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// Describes what a [`DataBus`] does with accesses that are wider than the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WidePolicy {
+    /// The access is split into multiple bus cycles, one for every aligned bus word that
+    /// is touched, which are forwarded to the inner memory in ascending address order.
+    Split,
+    /// The access fails with [`DataBusError::TooWide`].
+    Reject,
+}
+
+/// Describes what a [`DataBus`] does with accesses that are not naturally aligned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MisalignedPolicy {
+    /// The access is split into one bus cycle for every aligned bus word that is touched,
+    /// which are forwarded to the inner memory in ascending address order, like the
+    /// hardware of many CPUs does.
+    Split,
+    /// The access fails with [`DataBusError::Misaligned`].
+    Reject,
+}
+
+/// A wrapper that makes accesses to the inner memory behave like accesses over a data bus
+/// with a fixed width, e.g. the 16 bit bus of a 68000.
+///
+/// Accesses of 2, 4, 8 or 16 bytes that are not naturally aligned are handled according
+/// to the [`MisalignedPolicy`], and accesses that are wider than the bus according to the
+/// [`WidePolicy`]. This way, a device behind the bus sees the same sequence of narrow
+/// accesses that the real hardware would make, instead of a single wide access.
+///
+/// Accesses of other lengths, like bulk copies, are never rejected and always split
+/// at the boundaries of the bus words.
+///
+/// Every access to the inner memory counts as one bus cycle, so the [`cycles`](Self::cycles)
+/// can be used to charge the extra cost of split accesses.
+pub struct DataBus<M> {
+    inner: M,
+    width: Width,
+    policy: WidePolicy,
+    misaligned: MisalignedPolicy,
+    cycles: Cell<u64>,
+}
+
+impl<M: MemoryStorage> DataBus<M> {
+    /// Creates a new `DataBus` with the given width, that splits wider accesses and
+    /// rejects misaligned accesses.
+    pub fn new(inner: M, width: Width) -> Self {
+        Self {
+            inner,
+            width,
+            policy: WidePolicy::Split,
+            misaligned: MisalignedPolicy::Reject,
+            cycles: Cell::new(0),
+        }
+    }
+
+    /// Sets what happens with accesses that are wider than the bus.
+    pub fn wide_policy(mut self, policy: WidePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets what happens with accesses that are not naturally aligned.
+    pub fn misaligned_policy(mut self, policy: MisalignedPolicy) -> Self {
+        self.misaligned = policy;
+        self
+    }
+
+    /// Returns the width of this bus.
+    pub fn width(&self) -> Width {
+        self.width
+    }
+
+    /// Returns the number of bus cycles that were made so far.
+    pub fn cycles(&self) -> u64 {
+        self.cycles.get()
+    }
+
+    /// Resets the number of bus cycles to zero.
+    pub fn reset_cycles(&self) {
+        self.cycles.set(0);
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `DataBus` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Checks whether an access of `len` bytes at `addr` may be made over this bus.
+    fn check(&self, addr: usize, len: usize) -> Result<(), DataBusError<M::Error>> {
+        let sized = len.is_power_of_two() && len <= 16;
+        if sized && !addr.is_multiple_of(len) && self.misaligned == MisalignedPolicy::Reject {
+            return Err(DataBusError::Misaligned { addr, len });
+        }
+        if sized && len > self.width.bytes() && self.policy == WidePolicy::Reject {
+            return Err(DataBusError::TooWide { addr, len });
+        }
+        Ok(())
+    }
+
+    /// Returns the length of the bus cycle that starts at `addr`, with `len` bytes left,
+    /// and counts the cycle.
+    fn cycle_len(&self, addr: usize, len: usize) -> usize {
+        let width = self.width.bytes();
+        self.cycles.set(self.cycles.get() + 1);
+        (width - addr % width).min(len)
+    }
+}
+
+impl<M: MemoryStorage> MemoryStorage for DataBus<M> {
+    type Error = DataBusError<M::Error>;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.cycles.set(self.cycles.get() + 1);
+        self.inner.try_read_byte(addr).map_err(DataBusError::Memory)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.cycles.set(self.cycles.get() + 1);
+        self.inner
+            .try_write_byte(addr, byte)
+            .map_err(DataBusError::Memory)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+
+        let mut done = 0;
+        while done < buf.len() {
+            let len = self.cycle_len(addr + done, buf.len() - done);
+            self.inner
+                .try_read_into(addr + done, &mut buf[done..done + len])
+                .map_err(DataBusError::Memory)?;
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+
+        let mut done = 0;
+        while done < buf.len() {
+            let len = self.cycle_len(addr + done, buf.len() - done);
+            self.inner
+                .try_write_from(addr + done, &buf[done..done + len])
+                .map_err(DataBusError::Memory)?;
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M> core::fmt::Debug for DataBus<M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DataBus")
+            .field("width", &self.width)
+            .field("policy", &self.policy)
+            .field("misaligned", &self.misaligned)
+            .field("cycles", &self.cycles.get())
+            .finish()
+    }
+}
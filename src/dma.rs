@@ -0,0 +1,317 @@
+use crate::{DmaError, MemoryStorage};
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+
+/// A single transfer that is executed by a [`DmaEngine`].
+///
+/// The transfer moves `len` bytes, and advances the source and destination address by
+/// their stride after every byte. A stride of zero keeps accessing the same address,
+/// e.g. the data register of a device, and a negative stride transfers backwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DmaDescriptor {
+    /// The address of the first byte that is read, which is ignored if the transfer
+    /// fills the destination.
+    pub src: usize,
+    /// The address of the first byte that is written.
+    pub dst: usize,
+    /// The number of bytes that are transferred.
+    pub len: usize,
+    /// The distance between two source addresses.
+    pub src_stride: isize,
+    /// The distance between two destination addresses.
+    pub dst_stride: isize,
+    /// The byte that is written to the destination, instead of reading the source.
+    pub fill: Option<u8>,
+}
+
+impl DmaDescriptor {
+    /// Creates a new `DmaDescriptor` that copies `len` bytes from `src` to `dst`.
+    pub fn copy(src: usize, dst: usize, len: usize) -> Self {
+        Self {
+            src,
+            dst,
+            len,
+            src_stride: 1,
+            dst_stride: 1,
+            fill: None,
+        }
+    }
+
+    /// Creates a new `DmaDescriptor` that fills `len` bytes at `dst` with `byte`.
+    pub fn fill(dst: usize, byte: u8, len: usize) -> Self {
+        Self {
+            fill: Some(byte),
+            ..Self::copy(0, dst, len)
+        }
+    }
+
+    /// Sets the distance between two source addresses.
+    pub fn src_stride(mut self, stride: isize) -> Self {
+        self.src_stride = stride;
+        self
+    }
+
+    /// Sets the distance between two destination addresses.
+    pub fn dst_stride(mut self, stride: isize) -> Self {
+        self.dst_stride = stride;
+        self
+    }
+
+    /// Returns the source address of the `n`th byte.
+    pub fn src_addr(&self, n: usize) -> usize {
+        advance(self.src, self.src_stride, n)
+    }
+
+    /// Returns the destination address of the `n`th byte.
+    pub fn dst_addr(&self, n: usize) -> usize {
+        advance(self.dst, self.dst_stride, n)
+    }
+}
+
+fn advance(base: usize, stride: isize, n: usize) -> usize {
+    base.wrapping_add((stride as usize).wrapping_mul(n))
+}
+
+type Callback = Box<dyn FnMut(&DmaDescriptor, u64)>;
+
+struct Transfer {
+    desc: DmaDescriptor,
+    started: bool,
+    done: usize,
+    cycles: u64,
+    on_complete: Option<Callback>,
+}
+
+/// A DMA controller that executes queued [`DmaDescriptor`]s against memories.
+///
+/// Transfers are executed in the order they were submitted, one byte at a time, so
+/// [`try_step`](Self::try_step) can interleave them with the CPU using a cycle budget.
+/// Every transfer costs a fixed number of setup cycles, and a number of cycles per byte.
+/// The callback of a transfer is called once its last byte was written, together with
+/// the number of cycles the transfer took.
+///
+/// If a transfer fails, it is removed from the queue without calling its callback.
+pub struct DmaEngine {
+    queue: VecDeque<Transfer>,
+    cycles_per_byte: u64,
+    setup_cycles: u64,
+    cycles: u64,
+}
+
+impl DmaEngine {
+    /// Creates a new, idle `DmaEngine`, which takes one cycle per byte and no setup cycles.
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            cycles_per_byte: 1,
+            setup_cycles: 0,
+            cycles: 0,
+        }
+    }
+
+    /// Sets the number of cycles it takes to transfer a single byte.
+    pub fn cycles_per_byte(mut self, cycles: u64) -> Self {
+        self.cycles_per_byte = cycles;
+        self
+    }
+
+    /// Sets the number of cycles it takes to start a transfer.
+    pub fn setup_cycles(mut self, cycles: u64) -> Self {
+        self.setup_cycles = cycles;
+        self
+    }
+
+    /// Queues a transfer.
+    pub fn submit(&mut self, desc: DmaDescriptor) {
+        self.queue.push_back(Transfer {
+            desc,
+            started: false,
+            done: 0,
+            cycles: 0,
+            on_complete: None,
+        });
+    }
+
+    /// Queues a transfer, and calls `on_complete` with the descriptor and the number of
+    /// cycles it took once the transfer is complete, e.g. to raise an interrupt.
+    pub fn submit_with<F>(&mut self, desc: DmaDescriptor, on_complete: F)
+    where
+        F: FnMut(&DmaDescriptor, u64) + 'static,
+    {
+        self.submit(desc);
+        if let Some(transfer) = self.queue.back_mut() {
+            transfer.on_complete = Some(Box::new(on_complete));
+        }
+    }
+
+    /// Returns the number of transfers that are not complete yet.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns `true` if there are no pending transfers.
+    pub fn is_idle(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Removes every pending transfer, without calling their callbacks.
+    pub fn cancel(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Returns the number of cycles that were spent since the last reset.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Resets the cycle counter to zero.
+    pub fn reset_cycles(&mut self) {
+        self.cycles = 0;
+    }
+
+    /// Tries to execute pending transfers from `src` to `dst`, until at least `budget`
+    /// cycles were spent or every transfer is complete, and returns the spent cycles.
+    ///
+    /// Returns `Err(x)` if a byte could not be read from `src` or written to `dst`.
+    pub fn try_step<S, D>(
+        &mut self,
+        src: &S,
+        dst: &mut D,
+        budget: u64,
+    ) -> Result<u64, DmaError<S::Error, D::Error>>
+    where
+        S: MemoryStorage + ?Sized,
+        D: MemoryStorage + ?Sized,
+    {
+        self.step_with(budget, |desc, n| {
+            let byte = match desc.fill {
+                Some(byte) => byte,
+                None => {
+                    let addr = desc.src_addr(n);
+                    src.try_read_byte(addr)
+                        .map_err(|error| DmaError::Source { addr, error })?
+                }
+            };
+            let addr = desc.dst_addr(n);
+            dst.try_write_byte(addr, byte)
+                .map_err(|error| DmaError::Destination { addr, error })
+        })
+    }
+
+    /// Tries to execute pending transfers inside a single memory, like
+    /// [`try_step`](Self::try_step).
+    ///
+    /// Returns `Err(x)` if a byte could not be read or written.
+    pub fn try_step_in<M>(
+        &mut self,
+        mem: &mut M,
+        budget: u64,
+    ) -> Result<u64, DmaError<M::Error, M::Error>>
+    where
+        M: MemoryStorage + ?Sized,
+    {
+        self.step_with(budget, |desc, n| {
+            let byte = match desc.fill {
+                Some(byte) => byte,
+                None => {
+                    let addr = desc.src_addr(n);
+                    mem.try_read_byte(addr)
+                        .map_err(|error| DmaError::Source { addr, error })?
+                }
+            };
+            let addr = desc.dst_addr(n);
+            mem.try_write_byte(addr, byte)
+                .map_err(|error| DmaError::Destination { addr, error })
+        })
+    }
+
+    /// Tries to execute every pending transfer from `src` to `dst`, and returns the
+    /// spent cycles.
+    ///
+    /// Returns `Err(x)` if a byte could not be read from `src` or written to `dst`.
+    pub fn try_run<S, D>(
+        &mut self,
+        src: &S,
+        dst: &mut D,
+    ) -> Result<u64, DmaError<S::Error, D::Error>>
+    where
+        S: MemoryStorage + ?Sized,
+        D: MemoryStorage + ?Sized,
+    {
+        self.try_step(src, dst, u64::MAX)
+    }
+
+    /// Executes every pending transfer from `src` to `dst`, and returns the spent cycles.
+    ///
+    /// Panics if a byte could not be read from `src` or written to `dst`.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_run` instead"))]
+    pub fn run<S, D>(&mut self, src: &S, dst: &mut D) -> u64
+    where
+        S: MemoryStorage + ?Sized,
+        D: MemoryStorage + ?Sized,
+    {
+        let pending = self.pending();
+        or_panic!(self.try_run(src, dst), "run {} DMA transfers", pending)
+    }
+
+    fn step_with<ES, ED, F>(
+        &mut self,
+        budget: u64,
+        mut transfer: F,
+    ) -> Result<u64, DmaError<ES, ED>>
+    where
+        F: FnMut(&DmaDescriptor, usize) -> Result<(), DmaError<ES, ED>>,
+    {
+        let mut spent = 0u64;
+        while spent < budget {
+            let current = match self.queue.front_mut() {
+                Some(current) => current,
+                None => break,
+            };
+
+            let cost = if !current.started {
+                current.started = true;
+                self.setup_cycles
+            } else {
+                if let Err(err) = transfer(&current.desc, current.done) {
+                    self.queue.pop_front();
+                    self.cycles = self.cycles.saturating_add(spent);
+                    return Err(err);
+                }
+                current.done += 1;
+                self.cycles_per_byte
+            };
+            current.cycles = current.cycles.saturating_add(cost);
+            spent = spent.saturating_add(cost);
+
+            if current.done == current.desc.len {
+                if let Some(mut done) = self.queue.pop_front() {
+                    if let Some(on_complete) = done.on_complete.as_mut() {
+                        on_complete(&done.desc, done.cycles);
+                    }
+                }
+            }
+        }
+
+        self.cycles = self.cycles.saturating_add(spent);
+        Ok(spent)
+    }
+}
+
+impl Default for DmaEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for DmaEngine {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DmaEngine")
+            .field("pending", &self.queue.len())
+            .field("cycles_per_byte", &self.cycles_per_byte)
+            .field("setup_cycles", &self.setup_cycles)
+            .field("cycles", &self.cycles)
+            .finish()
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{EepromError, MemoryStorage, WearReport};
+use alloc::{boxed::Box, vec};
+use core::ops::Range;
+
+/// Describes what happens to a write to an EEPROM cell that exceeded its endurance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WearOut {
+    /// The write fails with [`EepromError::WornOut`], without modifying the EEPROM.
+    Fail,
+    /// The write succeeds, but the worn out cells can no longer set bits, so they store
+    /// the written byte AND-ed with their previous content.
+    Corrupt,
+}
+
+/// A heap allocated memory that models an EEPROM, for validating wear-aware firmware.
+///
+/// Every cell counts how often it was written. If an endurance is configured, writes to
+/// cells that were written more often fail or corrupt the data, depending on the
+/// [`WearOut`] mode.
+///
+/// Every write starts a write cycle, which keeps the EEPROM busy for the configured
+/// number of cycles. Accesses during a write cycle fail with [`EepromError::Busy`],
+/// and the time is advanced using [`tick`](Self::tick).
+pub struct EepromMemory {
+    data: Box<[u8]>,
+    writes: Box<[u32]>,
+    endurance: Option<(u32, WearOut)>,
+    write_latency: u64,
+    busy_for: u64,
+}
+
+impl EepromMemory {
+    /// Creates a new, erased `EepromMemory` with `size` bytes set to `0xFF`.
+    ///
+    /// The EEPROM has no endurance limit and no write latency.
+    pub fn new(size: usize) -> Self {
+        Self {
+            data: vec![0xFF; size].into_boxed_slice(),
+            writes: vec![0; size].into_boxed_slice(),
+            endurance: None,
+            write_latency: 0,
+            busy_for: 0,
+        }
+    }
+
+    /// Limits the number of writes every cell survives, and sets the behaviour of
+    /// writes to cells that exceeded the limit.
+    pub fn with_endurance(mut self, writes: u32, mode: WearOut) -> Self {
+        self.endurance = Some((writes, mode));
+        self
+    }
+
+    /// Sets the number of cycles every write cycle keeps the EEPROM busy.
+    pub fn with_write_latency(mut self, cycles: u64) -> Self {
+        self.write_latency = cycles;
+        self
+    }
+
+    /// Returns the number of bytes inside this EEPROM.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if this EEPROM has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the content of this EEPROM.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns how often the cell at the given address was written.
+    ///
+    /// Panics if the address is out of bounds.
+    pub fn write_count(&self, addr: usize) -> u32 {
+        self.writes[addr]
+    }
+
+    /// Returns the write counts of every cell.
+    pub fn write_counts(&self) -> &[u32] {
+        &self.writes
+    }
+
+    /// Returns the wear of every block of `block_size` bytes, which is the largest write
+    /// count of all cells inside the block.
+    ///
+    /// Panics if `block_size` is zero.
+    pub fn block_wear(&self, block_size: usize) -> impl Iterator<Item = u32> + '_ {
+        self.writes
+            .chunks(block_size)
+            .map(|block| block.iter().copied().max().unwrap_or(0))
+    }
+
+    /// Summarizes the [`block_wear`](Self::block_wear) of all blocks.
+    ///
+    /// Panics if `block_size` is zero.
+    pub fn wear_report(&self, block_size: usize) -> WearReport {
+        WearReport::from_counts(self.block_wear(block_size))
+    }
+
+    /// Returns `true` if the EEPROM is busy with a write cycle.
+    pub fn is_busy(&self) -> bool {
+        self.busy_for > 0
+    }
+
+    /// Advances the time by the given number of cycles.
+    pub fn tick(&mut self, cycles: u64) {
+        self.busy_for = self.busy_for.saturating_sub(cycles);
+    }
+
+    fn range(&self, addr: usize, len: usize) -> Result<Range<usize>, EepromError> {
+        if self.is_busy() {
+            return Err(EepromError::Busy);
+        }
+
+        match addr.checked_add(len) {
+            Some(end) if end <= self.data.len() => Ok(addr..end),
+            _ => Err(EepromError::OutOfBounds {
+                addr: addr.max(self.data.len()),
+            }),
+        }
+    }
+}
+
+impl MemoryStorage for EepromMemory {
+    type Error = EepromError;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let range = self.range(addr, buf.len())?;
+        buf.copy_from_slice(&self.data[range]);
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let range = self.range(addr, buf.len())?;
+        let writes = &mut self.writes[range.clone()];
+
+        if let Some((limit, WearOut::Fail)) = self.endurance {
+            if let Some(offset) = writes.iter().position(|&count| count >= limit) {
+                return Err(EepromError::WornOut {
+                    addr: addr + offset,
+                });
+            }
+        }
+
+        let cells = self.data[range].iter_mut().zip(writes.iter_mut());
+        for ((cell, count), &byte) in cells.zip(buf) {
+            *cell = match self.endurance {
+                Some((limit, WearOut::Corrupt)) if *count >= limit => *cell & byte,
+                _ => byte,
+            };
+            *count = count.saturating_add(1);
+        }
+
+        self.busy_for = self.write_latency;
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for EepromMemory {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EepromMemory")
+            .field("len", &self.data.len())
+            .field("busy", &self.is_busy())
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for EepromMemory {
+    fn zeroize(&mut self) {
+        self.data.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for EepromMemory {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for EepromMemory {}
@@ -0,0 +1,227 @@
+use crate::MemoryStorage;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// A cipher that is used by an [`EncryptedMemory`] to encrypt and decrypt single pages.
+///
+/// The page index is passed to both methods, so it can be used as a nonce or tweak.
+pub trait PageCipher {
+    /// Encrypts the page with the given index in place.
+    fn encrypt_page(&self, page: usize, data: &mut [u8]);
+
+    /// Decrypts the page with the given index in place.
+    fn decrypt_page(&self, page: usize, data: &mut [u8]);
+}
+
+/// A wrapper around a `MemoryStorage` that only ever stores encrypted data inside
+/// the inner memory.
+///
+/// Every access decrypts the affected pages into a temporary buffer on the stack,
+/// which is wiped afterwards, even if the access failed. The inner memory, and thus
+/// every snapshot of it, only contains ciphertext.
+///
+/// The inner memory is divided into pages of `PAGE_SIZE` bytes, where page `n` starts
+/// at address `n * PAGE_SIZE`.
+pub struct EncryptedMemory<M, C, const PAGE_SIZE: usize = 4096> {
+    inner: M,
+    cipher: C,
+}
+
+impl<M, C, const PAGE_SIZE: usize> EncryptedMemory<M, C, PAGE_SIZE>
+where
+    M: MemoryStorage,
+    C: PageCipher,
+{
+    /// Creates a new `EncryptedMemory` from a memory that already contains encrypted data.
+    pub fn new(inner: M, cipher: C) -> Self {
+        Self { inner, cipher }
+    }
+
+    /// Creates a new `EncryptedMemory` by encrypting the first `pages` pages of
+    /// a memory that contains plaintext.
+    ///
+    /// Returns `Err(x)` if the method failed to access one of the pages.
+    pub fn from_plaintext(mut inner: M, cipher: C, pages: usize) -> Result<Self, M::Error> {
+        let mut data = [0u8; PAGE_SIZE];
+        let result = (0..pages).try_for_each(|page| {
+            inner.try_read_into(page * PAGE_SIZE, &mut data)?;
+            cipher.encrypt_page(page, &mut data);
+            inner.try_write_from(page * PAGE_SIZE, &data)
+        });
+        wipe(&mut data);
+        result?;
+
+        Ok(Self { inner, cipher })
+    }
+
+    /// Returns a reference to the inner memory, which only contains encrypted data.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a reference to the cipher of this memory.
+    pub fn cipher(&self) -> &C {
+        &self.cipher
+    }
+
+    /// Consumes this `EncryptedMemory` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    fn decrypt_into(&self, page: usize, plain: &mut [u8; PAGE_SIZE]) -> Result<(), M::Error> {
+        self.inner.try_read_into(page * PAGE_SIZE, plain)?;
+        self.cipher.decrypt_page(page, plain);
+        Ok(())
+    }
+}
+
+impl<M, C, const PAGE_SIZE: usize> MemoryStorage for EncryptedMemory<M, C, PAGE_SIZE>
+where
+    M: MemoryStorage,
+    C: PageCipher,
+{
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let mut done = 0;
+        while done < buf.len() {
+            let (page, offset) = split_addr::<PAGE_SIZE>(addr + done);
+            let len = (PAGE_SIZE - offset).min(buf.len() - done);
+
+            let mut plain = [0u8; PAGE_SIZE];
+            let result = self.decrypt_into(page, &mut plain);
+            if result.is_ok() {
+                buf[done..done + len].copy_from_slice(&plain[offset..offset + len]);
+            }
+            wipe(&mut plain);
+            result?;
+
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let mut done = 0;
+        while done < buf.len() {
+            let (page, offset) = split_addr::<PAGE_SIZE>(addr + done);
+            let len = (PAGE_SIZE - offset).min(buf.len() - done);
+
+            let mut plain = [0u8; PAGE_SIZE];
+            let result = self.decrypt_into(page, &mut plain).and_then(|_| {
+                plain[offset..offset + len].copy_from_slice(&buf[done..done + len]);
+                self.cipher.encrypt_page(page, &mut plain);
+                self.inner.try_write_from(page * PAGE_SIZE, &plain)
+            });
+            wipe(&mut plain);
+            result?;
+
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<M, C, const PAGE_SIZE: usize> crate::ReadRef for EncryptedMemory<M, C, PAGE_SIZE>
+where
+    M: MemoryStorage,
+    C: PageCipher,
+{
+    type Bytes<'a>
+        = Plaintext
+    where
+        Self: 'a;
+
+    fn try_read_ref(&self, range: Range<usize>) -> Result<Self::Bytes<'_>, Self::Error> {
+        // The buffer is wrapped before reading, so it is also wiped if the read fails.
+        let mut bytes = Plaintext(alloc::vec![0u8; range.len()]);
+        self.try_read_into(range.start, &mut bytes.0)?;
+        Ok(bytes)
+    }
+}
+
+/// Decrypted bytes that were read out of an [`EncryptedMemory`] using
+/// [`ReadRef`](crate::ReadRef), which are wiped when they are dropped.
+///
+/// Dereferences to the bytes.
+#[cfg(feature = "alloc")]
+pub struct Plaintext(alloc::vec::Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl core::ops::Deref for Plaintext {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for Plaintext {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for Plaintext {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for Plaintext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Plaintext")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+/// Splits an address into its page index and the offset inside the page.
+fn split_addr<const PAGE_SIZE: usize>(addr: usize) -> (usize, usize) {
+    (addr / PAGE_SIZE, addr % PAGE_SIZE)
+}
+
+/// Overwrites the plaintext buffer with zeros, using volatile writes so
+/// the compiler can not remove them.
+fn wipe(buf: &mut [u8]) {
+    #[cfg(not(feature = "forbid-unsafe"))]
+    for byte in buf.iter_mut() {
+        // Safety: `byte` is a valid and aligned reference.
+        unsafe { core::ptr::write_volatile(byte, 0) };
+    }
+    // Without volatile writes, passing the zeroed buffer to `black_box` keeps the
+    // compiler from removing the writes.
+    #[cfg(feature = "forbid-unsafe")]
+    {
+        buf.fill(0);
+        core::hint::black_box(buf);
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
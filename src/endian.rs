@@ -0,0 +1,55 @@
+use crate::Value;
+
+/// A zero-sized marker that describes the byte order values are stored in.
+///
+/// This allows writing code that is generic over the byte order, e.g. a CPU core that
+/// is used for both little and big endian machines, using
+/// [`read_with`](crate::MemoryStorage::read_with) and
+/// [`write_with`](crate::MemoryStorage::write_with).
+pub trait Endianness: crate::private::Sealed + Copy + Default + 'static {
+    /// Creates a value from its representation in this byte order.
+    ///
+    /// Panics if the length of `bytes` is not equal to the size of `V`.
+    fn from_slice<V: Value>(bytes: &[u8]) -> V;
+
+    /// Writes the representation of `val` in this byte order into `out`.
+    ///
+    /// Panics if the length of `out` is not equal to the size of `V`.
+    fn write_bytes<V: Value>(val: V, out: &mut [u8]);
+}
+
+/// Little endian byte order, where the least significant byte is stored first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LittleEndian;
+
+/// Big endian byte order, where the most significant byte is stored first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BigEndian;
+
+/// The byte order of the target platform.
+#[cfg(target_endian = "little")]
+pub type NativeEndian = LittleEndian;
+
+/// The byte order of the target platform.
+#[cfg(target_endian = "big")]
+pub type NativeEndian = BigEndian;
+
+impl Endianness for LittleEndian {
+    fn from_slice<V: Value>(bytes: &[u8]) -> V {
+        V::from_le_slice(bytes)
+    }
+
+    fn write_bytes<V: Value>(val: V, out: &mut [u8]) {
+        val.write_le_bytes(out)
+    }
+}
+
+impl Endianness for BigEndian {
+    fn from_slice<V: Value>(bytes: &[u8]) -> V {
+        V::from_be_slice(bytes)
+    }
+
+    fn write_bytes<V: Value>(val: V, out: &mut [u8]) {
+        val.write_be_bytes(out)
+    }
+}
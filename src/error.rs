@@ -0,0 +1,920 @@
+use core::fmt;
+
+/// The category of a failed memory access.
+///
+/// Every error type of this crate implements [`MemoryError`], so callers can
+/// branch on the kind of failure without knowing the concrete memory, e.g. to
+/// raise the matching exception inside the emulated machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The access is outside of the memory.
+    OutOfBounds,
+    /// No memory is mapped at the accessed address.
+    Unmapped,
+    /// The access does not satisfy the alignment or width requirements of the memory.
+    Misaligned,
+    /// The access is not allowed, e.g. because of its privilege level.
+    PermissionDenied,
+    /// The device or storage behind the memory failed to perform the access.
+    DeviceError,
+    /// The accessed data is known to be corrupted.
+    Poisoned,
+    /// The access tried to modify read-only memory.
+    WriteToRom,
+    /// The memory does not support the requested operation.
+    Unsupported,
+    /// The memory failed to allocate storage.
+    OutOfMemory,
+    /// The arguments of the access are invalid, independent of the memory.
+    InvalidArgument,
+    /// The access failed for any other reason.
+    Other,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ErrorKind::OutOfBounds => "out of bounds",
+            ErrorKind::Unmapped => "unmapped address",
+            ErrorKind::Misaligned => "misaligned access",
+            ErrorKind::PermissionDenied => "permission denied",
+            ErrorKind::DeviceError => "device error",
+            ErrorKind::Poisoned => "poisoned data",
+            ErrorKind::WriteToRom => "write to read-only memory",
+            ErrorKind::Unsupported => "unsupported operation",
+            ErrorKind::OutOfMemory => "out of memory",
+            ErrorKind::InvalidArgument => "invalid argument",
+            ErrorKind::Other => "other error",
+        })
+    }
+}
+
+/// An error that can be categorized using an [`ErrorKind`].
+///
+/// Errors that wrap the error of an inner memory return the kind of the inner error.
+pub trait MemoryError {
+    /// Returns the category of this error.
+    fn kind(&self) -> ErrorKind;
+}
+
+/// The error that is returned by the built-in memories if an access is out of bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OutOfBounds {
+    /// The first address of the access that was out of bounds.
+    pub addr: usize,
+}
+
+impl fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "memory access at {:#x} is out of bounds", self.addr)
+    }
+}
+
+/// The error that is returned by methods that only accept some ranges, like
+/// [`try_swap_endianness`](crate::MemoryStorage::try_swap_endianness).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RangeError<E> {
+    /// The method does not accept the given range.
+    Invalid {
+        /// The start of the range.
+        start: usize,
+        /// The end of the range.
+        end: usize,
+    },
+    /// The memory failed to access one of the bytes.
+    Memory(E),
+}
+
+impl<E: fmt::Display> fmt::Display for RangeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::Invalid { start, end } => write!(f, "invalid range {}..{}", start, end),
+            RangeError::Memory(err) => write!(f, "failed to access memory: {}", err),
+        }
+    }
+}
+
+/// The error that is returned if a buffer does not have the length that is required
+/// by a method, like restoring a memory from an archive of a different size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LengthMismatch {
+    /// The required length in bytes.
+    pub expected: usize,
+    /// The actual length in bytes.
+    pub found: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} bytes, but found {} bytes",
+            self.expected, self.found
+        )
+    }
+}
+
+/// The error that is returned by a [`ResizableMemory`](crate::ResizableMemory) if it
+/// could not be resized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ResizeError {
+    /// The memory for the given size could not be allocated.
+    AllocFailed {
+        /// The requested size in bytes.
+        size: usize,
+    },
+    /// The memory can not be resized to the given size, e.g. because it can only grow
+    /// in whole pages.
+    Unsupported {
+        /// The requested size in bytes.
+        size: usize,
+    },
+}
+
+impl fmt::Display for ResizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResizeError::AllocFailed { size } => {
+                write!(f, "failed to allocate {} bytes of memory", size)
+            }
+            ResizeError::Unsupported { size } => {
+                write!(f, "memory can not be resized to {} bytes", size)
+            }
+        }
+    }
+}
+
+/// The error that is returned by the flash memories if an access violates the
+/// flash semantics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FlashError {
+    /// The access at the given address is out of bounds.
+    OutOfBounds {
+        /// The first address of the access that was out of bounds.
+        addr: usize,
+    },
+    /// A write tried to set a bit at the given address, which requires an erase first.
+    NotErased {
+        /// The address of the byte that was not erased.
+        addr: usize,
+    },
+    /// An erase did not start or end at a block boundary.
+    NotAligned {
+        /// The address that is not aligned to a block.
+        addr: usize,
+    },
+}
+
+impl fmt::Display for FlashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlashError::OutOfBounds { addr } => {
+                write!(f, "flash access at {:#x} is out of bounds", addr)
+            }
+            FlashError::NotErased { addr } => {
+                write!(f, "flash write at {:#x} requires an erase", addr)
+            }
+            FlashError::NotAligned { addr } => {
+                write!(f, "flash erase at {:#x} is not aligned to a block", addr)
+            }
+        }
+    }
+}
+
+/// The error that is returned by the EEPROM memories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EepromError {
+    /// The access at the given address is out of bounds.
+    OutOfBounds {
+        /// The first address of the access that was out of bounds.
+        addr: usize,
+    },
+    /// The EEPROM is still busy with the previous write cycle.
+    Busy,
+    /// The cell at the given address exceeded its write endurance.
+    WornOut {
+        /// The address of the worn out cell.
+        addr: usize,
+    },
+}
+
+impl fmt::Display for EepromError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EepromError::OutOfBounds { addr } => {
+                write!(f, "eeprom access at {:#x} is out of bounds", addr)
+            }
+            EepromError::Busy => f.write_str("eeprom is busy with a write cycle"),
+            EepromError::WornOut { addr } => write!(f, "eeprom cell at {:#x} is worn out", addr),
+        }
+    }
+}
+
+/// The error that is returned if a guest address can not be translated to host memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum GuestMemoryError {
+    /// The guest address is not backed by any region.
+    Unmapped {
+        /// The first guest address that is not mapped.
+        addr: usize,
+    },
+    /// The range starts inside one region, but does not end inside the same region.
+    CrossesRegion {
+        /// The guest address of the first byte that is outside of the region.
+        addr: usize,
+    },
+}
+
+impl fmt::Display for GuestMemoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuestMemoryError::Unmapped { addr } => {
+                write!(f, "guest address {:#x} is not mapped", addr)
+            }
+            GuestMemoryError::CrossesRegion { addr } => {
+                write!(f, "guest range crosses a region boundary at {:#x}", addr)
+            }
+        }
+    }
+}
+
+/// The error that is returned by a [`LazyMemory`](crate::LazyMemory), and by the
+/// memory of the `uffd` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LazyError<E> {
+    /// The access at the given address is out of bounds.
+    OutOfBounds {
+        /// The first address of the access that was out of bounds.
+        addr: usize,
+    },
+    /// The page source failed to fetch a page.
+    Fetch {
+        /// The index of the page that could not be fetched.
+        page: usize,
+        /// The error that was returned by the page source.
+        error: E,
+    },
+    /// The fetched page could not be placed into the mapping of a userfaultfd backed
+    /// memory.
+    Resolve {
+        /// The index of the page that could not be placed.
+        page: usize,
+        /// The OS error code that was returned by the kernel.
+        errno: i32,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for LazyError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LazyError::OutOfBounds { addr } => {
+                write!(f, "memory access at {:#x} is out of bounds", addr)
+            }
+            LazyError::Fetch { page, error } => {
+                write!(f, "failed to fetch page {}: {}", page, error)
+            }
+            LazyError::Resolve { page, errno } => {
+                write!(f, "failed to map page {}: os error {}", page, errno)
+            }
+        }
+    }
+}
+
+/// The error that is returned by a [`SwapMemory`](crate::SwapMemory).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SwapError<E> {
+    /// The access at the given address is out of bounds.
+    OutOfBounds {
+        /// The first address of the access that was out of bounds.
+        addr: usize,
+    },
+    /// The swap store failed to load or store a page.
+    Store {
+        /// The index of the page that could not be loaded or stored.
+        page: usize,
+        /// The error that was returned by the swap store.
+        error: E,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for SwapError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapError::OutOfBounds { addr } => {
+                write!(f, "memory access at {:#x} is out of bounds", addr)
+            }
+            SwapError::Store { page, error } => {
+                write!(f, "failed to swap page {}: {}", page, error)
+            }
+        }
+    }
+}
+
+/// The error that is returned by a [`TieredMemory`](crate::TieredMemory).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TieredError<F, S> {
+    /// The access at the given address is out of bounds.
+    OutOfBounds {
+        /// The first address of the access that was out of bounds.
+        addr: usize,
+    },
+    /// The fast tier failed to access a page.
+    Fast(F),
+    /// The slow tier failed to access a page.
+    Slow(S),
+}
+
+impl<F: fmt::Display, S: fmt::Display> fmt::Display for TieredError<F, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TieredError::OutOfBounds { addr } => {
+                write!(f, "memory access at {:#x} is out of bounds", addr)
+            }
+            TieredError::Fast(err) => write!(f, "fast tier failed: {}", err),
+            TieredError::Slow(err) => write!(f, "slow tier failed: {}", err),
+        }
+    }
+}
+
+/// The error that is returned by a [`RemoteMemory`](crate::RemoteMemory).
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum RemoteError {
+    /// The connection to the server failed.
+    Io(std::io::Error),
+    /// The server failed to access the memory at the given address.
+    Rejected {
+        /// The first address of the access that failed.
+        addr: usize,
+    },
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteError::Io(err) => write!(f, "remote memory connection failed: {}", err),
+            RemoteError::Rejected { addr } => {
+                write!(f, "remote memory access at {:#x} failed", addr)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for RemoteError {
+    fn from(err: std::io::Error) -> Self {
+        RemoteError::Io(err)
+    }
+}
+
+/// The error that is returned by a [`ReplayMemory`](crate::ReplayMemory) if an access
+/// does not match the recorded trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReplayError {
+    /// The access at the given address differs from the recorded access.
+    Diverged {
+        /// The index of the recorded access inside the trace.
+        index: usize,
+        /// The first address of the access that was made.
+        addr: usize,
+    },
+    /// The access at the given address was made after the end of the trace.
+    Exhausted {
+        /// The first address of the access that was made.
+        addr: usize,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Diverged { index, addr } => {
+                write!(
+                    f,
+                    "access at {:#x} diverged from recorded access {}",
+                    addr, index
+                )
+            }
+            ReplayError::Exhausted { addr } => {
+                write!(
+                    f,
+                    "access at {:#x} was made after the end of the trace",
+                    addr
+                )
+            }
+        }
+    }
+}
+
+/// The error that is returned by [`CoreDump::write`](crate::core_dump::CoreDump::write).
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum CoreDumpError<E> {
+    /// The core file could not be written.
+    Io(std::io::Error),
+    /// The memory failed to read one of the regions.
+    Memory(E),
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Display> fmt::Display for CoreDumpError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoreDumpError::Io(err) => write!(f, "failed to write core file: {}", err),
+            CoreDumpError::Memory(err) => write!(f, "failed to read memory: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> From<std::io::Error> for CoreDumpError<E> {
+    fn from(err: std::io::Error) -> Self {
+        CoreDumpError::Io(err)
+    }
+}
+
+/// The error that is returned when loading or dumping a memory image through the
+/// functions of the [`image`](crate::image) module.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum ImageError<E> {
+    /// The image could not be read or written.
+    Io(std::io::Error),
+    /// The memory failed to access one of the bytes.
+    Memory(E),
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Display> fmt::Display for ImageError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Io(err) => write!(f, "failed to transfer memory image: {}", err),
+            ImageError::Memory(err) => write!(f, "failed to access memory: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> From<std::io::Error> for ImageError<E> {
+    fn from(err: std::io::Error) -> Self {
+        ImageError::Io(err)
+    }
+}
+
+/// The error that is returned by a [`ChecksumMemory`](crate::ChecksumMemory).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChecksumError<E> {
+    /// The content of the given page does not match its checksum.
+    Corrupted {
+        /// The index of the corrupted page.
+        page: usize,
+    },
+    /// The inner memory failed to access one of the bytes.
+    Memory(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ChecksumError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChecksumError::Corrupted { page } => write!(f, "page {} is corrupted", page),
+            ChecksumError::Memory(err) => write!(f, "failed to access memory: {}", err),
+        }
+    }
+}
+
+/// The error that is returned by a [`FaultMemory`](crate::FaultMemory).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FaultError<E> {
+    /// A fault was injected into the access at the given address.
+    Injected {
+        /// The first address of the access that failed.
+        addr: usize,
+    },
+    /// The inner memory failed to access one of the bytes.
+    Memory(E),
+}
+
+impl<E: fmt::Display> fmt::Display for FaultError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaultError::Injected { addr } => write!(f, "bus error at {:#x}", addr),
+            FaultError::Memory(err) => write!(f, "failed to access memory: {}", err),
+        }
+    }
+}
+
+/// The error that is returned by a [`StorageMemory`](crate::StorageMemory).
+#[cfg(feature = "embedded-storage")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StorageError<E> {
+    /// The access does not fit into the `u32` offsets of the storage device.
+    OutOfRange {
+        /// The first address of the access.
+        addr: usize,
+    },
+    /// The storage device failed to access one of the bytes.
+    Storage(E),
+}
+
+#[cfg(feature = "embedded-storage")]
+impl<E: fmt::Debug> fmt::Display for StorageError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::OutOfRange { addr } => {
+                write!(
+                    f,
+                    "access at {:#x} does not fit into a storage offset",
+                    addr
+                )
+            }
+            StorageError::Storage(err) => write!(f, "failed to access storage: {:?}", err),
+        }
+    }
+}
+
+/// The error that is returned by a [`DataBus`](crate::DataBus).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DataBusError<E> {
+    /// The access is not naturally aligned, and the bus rejects such accesses.
+    Misaligned {
+        /// The first address of the access.
+        addr: usize,
+        /// The number of bytes that were accessed.
+        len: usize,
+    },
+    /// The access is wider than the bus, and the bus rejects such accesses.
+    TooWide {
+        /// The first address of the access.
+        addr: usize,
+        /// The number of bytes that were accessed.
+        len: usize,
+    },
+    /// The inner memory failed to access one of the bytes.
+    Memory(E),
+}
+
+impl<E: fmt::Display> fmt::Display for DataBusError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataBusError::Misaligned { addr, len } => {
+                write!(f, "misaligned {} byte access at {:#x}", len, addr)
+            }
+            DataBusError::TooWide { addr, len } => {
+                write!(
+                    f,
+                    "{} byte access at {:#x} is wider than the bus",
+                    len, addr
+                )
+            }
+            DataBusError::Memory(err) => write!(f, "failed to access memory: {}", err),
+        }
+    }
+}
+
+/// The error that is returned by a [`DmaEngine`](crate::DmaEngine) if a transfer failed.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DmaError<S, D> {
+    /// The source memory failed to read the byte at the given address.
+    Source {
+        /// The address of the byte that could not be read.
+        addr: usize,
+        /// The error that was returned by the source memory.
+        error: S,
+    },
+    /// The destination memory failed to write the byte at the given address.
+    Destination {
+        /// The address of the byte that could not be written.
+        addr: usize,
+        /// The error that was returned by the destination memory.
+        error: D,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl<S: fmt::Display, D: fmt::Display> fmt::Display for DmaError<S, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DmaError::Source { addr, error } => {
+                write!(f, "DMA read at {:#x} failed: {}", addr, error)
+            }
+            DmaError::Destination { addr, error } => {
+                write!(f, "DMA write at {:#x} failed: {}", addr, error)
+            }
+        }
+    }
+}
+
+/// The error that is returned by a [`Framebuffer`](crate::Framebuffer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PixelError<E> {
+    /// The pixel is outside of the framebuffer, or its address does not fit into a
+    /// `usize`.
+    Outside {
+        /// The column of the pixel.
+        x: usize,
+        /// The row of the pixel.
+        y: usize,
+    },
+    /// The memory failed to access one of the bytes.
+    Memory(E),
+}
+
+impl<E: fmt::Display> fmt::Display for PixelError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PixelError::Outside { x, y } => {
+                write!(f, "pixel ({}, {}) is outside of the framebuffer", x, y)
+            }
+            PixelError::Memory(err) => write!(f, "failed to access memory: {}", err),
+        }
+    }
+}
+
+/// The error that is returned by a [`PrivilegedMemory`](crate::PrivilegedMemory).
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PrivilegeError<E> {
+    /// The access is not allowed with the current privilege level.
+    Denied {
+        /// The first address of the access that failed.
+        addr: usize,
+        /// The privilege level that the access was made with.
+        privilege: crate::Privilege,
+    },
+    /// The inner memory failed to access one of the bytes.
+    Memory(E),
+}
+
+#[cfg(feature = "alloc")]
+impl<E: fmt::Display> fmt::Display for PrivilegeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrivilegeError::Denied { addr, privilege } => {
+                write!(f, "{:?} access at {:#x} is not allowed", privilege, addr)
+            }
+            PrivilegeError::Memory(err) => write!(f, "failed to access memory: {}", err),
+        }
+    }
+}
+
+/// The error that is returned by a [`LockableMemory`](crate::LockableMemory).
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LockError<E> {
+    /// The access touches a range that is locked by a
+    /// [`RegionGuard`](crate::RegionGuard).
+    Locked {
+        /// The first address of the access that failed.
+        addr: usize,
+    },
+    /// The inner memory failed to access one of the bytes.
+    Memory(E),
+}
+
+#[cfg(feature = "alloc")]
+impl<E: fmt::Display> fmt::Display for LockError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockError::Locked { addr } => write!(f, "access at {:#x} touches a locked range", addr),
+            LockError::Memory(err) => write!(f, "failed to access memory: {}", err),
+        }
+    }
+}
+
+impl MemoryError for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "std")]
+impl MemoryError for std::io::Error {
+    fn kind(&self) -> ErrorKind {
+        match std::io::Error::kind(self) {
+            std::io::ErrorKind::UnexpectedEof => ErrorKind::OutOfBounds,
+            std::io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+            std::io::ErrorKind::OutOfMemory => ErrorKind::OutOfMemory,
+            std::io::ErrorKind::Unsupported => ErrorKind::Unsupported,
+            _ => ErrorKind::DeviceError,
+        }
+    }
+}
+
+impl MemoryError for OutOfBounds {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::OutOfBounds
+    }
+}
+
+impl<E: MemoryError> MemoryError for RangeError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RangeError::Invalid { .. } => ErrorKind::InvalidArgument,
+            RangeError::Memory(err) => err.kind(),
+        }
+    }
+}
+
+impl MemoryError for LengthMismatch {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::InvalidArgument
+    }
+}
+
+impl MemoryError for ResizeError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ResizeError::AllocFailed { .. } => ErrorKind::OutOfMemory,
+            ResizeError::Unsupported { .. } => ErrorKind::Unsupported,
+        }
+    }
+}
+
+impl MemoryError for FlashError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            FlashError::OutOfBounds { .. } => ErrorKind::OutOfBounds,
+            // Flash can only clear bits without an erase, so it behaves like ROM.
+            FlashError::NotErased { .. } => ErrorKind::WriteToRom,
+            FlashError::NotAligned { .. } => ErrorKind::Misaligned,
+        }
+    }
+}
+
+impl MemoryError for EepromError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            EepromError::OutOfBounds { .. } => ErrorKind::OutOfBounds,
+            EepromError::Busy | EepromError::WornOut { .. } => ErrorKind::DeviceError,
+        }
+    }
+}
+
+impl MemoryError for GuestMemoryError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            GuestMemoryError::Unmapped { .. } => ErrorKind::Unmapped,
+            GuestMemoryError::CrossesRegion { .. } => ErrorKind::Unsupported,
+        }
+    }
+}
+
+impl<E> MemoryError for LazyError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            LazyError::OutOfBounds { .. } => ErrorKind::OutOfBounds,
+            LazyError::Fetch { .. } | LazyError::Resolve { .. } => ErrorKind::DeviceError,
+        }
+    }
+}
+
+impl<E> MemoryError for SwapError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            SwapError::OutOfBounds { .. } => ErrorKind::OutOfBounds,
+            SwapError::Store { .. } => ErrorKind::DeviceError,
+        }
+    }
+}
+
+impl<F: MemoryError, S: MemoryError> MemoryError for TieredError<F, S> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            TieredError::OutOfBounds { .. } => ErrorKind::OutOfBounds,
+            TieredError::Fast(err) => err.kind(),
+            TieredError::Slow(err) => err.kind(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl MemoryError for RemoteError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::DeviceError
+    }
+}
+
+impl MemoryError for ReplayError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: MemoryError> MemoryError for CoreDumpError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            CoreDumpError::Io(_) => ErrorKind::DeviceError,
+            CoreDumpError::Memory(err) => err.kind(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: MemoryError> MemoryError for ImageError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ImageError::Io(_) => ErrorKind::DeviceError,
+            ImageError::Memory(err) => err.kind(),
+        }
+    }
+}
+
+impl<E: MemoryError> MemoryError for ChecksumError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            ChecksumError::Corrupted { .. } => ErrorKind::Poisoned,
+            ChecksumError::Memory(err) => err.kind(),
+        }
+    }
+}
+
+impl<E: MemoryError> MemoryError for FaultError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            FaultError::Injected { .. } => ErrorKind::DeviceError,
+            FaultError::Memory(err) => err.kind(),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-storage")]
+impl<E> MemoryError for StorageError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            StorageError::OutOfRange { .. } => ErrorKind::OutOfBounds,
+            StorageError::Storage(_) => ErrorKind::DeviceError,
+        }
+    }
+}
+
+impl<E: MemoryError> MemoryError for DataBusError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DataBusError::Misaligned { .. } | DataBusError::TooWide { .. } => ErrorKind::Misaligned,
+            DataBusError::Memory(err) => err.kind(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: MemoryError, D: MemoryError> MemoryError for DmaError<S, D> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            DmaError::Source { error, .. } => error.kind(),
+            DmaError::Destination { error, .. } => error.kind(),
+        }
+    }
+}
+
+impl<E: MemoryError> MemoryError for PixelError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            PixelError::Outside { .. } => ErrorKind::OutOfBounds,
+            PixelError::Memory(err) => err.kind(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: MemoryError> MemoryError for PrivilegeError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            PrivilegeError::Denied { .. } => ErrorKind::PermissionDenied,
+            PrivilegeError::Memory(err) => err.kind(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: MemoryError> MemoryError for LockError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            LockError::Locked { .. } => ErrorKind::PermissionDenied,
+            LockError::Memory(err) => err.kind(),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::shm::protect;
+use crate::{AccessKind, Protection, SharedMemory};
+use core::ops::Range;
+use std::io;
+use std::vec::Vec;
+
+/// A fault inside a [`FastmemArena`], as returned by [`FastmemArena::classify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FastmemFault {
+    /// The guest address that was accessed.
+    pub guest_addr: usize,
+    /// The index of the region that contains the address, or `None` if the address is
+    /// not mapped.
+    pub region: Option<usize>,
+    /// The kind of the access, if it is known by the caller.
+    pub access: Option<AccessKind>,
+}
+
+/// A large, reserved range of host addresses, into which guest regions are mapped at
+/// their guest addresses, so a JIT can access guest address `addr` at `base + addr`.
+///
+/// The arena is surrounded by guard pages, and every address that is not mapped traps
+/// with a `SIGSEGV`, like the pages whose [`Protection`] does not allow the access. The
+/// signal handler of the JIT uses [`classify`](Self::classify) to turn the faulting host
+/// address back into a guest address, and then handles the access on a slow path.
+#[derive(Debug)]
+pub struct FastmemArena {
+    base: *mut u8,
+    size: usize,
+    guard: usize,
+    regions: Vec<Range<usize>>,
+}
+
+// Safety: the reservation is owned by the arena, which never accesses it.
+unsafe impl Send for FastmemArena {}
+// Safety: shared references only allow reading the regions.
+unsafe impl Sync for FastmemArena {}
+
+impl FastmemArena {
+    /// Reserves an arena for `size` bytes of guest addresses, with `guard` bytes of
+    /// inaccessible addresses in front of and behind it.
+    ///
+    /// The reservation does not use any memory, until regions are mapped into it.
+    ///
+    /// Returns `Err(x)` if the addresses could not be reserved.
+    pub fn new(size: usize, guard: usize) -> io::Result<Self> {
+        let page_size = page_size();
+        let size = align_up(size, page_size)?;
+        let guard = align_up(guard, page_size)?;
+        let total = guard
+            .checked_mul(2)
+            .and_then(|guards| guards.checked_add(size))
+            .filter(|&total| total > 0)
+            .ok_or_else(|| invalid("arena is too large"))?;
+
+        // Safety: a new, inaccessible mapping is created, which does not alias any memory.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                total,
+                libc::PROT_NONE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            // Safety: the guard is inside the reservation.
+            base: unsafe { (ptr as *mut u8).add(guard) },
+            size,
+            guard,
+            regions: Vec::new(),
+        })
+    }
+
+    /// Returns the host address of guest address zero.
+    pub fn base(&self) -> *mut u8 {
+        self.base
+    }
+
+    /// Returns the number of guest addresses inside this arena.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the guest address ranges of the mapped regions, where the index of a
+    /// region is the value that was returned by [`map`](Self::map).
+    pub fn regions(&self) -> &[Range<usize>] {
+        &self.regions
+    }
+
+    /// Maps the bytes of `mem` at the guest address `guest_addr` with the given
+    /// protection, and returns the index of the new region.
+    ///
+    /// The region shares its bytes with `mem`, so accesses through the arena and
+    /// through the memory see the same data. It stays mapped until the arena is dropped.
+    ///
+    /// Returns `Err(x)` if `guest_addr` is not page aligned, the region does not fit into
+    /// the arena or overlaps another region, or the memory could not be mapped.
+    pub fn map(
+        &mut self,
+        guest_addr: usize,
+        mem: &SharedMemory,
+        prot: Protection,
+    ) -> io::Result<usize> {
+        let page_size = page_size();
+        let len = align_up(mem.len().max(1), page_size)?;
+        let range = match guest_addr.checked_add(len) {
+            Some(end) if guest_addr.is_multiple_of(page_size) && end <= self.size => {
+                guest_addr..end
+            }
+            _ => return Err(invalid("region does not fit into the arena")),
+        };
+        if self
+            .regions
+            .iter()
+            .any(|other| range.start < other.end && other.start < range.end)
+        {
+            return Err(invalid("region overlaps another region"));
+        }
+
+        // Safety: the range is inside the reservation, which is owned by the arena.
+        let addr = unsafe { self.base.add(guest_addr) };
+        mem.map_data(addr, libc::MAP_FIXED, prot)?;
+        self.regions.push(range);
+        Ok(self.regions.len() - 1)
+    }
+
+    /// Changes the protection of every page that overlaps the given guest range.
+    ///
+    /// Returns `Err(x)` if the range is not inside a single region, or the protection
+    /// could not be changed.
+    pub fn protect(&mut self, range: Range<usize>, prot: Protection) -> io::Result<()> {
+        let inside = self
+            .regions
+            .iter()
+            .any(|region| region.start <= range.start && range.end <= region.end);
+        if range.start > range.end || !inside {
+            return Err(invalid("range is not inside a region"));
+        }
+        if range.start == range.end {
+            return Ok(());
+        }
+        protect(self.base, range, prot)
+    }
+
+    /// Turns the host address of a fault into the guest address that was accessed.
+    ///
+    /// The kind of the access can not be derived from the address, so it is passed
+    /// through from the caller, which may decode it from the signal context.
+    ///
+    /// Returns `None` if the address is not inside the arena, e.g. inside the guard
+    /// pages. This method neither allocates nor locks, so it can be called from a
+    /// signal handler.
+    pub fn classify(&self, host_addr: usize, access: Option<AccessKind>) -> Option<FastmemFault> {
+        let guest_addr = host_addr.checked_sub(self.base as usize)?;
+        if guest_addr >= self.size {
+            return None;
+        }
+        let region = self
+            .regions
+            .iter()
+            .position(|region| region.contains(&guest_addr));
+        Some(FastmemFault {
+            guest_addr,
+            region,
+            access,
+        })
+    }
+}
+
+impl Drop for FastmemArena {
+    fn drop(&mut self) {
+        // Safety: the reservation was created by `new`, and the regions are replaced by
+        // the unmapping as well.
+        unsafe {
+            libc::munmap(
+                self.base.sub(self.guard) as *mut libc::c_void,
+                self.size + 2 * self.guard,
+            )
+        };
+    }
+}
+
+fn page_size() -> usize {
+    // Safety: `sysconf` has no preconditions.
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
+
+fn align_up(len: usize, page_size: usize) -> io::Result<usize> {
+    len.checked_next_multiple_of(page_size)
+        .ok_or_else(|| invalid("size is too large"))
+}
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
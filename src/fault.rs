@@ -0,0 +1,149 @@
+use crate::{FaultError, MemoryStorage};
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// A wrapper that makes accesses to the inner memory fail on demand, to test how the
+/// exception handlers and drivers of a guest deal with bus errors.
+///
+/// Accesses fail if they touch one of the fault ranges, or randomly with a configured
+/// rate. Failed accesses are not forwarded to the inner memory.
+pub struct FaultMemory<M> {
+    inner: M,
+    ranges: Vec<Range<usize>>,
+    one_in: u32,
+    rng: Cell<u64>,
+    injected: Cell<usize>,
+}
+
+impl<M: MemoryStorage> FaultMemory<M> {
+    /// Creates a new `FaultMemory` that does not inject any faults yet.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            ranges: Vec::new(),
+            one_in: 0,
+            rng: Cell::new(0),
+            injected: Cell::new(0),
+        }
+    }
+
+    /// Makes every access that touches the given range fail.
+    pub fn add_range(&mut self, range: Range<usize>) {
+        self.ranges.push(range);
+    }
+
+    /// Removes every fault range.
+    pub fn clear_ranges(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// Makes every access fail with a chance of one in `one_in`, where zero disables
+    /// the random faults.
+    ///
+    /// The faults are chosen by a pseudo random generator that is initialized using
+    /// `seed`, so a failing run can be reproduced.
+    pub fn set_random(&mut self, one_in: u32, seed: u64) {
+        self.one_in = one_in;
+        // The generator never leaves the zero state, so avoid it.
+        self.rng.set(seed | 1);
+    }
+
+    /// Returns the number of faults that were injected so far.
+    pub fn injected(&self) -> usize {
+        self.injected.get()
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `FaultMemory` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Returns an error if the access of `len` bytes at `addr` should fail.
+    fn check(&self, addr: usize, len: usize) -> Result<(), FaultError<M::Error>> {
+        let end = addr.saturating_add(len.max(1));
+        let hit = self
+            .ranges
+            .iter()
+            .any(|range| addr < range.end && range.start < end);
+        if hit || (self.one_in != 0 && self.next_random().is_multiple_of(self.one_in as u64)) {
+            self.injected.set(self.injected.get() + 1);
+            trace_event!(debug, addr, len, "injected fault");
+            return Err(FaultError::Injected { addr });
+        }
+        Ok(())
+    }
+
+    /// Advances the xorshift generator and returns its next value.
+    fn next_random(&self) -> u64 {
+        let mut x = self.rng.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng.set(x);
+        x
+    }
+}
+
+impl<M: MemoryStorage> MemoryStorage for FaultMemory<M> {
+    type Error = FaultError<M::Error>;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.check(addr, 1)?;
+        self.inner.try_read_byte(addr).map_err(FaultError::Memory)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.check(addr, 1)?;
+        self.inner
+            .try_write_byte(addr, byte)
+            .map_err(FaultError::Memory)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+        self.inner
+            .try_read_into(addr, buf)
+            .map_err(FaultError::Memory)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+        self.inner
+            .try_write_from(addr, buf)
+            .map_err(FaultError::Memory)
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M> core::fmt::Debug for FaultMemory<M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FaultMemory")
+            .field("ranges", &self.ranges)
+            .field("one_in", &self.one_in)
+            .field("injected", &self.injected.get())
+            .finish()
+    }
+}
@@ -0,0 +1,190 @@
+//! A C ABI for memories that are managed by Rust, for C and C++ frontends and plugins.
+//!
+//! Any memory is turned into an opaque [`MemHandle`] using [`into_handle`], and passed to
+//! C code, which accesses it using the `mem_storage_*` functions. Every function returns
+//! [`MEM_STORAGE_OK`] on success, or one of the negative error codes.
+//!
+//! ```c
+//! typedef struct MemHandle MemHandle;
+//!
+//! MemHandle *mem_storage_new(size_t size);
+//! void mem_storage_free(MemHandle *mem);
+//! int mem_storage_read(const MemHandle *mem, size_t addr, uint8_t *buf, size_t len);
+//! int mem_storage_write(MemHandle *mem, size_t addr, const uint8_t *buf, size_t len);
+//! int mem_storage_fill(MemHandle *mem, size_t start, size_t end, uint8_t byte);
+//! int mem_storage_copy(MemHandle *mem, size_t start, size_t end, size_t dest);
+//! ```
+
+use crate::{CellMemory, MemoryStorage};
+use alloc::boxed::Box;
+use core::ops::Range;
+
+/// The access succeeded.
+pub const MEM_STORAGE_OK: i32 = 0;
+/// A required pointer was null.
+pub const MEM_STORAGE_ERR_NULL: i32 = -1;
+/// The memory failed to access the range.
+pub const MEM_STORAGE_ERR_ACCESS: i32 = -2;
+/// The start of a range is after its end.
+pub const MEM_STORAGE_ERR_RANGE: i32 = -3;
+
+/// An object safe version of [`MemoryStorage`], which hides the error type.
+trait DynMemory {
+    fn read_into(&self, addr: usize, buf: &mut [u8]) -> bool;
+    fn write_from(&mut self, addr: usize, buf: &[u8]) -> bool;
+    fn fill(&mut self, range: Range<usize>, byte: u8) -> bool;
+    fn copy_within(&mut self, src: Range<usize>, dest: usize) -> bool;
+}
+
+impl<M: MemoryStorage> DynMemory for M {
+    fn read_into(&self, addr: usize, buf: &mut [u8]) -> bool {
+        self.try_read_into(addr, buf).is_ok()
+    }
+
+    fn write_from(&mut self, addr: usize, buf: &[u8]) -> bool {
+        self.try_write_from(addr, buf).is_ok()
+    }
+
+    fn fill(&mut self, range: Range<usize>, byte: u8) -> bool {
+        self.try_fill(range, byte).is_ok()
+    }
+
+    fn copy_within(&mut self, src: Range<usize>, dest: usize) -> bool {
+        self.try_copy_within(src, dest).is_ok()
+    }
+}
+
+/// An opaque handle to a memory, which is passed to C code as a pointer.
+pub struct MemHandle {
+    mem: Box<dyn DynMemory>,
+}
+
+/// Moves the memory to the heap and returns a handle to it, which must be released
+/// using `mem_storage_free`.
+pub fn into_handle<M: MemoryStorage + 'static>(mem: M) -> *mut MemHandle {
+    Box::into_raw(Box::new(MemHandle { mem: Box::new(mem) }))
+}
+
+/// Creates a new, zeroed [`CellMemory`] with `size` bytes and returns a handle to it.
+#[no_mangle]
+pub extern "C" fn mem_storage_new(size: usize) -> *mut MemHandle {
+    into_handle(CellMemory::new(size))
+}
+
+/// Releases a handle that was created by [`into_handle`] or [`mem_storage_new`].
+///
+/// # Safety
+///
+/// `mem` must be null or a handle that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn mem_storage_free(mem: *mut MemHandle) {
+    if !mem.is_null() {
+        drop(Box::from_raw(mem));
+    }
+}
+
+/// Reads `len` bytes starting at `addr` into `buf`.
+///
+/// # Safety
+///
+/// `mem` must be a valid handle and `buf` must be valid for writing `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mem_storage_read(
+    mem: *const MemHandle,
+    addr: usize,
+    buf: *mut u8,
+    len: usize,
+) -> i32 {
+    if mem.is_null() || (buf.is_null() && len > 0) {
+        return MEM_STORAGE_ERR_NULL;
+    }
+    let buf = slice_mut(buf, len);
+    status((*mem).mem.read_into(addr, buf))
+}
+
+/// Writes `len` bytes from `buf` starting at `addr`.
+///
+/// # Safety
+///
+/// `mem` must be a valid handle and `buf` must be valid for reading `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn mem_storage_write(
+    mem: *mut MemHandle,
+    addr: usize,
+    buf: *const u8,
+    len: usize,
+) -> i32 {
+    if mem.is_null() || (buf.is_null() && len > 0) {
+        return MEM_STORAGE_ERR_NULL;
+    }
+    let buf = slice(buf, len);
+    status((*mem).mem.write_from(addr, buf))
+}
+
+/// Sets every byte inside `start..end` to `byte`.
+///
+/// # Safety
+///
+/// `mem` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mem_storage_fill(
+    mem: *mut MemHandle,
+    start: usize,
+    end: usize,
+    byte: u8,
+) -> i32 {
+    if mem.is_null() {
+        return MEM_STORAGE_ERR_NULL;
+    }
+    if start > end {
+        return MEM_STORAGE_ERR_RANGE;
+    }
+    status((*mem).mem.fill(start..end, byte))
+}
+
+/// Copies the bytes inside `start..end` to `dest`. The ranges may overlap.
+///
+/// # Safety
+///
+/// `mem` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mem_storage_copy(
+    mem: *mut MemHandle,
+    start: usize,
+    end: usize,
+    dest: usize,
+) -> i32 {
+    if mem.is_null() {
+        return MEM_STORAGE_ERR_NULL;
+    }
+    if start > end {
+        return MEM_STORAGE_ERR_RANGE;
+    }
+    status((*mem).mem.copy_within(start..end, dest))
+}
+
+fn status(ok: bool) -> i32 {
+    if ok {
+        MEM_STORAGE_OK
+    } else {
+        MEM_STORAGE_ERR_ACCESS
+    }
+}
+
+/// Creates a slice from a pointer that may be null if `len` is zero.
+unsafe fn slice<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    }
+}
+
+/// Creates a mutable slice from a pointer that may be null if `len` is zero.
+unsafe fn slice_mut<'a>(ptr: *mut u8, len: usize) -> &'a mut [u8] {
+    if len == 0 {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(ptr, len)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{MemoryStorage, OverflowPolicy, RingRegion, Value};
+use core::ops::Range;
+
+/// The size of the header that stores the head and tail indices of a [`MemFifo`].
+const HEADER_SIZE: usize = 8;
+
+/// A FIFO of `V`s whose entries and indices are both stored inside a memory region,
+/// like the hardware FIFOs behind UART, SPI or audio peripherals.
+///
+/// The region starts with the head (next entry to pop) and tail (next entry to push)
+/// indices, stored as little endian `u32`s, followed by the entries. Apart from this
+/// layout, the FIFO behaves exactly like a [`RingRegion`], so one entry is always kept
+/// free, and pushing to a full FIFO drops a value according to the [`OverflowPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemFifo<V> {
+    ring: RingRegion<V>,
+}
+
+impl<V: Value> MemFifo<V> {
+    /// Creates a new `MemFifo` that is stored inside `region`.
+    ///
+    /// Panics if `region` can not hold the indices and at least two entries.
+    pub fn new(region: Range<usize>) -> Self {
+        let data = region.start.saturating_add(HEADER_SIZE).min(region.end)..region.end;
+        Self {
+            ring: RingRegion::new(data, region.start, region.start.saturating_add(4)),
+        }
+    }
+
+    /// Sets what happens if a value is pushed to a full FIFO.
+    pub fn overflow(mut self, policy: OverflowPolicy) -> Self {
+        self.ring = self.ring.overflow(policy);
+        self
+    }
+
+    /// Returns the maximum number of values that can be stored inside this FIFO.
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    /// Tries to reset the head and tail indices, which makes the FIFO empty.
+    ///
+    /// Returns `Err(x)` if the method failed to write the indices.
+    pub fn try_reset<M: MemoryStorage>(&self, mem: &mut M) -> Result<(), M::Error> {
+        self.ring.try_reset(mem)
+    }
+
+    /// Tries to get the number of values that are currently stored inside the FIFO.
+    ///
+    /// Returns `Err(x)` if the method failed to read the indices.
+    pub fn try_len<M: MemoryStorage>(&self, mem: &M) -> Result<usize, M::Error> {
+        self.ring.try_len(mem)
+    }
+
+    /// Tries to push a value to the tail of the FIFO.
+    ///
+    /// Returns `Ok(false)` if the FIFO was full, and a value was dropped according to
+    /// the overflow policy, and `Err(x)` if the method failed to access the memory.
+    pub fn try_push<M: MemoryStorage>(&self, mem: &mut M, val: V) -> Result<bool, M::Error> {
+        self.ring.try_push(mem, val)
+    }
+
+    /// Pushes a value to the tail of the FIFO.
+    ///
+    /// Returns `false` if the FIFO was full, and a value was dropped according to the
+    /// overflow policy.
+    /// Panics if the method failed to access the memory.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_push` instead"))]
+    pub fn push<M: MemoryStorage>(&self, mem: &mut M, val: V) -> bool {
+        or_panic!(
+            self.try_push(mem, val),
+            "push to FIFO at {:#x}",
+            self.ring.head_addr()
+        )
+    }
+
+    /// Tries to pop a value from the head of the FIFO.
+    ///
+    /// Returns `Ok(None)` if the FIFO is empty, and `Err(x)` if the method failed to
+    /// access the memory.
+    pub fn try_pop<M: MemoryStorage>(&self, mem: &mut M) -> Result<Option<V>, M::Error> {
+        self.ring.try_pop(mem)
+    }
+
+    /// Pops a value from the head of the FIFO.
+    ///
+    /// Returns `None` if the FIFO is empty.
+    /// Panics if the method failed to access the memory.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_pop` instead"))]
+    pub fn pop<M: MemoryStorage>(&self, mem: &mut M) -> Option<V> {
+        or_panic!(
+            self.try_pop(mem),
+            "pop from FIFO at {:#x}",
+            self.ring.head_addr()
+        )
+    }
+
+    /// Tries to read the value at the head of the FIFO, without removing it.
+    ///
+    /// Returns `Ok(None)` if the FIFO is empty, and `Err(x)` if the method failed to
+    /// access the memory.
+    pub fn try_peek<M: MemoryStorage>(&self, mem: &M) -> Result<Option<V>, M::Error> {
+        self.ring.try_peek(mem)
+    }
+
+    /// Reads the value at the head of the FIFO, without removing it.
+    ///
+    /// Returns `None` if the FIFO is empty.
+    /// Panics if the method failed to access the memory.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_peek` instead"))]
+    pub fn peek<M: MemoryStorage>(&self, mem: &M) -> Option<V> {
+        or_panic!(
+            self.try_peek(mem),
+            "peek into FIFO at {:#x}",
+            self.ring.head_addr()
+        )
+    }
+}
@@ -0,0 +1,48 @@
+/// Describes the initial content of a newly allocated memory.
+///
+/// Filling memory with a recognizable pattern instead of zeros makes reads of
+/// never-initialized guest memory obvious in traces and hexdumps.
+///
+/// The policy is accepted by [`CellMemory::with_fill`](crate::CellMemory::with_fill),
+/// [`AlignedMemory::with_fill`](crate::AlignedMemory::with_fill) and
+/// [`SparseMemory::fill_policy`](crate::SparseMemory::fill_policy). The flash and EEPROM
+/// memories always start out erased, like the real hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillPolicy {
+    /// Every byte is zero.
+    #[default]
+    Zero,
+    /// Every byte is set to the given value, e.g. `0xCC`.
+    Byte(u8),
+    /// The given bytes are repeated over the whole memory, starting at address zero.
+    ///
+    /// For example, `Pattern(&[0xDE, 0xAD, 0xBE, 0xEF])` makes every aligned `u32`
+    /// read as `0xDEADBEEF` in big endian format.
+    Pattern(&'static [u8]),
+}
+
+impl FillPolicy {
+    /// Fills the given buffer, which is assumed to start at address zero.
+    ///
+    /// An empty pattern leaves the buffer untouched.
+    pub fn fill(&self, buf: &mut [u8]) {
+        self.fill_at(0, buf)
+    }
+
+    /// Fills the given buffer, which starts at address `addr`, so a pattern continues
+    /// where the previous bytes left off.
+    ///
+    /// An empty pattern leaves the buffer untouched.
+    pub fn fill_at(&self, addr: u64, buf: &mut [u8]) {
+        match *self {
+            FillPolicy::Zero => buf.fill(0),
+            FillPolicy::Byte(byte) => buf.fill(byte),
+            FillPolicy::Pattern([]) => {}
+            FillPolicy::Pattern(pattern) => {
+                let start = (addr % pattern.len() as u64) as usize;
+                let pattern = pattern.iter().cycle().skip(start);
+                buf.iter_mut().zip(pattern).for_each(|(byte, &x)| *byte = x);
+            }
+        }
+    }
+}
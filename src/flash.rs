@@ -0,0 +1,226 @@
+use crate::{FlashError, MemoryStorage, ReadRef, WearReport};
+use alloc::{boxed::Box, vec};
+use core::ops::Range;
+
+/// A heap allocated memory that models the semantics of NOR flash.
+///
+/// Writes can only clear bits, so a byte has to be erased before a bit can be set again.
+/// Erasing is only possible for whole blocks of `BLOCK_SIZE` bytes, and sets every byte
+/// of the block to `0xFF`. Writes that would set a bit fail with [`FlashError::NotErased`]
+/// without modifying the flash, so firmware that manages flash can be tested in emulation.
+///
+/// The number of erases and writes is counted per block, and can be summarized using
+/// [`erase_report`](Self::erase_report) to verify wear-leveling.
+pub struct NorFlashMemory<const BLOCK_SIZE: usize = 4096> {
+    data: Box<[u8]>,
+    erases: Box<[u32]>,
+    writes: Box<[u32]>,
+}
+
+impl<const BLOCK_SIZE: usize> NorFlashMemory<BLOCK_SIZE> {
+    /// Creates a new, fully erased `NorFlashMemory` with the given number of blocks.
+    pub fn new(blocks: usize) -> Self {
+        const { assert!(BLOCK_SIZE > 0, "block size must be non-zero") };
+        Self {
+            data: vec![0xFF; blocks * BLOCK_SIZE].into_boxed_slice(),
+            erases: vec![0; blocks].into_boxed_slice(),
+            writes: vec![0; blocks].into_boxed_slice(),
+        }
+    }
+
+    /// Returns the number of bytes inside this flash.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if this flash has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of erase blocks inside this flash.
+    pub fn blocks(&self) -> usize {
+        self.data.len() / BLOCK_SIZE
+    }
+
+    /// Returns the content of this flash.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns how often every block was erased.
+    pub fn erase_counts(&self) -> &[u32] {
+        &self.erases
+    }
+
+    /// Returns how often every block was written, where a write that spans multiple
+    /// blocks counts once for every block.
+    pub fn write_counts(&self) -> &[u32] {
+        &self.writes
+    }
+
+    /// Summarizes the erase counts of all blocks.
+    pub fn erase_report(&self) -> WearReport {
+        WearReport::from_counts(self.erases.iter().copied())
+    }
+
+    /// Tries to erase the block with the given index.
+    ///
+    /// Returns `Err(x)` if the block is out of bounds.
+    pub fn try_erase_block(&mut self, block: usize) -> Result<(), FlashError> {
+        let start = block.saturating_mul(BLOCK_SIZE);
+        self.try_erase(start..start.saturating_add(BLOCK_SIZE))
+    }
+
+    /// Erases the block with the given index.
+    ///
+    /// Panics if the block is out of bounds.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_erase_block` instead")
+    )]
+    pub fn erase_block(&mut self, block: usize) {
+        or_panic!(
+            self.try_erase_block(block),
+            "erase flash block {} at {:#x}",
+            block,
+            block.saturating_mul(BLOCK_SIZE)
+        )
+    }
+
+    /// Tries to erase every block inside the given range.
+    ///
+    /// Returns `Err(x)` if the range does not start and end at a block boundary, or if
+    /// it is out of bounds.
+    pub fn try_erase(&mut self, range: Range<usize>) -> Result<(), FlashError> {
+        for addr in [range.start, range.end] {
+            if addr % BLOCK_SIZE != 0 {
+                return Err(FlashError::NotAligned { addr });
+            }
+        }
+
+        let len = self.data.len();
+        let blocks = self
+            .data
+            .get_mut(range.clone())
+            .ok_or(FlashError::OutOfBounds {
+                addr: range.start.max(len),
+            })?;
+        blocks.fill(0xFF);
+        count::<BLOCK_SIZE>(&mut self.erases, range);
+        Ok(())
+    }
+
+    /// Erases every block inside the given range.
+    ///
+    /// Panics if the range does not start and end at a block boundary, or if it is
+    /// out of bounds.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_erase` instead"))]
+    pub fn erase(&mut self, range: Range<usize>) {
+        let (start, end) = (range.start, range.end);
+        or_panic!(
+            self.try_erase(range),
+            "erase flash {:#x}..{:#x}",
+            start,
+            end
+        )
+    }
+
+    fn range(&self, addr: usize, len: usize) -> Result<Range<usize>, FlashError> {
+        match addr.checked_add(len) {
+            Some(end) if end <= self.data.len() => Ok(addr..end),
+            _ => Err(FlashError::OutOfBounds {
+                addr: addr.max(self.data.len()),
+            }),
+        }
+    }
+}
+
+/// Increments the counters of every block that overlaps the given range.
+fn count<const BLOCK_SIZE: usize>(counters: &mut [u32], range: Range<usize>) {
+    if range.is_empty() {
+        return;
+    }
+
+    let blocks = range.start / BLOCK_SIZE..(range.end - 1) / BLOCK_SIZE + 1;
+    for counter in &mut counters[blocks] {
+        *counter = counter.saturating_add(1);
+    }
+}
+
+impl<const BLOCK_SIZE: usize> MemoryStorage for NorFlashMemory<BLOCK_SIZE> {
+    type Error = FlashError;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.data
+            .get(addr)
+            .copied()
+            .ok_or(FlashError::OutOfBounds { addr })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let range = self.range(addr, buf.len())?;
+        buf.copy_from_slice(&self.data[range]);
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let range = self.range(addr, buf.len())?;
+        let dst = &mut self.data[range];
+
+        // Check the whole write first, so a failed write does not modify the flash.
+        let set_bit = dst.iter().zip(buf).position(|(&old, &new)| new & !old != 0);
+        if let Some(offset) = set_bit {
+            return Err(FlashError::NotErased {
+                addr: addr + offset,
+            });
+        }
+
+        dst.copy_from_slice(buf);
+        count::<BLOCK_SIZE>(&mut self.writes, addr..addr + buf.len());
+        Ok(())
+    }
+}
+
+impl<const BLOCK_SIZE: usize> ReadRef for NorFlashMemory<BLOCK_SIZE> {
+    type Bytes<'a> = &'a [u8];
+
+    fn try_read_ref(&self, range: Range<usize>) -> Result<Self::Bytes<'_>, Self::Error> {
+        let range = self.range(range.start, range.len())?;
+        Ok(&self.data[range])
+    }
+}
+
+impl<const BLOCK_SIZE: usize> core::fmt::Debug for NorFlashMemory<BLOCK_SIZE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NorFlashMemory")
+            .field("len", &self.data.len())
+            .field("block_size", &BLOCK_SIZE)
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const BLOCK_SIZE: usize> zeroize::Zeroize for NorFlashMemory<BLOCK_SIZE> {
+    fn zeroize(&mut self) {
+        self.data.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const BLOCK_SIZE: usize> Drop for NorFlashMemory<BLOCK_SIZE> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const BLOCK_SIZE: usize> zeroize::ZeroizeOnDrop for NorFlashMemory<BLOCK_SIZE> {}
@@ -0,0 +1,215 @@
+use crate::{MemoryStorage, PixelError};
+use core::ops::Range;
+
+/// The layout of a single pixel inside a [`Framebuffer`].
+///
+/// Pixels are stored as unsigned integers that are [`bytes_per_pixel`](Self::bytes_per_pixel)
+/// wide, with the channels listed from the most to the least significant bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PixelFormat {
+    /// An 8-bit index into a palette.
+    Indexed8,
+    /// 16-bit pixels with 5 bits of red, 6 bits of green and 5 bits of blue.
+    Rgb565,
+    /// 16-bit pixels with an unused bit and 5 bits per channel.
+    Xrgb1555,
+    /// 24-bit pixels with 8 bits per channel.
+    Rgb888,
+    /// 32-bit pixels with an unused byte and 8 bits per channel.
+    Xrgb8888,
+    /// 32-bit pixels with 8 bits per channel, and 8 bits of alpha.
+    Argb8888,
+}
+
+impl PixelFormat {
+    /// Returns the number of bytes that a single pixel occupies.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Indexed8 => 1,
+            PixelFormat::Rgb565 | PixelFormat::Xrgb1555 => 2,
+            PixelFormat::Rgb888 => 3,
+            PixelFormat::Xrgb8888 | PixelFormat::Argb8888 => 4,
+        }
+    }
+}
+
+/// A view of the pixels of a framebuffer that is stored inside a memory, like the
+/// VRAM of an emulated video device.
+///
+/// Row `y` starts at `base + y * stride`, and contains `width` pixels that are stored
+/// next to each other. Pixels are little endian integers, unless the framebuffer was
+/// configured using [`big_endian`](Self::big_endian).
+///
+/// Like [`RingRegion`](crate::RingRegion), the framebuffer only describes the layout,
+/// and the memory is passed to every access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Framebuffer {
+    /// The address of the first pixel.
+    pub base: usize,
+    /// The layout of a single pixel.
+    pub format: PixelFormat,
+    /// The number of pixels in a row.
+    pub width: usize,
+    /// The number of rows.
+    pub height: usize,
+    /// The distance in bytes between the start of two rows.
+    pub stride: usize,
+    /// If `true`, pixels are stored in big endian format.
+    pub big_endian: bool,
+}
+
+impl Framebuffer {
+    /// Creates a new `Framebuffer` at `base`, whose rows are stored without any
+    /// padding in between.
+    pub fn new(base: usize, format: PixelFormat, width: usize, height: usize) -> Self {
+        Self {
+            base,
+            format,
+            width,
+            height,
+            stride: width.saturating_mul(format.bytes_per_pixel()),
+            big_endian: false,
+        }
+    }
+
+    /// Sets the distance in bytes between the start of two rows.
+    pub fn stride(mut self, stride: usize) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// Makes this framebuffer store pixels in big endian format.
+    pub fn big_endian(mut self) -> Self {
+        self.big_endian = true;
+        self
+    }
+
+    /// Returns the address of the pixel at the given coordinates.
+    ///
+    /// Returns `None` if the coordinates are outside of the framebuffer, or the address
+    /// does not fit into a `usize`.
+    pub fn pixel_addr(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let row = y.checked_mul(self.stride)?;
+        let column = x.checked_mul(self.format.bytes_per_pixel())?;
+        self.base.checked_add(row)?.checked_add(column)
+    }
+
+    /// Returns the range of addresses that store the pixels of the given row.
+    ///
+    /// Returns `None` if the row is outside of the framebuffer, or one of its addresses
+    /// does not fit into a `usize`.
+    pub fn row_range(&self, y: usize) -> Option<Range<usize>> {
+        let start = self.pixel_addr(0, y)?;
+        let len = self.width.checked_mul(self.format.bytes_per_pixel())?;
+        Some(start..start.checked_add(len)?)
+    }
+
+    /// Tries to read the pixel at the given coordinates.
+    ///
+    /// Returns `Err(PixelError::Outside)` if the coordinates are outside of the
+    /// framebuffer, and `Err(PixelError::Memory(x))` if the method failed to read the
+    /// pixel.
+    pub fn try_get_pixel<M>(&self, mem: &M, x: usize, y: usize) -> Result<u32, PixelError<M::Error>>
+    where
+        M: MemoryStorage + ?Sized,
+    {
+        let addr = self.pixel_addr(x, y).ok_or(PixelError::Outside { x, y })?;
+        let nbytes = self.format.bytes_per_pixel();
+        let mut buf = [0u8; 4];
+        let result = if self.big_endian {
+            mem.try_read_into(addr, &mut buf[4 - nbytes..])
+                .map(|()| u32::from_be_bytes(buf))
+        } else {
+            mem.try_read_into(addr, &mut buf[..nbytes])
+                .map(|()| u32::from_le_bytes(buf))
+        };
+        result.map_err(PixelError::Memory)
+    }
+
+    /// Reads the pixel at the given coordinates.
+    ///
+    /// Panics if the coordinates are outside of the framebuffer, or if the method failed
+    /// to read the pixel.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_get_pixel` instead"))]
+    pub fn get_pixel<M>(&self, mem: &M, x: usize, y: usize) -> u32
+    where
+        M: MemoryStorage + ?Sized,
+    {
+        or_panic!(self.try_get_pixel(mem, x, y), "read pixel ({}, {})", x, y)
+    }
+
+    /// Tries to write the pixel at the given coordinates. Bits of `pixel` that do not
+    /// fit into the pixel format are ignored.
+    ///
+    /// Returns `Err(PixelError::Outside)` if the coordinates are outside of the
+    /// framebuffer, and `Err(PixelError::Memory(x))` if the method failed to write the
+    /// pixel.
+    pub fn try_set_pixel<M>(
+        &self,
+        mem: &mut M,
+        x: usize,
+        y: usize,
+        pixel: u32,
+    ) -> Result<(), PixelError<M::Error>>
+    where
+        M: MemoryStorage + ?Sized,
+    {
+        let addr = self.pixel_addr(x, y).ok_or(PixelError::Outside { x, y })?;
+        let nbytes = self.format.bytes_per_pixel();
+        let result = if self.big_endian {
+            mem.try_write_from(addr, &pixel.to_be_bytes()[4 - nbytes..])
+        } else {
+            mem.try_write_from(addr, &pixel.to_le_bytes()[..nbytes])
+        };
+        result.map_err(PixelError::Memory)
+    }
+
+    /// Writes the pixel at the given coordinates.
+    ///
+    /// Panics if the coordinates are outside of the framebuffer, or if the method failed
+    /// to write the pixel.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_set_pixel` instead"))]
+    pub fn set_pixel<M>(&self, mem: &mut M, x: usize, y: usize, pixel: u32)
+    where
+        M: MemoryStorage + ?Sized,
+    {
+        or_panic!(
+            self.try_set_pixel(mem, x, y, pixel),
+            "write pixel ({}, {})",
+            x,
+            y
+        )
+    }
+
+    /// Returns the bytes of the given row, if the memory can be accessed as a slice.
+    ///
+    /// Returns `None` if the row is outside of the framebuffer, if the memory does not
+    /// support [`as_slice`](MemoryStorage::as_slice), or if the row is not inside the
+    /// slice. The row can still be copied out of such memories using
+    /// [`row_range`](Self::row_range).
+    pub fn row<'mem, M>(&self, mem: &'mem M, y: usize) -> Option<&'mem [u8]>
+    where
+        M: MemoryStorage + ?Sized,
+    {
+        mem.as_slice()?.get(self.row_range(y)?)
+    }
+
+    /// Returns the bytes of the given row as a mutable slice, if the memory can be
+    /// accessed as a slice.
+    ///
+    /// Returns `None` if the row is outside of the framebuffer, if the memory does not
+    /// support [`as_mut_slice`](MemoryStorage::as_mut_slice), or if the row is not
+    /// inside the slice.
+    pub fn row_mut<'mem, M>(&self, mem: &'mem mut M, y: usize) -> Option<&'mem mut [u8]>
+    where
+        M: MemoryStorage + ?Sized,
+    {
+        let range = self.row_range(y)?;
+        mem.as_mut_slice()?.get_mut(range)
+    }
+}
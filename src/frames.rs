@@ -0,0 +1,98 @@
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+
+/// A page that is resident inside a [`Frames`] table.
+pub(crate) struct Frame {
+    pub(crate) page: usize,
+    pub(crate) data: Box<[u8]>,
+    pub(crate) dirty: bool,
+    last_use: u64,
+}
+
+/// A fixed number of page frames, which are replaced in least recently used order.
+///
+/// Shared by the memories that keep a subset of their pages resident.
+pub(crate) struct Frames {
+    frames: Vec<Frame>,
+    index: BTreeMap<usize, usize>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl Frames {
+    /// Panics if `capacity` is zero.
+    pub(crate) fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "at least one page must be resident");
+        Self {
+            frames: Vec::new(),
+            index: BTreeMap::new(),
+            capacity,
+            clock: 0,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.frames.len() >= self.capacity
+    }
+
+    /// Returns the frame of the given page and marks it as used.
+    pub(crate) fn get(&mut self, page: usize) -> Option<&mut Frame> {
+        let idx = *self.index.get(&page)?;
+        self.clock += 1;
+        let frame = &mut self.frames[idx];
+        frame.last_use = self.clock;
+        Some(frame)
+    }
+
+    /// Returns the least recently used frame, which is the next one to be evicted.
+    pub(crate) fn lru(&mut self) -> Option<&mut Frame> {
+        self.frames.iter_mut().min_by_key(|frame| frame.last_use)
+    }
+
+    /// Removes the least recently used frame and returns it.
+    pub(crate) fn evict(&mut self) -> Option<Frame> {
+        let (idx, _) = self
+            .frames
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, frame)| frame.last_use)?;
+        let frame = self.frames.swap_remove(idx);
+        self.index.remove(&frame.page);
+        if let Some(moved) = self.frames.get(idx) {
+            self.index.insert(moved.page, idx);
+        }
+        Some(frame)
+    }
+
+    /// Inserts a new, clean frame for the given page and marks it as used.
+    ///
+    /// The table must not be full and must not contain the page already.
+    pub(crate) fn insert(&mut self, page: usize, data: Box<[u8]>) -> &mut Frame {
+        debug_assert!(!self.is_full() && !self.index.contains_key(&page));
+        self.clock += 1;
+        self.index.insert(page, self.frames.len());
+        self.frames.push(Frame {
+            page,
+            data,
+            dirty: false,
+            last_use: self.clock,
+        });
+        self.frames.last_mut().unwrap()
+    }
+
+    /// Returns the number of frames that were modified.
+    pub(crate) fn dirty_pages(&self) -> usize {
+        self.frames.iter().filter(|frame| frame.dirty).count()
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut Frame> {
+        self.frames.iter_mut()
+    }
+}
@@ -0,0 +1,190 @@
+use crate::remote::invalid;
+use crate::{MemoryStorage, RemoteError};
+use core::cell::RefCell;
+use core::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::vec::Vec;
+
+/// The default number of bytes that are transferred by a single packet.
+const DEFAULT_CHUNK: usize = 256;
+
+/// A memory that accesses the memory of a live target through the `m` and `M` packets of
+/// the GDB remote serial protocol, e.g. served by gdbserver or OpenOCD.
+///
+/// Accesses are split into packets of at most 256 bytes, which can be changed using
+/// [`with_chunk_size`](Self::with_chunk_size).
+/// The stream is stored inside a [`RefCell`], because reads send packets too.
+#[derive(Debug)]
+pub struct GdbMemory<T = TcpStream> {
+    stream: RefCell<T>,
+    chunk: usize,
+}
+
+impl GdbMemory<TcpStream> {
+    /// Connects to a GDB server at the given address.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream))
+    }
+}
+
+impl<T: Read + Write> GdbMemory<T> {
+    /// Creates a new `GdbMemory` that sends its packets over the given stream.
+    pub fn new(stream: T) -> Self {
+        Self {
+            stream: RefCell::new(stream),
+            chunk: DEFAULT_CHUNK,
+        }
+    }
+
+    /// Sets the maximum number of bytes that are transferred by a single packet.
+    ///
+    /// Panics if `bytes` is zero.
+    pub fn with_chunk_size(mut self, bytes: usize) -> Self {
+        assert!(bytes > 0, "chunk size must be non-zero");
+        self.chunk = bytes;
+        self
+    }
+
+    /// Consumes this `GdbMemory` and returns the stream.
+    pub fn into_inner(self) -> T {
+        self.stream.into_inner()
+    }
+}
+
+impl<T: Read + Write> MemoryStorage for GdbMemory<T> {
+    type Error = RemoteError;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let mut stream = self.stream.borrow_mut();
+        for (idx, chunk) in buf.chunks_mut(self.chunk).enumerate() {
+            let addr = addr + idx * self.chunk;
+            let packet = std::format!("m{:x},{:x}", addr, chunk.len());
+            let reply = transact(&mut *stream, &packet)?;
+            if is_error(&reply) {
+                return Err(RemoteError::Rejected { addr });
+            }
+            decode_hex(&reply, chunk)?;
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let stream = self.stream.get_mut();
+        for (idx, chunk) in buf.chunks(self.chunk).enumerate() {
+            let addr = addr + idx * self.chunk;
+            let mut packet = std::format!("M{:x},{:x}:", addr, chunk.len());
+            for byte in chunk {
+                write!(packet, "{:02x}", byte).unwrap();
+            }
+
+            let reply = transact(stream, &packet)?;
+            if reply != b"OK" {
+                return Err(RemoteError::Rejected { addr });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_error(reply: &[u8]) -> bool {
+    reply.len() == 3 && reply[0] == b'E'
+}
+
+/// Sends a packet, waits for it to be acknowledged, and returns the decoded reply.
+fn transact<T: Read + Write>(stream: &mut T, data: &str) -> io::Result<Vec<u8>> {
+    let checksum = data.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+    let packet = std::format!("${}#{:02x}", data, checksum);
+
+    loop {
+        stream.write_all(packet.as_bytes())?;
+        stream.flush()?;
+        match read_byte(stream)? {
+            b'+' => break,
+            b'-' => continue,
+            _ => return Err(invalid("expected an acknowledgement")),
+        }
+    }
+
+    loop {
+        if let Some(reply) = read_packet(stream)? {
+            stream.write_all(b"+")?;
+            stream.flush()?;
+            return Ok(reply);
+        }
+        stream.write_all(b"-")?;
+        stream.flush()?;
+    }
+}
+
+/// Reads the next packet, and returns `None` if its checksum does not match.
+fn read_packet<T: Read>(stream: &mut T) -> io::Result<Option<Vec<u8>>> {
+    while read_byte(stream)? != b'$' {}
+
+    let mut raw = Vec::new();
+    loop {
+        match read_byte(stream)? {
+            b'#' => break,
+            byte => raw.push(byte),
+        }
+    }
+
+    let mut checksum = [0u8; 1];
+    let mut digits = [0u8; 2];
+    stream.read_exact(&mut digits)?;
+    decode_hex(&digits, &mut checksum).map_err(|_| invalid("invalid checksum"))?;
+    let sum = raw.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+    if sum != checksum[0] {
+        return Ok(None);
+    }
+
+    // Expand the run-length encoding, where `x*n` repeats `x` another `n - 29` times.
+    let mut data = Vec::with_capacity(raw.len());
+    let mut bytes = raw.into_iter();
+    while let Some(byte) = bytes.next() {
+        if byte == b'*' {
+            let count = bytes.next().ok_or_else(|| invalid("truncated run"))?;
+            let last = *data.last().ok_or_else(|| invalid("run without a byte"))?;
+            let repeat = usize::from(count.wrapping_sub(29));
+            data.extend(core::iter::repeat_n(last, repeat));
+        } else {
+            data.push(byte);
+        }
+    }
+    Ok(Some(data))
+}
+
+fn read_byte<T: Read>(stream: &mut T) -> io::Result<u8> {
+    let mut byte = [0u8];
+    stream.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn decode_hex(hex: &[u8], out: &mut [u8]) -> io::Result<()> {
+    if hex.len() != out.len() * 2 {
+        return Err(invalid("unexpected reply length"));
+    }
+
+    let digit = |c: u8| match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(invalid("invalid hex digit")),
+    };
+    for (byte, pair) in out.iter_mut().zip(hex.chunks(2)) {
+        *byte = digit(pair[0])? << 4 | digit(pair[1])?;
+    }
+    Ok(())
+}
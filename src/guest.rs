@@ -0,0 +1,328 @@
+use crate::{ContiguousMemory, GuestMemoryError, MemoryStorage, MemoryUsage, ReadRef, ReportUsage};
+use alloc::{borrow::Cow, vec::Vec};
+use core::ops::Range;
+
+/// A guest-physical address space that is backed by host memory, like the memory model
+/// of a virtual machine monitor.
+///
+/// In contrast to [`MemoryStorage`], which copies the bytes, a `GuestMemory` translates
+/// a guest range into a host slice, which can be handed to devices or the hypervisor.
+/// A range can only be translated if it lies inside a single region, because the regions
+/// are not contiguous in host memory.
+pub trait GuestMemory {
+    /// Translates `len` bytes starting at the guest address `addr` into a host slice.
+    ///
+    /// Returns `Err(x)` if a byte of the range is not mapped, or if the range crosses
+    /// the end of a region.
+    fn translate(&self, addr: usize, len: usize) -> Result<&[u8], GuestMemoryError>;
+
+    /// Translates `len` bytes starting at the guest address `addr` into a mutable host slice.
+    ///
+    /// Returns `Err(x)` if a byte of the range is not mapped, or if the range crosses
+    /// the end of a region.
+    fn translate_mut(&mut self, addr: usize, len: usize) -> Result<&mut [u8], GuestMemoryError>;
+}
+
+/// A contiguous block of host memory that is mapped at a guest address.
+#[derive(Debug, Clone)]
+pub struct GuestRegion<M> {
+    base: usize,
+    mem: M,
+    name: Option<&'static str>,
+}
+
+impl<M: ContiguousMemory> GuestRegion<M> {
+    /// Creates a new region that maps the bytes of `mem` starting at the guest address `base`.
+    pub fn new(base: usize, mem: M) -> Self {
+        Self {
+            base,
+            mem,
+            name: None,
+        }
+    }
+
+    /// Gives this region a name, like `"ram"` or `"vga"`, that can be shown by debuggers.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Returns the name of this region, if it has one.
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Returns the first guest address of this region.
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Returns the number of bytes inside this region.
+    pub fn len(&self) -> usize {
+        self.bytes().len()
+    }
+
+    /// Returns `true` if this region has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the guest addresses that are covered by this region.
+    pub fn range(&self) -> Range<usize> {
+        self.base..self.base + self.len()
+    }
+
+    /// Returns a reference to the host memory of this region.
+    pub fn inner(&self) -> &M {
+        &self.mem
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.mem.as_bytes()
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self.mem.as_bytes_mut()
+    }
+}
+
+/// The region that contains a guest address, as returned by [`GuestMemoryMap::region_at`].
+#[derive(Debug)]
+pub struct RegionAt<'a, M> {
+    /// The index of the region inside [`GuestMemoryMap::regions`].
+    pub index: usize,
+    /// The region that contains the address.
+    pub region: &'a GuestRegion<M>,
+    /// The offset of the address from the start of the region.
+    pub offset: usize,
+}
+
+/// A guest address space that consists of multiple, non-overlapping [`GuestRegion`]s.
+///
+/// Accesses through `MemoryStorage` may span multiple adjacent regions, while
+/// [`GuestMemory::translate`] only succeeds for ranges inside a single region.
+#[derive(Debug, Clone)]
+pub struct GuestMemoryMap<M> {
+    regions: Vec<GuestRegion<M>>,
+}
+
+impl<M: ContiguousMemory> GuestMemoryMap<M> {
+    /// Creates a new address space from the given regions.
+    ///
+    /// Returns `None` if two regions overlap, or if a region ends after `usize::MAX`.
+    pub fn new(mut regions: Vec<GuestRegion<M>>) -> Option<Self> {
+        regions.sort_by_key(GuestRegion::base);
+        for region in &regions {
+            region.base.checked_add(region.len())?;
+        }
+
+        let overlaps = regions
+            .windows(2)
+            .any(|pair| pair[0].range().end > pair[1].base);
+        if overlaps {
+            return None;
+        }
+
+        #[cfg(feature = "tracing")]
+        for region in &regions {
+            tracing::debug!(
+                base = region.base,
+                len = region.len(),
+                "mapped guest region"
+            );
+        }
+        Some(Self { regions })
+    }
+
+    /// Returns all regions, sorted by their base address.
+    pub fn regions(&self) -> &[GuestRegion<M>] {
+        &self.regions
+    }
+
+    /// Returns the host location of every region, sorted by their guest base address,
+    /// so generated code can access guest memory directly.
+    ///
+    /// # Safety
+    ///
+    /// The same rules as for [`ContiguousMemory::host_region`] apply to every region.
+    /// Additionally, the pointers are invalidated if the map is modified.
+    #[cfg(not(feature = "forbid-unsafe"))]
+    pub unsafe fn host_regions(&mut self) -> Vec<crate::HostRegion> {
+        self.regions
+            .iter_mut()
+            .map(|region| crate::HostRegion {
+                base: region.base,
+                ..region.mem.host_region()
+            })
+            .collect()
+    }
+
+    /// Returns the region that contains the given guest address.
+    pub fn find_region(&self, addr: usize) -> Option<&GuestRegion<M>> {
+        self.position(addr).map(|idx| &self.regions[idx])
+    }
+
+    /// Returns the region that contains the given guest address, together with its index
+    /// and the offset of the address inside the region, e.g. to explain a faulting address.
+    pub fn region_at(&self, addr: usize) -> Option<RegionAt<'_, M>> {
+        self.position(addr).map(|index| {
+            let region = &self.regions[index];
+            RegionAt {
+                index,
+                region,
+                offset: addr - region.base,
+            }
+        })
+    }
+
+    fn position(&self, addr: usize) -> Option<usize> {
+        let idx = self.regions.partition_point(|region| region.base <= addr);
+        let idx = idx.checked_sub(1)?;
+        if self.regions[idx].range().contains(&addr) {
+            Some(idx)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the region index and the offset into the region for `len` bytes at `addr`.
+    fn locate(&self, addr: usize, len: usize) -> Result<(usize, usize), GuestMemoryError> {
+        let idx = self
+            .position(addr)
+            .ok_or(GuestMemoryError::Unmapped { addr })?;
+        let region = &self.regions[idx];
+        let offset = addr - region.base;
+        if len > region.len() - offset {
+            return Err(GuestMemoryError::CrossesRegion {
+                addr: region.range().end,
+            });
+        }
+        Ok((idx, offset))
+    }
+
+    /// Calls `f` with the region index, region offset and buffer offset of every part of
+    /// an access that may span multiple adjacent regions.
+    fn split<F>(&self, addr: usize, len: usize, mut f: F) -> Result<(), GuestMemoryError>
+    where
+        F: FnMut(usize, Range<usize>, usize),
+    {
+        let mut done = 0;
+        while done < len {
+            let cur = addr + done;
+            let idx = self
+                .position(cur)
+                .ok_or(GuestMemoryError::Unmapped { addr: cur })?;
+            let region = &self.regions[idx];
+            let offset = cur - region.base;
+            let n = (region.len() - offset).min(len - done);
+            f(idx, offset..offset + n, done);
+            done += n;
+        }
+        Ok(())
+    }
+}
+
+impl<M: ContiguousMemory> GuestMemory for GuestMemoryMap<M> {
+    fn translate(&self, addr: usize, len: usize) -> Result<&[u8], GuestMemoryError> {
+        let (idx, offset) = self.locate(addr, len)?;
+        Ok(&self.regions[idx].bytes()[offset..offset + len])
+    }
+
+    fn translate_mut(&mut self, addr: usize, len: usize) -> Result<&mut [u8], GuestMemoryError> {
+        let (idx, offset) = self.locate(addr, len)?;
+        Ok(&mut self.regions[idx].bytes_mut()[offset..offset + len])
+    }
+}
+
+impl<M: ContiguousMemory> MemoryStorage for GuestMemoryMap<M> {
+    type Error = GuestMemoryError;
+
+    /// Returns the sum of all region lengths.
+    fn size(&self) -> Option<usize> {
+        Some(self.regions.iter().map(GuestRegion::len).sum())
+    }
+
+    /// Returns the range from the base of the first region to the end of the last one,
+    /// which includes the holes between the regions.
+    fn addr_range(&self) -> Option<Range<usize>> {
+        let start = self.regions.first()?.base();
+        let end = self.regions.last()?.range().end;
+        Some(start..end)
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.translate(addr, 1).map(|bytes| bytes[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.translate_mut(addr, 1).map(|bytes| bytes[0] = byte)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        // Validate the whole range first, so a failed read does not partially fill `buf`.
+        self.split(addr, buf.len(), |_, _, _| {})?;
+        self.split(addr, buf.len(), |idx, range, done| {
+            let len = range.len();
+            buf[done..done + len].copy_from_slice(&self.regions[idx].bytes()[range]);
+        })
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        match self.translate_mut(addr, buf.len()) {
+            Ok(bytes) => {
+                bytes.copy_from_slice(buf);
+                return Ok(());
+            }
+            Err(GuestMemoryError::CrossesRegion { .. }) => {}
+            Err(err) => return Err(err),
+        }
+
+        // Only writes that span multiple regions have to collect the parts first,
+        // because the regions can not be borrowed mutably while splitting the range.
+        let mut parts = Vec::new();
+        self.split(addr, buf.len(), |idx, range, done| {
+            parts.push((idx, range, done))
+        })?;
+
+        for (idx, range, done) in parts {
+            let len = range.len();
+            self.regions[idx].bytes_mut()[range].copy_from_slice(&buf[done..done + len]);
+        }
+        Ok(())
+    }
+}
+
+impl<M: ContiguousMemory> ReadRef for GuestMemoryMap<M> {
+    type Bytes<'a>
+        = Cow<'a, [u8]>
+    where
+        Self: 'a;
+
+    /// Borrows the bytes if the range lies inside a single region, and copies them
+    /// otherwise.
+    fn try_read_ref(&self, range: Range<usize>) -> Result<Self::Bytes<'_>, Self::Error> {
+        match self.translate(range.start, range.len()) {
+            Ok(bytes) => Ok(Cow::Borrowed(bytes)),
+            Err(GuestMemoryError::CrossesRegion { .. }) => {
+                crate::read_ref::gather(self, range).map(Cow::Owned)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Sums up the usage of every region. The usage of a single region can be queried
+/// through [`GuestRegion::inner`].
+impl<M: ContiguousMemory + ReportUsage> ReportUsage for GuestMemoryMap<M> {
+    fn usage(&self) -> MemoryUsage {
+        self.regions.iter().map(|region| region.mem.usage()).fold(
+            MemoryUsage::default(),
+            |sum, usage| MemoryUsage {
+                addressable: sum.addressable + usage.addressable,
+                allocated: sum.allocated + usage.allocated,
+                resident_pages: sum.resident_pages + usage.resident_pages,
+                dirty_pages: sum.dirty_pages + usage.dirty_pages,
+            },
+        )
+    }
+}
@@ -0,0 +1,40 @@
+use core::ops::Range;
+
+/// The location of a memory inside the host address space, which allows generated code,
+/// e.g. of a JIT, to access the memory directly instead of calling through the traits.
+///
+/// Created by [`ContiguousMemory::host_region`](crate::ContiguousMemory::host_region) and
+/// `GuestMemoryMap::host_regions`. See their documentation for how long the pointer
+/// stays valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostRegion {
+    /// The address of the first byte of the region, as seen by the memory user.
+    pub base: usize,
+    /// The host pointer to the first byte of the region.
+    pub host: *mut u8,
+    /// The number of bytes inside the region.
+    pub len: usize,
+}
+
+impl HostRegion {
+    /// Returns the range of addresses that are covered by this region.
+    pub fn range(&self) -> Range<usize> {
+        self.base..self.base + self.len
+    }
+
+    /// Returns the host pointer for the given address, or `None` if the address
+    /// is not inside this region.
+    pub fn host_ptr(&self, addr: usize) -> Option<*mut u8> {
+        let offset = addr.checked_sub(self.base).filter(|&off| off < self.len)?;
+        Some(self.host.wrapping_add(offset))
+    }
+
+    /// Returns the address that belongs to the given host pointer, or `None` if the
+    /// pointer does not point into this region.
+    pub fn addr_of(&self, ptr: *const u8) -> Option<usize> {
+        let offset = (ptr as usize)
+            .checked_sub(self.host as usize)
+            .filter(|&off| off < self.len)?;
+        Some(self.base + offset)
+    }
+}
@@ -0,0 +1,122 @@
+//! Loading and dumping raw memory images through byte streams, optionally compressed
+//! using gzip or zstd.
+//!
+//! A raw image contains the bytes of a range of memory without any header, like the
+//! files that are written by `dd` or most debuggers. Compressed images are plain gzip or
+//! zstd streams of a raw image, so they can be created and inspected using the usual
+//! command line tools.
+
+use crate::{ImageError, MemoryStorage};
+use core::ops::Range;
+use std::io::{self, Read, Write};
+use std::vec;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Loads a raw image from `reader` into the memory, starting at `addr`, until the
+/// reader is exhausted.
+///
+/// Returns the number of bytes that were loaded, or `Err(x)` if the image could not be
+/// read, or the memory failed to write one of the bytes.
+pub fn load<M, R>(mem: &mut M, addr: usize, mut reader: R) -> Result<usize, ImageError<M::Error>>
+where
+    M: MemoryStorage + ?Sized,
+    R: Read,
+{
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut done = 0;
+    loop {
+        let len = match reader.read(&mut buf) {
+            Ok(0) => return Ok(done),
+            Ok(len) => len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(ImageError::Io(err)),
+        };
+        mem.try_write_from(addr + done, &buf[..len])
+            .map_err(ImageError::Memory)?;
+        done += len;
+    }
+}
+
+/// Dumps the given range of the memory to `writer` as a raw image.
+///
+/// Returns `Err(x)` if the memory failed to read one of the bytes, or the image could
+/// not be written.
+pub fn dump<M, W>(mem: &M, range: Range<usize>, mut writer: W) -> Result<(), ImageError<M::Error>>
+where
+    M: MemoryStorage + ?Sized,
+    W: Write,
+{
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut addr = range.start;
+    while addr < range.end {
+        let buf = &mut buf[..(range.end - addr).min(CHUNK_SIZE)];
+        mem.try_read_into(addr, buf).map_err(ImageError::Memory)?;
+        writer.write_all(buf)?;
+        addr += buf.len();
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Loads a gzip compressed raw image from `reader` into the memory, like [`load`].
+#[cfg(feature = "gzip")]
+pub fn load_gzip<M, R>(mem: &mut M, addr: usize, reader: R) -> Result<usize, ImageError<M::Error>>
+where
+    M: MemoryStorage + ?Sized,
+    R: Read,
+{
+    load(mem, addr, flate2::read::MultiGzDecoder::new(reader))
+}
+
+/// Dumps the given range of the memory to `writer` as a gzip compressed raw image,
+/// like [`dump`].
+///
+/// The `level` ranges from 0 (no compression) to 9 (best compression).
+#[cfg(feature = "gzip")]
+pub fn dump_gzip<M, W>(
+    mem: &M,
+    range: Range<usize>,
+    writer: W,
+    level: u32,
+) -> Result<(), ImageError<M::Error>>
+where
+    M: MemoryStorage + ?Sized,
+    W: Write,
+{
+    let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::new(level));
+    dump(mem, range, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Loads a zstd compressed raw image from `reader` into the memory, like [`load`].
+#[cfg(feature = "zstd")]
+pub fn load_zstd<M, R>(mem: &mut M, addr: usize, reader: R) -> Result<usize, ImageError<M::Error>>
+where
+    M: MemoryStorage + ?Sized,
+    R: Read,
+{
+    load(mem, addr, zstd::Decoder::new(reader)?)
+}
+
+/// Dumps the given range of the memory to `writer` as a zstd compressed raw image,
+/// like [`dump`].
+///
+/// The `level` ranges from 1 to 22, where 0 selects the default level of zstd.
+#[cfg(feature = "zstd")]
+pub fn dump_zstd<M, W>(
+    mem: &M,
+    range: Range<usize>,
+    writer: W,
+    level: i32,
+) -> Result<(), ImageError<M::Error>>
+where
+    M: MemoryStorage + ?Sized,
+    W: Write,
+{
+    let mut encoder = zstd::Encoder::new(writer, level)?;
+    dump(mem, range, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use crate::{Endianness, LittleEndian, MemoryStorage};
+use core::cell::RefCell;
+use core::marker::PhantomData;
+
+/// The width of a single access to an [`IoMemory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Width {
+    /// An access of 1 byte.
+    W8,
+    /// An access of 2 bytes.
+    W16,
+    /// An access of 4 bytes.
+    W32,
+    /// An access of 8 bytes.
+    W64,
+}
+
+impl Width {
+    /// Returns the width that accesses `len` bytes, if there is one.
+    pub fn from_bytes(len: usize) -> Option<Self> {
+        match len {
+            1 => Some(Width::W8),
+            2 => Some(Width::W16),
+            4 => Some(Width::W32),
+            8 => Some(Width::W64),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of bytes that are accessed.
+    pub fn bytes(self) -> usize {
+        match self {
+            Width::W8 => 1,
+            Width::W16 => 2,
+            Width::W32 => 4,
+            Width::W64 => 8,
+        }
+    }
+}
+
+/// A device that is accessed through registers, like a timer or a UART.
+///
+/// In contrast to a [`MemoryStorage`], every access has a width and is made through
+/// `&mut self`, because reading a register may change the state of the device, e.g. pop
+/// a byte out of a FIFO. Values are passed as integers, so the device does not have to
+/// care about byte order.
+///
+/// A device can be used where a `MemoryStorage` is expected by wrapping it inside an
+/// [`IoStorage`], and a memory can be used as a device by wrapping it inside a
+/// [`StorageIo`].
+pub trait IoMemory {
+    /// The error that is returned if an access fails.
+    type Error: core::fmt::Debug;
+
+    /// Reads the register of the given width at the given address.
+    fn io_read(&mut self, addr: usize, width: Width) -> Result<u64, Self::Error>;
+
+    /// Writes `value` to the register of the given width at the given address.
+    ///
+    /// Only the lower bits of `value` that fit into the width are used.
+    fn io_write(&mut self, addr: usize, width: Width, value: u64) -> Result<(), Self::Error>;
+
+    /// Advances the state of the device by the given number of cycles, which elapsed
+    /// since the last call, e.g. to count down a timer.
+    ///
+    /// The default implementation does nothing, which is correct for every device that
+    /// only changes its state when it is accessed.
+    fn tick(&mut self, cycles: u64) {
+        let _ = cycles;
+    }
+
+    /// Returns `true` if the interrupt line of the device is currently raised.
+    ///
+    /// The line is level triggered, so it stays raised until the cause is cleared by
+    /// accessing the device. The default implementation never raises the line.
+    fn interrupt(&self) -> bool {
+        false
+    }
+}
+
+/// An adapter that implements [`IoMemory`] for a `MemoryStorage`, where every access
+/// reads or writes the value in the byte order `E`.
+#[derive(Debug, Clone)]
+pub struct StorageIo<M, E = LittleEndian> {
+    inner: M,
+    _endian: PhantomData<E>,
+}
+
+impl<M: MemoryStorage, E: Endianness> StorageIo<M, E> {
+    /// Creates a new `StorageIo` that forwards every access to `inner`.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            _endian: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Consumes this `StorageIo` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
+
+impl<M: MemoryStorage, E: Endianness> IoMemory for StorageIo<M, E> {
+    type Error = M::Error;
+
+    fn io_read(&mut self, addr: usize, width: Width) -> Result<u64, Self::Error> {
+        let mut bytes = [0u8; 8];
+        let bytes = &mut bytes[..width.bytes()];
+        self.inner.try_read_into(addr, bytes)?;
+        Ok(decode::<E>(bytes))
+    }
+
+    fn io_write(&mut self, addr: usize, width: Width, value: u64) -> Result<(), Self::Error> {
+        let mut bytes = [0u8; 8];
+        let bytes = &mut bytes[..width.bytes()];
+        encode::<E>(value, bytes);
+        self.inner.try_write_from(addr, bytes)
+    }
+}
+
+/// An adapter that implements `MemoryStorage` for an [`IoMemory`], where values are
+/// stored in the byte order `E`.
+///
+/// Accesses of 1, 2, 4 or 8 bytes are forwarded as a single access of that width, while
+/// all other accesses are split into single bytes. The device is stored inside a
+/// [`RefCell`], because reads of a device need `&mut` access.
+#[derive(Debug)]
+pub struct IoStorage<D, E = LittleEndian> {
+    device: RefCell<D>,
+    _endian: PhantomData<E>,
+}
+
+impl<D: IoMemory, E: Endianness> IoStorage<D, E> {
+    /// Creates a new `IoStorage` that forwards every access to `device`.
+    pub fn new(device: D) -> Self {
+        Self {
+            device: RefCell::new(device),
+            _endian: PhantomData,
+        }
+    }
+
+    /// Returns a mutable reference to the device.
+    pub fn device_mut(&mut self) -> &mut D {
+        self.device.get_mut()
+    }
+
+    /// Advances the state of the device by the given number of cycles, using
+    /// [`IoMemory::tick`].
+    pub fn tick(&mut self, cycles: u64) {
+        self.device.get_mut().tick(cycles)
+    }
+
+    /// Returns `true` if the interrupt line of the device is currently raised, using
+    /// [`IoMemory::interrupt`].
+    pub fn interrupt(&self) -> bool {
+        self.device.borrow().interrupt()
+    }
+
+    /// Consumes this `IoStorage` and returns the device.
+    pub fn into_inner(self) -> D {
+        self.device.into_inner()
+    }
+}
+
+impl<D: IoMemory, E: Endianness> MemoryStorage for IoStorage<D, E> {
+    type Error = D::Error;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let value = self.device.borrow_mut().io_read(addr, Width::W8)?;
+        Ok(value as u8)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.device.get_mut().io_write(addr, Width::W8, byte as u64)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let mut device = self.device.borrow_mut();
+        match Width::from_bytes(buf.len()) {
+            Some(width) => encode::<E>(device.io_read(addr, width)?, buf),
+            None => {
+                for (offset, byte) in buf.iter_mut().enumerate() {
+                    *byte = device.io_read(addr + offset, Width::W8)? as u8;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let device = self.device.get_mut();
+        match Width::from_bytes(buf.len()) {
+            Some(width) => device.io_write(addr, width, decode::<E>(buf)),
+            None => buf.iter().enumerate().try_for_each(|(offset, &byte)| {
+                device.io_write(addr + offset, Width::W8, byte as u64)
+            }),
+        }
+    }
+}
+
+/// Converts the 1, 2, 4 or 8 bytes to an integer using the byte order `E`.
+fn decode<E: Endianness>(bytes: &[u8]) -> u64 {
+    match bytes.len() {
+        1 => bytes[0] as u64,
+        2 => E::from_slice::<u16>(bytes) as u64,
+        4 => E::from_slice::<u32>(bytes) as u64,
+        _ => E::from_slice::<u64>(bytes),
+    }
+}
+
+/// Writes the lower bits of `value` into the 1, 2, 4 or 8 bytes using the byte order `E`.
+fn encode<E: Endianness>(value: u64, out: &mut [u8]) {
+    match out.len() {
+        1 => out[0] = value as u8,
+        2 => E::write_bytes(value as u16, out),
+        4 => E::write_bytes(value as u32, out),
+        _ => E::write_bytes(value, out),
+    }
+}
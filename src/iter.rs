@@ -0,0 +1,176 @@
+use crate::{MemoryStorage, Value};
+use core::marker::PhantomData;
+use core::ops::Range;
+
+/// An iterator that sequentially reads `V`s out of a memory.
+///
+/// The reader keeps track of its own cursor and stops if the next value would cross
+/// the limit. If a read fails, the error is returned once and the iteration ends.
+///
+/// Created by [`MemoryStorage::values`].
+pub struct ValueReader<'mem, M: ?Sized, V> {
+    mem: &'mem M,
+    addr: usize,
+    limit: usize,
+    big_endian: bool,
+    _value: PhantomData<V>,
+}
+
+impl<'mem, M, V> ValueReader<'mem, M, V>
+where
+    M: MemoryStorage + ?Sized,
+    V: Value,
+{
+    /// Creates a new `ValueReader` that reads values starting at `addr`, until the
+    /// next value would cross `limit`.
+    pub fn new(mem: &'mem M, addr: usize, limit: usize) -> Self {
+        Self {
+            mem,
+            addr,
+            limit,
+            big_endian: false,
+            _value: PhantomData,
+        }
+    }
+
+    /// Makes this reader read values using big endian format.
+    pub fn big_endian(mut self) -> Self {
+        self.big_endian = true;
+        self
+    }
+
+    /// Returns the address of the next value that is read.
+    pub fn addr(&self) -> usize {
+        self.addr
+    }
+}
+
+impl<M, V> Iterator for ValueReader<'_, M, V>
+where
+    M: MemoryStorage + ?Sized,
+    V: Value,
+{
+    type Item = Result<V, M::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = core::mem::size_of::<V>();
+        let end = self
+            .addr
+            .checked_add(size)
+            .filter(|&end| end <= self.limit)?;
+
+        let val = if self.big_endian {
+            self.mem.try_read_be(self.addr)
+        } else {
+            self.mem.try_read(self.addr)
+        };
+
+        // Stop the iteration after the first error, by moving the cursor to the limit.
+        self.addr = if val.is_ok() { end } else { self.limit };
+        Some(val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.limit.saturating_sub(self.addr) / core::mem::size_of::<V>();
+        (0, Some(remaining))
+    }
+}
+
+impl<M, V> core::iter::FusedIterator for ValueReader<'_, M, V>
+where
+    M: MemoryStorage + ?Sized,
+    V: Value,
+{
+}
+
+/// A chunk of bytes that was copied out of a memory by [`Chunks`].
+///
+/// Dereferences to the bytes of the chunk, which are shorter than `N` if the
+/// chunk is at the end of the range, or at the start of an unaligned page range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk<const N: usize> {
+    addr: usize,
+    len: usize,
+    bytes: [u8; N],
+}
+
+impl<const N: usize> Chunk<N> {
+    /// Returns the address of the first byte in this chunk.
+    pub fn addr(&self) -> usize {
+        self.addr
+    }
+}
+
+impl<const N: usize> core::ops::Deref for Chunk<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+/// An iterator that copies a range of memory in chunks of up to `N` bytes.
+///
+/// If the iterator was created using [`MemoryStorage::pages`], the chunks are split at
+/// every address that is a multiple of `N`, instead of every `N` bytes from the start
+/// of the range. If a read fails, the error is returned once and the iteration ends.
+///
+/// Created by [`MemoryStorage::chunks`] and [`MemoryStorage::pages`].
+pub struct Chunks<'mem, M: ?Sized, const N: usize> {
+    mem: &'mem M,
+    addr: usize,
+    end: usize,
+    aligned: bool,
+}
+
+impl<'mem, M, const N: usize> Chunks<'mem, M, N>
+where
+    M: MemoryStorage + ?Sized,
+{
+    pub(crate) fn new(mem: &'mem M, range: Range<usize>, aligned: bool) -> Self {
+        const { assert!(N > 0, "chunk size must be non-zero") };
+        Self {
+            mem,
+            addr: range.start,
+            end: range.end,
+            aligned,
+        }
+    }
+}
+
+impl<M, const N: usize> Iterator for Chunks<'_, M, N>
+where
+    M: MemoryStorage + ?Sized,
+{
+    type Item = Result<Chunk<N>, M::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.addr >= self.end {
+            return None;
+        }
+
+        let max = if self.aligned { N - self.addr % N } else { N };
+        let len = max.min(self.end - self.addr);
+        let mut chunk = Chunk {
+            addr: self.addr,
+            len,
+            bytes: [0u8; N],
+        };
+
+        match self.mem.try_read_into(self.addr, &mut chunk.bytes[..len]) {
+            Ok(()) => {
+                self.addr += len;
+                Some(Ok(chunk))
+            }
+            Err(err) => {
+                self.addr = self.end;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<M, const N: usize> core::iter::FusedIterator for Chunks<'_, M, N> where
+    M: MemoryStorage + ?Sized
+{
+}
@@ -0,0 +1,77 @@
+use crate::{ContiguousMemory, GuestMemoryMap};
+use alloc::vec::Vec;
+
+/// The flag that enables dirty page logging for a memory slot.
+pub const KVM_MEM_LOG_DIRTY_PAGES: u32 = 1;
+
+/// A KVM memory slot, with the same layout as `struct kvm_userspace_memory_region`, so it
+/// can be passed to the `KVM_SET_USER_MEMORY_REGION` ioctl directly.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KvmMemorySlot {
+    /// The index of the slot.
+    pub slot: u32,
+    /// The flags of the slot, e.g. [`KVM_MEM_LOG_DIRTY_PAGES`].
+    pub flags: u32,
+    /// The guest address the slot is mapped at.
+    pub guest_phys_addr: u64,
+    /// The size of the slot in bytes.
+    pub memory_size: u64,
+    /// The host address of the memory that backs the slot.
+    pub userspace_addr: u64,
+}
+
+impl KvmMemorySlot {
+    /// Returns the guest addresses of every page that is marked as dirty inside a bitmap,
+    /// which was returned by the `KVM_GET_DIRTY_LOG` ioctl for this slot.
+    ///
+    /// Bit `n` of the bitmap marks the page at `guest_phys_addr + n * page_size`.
+    pub fn dirty_pages<'a>(
+        &self,
+        bitmap: &'a [u64],
+        page_size: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let base = self.guest_phys_addr as usize;
+        let pages = (self.memory_size as usize).div_ceil(page_size);
+
+        bitmap
+            .iter()
+            .enumerate()
+            .flat_map(|(word, &bits)| {
+                (0..64)
+                    .filter(move |bit| bits & (1 << bit) != 0)
+                    .map(move |bit| word * 64 + bit)
+            })
+            .take_while(move |&page| page < pages)
+            .map(move |page| base + page * page_size)
+    }
+}
+
+impl<M: ContiguousMemory> GuestMemoryMap<M> {
+    /// Returns a KVM memory slot for every region, where the index of the slot is the index
+    /// of the region.
+    ///
+    /// The host addresses stay valid as long as the regions are neither dropped nor
+    /// reallocated. The map takes a mutable reference, because the guest writes to the
+    /// memory once the slots are registered.
+    pub fn kvm_slots(&mut self, log_dirty: bool) -> Vec<KvmMemorySlot> {
+        let flags = if log_dirty {
+            KVM_MEM_LOG_DIRTY_PAGES
+        } else {
+            0
+        };
+        // Safety: the pointers are only converted to integers, and never dereferenced.
+        let regions = unsafe { self.host_regions() };
+        regions
+            .into_iter()
+            .enumerate()
+            .map(|(idx, region)| KvmMemorySlot {
+                slot: idx as u32,
+                flags,
+                guest_phys_addr: region.base as u64,
+                memory_size: region.len as u64,
+                userspace_addr: region.host as u64,
+            })
+            .collect()
+    }
+}
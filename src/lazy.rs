@@ -0,0 +1,226 @@
+use crate::{LazyError, MemoryStorage, MemoryUsage, ReportUsage};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::cell::RefCell;
+use core::ops::Range;
+
+/// The source that provides the initial content of the pages of a [`LazyMemory`].
+///
+/// Implemented for every closure that takes the page index and the buffer of the page.
+pub trait PageSource {
+    /// The error that is returned if a page can not be fetched.
+    type Error: core::fmt::Debug;
+
+    /// Fills `data` with the initial content of the page with the given index.
+    fn fetch_page(&mut self, page: usize, data: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+impl<F, E> PageSource for F
+where
+    F: FnMut(usize, &mut [u8]) -> Result<(), E>,
+    E: core::fmt::Debug,
+{
+    type Error = E;
+
+    fn fetch_page(&mut self, page: usize, data: &mut [u8]) -> Result<(), Self::Error> {
+        self(page, data)
+    }
+}
+
+/// A heap allocated memory that fetches every page from a [`PageSource`] the first time
+/// it is accessed, e.g. from a snapshot file or over the network.
+///
+/// This allows restoring big memories lazily, and loading them post-copy style, while
+/// only the pages that are actually accessed are ever fetched and allocated. Reads
+/// fetch pages too, so the memory stores the pages and the source inside a [`RefCell`].
+///
+/// The memory is divided into pages of `PAGE_SIZE` bytes, where page `n` starts
+/// at address `n * PAGE_SIZE`.
+pub struct LazyMemory<S, const PAGE_SIZE: usize = 4096> {
+    source: RefCell<S>,
+    pages: RefCell<Vec<Option<Box<[u8]>>>>,
+    len: usize,
+}
+
+impl<S: PageSource, const PAGE_SIZE: usize> LazyMemory<S, PAGE_SIZE> {
+    /// Creates a new `LazyMemory` with the given number of pages, which are all fetched
+    /// from `source` on their first access.
+    ///
+    /// Panics if the size of the memory in bytes does not fit into a `usize`.
+    pub fn new(source: S, pages: usize) -> Self {
+        const { assert!(PAGE_SIZE > 0, "page size must be non-zero") };
+        let len = match pages.checked_mul(PAGE_SIZE) {
+            Some(len) => len,
+            None => panic!(
+                "{} pages of {} bytes overflow the address space",
+                pages, PAGE_SIZE
+            ),
+        };
+        Self {
+            source: RefCell::new(source),
+            pages: RefCell::new(vec![None; pages]),
+            len,
+        }
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the page with the given index was already fetched.
+    ///
+    /// Panics if the page is out of bounds.
+    pub fn is_resident(&self, page: usize) -> bool {
+        self.pages.borrow()[page].is_some()
+    }
+
+    /// Returns the number of pages that were already fetched.
+    pub fn resident_pages(&self) -> usize {
+        self.pages
+            .borrow()
+            .iter()
+            .filter(|page| page.is_some())
+            .count()
+    }
+
+    /// Tries to fetch every page that overlaps the given range, which have not been
+    /// fetched yet.
+    ///
+    /// Returns `Err(x)` if the range is out of bounds, or a page could not be fetched.
+    pub fn try_populate(&self, range: Range<usize>) -> Result<(), LazyError<S::Error>> {
+        if range.is_empty() {
+            return Ok(());
+        }
+        let mut pages = self.page_range(range.start, range.end - range.start)?;
+        let mut resident = self.pages.borrow_mut();
+        pages.try_for_each(|page| self.resident(&mut resident, page).map(drop))
+    }
+
+    /// Fetches every page that overlaps the given range, which have not been
+    /// fetched yet.
+    ///
+    /// Panics if the range is out of bounds, or a page could not be fetched.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_populate` instead"))]
+    pub fn populate(&self, range: Range<usize>) {
+        or_panic!(
+            self.try_populate(range.clone()),
+            "populate {:#x}..{:#x}",
+            range.start,
+            range.end
+        )
+    }
+
+    /// Consumes this `LazyMemory` and returns the page source.
+    pub fn into_source(self) -> S {
+        self.source.into_inner()
+    }
+
+    /// Returns the indices of the pages that are covered by the given access.
+    fn page_range(&self, addr: usize, len: usize) -> Result<Range<usize>, LazyError<S::Error>> {
+        let size = self.len();
+        match addr.checked_add(len) {
+            Some(end) if end <= size => Ok(addr / PAGE_SIZE..end.div_ceil(PAGE_SIZE)),
+            _ => Err(LazyError::OutOfBounds {
+                addr: addr.max(size),
+            }),
+        }
+    }
+
+    /// Returns the data of the given page, and fetches it first if necessary.
+    fn resident<'a>(
+        &self,
+        pages: &'a mut [Option<Box<[u8]>>],
+        page: usize,
+    ) -> Result<&'a mut [u8], LazyError<S::Error>> {
+        let slot = &mut pages[page];
+        if slot.is_none() {
+            let mut data = vec![0u8; PAGE_SIZE].into_boxed_slice();
+            self.source
+                .borrow_mut()
+                .fetch_page(page, &mut data)
+                .map_err(|error| LazyError::Fetch { page, error })?;
+            *slot = Some(data);
+        }
+        Ok(slot.as_deref_mut().unwrap())
+    }
+}
+
+impl<S: PageSource, const PAGE_SIZE: usize> MemoryStorage for LazyMemory<S, PAGE_SIZE> {
+    type Error = LazyError<S::Error>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.page_range(addr, buf.len())?;
+        let mut pages = self.pages.borrow_mut();
+
+        let mut done = 0;
+        while done < buf.len() {
+            let (page, offset) = ((addr + done) / PAGE_SIZE, (addr + done) % PAGE_SIZE);
+            let len = (PAGE_SIZE - offset).min(buf.len() - done);
+
+            let data = self.resident(&mut pages, page)?;
+            buf[done..done + len].copy_from_slice(&data[offset..offset + len]);
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.page_range(addr, buf.len())?;
+        let mut pages = self.pages.borrow_mut();
+
+        let mut done = 0;
+        while done < buf.len() {
+            let (page, offset) = ((addr + done) / PAGE_SIZE, (addr + done) % PAGE_SIZE);
+            let len = (PAGE_SIZE - offset).min(buf.len() - done);
+
+            let data = self.resident(&mut pages, page)?;
+            data[offset..offset + len].copy_from_slice(&buf[done..done + len]);
+            done += len;
+        }
+        Ok(())
+    }
+}
+
+impl<S, const PAGE_SIZE: usize> core::fmt::Debug for LazyMemory<S, PAGE_SIZE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let pages = self.pages.borrow();
+        f.debug_struct("LazyMemory")
+            .field("pages", &pages.len())
+            .field(
+                "resident",
+                &pages.iter().filter(|page| page.is_some()).count(),
+            )
+            .finish()
+    }
+}
+
+impl<S: PageSource, const PAGE_SIZE: usize> ReportUsage for LazyMemory<S, PAGE_SIZE> {
+    fn usage(&self) -> MemoryUsage {
+        let resident = self.resident_pages();
+        MemoryUsage {
+            addressable: self.len(),
+            allocated: resident * PAGE_SIZE,
+            resident_pages: resident,
+            dirty_pages: 0,
+        }
+    }
+}
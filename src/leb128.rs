@@ -0,0 +1,87 @@
+//! Decoding and encoding of LEB128 variable length integers, as used by DWARF,
+//! WebAssembly and protobuf.
+
+/// The maximum number of bytes of an encoded 64 bit value.
+pub(crate) const MAX_LEN: usize = 10;
+
+/// Decodes an unsigned value from the bytes returned by `next`.
+///
+/// Returns `Ok(None)` if the value does not fit into 64 bits.
+pub(crate) fn decode_unsigned<E, F>(mut next: F) -> Result<Option<(u64, usize)>, E>
+where
+    F: FnMut(usize) -> Result<u8, E>,
+{
+    let mut value = 0u64;
+    for idx in 0..MAX_LEN {
+        let byte = next(idx)?;
+        let bits = u64::from(byte & 0x7F);
+        if idx == MAX_LEN - 1 && bits > 1 {
+            return Ok(None);
+        }
+
+        value |= bits << (idx * 7);
+        if byte & 0x80 == 0 {
+            return Ok(Some((value, idx + 1)));
+        }
+    }
+    Ok(None)
+}
+
+/// Decodes a signed value from the bytes returned by `next`.
+///
+/// Returns `Ok(None)` if the value does not fit into 64 bits.
+pub(crate) fn decode_signed<E, F>(mut next: F) -> Result<Option<(i64, usize)>, E>
+where
+    F: FnMut(usize) -> Result<u8, E>,
+{
+    let mut value = 0i64;
+    for idx in 0..MAX_LEN {
+        let byte = next(idx)?;
+        let bits = byte & 0x7F;
+        // The last byte only holds the sign bit, so the rest must be its extension.
+        if idx == MAX_LEN - 1 && bits != 0 && bits != 0x7F {
+            return Ok(None);
+        }
+
+        let shift = idx * 7;
+        value |= i64::from(bits) << shift;
+        if byte & 0x80 == 0 {
+            if shift + 7 < 64 && bits & 0x40 != 0 {
+                value |= -1 << (shift + 7);
+            }
+            return Ok(Some((value, idx + 1)));
+        }
+    }
+    Ok(None)
+}
+
+/// Encodes an unsigned value into `out` and returns the number of bytes used.
+pub(crate) fn encode_unsigned(mut value: u64, out: &mut [u8; MAX_LEN]) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out[len] = byte;
+            return len + 1;
+        }
+        out[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Encodes a signed value into `out` and returns the number of bytes used.
+pub(crate) fn encode_signed(mut value: i64, out: &mut [u8; MAX_LEN]) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out[len] = byte;
+            return len + 1;
+        }
+        out[len] = byte | 0x80;
+        len += 1;
+    }
+}
@@ -17,11 +17,11 @@
 //! ```compile_fail
 //! use mem_storage::MemoryStorage;
 //!
-//! let mem = MyMemory::new();
+//! let mut mem = MyMemory::new();
 //!
 //! /// The `read` and `write` method will read / write data using little endian format.
 //! /// For big endian format use `read_be` and `write_be`.
-//! mem.write(0xABCD, 123);
+//! mem.write(0xABCD, 123u8);
 //!
 //! let value = mem.read::<u8>(0xABCD);
 //! assert_eq!(123u8, value);
@@ -40,7 +40,7 @@
 //! ### Implement the Memory trait
 //!
 //! ```
-//! use mem_storage::MemoryStorage;
+//! use mem_storage::{ContiguousMemory, MemoryStorage};
 //!
 //! /// This time your struct is responsible for storing the data.
 //! struct MyMemory {
@@ -58,65 +58,417 @@
 //!   /// If an `Err` is returned, the addr is out of bounds
 //!   type Error = ();
 //!
-//!   fn get<I>(&self, index: I) -> Result<&I::Output, Self::Error>
-//!   where
-//!       I: std::slice::SliceIndex<[u8]>,
-//!   {
-//!       self.ram.get(index).ok_or(())
-//!   }
-//!
-//!   fn get_mut<I>(&mut self, index: I) -> Result<&mut I::Output, Self::Error>
-//!   where
-//!       I: std::slice::SliceIndex<[u8]>,
-//!   {
-//!       self.ram.get_mut(index).ok_or(())
-//!   }
-//!
 //!   fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
 //!     self.ram.get(addr).copied().ok_or(())
 //!   }
 //!
 //!   fn try_write_byte(&mut self, addr: usize, value: u8) -> Result<(), Self::Error> {
-//!     let mut value = self.ram.get_mut(addr).ok_or(())?;
-//!     *value = *value;
+//!     let entry = self.ram.get_mut(addr).ok_or(())?;
+//!     *entry = value;
 //!     Ok(())
 //!   }
 //!
+//!   /// `MyMemory` stores its bytes in one slice, which makes every provided method use it directly.
+//!   fn as_slice(&self) -> Option<&[u8]> {
+//!     Some(&self.ram)
+//!   }
+//!
+//!   fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+//!     Some(&mut self.ram)
+//!   }
+//!
 //!   // The trait will provide a generic `read` and `read_be` method for you.
 //! }
+//!
+//! /// This allows handing out references into `MyMemory` using `get` and `get_mut`.
+//! impl ContiguousMemory for MyMemory {
+//!   fn as_bytes(&self) -> &[u8] {
+//!     &self.ram
+//!   }
+//!
+//!   fn as_bytes_mut(&mut self) -> &mut [u8] {
+//!     &mut self.ram
+//!   }
+//!
+//!   fn out_of_bounds(&self, _addr: usize) -> Self::Error {}
+//! }
 //! ```
 //!
+//! ## Features
+//!
+//! - `alloc` (default): Enables the heap allocated memories, like [`CellMemory`].
+//! - `defmt`: Implements `defmt::Format` for the error types, and for event types like
+//!   [`Access`] and [`CmpLogEntry`], for logging on microcontrollers.
+//! - `derive`: Adds `#[derive(MemoryStorage)]`, which forwards the trait to a field marked with `#[memory]`.
+//! - `serde`: Implements `Serialize` and `Deserialize` for the heap allocated memories.
+//! - `postcard` / `bincode`: Adds `to_postcard` / `to_bincode` and the matching `from_*`
+//!   constructors to the heap allocated memories.
+//! - `rkyv`: Implements `rkyv` archiving for the heap allocated memories, which allows restoring
+//!   them from a memory mapped archive without a deserialization pass.
+//! - `binrw`: Adds `MemoryCursor`, which parses and writes `binrw` types at any address of a memory.
+//! - `embedded-storage`: Implements the `embedded-storage` traits for [`CellMemory`] and
+//!   [`NorFlashMemory`], and adds `StorageDevice` and `StorageMemory` for converting between
+//!   memories and storage devices.
+//! - `devmem`: Adds `PhysMemory` for mapping physical memory through `/dev/mem`, or device
+//!   memory through UIO. Mapping it is `unsafe`, because writes can corrupt the whole system.
+//! - `ffi`: Adds the `ffi` module, which exposes memories to C code through an opaque handle.
+//! - `forbid-unsafe`: Forbids unsafe code inside this crate. The default methods and the heap
+//!   allocated memories fall back to safe code, which is slightly slower, and the memories that
+//!   can not be implemented without unsafe code, like `AlignedMemory`, `PtrMemory` and
+//!   `VolatileRegion`, are removed together with `host_region` and the `devmem`, `ffi`, `kvm`,
+//!   `process`, `shm` and `uffd` features.
+//! - `gzip`: Adds `load_gzip` and `dump_gzip` to the `image` module, for gzip compressed
+//!   memory images.
+//! - `kvm`: Adds `GuestMemoryMap::kvm_slots` for registering guest memory as KVM memory slots
+//!   on Linux, and decoding their dirty page bitmaps.
+//! - `log`: Logs warnings for suspicious accesses, like unmapped reads that are served by
+//!   an [`OpenBus`], or writes to the CHR ROM of a NES cartridge.
+//! - `mappers`: Adds ready-made cartridge mappers, like the Game Boy memory bank controllers
+//!   and the common NES mappers.
+//! - `no-panic`: Deprecates every method that panics if an access fails, like `read` and
+//!   `write`, so safety-critical builds can deny their use and only call the `try_*` methods.
+//! - `process`: Adds `ProcessMemory` for accessing the memory of another process on Linux.
+//! - `shm`: Adds `SharedMemory` for sharing a memory between processes on Unix, using
+//!   POSIX shared memory objects or `memfd_create`, `SharedView` for mapping it a second
+//!   time with page protections that trap in hardware, and `FastmemArena` for mapping
+//!   guest regions into a reserved range of host addresses for a JIT. Also adds
+//!   `AflSharedMap` for attaching the coverage map of an AFL fuzzer, and `CowMemory` for
+//!   resetting a memory to a copy-on-write snapshot almost instantly on Linux, e.g.
+//!   between fuzzing testcases.
+//! - `std`: Implements [`SwapStore`] for every type that implements `Read`, `Write` and
+//!   `Seek`, like a temporary file, adds [`RemoteMemory`] and [`remote::serve`] for
+//!   accessing a memory over TCP, [`GdbMemory`] for accessing live targets through
+//!   a GDB server, [`CoreDump`] for exporting memory as an ELF core file, the [`image`] module
+//!   for loading and dumping raw memory images, and the [`stats`] module for exporting
+//!   statistics to JSON or CSV.
+//! - `tracing`: Emits `tracing` events for injected faults, checksum mismatches, mapped
+//!   regions and bank switches, and adds `TracedMemory` for tracing (sampled) accesses.
+//! - `uffd`: Adds `UffdMemory` on Linux, which fetches its pages from a [`PageSource`]
+//!   through `userfaultfd` on their first access, even if the host memory is accessed
+//!   directly, e.g. for lazily restoring the memory of a virtual machine.
+//! - `wasmtime`: Adds `WasmMemory`, which wraps the linear memory of a wasmtime instance.
+//! - `zeroize`: Adds `secure_clear` for wiping memory in a way that is not optimized away.
+//!   `CellMemory`, `AlignedMemory`, `SparseMemory`, `NorFlashMemory` and `EepromMemory`
+//!   implement `Zeroize`, and wipe their contents when they are dropped.
+//! - `zstd`: Adds `load_zstd` and `dump_zstd` to the `image` module, for zstd compressed
+//!   memory images.
+//!
 //! ## License
 //!
 //! This project is double-licensed under the Zlib or Apache2.0 license.
 
 #![no_std]
+#![cfg_attr(feature = "forbid-unsafe", forbid(unsafe_code))]
 #![warn(rust_2018_idioms)]
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::convert::TryInto;
+use core::ops::Range;
 use core::slice::SliceIndex;
+use core::sync::atomic::Ordering;
+
+/// Emits a `tracing` event with the given level if the `tracing` feature is enabled,
+/// and compiles to nothing otherwise.
+///
+/// Every user is behind a feature, so the macro is unused in minimal builds.
+#[allow(unused_macros)]
+macro_rules! trace_event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    };
+}
+
+/// Returns the value of a successful access, or panics with a message that describes the
+/// failed access, followed by the error.
+macro_rules! or_panic {
+    ($result:expr, $($arg:tt)+) => {
+        match $result {
+            Ok(val) => val,
+            Err(err) => $crate::access_failed(format_args!($($arg)+), &err),
+        }
+    };
+}
+
+/// Emits a `log` warning if the `log` feature is enabled, and compiles to nothing otherwise.
+macro_rules! log_warn {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::warn!($($arg)+);
+    };
+}
+
+mod addr;
+#[cfg(all(feature = "shm", unix, not(feature = "forbid-unsafe")))]
+mod afl;
+#[cfg(all(feature = "alloc", not(feature = "forbid-unsafe")))]
+mod aligned;
+#[cfg(not(feature = "forbid-unsafe"))]
+mod allocator;
+#[cfg(feature = "alloc")]
+mod arbiter;
+#[cfg(feature = "rkyv")]
+mod archive;
+mod bcd;
+#[cfg(feature = "binrw")]
+mod binrw_io;
+#[cfg(feature = "alloc")]
+mod bounds;
+mod bulk;
+#[cfg(feature = "alloc")]
+mod cache;
+#[cfg(feature = "alloc")]
+mod cell;
+#[cfg(feature = "alloc")]
+mod checksum;
+mod cmplog;
+#[cfg(feature = "std")]
+pub mod core_dump;
+mod coverage;
+#[cfg(all(feature = "shm", target_os = "linux", not(feature = "forbid-unsafe")))]
+mod cow;
+mod data_bus;
+#[cfg(feature = "alloc")]
+mod dma;
+#[cfg(feature = "alloc")]
+mod eeprom;
+mod encrypted;
+mod endian;
+mod error;
+#[cfg(all(feature = "shm", unix, not(feature = "forbid-unsafe")))]
+mod fastmem;
+#[cfg(feature = "alloc")]
+mod fault;
+#[cfg(all(feature = "ffi", not(feature = "forbid-unsafe")))]
+pub mod ffi;
+mod fifo;
+mod fill;
+#[cfg(feature = "alloc")]
+mod flash;
+mod framebuffer;
+#[cfg(feature = "alloc")]
+mod frames;
+#[cfg(feature = "std")]
+mod gdb;
+#[cfg(feature = "alloc")]
+mod guest;
+mod host;
+#[cfg(feature = "std")]
+pub mod image;
+mod io;
+mod iter;
+#[cfg(all(feature = "kvm", target_os = "linux", not(feature = "forbid-unsafe")))]
+mod kvm;
+#[cfg(feature = "alloc")]
+mod lazy;
+mod leb128;
+#[cfg(feature = "alloc")]
+mod lock;
+#[cfg(feature = "mappers")]
+pub mod mappers;
+mod masked;
+mod open_bus;
+#[cfg(all(feature = "devmem", unix, not(feature = "forbid-unsafe")))]
+mod phys;
+#[cfg(feature = "alloc")]
+mod privilege;
+#[cfg(all(
+    feature = "process",
+    target_os = "linux",
+    not(feature = "forbid-unsafe")
+))]
+mod process;
+#[cfg(feature = "alloc")]
+mod profile;
+#[cfg(not(feature = "forbid-unsafe"))]
+mod ptr_mem;
+mod read_ref;
+#[cfg(feature = "alloc")]
+mod record;
+#[cfg(feature = "std")]
+pub mod remote;
+mod resize;
+mod ring;
+pub mod save_state;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(all(feature = "shm", unix, not(feature = "forbid-unsafe")))]
+mod shm;
+#[cfg(feature = "alloc")]
+mod sparse;
+mod static_mem;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "embedded-storage")]
+mod storage;
+#[cfg(feature = "alloc")]
+mod swap;
+#[cfg(feature = "alloc")]
+mod tiered;
+#[cfg(feature = "alloc")]
+mod tlb;
+#[cfg(feature = "tracing")]
+mod traced;
+#[cfg(all(feature = "uffd", target_os = "linux", not(feature = "forbid-unsafe")))]
+mod uffd;
+mod usage;
+#[cfg(feature = "alloc")]
+mod utf16;
+#[cfg(not(feature = "forbid-unsafe"))]
+mod volatile;
+#[cfg(feature = "wasmtime")]
+mod wasm;
+mod wear;
+
+pub use addr::Addr;
+#[cfg(all(feature = "shm", unix, not(feature = "forbid-unsafe")))]
+pub use afl::{AflSharedMap, AFL_SHM_ENV};
+#[cfg(all(feature = "alloc", not(feature = "forbid-unsafe")))]
+pub use aligned::{AlignedMemory, PAGE_ALIGN};
+#[cfg(all(feature = "alloc", not(feature = "forbid-unsafe")))]
+pub use allocator::Global;
+#[cfg(not(feature = "forbid-unsafe"))]
+pub use allocator::RawAllocator;
+#[cfg(feature = "alloc")]
+pub use arbiter::{ArbiterPort, BusArbiter, Initiator, InitiatorStats};
+#[cfg(feature = "binrw")]
+pub use binrw_io::MemoryCursor;
+#[cfg(feature = "alloc")]
+pub use bounds::{assert_accesses_within, AccessGuard};
+#[cfg(feature = "alloc")]
+pub use cache::{CacheStats, PageCache};
+#[cfg(feature = "alloc")]
+pub use cell::CellMemory;
+#[cfg(feature = "alloc")]
+pub use checksum::ChecksumMemory;
+pub use cmplog::{CmpLog, CmpLogEntry, CmpLogMemory};
+#[cfg(feature = "std")]
+pub use core_dump::CoreDump;
+pub use coverage::{CoverageMap, AFL_MAP_SIZE};
+#[cfg(all(feature = "shm", target_os = "linux", not(feature = "forbid-unsafe")))]
+pub use cow::{CowMemory, MapOptions};
+pub use data_bus::{DataBus, MisalignedPolicy, WidePolicy};
+#[cfg(feature = "alloc")]
+pub use dma::{DmaDescriptor, DmaEngine};
+#[cfg(feature = "alloc")]
+pub use eeprom::{EepromMemory, WearOut};
+#[cfg(feature = "alloc")]
+pub use encrypted::Plaintext;
+pub use encrypted::{EncryptedMemory, PageCipher};
+pub use endian::{BigEndian, Endianness, LittleEndian, NativeEndian};
+#[cfg(feature = "embedded-storage")]
+pub use error::StorageError;
+pub use error::{
+    ChecksumError, DataBusError, EepromError, ErrorKind, FaultError, FlashError, GuestMemoryError,
+    LazyError, LengthMismatch, MemoryError, OutOfBounds, PixelError, RangeError, ReplayError,
+    ResizeError, SwapError, TieredError,
+};
+#[cfg(feature = "std")]
+pub use error::{CoreDumpError, ImageError, RemoteError};
+#[cfg(feature = "alloc")]
+pub use error::{DmaError, LockError, PrivilegeError};
+#[cfg(all(feature = "shm", unix, not(feature = "forbid-unsafe")))]
+pub use fastmem::{FastmemArena, FastmemFault};
+#[cfg(feature = "alloc")]
+pub use fault::FaultMemory;
+pub use fifo::MemFifo;
+pub use fill::FillPolicy;
+#[cfg(feature = "alloc")]
+pub use flash::NorFlashMemory;
+pub use framebuffer::{Framebuffer, PixelFormat};
+#[cfg(feature = "std")]
+pub use gdb::GdbMemory;
+#[cfg(feature = "alloc")]
+pub use guest::{GuestMemory, GuestMemoryMap, GuestRegion, RegionAt};
+pub use host::HostRegion;
+pub use io::{IoMemory, IoStorage, StorageIo, Width};
+pub use iter::{Chunk, Chunks, ValueReader};
+#[cfg(all(feature = "kvm", target_os = "linux", not(feature = "forbid-unsafe")))]
+pub use kvm::{KvmMemorySlot, KVM_MEM_LOG_DIRTY_PAGES};
+#[cfg(feature = "alloc")]
+pub use lazy::{LazyMemory, PageSource};
+#[cfg(feature = "alloc")]
+pub use lock::{LockableMemory, RegionGuard};
+pub use masked::MaskedMemory;
+/// Derives `MemoryStorage` for a struct by forwarding every access to the field that is
+/// marked with `#[memory]`.
+#[cfg(feature = "derive")]
+pub use mem_storage_derive::MemoryStorage;
+// Lets the derived implementations name `String`, without requiring the crate that uses
+// the derive to declare `extern crate alloc`.
+#[cfg(all(feature = "derive", feature = "alloc"))]
+#[doc(hidden)]
+pub use alloc::string::String as __String;
+pub use open_bus::{OpenBus, OpenBusMode};
+#[cfg(all(feature = "devmem", unix, not(feature = "forbid-unsafe")))]
+pub use phys::PhysMemory;
+#[cfg(feature = "alloc")]
+pub use privilege::{Privilege, PrivilegedMemory};
+#[cfg(all(
+    feature = "process",
+    target_os = "linux",
+    not(feature = "forbid-unsafe")
+))]
+pub use process::ProcessMemory;
+#[cfg(feature = "alloc")]
+pub use profile::{FetchPort, ProfileMemory, ProfileRegion};
+#[cfg(not(feature = "forbid-unsafe"))]
+pub use ptr_mem::PtrMemory;
+pub use read_ref::ReadRef;
+#[cfg(feature = "alloc")]
+pub use record::{compare_traces, Access, AccessKind, Divergence, RecordMemory, ReplayMemory};
+#[cfg(feature = "std")]
+pub use remote::RemoteMemory;
+pub use resize::ResizableMemory;
+pub use ring::{OverflowPolicy, RingRegion};
+#[cfg(all(feature = "shm", unix, not(feature = "forbid-unsafe")))]
+pub use shm::{Protection, SharedMemory, SharedView};
+#[cfg(feature = "alloc")]
+pub use sparse::{SparseMemory, SparseSnapshot};
+pub use static_mem::StaticMemory;
+#[cfg(feature = "embedded-storage")]
+pub use storage::{StorageDevice, StorageMemory};
+#[cfg(feature = "alloc")]
+pub use swap::{SwapMemory, SwapStore};
+#[cfg(feature = "alloc")]
+pub use tiered::{Tier, TierStats, TieredMemory};
+#[cfg(feature = "alloc")]
+pub use tlb::{Tlb, TlbEntry};
+#[cfg(feature = "tracing")]
+pub use traced::TracedMemory;
+#[cfg(all(feature = "uffd", target_os = "linux", not(feature = "forbid-unsafe")))]
+pub use uffd::UffdMemory;
+pub use usage::{MemoryUsage, ReportUsage};
+#[cfg(not(feature = "forbid-unsafe"))]
+pub use volatile::VolatileRegion;
+#[cfg(feature = "wasmtime")]
+pub use wasm::WasmMemory;
+pub use wear::WearReport;
 
 /// The `Memory` trait represents a chunk of memory that can read from,
 /// or written to.
+///
+/// Reading only requires a shared reference, while every method that writes to the memory
+/// requires a mutable reference. If the memory needs to be written through a shared reference,
+/// use a [`CellMemory`] instead.
+///
+/// Only the byte accessors are required, so this trait can also be implemented for
+/// memories that are not stored in one contiguous slice, like banked or sparse memories.
+/// Memories that are contiguous should return their bytes from [`as_slice`](Self::as_slice)
+/// and [`as_mut_slice`](Self::as_mut_slice), which makes every provided method
+/// operate on the slice directly, and can additionally implement [`ContiguousMemory`].
 pub trait MemoryStorage {
     /// The `Error` type can be used to indicate if memory access was invalid.
     ///
     /// Usually this is just `()` and if `Err(())` is returned, it means that the address is out of bounds.
     type Error: core::fmt::Debug;
 
-    /// Returns a reference to an element or subslice depending on the type of
-    /// index.
-    fn get<I>(&self, index: I) -> Result<&I::Output, Self::Error>
-    where
-        I: SliceIndex<[u8]>;
-
-    /// Returns a mutable reference to an element or subslice depending on the type of
-    /// index.
-    fn get_mut<I>(&mut self, index: I) -> Result<&mut I::Output, Self::Error>
-    where
-        I: SliceIndex<[u8]>;
-
     /// Tries to read a byte at the given address.
     ///
     /// Returns `Err(x)` if the method failed to read a byte from the address.
@@ -127,103 +479,1341 @@ pub trait MemoryStorage {
     /// Returns `Err(x)` if the method failed to write a byte to the address.
     fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error>;
 
+    /// Returns all bytes of this memory, if they are stored in one contiguous slice.
+    ///
+    /// If this returns `Some`, the provided methods access the slice directly instead of
+    /// going through the byte accessors. The default implementation returns `None`.
+    fn as_slice(&self) -> Option<&[u8]> {
+        None
+    }
+
+    /// Returns all bytes of this memory mutably, if they are stored in one contiguous slice.
+    ///
+    /// This must return `Some` if, and only if, [`as_slice`](Self::as_slice) returns `Some`.
+    /// The default implementation returns `None`.
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        None
+    }
+
+    /// Returns the number of bytes that can be addressed inside this memory, or `None`
+    /// if it is not known, e.g. for the memory of a remote target.
+    ///
+    /// The default implementation returns the length of [`as_slice`](Self::as_slice).
+    fn size(&self) -> Option<usize> {
+        self.as_slice().map(<[u8]>::len)
+    }
+
+    /// Returns the range of addresses that can be accessed, or `None` if it is not known.
+    ///
+    /// The range may contain holes, e.g. for a memory map with multiple regions, so an
+    /// access inside the range can still fail. The default implementation returns
+    /// `0..size`, using [`size`](Self::size).
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.size().map(|size| 0..size)
+    }
+
     /// Reads a byte at the given address.
     ///
     /// Panics if the read failed
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read_byte` instead"))]
     fn read_byte(&self, addr: usize) -> u8 {
-        self.try_read_byte(addr)
-            .expect("failed to read from memory")
+        or_panic!(self.try_read_byte(addr), "read 1 byte at {:#x}", addr)
     }
 
     /// Writes a byte to the given address.
     ///
     /// Panics if the write failed
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_write_byte` instead")
+    )]
     fn write_byte(&mut self, addr: usize, byte: u8) {
-        self.try_write_byte(addr, byte)
-            .expect("failed to write to memory")
+        or_panic!(
+            self.try_write_byte(addr, byte),
+            "write 1 byte at {:#x}",
+            addr
+        )
+    }
+
+    /// Tries to fill `buf` with the bytes starting at the given address.
+    ///
+    /// The default implementation copies the bytes out of [`as_slice`](Self::as_slice),
+    /// or reads every byte using [`try_read_byte`](Self::try_read_byte) if the memory is
+    /// not contiguous.
+    ///
+    /// Returns `Err(x)` if the method failed to read one of the bytes.
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        if let Some(src) = self
+            .as_slice()
+            .and_then(|s| s.get(slice_range(addr, buf.len())?))
+        {
+            buf.copy_from_slice(src);
+            return Ok(());
+        }
+
+        for (offset, byte) in buf.iter_mut().enumerate() {
+            *byte = self.try_read_byte(addr + offset)?;
+        }
+        Ok(())
+    }
+
+    /// Tries to write all bytes of `buf` starting at the given address.
+    ///
+    /// The default implementation copies the bytes into [`as_mut_slice`](Self::as_mut_slice),
+    /// or writes every byte using [`try_write_byte`](Self::try_write_byte) if the memory is
+    /// not contiguous.
+    ///
+    /// Returns `Err(x)` if the method failed to write one of the bytes.
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let range = slice_range(addr, buf.len());
+        if let Some(dst) = self.as_mut_slice().and_then(|s| s.get_mut(range?)) {
+            dst.copy_from_slice(buf);
+            return Ok(());
+        }
+
+        for (offset, byte) in buf.iter().enumerate() {
+            self.try_write_byte(addr + offset, *byte)?;
+        }
+        Ok(())
+    }
+
+    /// Tries to set every byte inside the given range to `byte`.
+    ///
+    /// Returns `Err(x)` if the method failed to write one of the bytes.
+    fn try_fill(&mut self, range: Range<usize>, byte: u8) -> Result<(), Self::Error> {
+        if let Some(dst) = self.as_mut_slice().and_then(|s| s.get_mut(range.clone())) {
+            dst.fill(byte);
+            return Ok(());
+        }
+
+        let buf = [byte; 256];
+        let mut addr = range.start;
+        while addr < range.end {
+            let len = (range.end - addr).min(buf.len());
+            self.try_write_from(addr, &buf[..len])?;
+            addr += len;
+        }
+        Ok(())
+    }
+
+    /// Sets every byte inside the given range to `byte`.
+    ///
+    /// Panics if the method failed to write one of the bytes.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_fill` instead"))]
+    fn fill(&mut self, range: Range<usize>, byte: u8) {
+        or_panic!(
+            self.try_fill(range.clone(), byte),
+            "fill {:#x}..{:#x}",
+            range.start,
+            range.end
+        )
+    }
+
+    /// Tries to copy the bytes inside the `src` range to `dest`.
+    ///
+    /// The ranges may overlap, in which case the bytes are copied as if they were
+    /// first copied into a temporary buffer. An empty or reversed `src` range copies
+    /// nothing.
+    ///
+    /// Returns `Err(x)` if the method failed to access one of the bytes.
+    fn try_copy_within(&mut self, src: Range<usize>, dest: usize) -> Result<(), Self::Error> {
+        if src.is_empty() {
+            return Ok(());
+        }
+
+        let len = src.len();
+        let dest_end = dest.checked_add(len);
+        if let Some(slice) = self.as_mut_slice() {
+            if src.end <= slice.len() && dest_end.is_some_and(|end| end <= slice.len()) {
+                slice.copy_within(src, dest);
+                return Ok(());
+            }
+        }
+
+        // Copy backwards if the destination overlaps the end of the source,
+        // so no byte is overwritten before it was copied.
+        let mut buf = [0u8; 256];
+        let backwards = dest > src.start;
+        let mut done = 0;
+        while done < len {
+            let n = (len - done).min(buf.len());
+            let offset = if backwards { len - done - n } else { done };
+            self.try_read_into(src.start + offset, &mut buf[..n])?;
+            self.try_write_from(dest + offset, &buf[..n])?;
+            done += n;
+        }
+        Ok(())
+    }
+
+    /// Copies the bytes inside the `src` range to `dest`.
+    ///
+    /// Panics if the method failed to access one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_copy_within` instead")
+    )]
+    fn copy_within(&mut self, src: Range<usize>, dest: usize) {
+        or_panic!(
+            self.try_copy_within(src.clone(), dest),
+            "copy {:#x}..{:#x} to {:#x}",
+            src.start,
+            src.end,
+            dest
+        )
+    }
+
+    /// Tries to find the address of the first occurrence of `byte` inside the given range.
+    ///
+    /// Returns `Ok(None)` if the byte was not found, and `Err(x)` if the method failed
+    /// to read one of the bytes.
+    fn try_find(&self, range: Range<usize>, byte: u8) -> Result<Option<usize>, Self::Error> {
+        if let Some(haystack) = self.as_slice().and_then(|s| s.get(range.clone())) {
+            return Ok(bulk::find_byte(haystack, byte).map(|idx| range.start + idx));
+        }
+
+        let mut buf = [0u8; 256];
+        let mut addr = range.start;
+        while addr < range.end {
+            let buf = &mut buf[..(range.end - addr).min(256)];
+            self.try_read_into(addr, buf)?;
+            if let Some(idx) = bulk::find_byte(buf, byte) {
+                return Ok(Some(addr + idx));
+            }
+            addr += buf.len();
+        }
+        Ok(None)
+    }
+
+    /// Finds the address of the first occurrence of `byte` inside the given range.
+    ///
+    /// Panics if the method failed to read one of the bytes.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_find` instead"))]
+    fn find(&self, range: Range<usize>, byte: u8) -> Option<usize> {
+        or_panic!(
+            self.try_find(range.clone(), byte),
+            "search {:#x}..{:#x}",
+            range.start,
+            range.end
+        )
+    }
+
+    /// Orders the accesses to this memory, like a `FENCE` or `DMB` instruction of
+    /// the emulated CPU.
+    ///
+    /// The default implementation does nothing, which is correct for every memory that
+    /// is only accessed through `self`. Memories that are shared with other threads,
+    /// processes or devices emit a fence with the given ordering, where
+    /// [`Ordering::Relaxed`] never emits a fence.
+    fn fence(&self, order: Ordering) {
+        let _ = order;
+    }
+
+    /// Tries to read a generic `Value` at the given address using the byte order `E`.
+    ///
+    /// Returns `Err(x)` if the method failed to read a value at the address.
+    fn try_read_with<V: Value, E: Endianness>(&self, addr: usize) -> Result<V, Self::Error> {
+        let mut bytes = [0u8; 16];
+        let bytes = &mut bytes[..core::mem::size_of::<V>()];
+        self.try_read_into(addr, bytes)?;
+        Ok(E::from_slice(bytes))
+    }
+
+    /// Reads a generic `Value` at the given address using the byte order `E`.
+    ///
+    /// Panics if the method failed to read a value at the address.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read_with` instead"))]
+    fn read_with<V: Value, E: Endianness>(&self, addr: usize) -> V {
+        or_panic!(
+            self.try_read_with::<V, E>(addr),
+            "read {} bytes at {:#x}",
+            core::mem::size_of::<V>(),
+            addr
+        )
+    }
+
+    /// Tries to write a generic `Value` to the given address using the byte order `E`.
+    ///
+    /// Returns `Err(x)` if the method failed to write a value to the address.
+    fn try_write_with<V: Value, E: Endianness>(
+        &mut self,
+        addr: usize,
+        val: V,
+    ) -> Result<(), Self::Error> {
+        let mut bytes = [0u8; 16];
+        let bytes = &mut bytes[..core::mem::size_of::<V>()];
+        E::write_bytes(val, bytes);
+        self.try_write_from(addr, bytes)
+    }
+
+    /// Writes a generic `Value` to the given address using the byte order `E`.
+    ///
+    /// Panics if the method failed to write a value to the address.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_write_with` instead")
+    )]
+    fn write_with<V: Value, E: Endianness>(&mut self, addr: usize, val: V) {
+        or_panic!(
+            self.try_write_with::<V, E>(addr, val),
+            "write {} bytes at {:#x}",
+            core::mem::size_of::<V>(),
+            addr
+        )
     }
 
     /// Tries to read a generic `Value` at the given address using little endian format.
     ///
     /// Returns `Err(x)` if the method failed to read a value at the address.
     fn try_read<V: Value>(&self, addr: usize) -> Result<V, Self::Error> {
-        let size = core::mem::size_of::<V>();
-        let slice = self.get(addr..addr + size)?;
-
-        // Safety: `Value` is only implemented for all primitive number types, and can not be implemented
-        // for any other types. Thus a transmute between raw bytes and a `Value` is safe.
-        // The length of the `slice` is checked before this method is called.
-        let value = unsafe {
-            debug_assert_eq!(core::mem::size_of::<V>(), slice.len());
-            let slice = core::slice::from_raw_parts(slice.as_ptr() as *const V, 1);
-            slice[0].to_le()
-        };
-
-        Ok(value)
+        self.try_read_with::<V, LittleEndian>(addr)
     }
 
     /// Reads a generic `Value` at the given address using little endian format.
     ///
     /// Panics if the method failed to read a value at the address.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read` instead"))]
     fn read<V: Value>(&self, addr: usize) -> V {
-        self.try_read::<V>(addr).expect("failed to read memory")
+        or_panic!(
+            self.try_read::<V>(addr),
+            "read {} bytes at {:#x}",
+            core::mem::size_of::<V>(),
+            addr
+        )
     }
 
     /// Tries to read a generic `Value` at the given address using big endian format.
     ///
     /// Returns `Err(x)` if the method failed to read a value at the address.
     fn try_read_be<V: Value>(&self, addr: usize) -> Result<V, Self::Error> {
-        self.try_read(addr).map(Value::to_be)
+        self.try_read_with::<V, BigEndian>(addr)
     }
 
     /// Reads a generic `Value` at the given address using big endian format.
     ///
     /// Panics if the method failed to read a value at the address.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read_be` instead"))]
     fn read_be<V: Value>(&self, addr: usize) -> V {
-        self.read::<V>(addr).to_be()
+        or_panic!(
+            self.try_read_be::<V>(addr),
+            "read {} bytes at {:#x}",
+            core::mem::size_of::<V>(),
+            addr
+        )
     }
 
     /// Tries to write a generic `Value` to the given address using little endian format.
     ///
     /// Returns `Err(x)` if the method failed to write a value to the address.
     fn try_write<V: Value>(&mut self, addr: usize, val: V) -> Result<(), Self::Error> {
-        let size = core::mem::size_of::<V>();
-        let val = val.to_le();
-        let slice = self.get_mut(addr..addr + size)?;
-
-        // Safety: `Value` is only implemented for all primitive number types, and can not be implemented
-        // for any other types. Thus a transmute between raw bytes and a `Value` is safe.
-        let raw_value = unsafe {
-            let ptr: *const V = &val;
-            core::slice::from_raw_parts(ptr as *const u8, size)
-        };
-        slice.copy_from_slice(raw_value);
-        Ok(())
+        self.try_write_with::<V, LittleEndian>(addr, val)
     }
 
     /// Writes a generic `Value` to the given address using little endian format.
     ///
     /// Panics if the method failed to write a value to the address.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_write` instead"))]
     fn write<V: Value>(&mut self, addr: usize, val: V) {
-        self.try_write::<V>(addr, val)
-            .expect("failed to write memory")
+        or_panic!(
+            self.try_write::<V>(addr, val),
+            "write {} bytes at {:#x}",
+            core::mem::size_of::<V>(),
+            addr
+        )
     }
 
     /// Tries to write a generic `Value` to the given address using big endian format.
     ///
     /// Returns `Err(x)` if the method failed to write a value to the address.
     fn try_write_be<V: Value>(&mut self, addr: usize, val: V) -> Result<(), Self::Error> {
-        self.try_write(addr, val.to_be())
+        self.try_write_with::<V, BigEndian>(addr, val)
     }
 
     /// Writes a generic `Value` to the given address using big endian format.
     ///
     /// Panics if the method failed to write a value to the address.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_write_be` instead"))]
     fn write_be<V: Value>(&mut self, addr: usize, val: V) {
-        self.write(addr, val.to_be());
+        or_panic!(
+            self.try_write_be::<V>(addr, val),
+            "write {} bytes at {:#x}",
+            core::mem::size_of::<V>(),
+            addr
+        )
+    }
+
+    /// Tries to read an unsigned integer that is `N` bytes wide at the given address using
+    /// little endian format, e.g. `try_read_uint::<3>(addr)` for a 24-bit address of the
+    /// 65816.
+    ///
+    /// `N` must be at most 16, which is checked at compile time.
+    ///
+    /// Returns `Err(x)` if the method failed to read one of the bytes.
+    fn try_read_uint<const N: usize>(&self, addr: usize) -> Result<u128, Self::Error> {
+        const { assert!(N <= 16, "integer width must be at most 16 bytes") };
+        let mut buf = [0u8; 16];
+        self.try_read_into(addr, &mut buf[..N])?;
+        Ok(u128::from_le_bytes(buf))
+    }
+
+    /// Reads an unsigned integer that is `N` bytes wide at the given address using little
+    /// endian format.
+    ///
+    /// Panics if the method failed to read one of the bytes.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read_uint` instead"))]
+    fn read_uint<const N: usize>(&self, addr: usize) -> u128 {
+        or_panic!(
+            self.try_read_uint::<N>(addr),
+            "read {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to read an unsigned integer that is `N` bytes wide at the given address using
+    /// big endian format.
+    ///
+    /// `N` must be at most 16, which is checked at compile time.
+    ///
+    /// Returns `Err(x)` if the method failed to read one of the bytes.
+    fn try_read_uint_be<const N: usize>(&self, addr: usize) -> Result<u128, Self::Error> {
+        const { assert!(N <= 16, "integer width must be at most 16 bytes") };
+        let mut buf = [0u8; 16];
+        self.try_read_into(addr, &mut buf[16 - N..])?;
+        Ok(u128::from_be_bytes(buf))
+    }
+
+    /// Reads an unsigned integer that is `N` bytes wide at the given address using big
+    /// endian format.
+    ///
+    /// Panics if the method failed to read one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_uint_be` instead")
+    )]
+    fn read_uint_be<const N: usize>(&self, addr: usize) -> u128 {
+        or_panic!(
+            self.try_read_uint_be::<N>(addr),
+            "read {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to write the lower `N` bytes of `val` to the given address using little
+    /// endian format. The upper bytes of `val` are ignored.
+    ///
+    /// `N` must be at most 16, which is checked at compile time.
+    ///
+    /// Returns `Err(x)` if the method failed to write one of the bytes.
+    fn try_write_uint<const N: usize>(
+        &mut self,
+        addr: usize,
+        val: u128,
+    ) -> Result<(), Self::Error> {
+        const { assert!(N <= 16, "integer width must be at most 16 bytes") };
+        self.try_write_from(addr, &val.to_le_bytes()[..N])
+    }
+
+    /// Writes the lower `N` bytes of `val` to the given address using little endian
+    /// format.
+    ///
+    /// Panics if the method failed to write one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_write_uint` instead")
+    )]
+    fn write_uint<const N: usize>(&mut self, addr: usize, val: u128) {
+        or_panic!(
+            self.try_write_uint::<N>(addr, val),
+            "write {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to write the lower `N` bytes of `val` to the given address using big
+    /// endian format. The upper bytes of `val` are ignored.
+    ///
+    /// `N` must be at most 16, which is checked at compile time.
+    ///
+    /// Returns `Err(x)` if the method failed to write one of the bytes.
+    fn try_write_uint_be<const N: usize>(
+        &mut self,
+        addr: usize,
+        val: u128,
+    ) -> Result<(), Self::Error> {
+        const { assert!(N <= 16, "integer width must be at most 16 bytes") };
+        self.try_write_from(addr, &val.to_be_bytes()[16 - N..])
+    }
+
+    /// Writes the lower `N` bytes of `val` to the given address using big endian
+    /// format.
+    ///
+    /// Panics if the method failed to write one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_write_uint_be` instead")
+    )]
+    fn write_uint_be<const N: usize>(&mut self, addr: usize, val: u128) {
+        or_panic!(
+            self.try_write_uint_be::<N>(addr, val),
+            "write {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to read a signed `V` at the given address using little endian format, and
+    /// sign-extends it into the wider type `W`.
+    ///
+    /// This is what the load byte and load halfword instructions of most CPUs do, e.g.
+    /// `read_signed::<i8, i32>(addr)` for `LB` on RISC-V.
+    ///
+    /// Returns `Err(x)` if the method failed to read a value at the address.
+    fn try_read_signed<V: SignedValue, W: From<V>>(&self, addr: usize) -> Result<W, Self::Error> {
+        self.try_read::<V>(addr).map(W::from)
+    }
+
+    /// Reads a signed `V` at the given address using little endian format, and
+    /// sign-extends it into the wider type `W`.
+    ///
+    /// Panics if the method failed to read a value at the address.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_signed` instead")
+    )]
+    fn read_signed<V: SignedValue, W: From<V>>(&self, addr: usize) -> W {
+        or_panic!(
+            self.try_read_signed::<V, W>(addr),
+            "read {} bytes at {:#x}",
+            core::mem::size_of::<V>(),
+            addr
+        )
+    }
+
+    /// Tries to read a signed `V` at the given address using big endian format, and
+    /// sign-extends it into the wider type `W`.
+    ///
+    /// Returns `Err(x)` if the method failed to read a value at the address.
+    fn try_read_signed_be<V: SignedValue, W: From<V>>(
+        &self,
+        addr: usize,
+    ) -> Result<W, Self::Error> {
+        self.try_read_be::<V>(addr).map(W::from)
+    }
+
+    /// Reads a signed `V` at the given address using big endian format, and
+    /// sign-extends it into the wider type `W`.
+    ///
+    /// Panics if the method failed to read a value at the address.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_signed_be` instead")
+    )]
+    fn read_signed_be<V: SignedValue, W: From<V>>(&self, addr: usize) -> W {
+        or_panic!(
+            self.try_read_signed_be::<V, W>(addr),
+            "read {} bytes at {:#x}",
+            core::mem::size_of::<V>(),
+            addr
+        )
+    }
+
+    /// Tries to read a single bit, where bit `n` is bit `n % 8` of the byte at
+    /// `addr + n / 8`.
+    ///
+    /// Returns `Err(x)` if the method failed to read the byte that contains the bit.
+    fn try_read_bit(&self, addr: usize, bit: usize) -> Result<bool, Self::Error> {
+        let byte = self.try_read_byte(addr + bit / 8)?;
+        Ok(byte & (1 << (bit % 8)) != 0)
+    }
+
+    /// Reads a single bit, where bit `n` is bit `n % 8` of the byte at `addr + n / 8`.
+    ///
+    /// Panics if the method failed to read the byte that contains the bit.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read_bit` instead"))]
+    fn read_bit(&self, addr: usize, bit: usize) -> bool {
+        or_panic!(
+            self.try_read_bit(addr, bit),
+            "read bit {} at {:#x}",
+            bit,
+            addr
+        )
+    }
+
+    /// Tries to set or clear a single bit, using a read-modify-write of the byte that
+    /// contains the bit.
+    ///
+    /// Returns `Err(x)` if the method failed to read or write the byte that contains the
+    /// bit.
+    fn try_write_bit(&mut self, addr: usize, bit: usize, value: bool) -> Result<(), Self::Error> {
+        let addr = addr + bit / 8;
+        let mask = 1 << (bit % 8);
+        let byte = self.try_read_byte(addr)?;
+        self.try_write_byte(addr, if value { byte | mask } else { byte & !mask })
+    }
+
+    /// Sets or clears a single bit, using a read-modify-write of the byte that contains
+    /// the bit.
+    ///
+    /// Panics if the method failed to read or write the byte that contains the bit.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_write_bit` instead"))]
+    fn write_bit(&mut self, addr: usize, bit: usize, value: bool) {
+        or_panic!(
+            self.try_write_bit(addr, bit, value),
+            "write bit {} at {:#x}",
+            bit,
+            addr
+        )
+    }
+
+    /// Tries to read the given range of bits, numbered like in
+    /// [`try_read_bit`](Self::try_read_bit), and returns them as an integer whose lowest
+    /// bit is the first bit of the range.
+    ///
+    /// Returns `Err(RangeError::Invalid)` if the range is reversed or wider than 64 bits,
+    /// and `Err(RangeError::Memory(x))` if the method failed to read one of the bytes that
+    /// contain the bits.
+    fn try_read_bits(
+        &self,
+        addr: usize,
+        bits: Range<usize>,
+    ) -> Result<u64, RangeError<Self::Error>> {
+        let (addr, nbytes, shift, mask) = bit_field(addr, bits)?;
+        let mut buf = [0u8; 16];
+        self.try_read_into(addr, &mut buf[..nbytes])
+            .map_err(RangeError::Memory)?;
+        let val = u128::from_le_bytes(buf);
+        Ok(((val >> shift) & mask) as u64)
+    }
+
+    /// Reads the given range of bits, and returns them as an integer whose lowest bit
+    /// is the first bit of the range.
+    ///
+    /// Panics if the range is reversed or wider than 64 bits, or if the method failed to
+    /// read one of the bytes that contain the bits.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read_bits` instead"))]
+    fn read_bits(&self, addr: usize, bits: Range<usize>) -> u64 {
+        or_panic!(
+            self.try_read_bits(addr, bits.clone()),
+            "read bits {:?} at {:#x}",
+            bits,
+            addr
+        )
+    }
+
+    /// Tries to replace the given range of bits with the lowest bits of `val`, using a
+    /// read-modify-write of the bytes that contain the bits. Bits of `val` that do not
+    /// fit into the range are ignored.
+    ///
+    /// Returns `Err(RangeError::Invalid)` if the range is reversed or wider than 64 bits,
+    /// and `Err(RangeError::Memory(x))` if the method failed to read or write one of the
+    /// bytes that contain the bits.
+    fn try_write_bits(
+        &mut self,
+        addr: usize,
+        bits: Range<usize>,
+        val: u64,
+    ) -> Result<(), RangeError<Self::Error>> {
+        let (addr, nbytes, shift, mask) = bit_field(addr, bits)?;
+        let mut buf = [0u8; 16];
+        self.try_read_into(addr, &mut buf[..nbytes])
+            .map_err(RangeError::Memory)?;
+        let old = u128::from_le_bytes(buf);
+        let new = (old & !(mask << shift)) | ((u128::from(val) & mask) << shift);
+        self.try_write_from(addr, &new.to_le_bytes()[..nbytes])
+            .map_err(RangeError::Memory)
+    }
+
+    /// Replaces the given range of bits with the lowest bits of `val`, using a
+    /// read-modify-write of the bytes that contain the bits.
+    ///
+    /// Panics if the range is reversed or wider than 64 bits, or if the method failed to
+    /// read or write one of the bytes that contain the bits.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_write_bits` instead")
+    )]
+    fn write_bits(&mut self, addr: usize, bits: Range<usize>, val: u64) {
+        or_panic!(
+            self.try_write_bits(addr, bits.clone(), val),
+            "write bits {:?} at {:#x}",
+            bits,
+            addr
+        )
+    }
+
+    /// Tries to read a packed BCD value that is `N` bytes wide at the given address, with
+    /// the least significant byte first. Every byte stores two decimal digits, with the
+    /// more significant digit in the upper nibble.
+    ///
+    /// `N` must be at most 8, which is checked at compile time.
+    ///
+    /// Returns `Ok(None)` if one of the nibbles is not a decimal digit, and `Err(x)` if
+    /// the method failed to read one of the bytes.
+    fn try_read_bcd<const N: usize>(&self, addr: usize) -> Result<Option<u64>, Self::Error> {
+        const { assert!(N <= bcd::MAX_LEN, "BCD value must be at most 8 bytes wide") };
+        let mut buf = [0u8; N];
+        self.try_read_into(addr, &mut buf)?;
+        Ok(bcd::decode(&buf))
+    }
+
+    /// Reads a packed BCD value that is `N` bytes wide at the given address, with the
+    /// least significant byte first.
+    ///
+    /// Returns `None` if one of the nibbles is not a decimal digit.
+    /// Panics if the method failed to read one of the bytes.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read_bcd` instead"))]
+    fn read_bcd<const N: usize>(&self, addr: usize) -> Option<u64> {
+        or_panic!(
+            self.try_read_bcd::<N>(addr),
+            "read {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to read a packed BCD value that is `N` bytes wide at the given address, with
+    /// the most significant byte first.
+    ///
+    /// `N` must be at most 8, which is checked at compile time.
+    ///
+    /// Returns `Ok(None)` if one of the nibbles is not a decimal digit, and `Err(x)` if
+    /// the method failed to read one of the bytes.
+    fn try_read_bcd_be<const N: usize>(&self, addr: usize) -> Result<Option<u64>, Self::Error> {
+        const { assert!(N <= bcd::MAX_LEN, "BCD value must be at most 8 bytes wide") };
+        let mut buf = [0u8; N];
+        self.try_read_into(addr, &mut buf)?;
+        buf.reverse();
+        Ok(bcd::decode(&buf))
+    }
+
+    /// Reads a packed BCD value that is `N` bytes wide at the given address, with the
+    /// most significant byte first.
+    ///
+    /// Returns `None` if one of the nibbles is not a decimal digit.
+    /// Panics if the method failed to read one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_bcd_be` instead")
+    )]
+    fn read_bcd_be<const N: usize>(&self, addr: usize) -> Option<u64> {
+        or_panic!(
+            self.try_read_bcd_be::<N>(addr),
+            "read {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to write the lowest `2 * N` decimal digits of `val` as a packed BCD value to
+    /// the given address, with the least significant byte first.
+    ///
+    /// `N` must be at most 8, which is checked at compile time.
+    ///
+    /// Returns `Err(x)` if the method failed to write one of the bytes.
+    fn try_write_bcd<const N: usize>(&mut self, addr: usize, val: u64) -> Result<(), Self::Error> {
+        const { assert!(N <= bcd::MAX_LEN, "BCD value must be at most 8 bytes wide") };
+        let mut buf = [0u8; N];
+        bcd::encode(val, &mut buf);
+        self.try_write_from(addr, &buf)
+    }
+
+    /// Writes the lowest `2 * N` decimal digits of `val` as a packed BCD value to the
+    /// given address, with the least significant byte first.
+    ///
+    /// Panics if the method failed to write one of the bytes.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_write_bcd` instead"))]
+    fn write_bcd<const N: usize>(&mut self, addr: usize, val: u64) {
+        or_panic!(
+            self.try_write_bcd::<N>(addr, val),
+            "write {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to write the lowest `2 * N` decimal digits of `val` as a packed BCD value to
+    /// the given address, with the most significant byte first.
+    ///
+    /// `N` must be at most 8, which is checked at compile time.
+    ///
+    /// Returns `Err(x)` if the method failed to write one of the bytes.
+    fn try_write_bcd_be<const N: usize>(
+        &mut self,
+        addr: usize,
+        val: u64,
+    ) -> Result<(), Self::Error> {
+        const { assert!(N <= bcd::MAX_LEN, "BCD value must be at most 8 bytes wide") };
+        let mut buf = [0u8; N];
+        bcd::encode(val, &mut buf);
+        buf.reverse();
+        self.try_write_from(addr, &buf)
+    }
+
+    /// Writes the lowest `2 * N` decimal digits of `val` as a packed BCD value to the
+    /// given address, with the most significant byte first.
+    ///
+    /// Panics if the method failed to write one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_write_bcd_be` instead")
+    )]
+    fn write_bcd_be<const N: usize>(&mut self, addr: usize, val: u64) {
+        or_panic!(
+            self.try_write_bcd_be::<N>(addr, val),
+            "write {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to increment the packed BCD value that is `N` bytes wide at the given
+    /// address, with the least significant byte first, carrying into the next digit
+    /// like a decimal counter.
+    ///
+    /// `N` must be at most 8, which is checked at compile time.
+    ///
+    /// Returns `Ok(true)` if the value wrapped around to zero, and `Err(x)` if the method
+    /// failed to read or write one of the bytes. Nibbles that are not a decimal digit
+    /// are treated like a nine.
+    fn try_increment_bcd<const N: usize>(&mut self, addr: usize) -> Result<bool, Self::Error> {
+        const { assert!(N <= bcd::MAX_LEN, "BCD value must be at most 8 bytes wide") };
+        let mut buf = [0u8; N];
+        self.try_read_into(addr, &mut buf)?;
+        let carry = bcd::increment(&mut buf);
+        self.try_write_from(addr, &buf)?;
+        Ok(carry)
+    }
+
+    /// Increments the packed BCD value that is `N` bytes wide at the given address, with
+    /// the least significant byte first, and returns `true` if it wrapped around to zero.
+    ///
+    /// Panics if the method failed to read or write one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_increment_bcd` instead")
+    )]
+    fn increment_bcd<const N: usize>(&mut self, addr: usize) -> bool {
+        or_panic!(
+            self.try_increment_bcd::<N>(addr),
+            "update {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to increment the packed BCD value that is `N` bytes wide at the given
+    /// address, with the most significant byte first, carrying into the next digit
+    /// like a decimal counter.
+    ///
+    /// `N` must be at most 8, which is checked at compile time.
+    ///
+    /// Returns `Ok(true)` if the value wrapped around to zero, and `Err(x)` if the method
+    /// failed to read or write one of the bytes. Nibbles that are not a decimal digit
+    /// are treated like a nine.
+    fn try_increment_bcd_be<const N: usize>(&mut self, addr: usize) -> Result<bool, Self::Error> {
+        const { assert!(N <= bcd::MAX_LEN, "BCD value must be at most 8 bytes wide") };
+        let mut buf = [0u8; N];
+        self.try_read_into(addr, &mut buf)?;
+        buf.reverse();
+        let carry = bcd::increment(&mut buf);
+        buf.reverse();
+        self.try_write_from(addr, &buf)?;
+        Ok(carry)
+    }
+
+    /// Increments the packed BCD value that is `N` bytes wide at the given address, with
+    /// the most significant byte first, and returns `true` if it wrapped around to zero.
+    ///
+    /// Panics if the method failed to read or write one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_increment_bcd_be` instead")
+    )]
+    fn increment_bcd_be<const N: usize>(&mut self, addr: usize) -> bool {
+        or_panic!(
+            self.try_increment_bcd_be::<N>(addr),
+            "update {} bytes at {:#x}",
+            N,
+            addr
+        )
+    }
+
+    /// Tries to read a non-zero value at the given address using little endian format.
+    ///
+    /// This is useful for reading handles or pointers that must not be null.
+    ///
+    /// Returns `Ok(None)` if the value is zero, and `Err(x)` if the method failed to read
+    /// a value at the address.
+    fn try_read_nonzero<N: NonZeroValue>(&self, addr: usize) -> Result<Option<N>, Self::Error> {
+        self.try_read::<N::Value>(addr).map(N::new)
+    }
+
+    /// Reads a non-zero value at the given address using little endian format.
+    ///
+    /// Returns `None` if the value is zero.
+    /// Panics if the method failed to read a value at the address.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_nonzero` instead")
+    )]
+    fn read_nonzero<N: NonZeroValue>(&self, addr: usize) -> Option<N> {
+        or_panic!(
+            self.try_read_nonzero::<N>(addr),
+            "read {} bytes at {:#x}",
+            core::mem::size_of::<N>(),
+            addr
+        )
+    }
+
+    /// Tries to read a non-zero value at the given address using big endian format.
+    ///
+    /// Returns `Ok(None)` if the value is zero, and `Err(x)` if the method failed to read
+    /// a value at the address.
+    fn try_read_nonzero_be<N: NonZeroValue>(&self, addr: usize) -> Result<Option<N>, Self::Error> {
+        self.try_read_be::<N::Value>(addr).map(N::new)
+    }
+
+    /// Reads a non-zero value at the given address using big endian format.
+    ///
+    /// Returns `None` if the value is zero.
+    /// Panics if the method failed to read a value at the address.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_nonzero_be` instead")
+    )]
+    fn read_nonzero_be<N: NonZeroValue>(&self, addr: usize) -> Option<N> {
+        or_panic!(
+            self.try_read_nonzero_be::<N>(addr),
+            "read {} bytes at {:#x}",
+            core::mem::size_of::<N>(),
+            addr
+        )
+    }
+
+    /// Tries to read an unsigned LEB128 value at the given address, like the ones used by
+    /// DWARF and WebAssembly.
+    ///
+    /// Returns the value together with the number of bytes it occupies, `Ok(None)` if the
+    /// value does not fit into a `u64`, and `Err(x)` if the method failed to read one of
+    /// the bytes.
+    fn try_read_uleb128(&self, addr: usize) -> Result<Option<(u64, usize)>, Self::Error> {
+        leb128::decode_unsigned(|idx| self.try_read_byte(addr + idx))
+    }
+
+    /// Reads an unsigned LEB128 value at the given address, and returns it together with
+    /// the number of bytes it occupies.
+    ///
+    /// Returns `None` if the value does not fit into a `u64`.
+    /// Panics if the method failed to read one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_uleb128` instead")
+    )]
+    fn read_uleb128(&self, addr: usize) -> Option<(u64, usize)> {
+        or_panic!(
+            self.try_read_uleb128(addr),
+            "read LEB128 value at {:#x}",
+            addr
+        )
+    }
+
+    /// Tries to read a signed LEB128 value at the given address.
+    ///
+    /// Returns the value together with the number of bytes it occupies, `Ok(None)` if the
+    /// value does not fit into an `i64`, and `Err(x)` if the method failed to read one of
+    /// the bytes.
+    fn try_read_sleb128(&self, addr: usize) -> Result<Option<(i64, usize)>, Self::Error> {
+        leb128::decode_signed(|idx| self.try_read_byte(addr + idx))
+    }
+
+    /// Reads a signed LEB128 value at the given address, and returns it together with
+    /// the number of bytes it occupies.
+    ///
+    /// Returns `None` if the value does not fit into an `i64`.
+    /// Panics if the method failed to read one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_sleb128` instead")
+    )]
+    fn read_sleb128(&self, addr: usize) -> Option<(i64, usize)> {
+        or_panic!(
+            self.try_read_sleb128(addr),
+            "read LEB128 value at {:#x}",
+            addr
+        )
+    }
+
+    /// Tries to write `val` as an unsigned LEB128 value at the given address, and returns
+    /// the number of bytes that were written.
+    ///
+    /// Returns `Err(x)` if the method failed to write one of the bytes.
+    fn try_write_uleb128(&mut self, addr: usize, val: u64) -> Result<usize, Self::Error> {
+        let mut buf = [0u8; leb128::MAX_LEN];
+        let len = leb128::encode_unsigned(val, &mut buf);
+        self.try_write_from(addr, &buf[..len])?;
+        Ok(len)
+    }
+
+    /// Writes `val` as an unsigned LEB128 value at the given address, and returns the
+    /// number of bytes that were written.
+    ///
+    /// Panics if the method failed to write one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_write_uleb128` instead")
+    )]
+    fn write_uleb128(&mut self, addr: usize, val: u64) -> usize {
+        or_panic!(
+            self.try_write_uleb128(addr, val),
+            "write LEB128 value at {:#x}",
+            addr
+        )
+    }
+
+    /// Tries to write `val` as a signed LEB128 value at the given address, and returns
+    /// the number of bytes that were written.
+    ///
+    /// Returns `Err(x)` if the method failed to write one of the bytes.
+    fn try_write_sleb128(&mut self, addr: usize, val: i64) -> Result<usize, Self::Error> {
+        let mut buf = [0u8; leb128::MAX_LEN];
+        let len = leb128::encode_signed(val, &mut buf);
+        self.try_write_from(addr, &buf[..len])?;
+        Ok(len)
+    }
+
+    /// Writes `val` as a signed LEB128 value at the given address, and returns the
+    /// number of bytes that were written.
+    ///
+    /// Panics if the method failed to write one of the bytes.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_write_sleb128` instead")
+    )]
+    fn write_sleb128(&mut self, addr: usize, val: i64) -> usize {
+        or_panic!(
+            self.try_write_sleb128(addr, val),
+            "write LEB128 value at {:#x}",
+            addr
+        )
+    }
+
+    /// Returns an iterator that reads all `V`s inside the given range using little endian format.
+    ///
+    /// Use [`ValueReader::big_endian`] to read the values using big endian format instead.
+    fn values<V: Value>(&self, range: Range<usize>) -> ValueReader<'_, Self, V> {
+        ValueReader::new(self, range.start, range.end)
+    }
+
+    /// Returns an iterator that copies the given range in chunks of `N` bytes.
+    ///
+    /// The last chunk is shorter than `N` bytes, if the length of the range is not a
+    /// multiple of `N`.
+    ///
+    /// Panics if `N` is zero.
+    fn chunks<const N: usize>(&self, range: Range<usize>) -> Chunks<'_, Self, N> {
+        Chunks::new(self, range, false)
+    }
+
+    /// Returns an iterator that copies the given range in pages of `N` bytes.
+    ///
+    /// In contrast to [`chunks`](Self::chunks), the range is split at every address that is
+    /// a multiple of `N`, so the first and last page are shorter if the range is not aligned.
+    ///
+    /// Panics if `N` is zero.
+    fn pages<const N: usize>(&self, range: Range<usize>) -> Chunks<'_, Self, N> {
+        Chunks::new(self, range, true)
+    }
+
+    /// Tries to swap the byte order of every `V` inside the given range in place.
+    ///
+    /// This can be used to convert a loaded ROM image or a framebuffer between little and
+    /// big endian format.
+    ///
+    /// Returns `Err(RangeError::Invalid)` if the length of the range is not a multiple of
+    /// the size of `V`, and `Err(RangeError::Memory(x))` if the method failed to access
+    /// the range.
+    fn try_swap_endianness<V: Value>(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), RangeError<Self::Error>> {
+        let size = core::mem::size_of::<V>();
+        if !range.len().is_multiple_of(size) {
+            return Err(RangeError::Invalid {
+                start: range.start,
+                end: range.end,
+            });
+        }
+
+        if let Some(slice) = self.as_mut_slice().and_then(|s| s.get_mut(range.clone())) {
+            // Swapping whole values instead of reversing every chunk byte by byte allows
+            // the compiler to vectorize this loop.
+            for chunk in slice.chunks_exact_mut(size) {
+                // Safety: `chunk` is exactly `size_of::<V>()` bytes long and `Value` is only
+                // implemented for primitive number types, which are valid for any bit pattern.
+                #[cfg(not(feature = "forbid-unsafe"))]
+                unsafe {
+                    let ptr = chunk.as_mut_ptr() as *mut V;
+                    ptr.write_unaligned(ptr.read_unaligned().swap_bytes());
+                }
+                #[cfg(feature = "forbid-unsafe")]
+                V::from_be_slice(chunk).write_le_bytes(chunk);
+            }
+            return Ok(());
+        }
+
+        for addr in range.step_by(size) {
+            let val = self.try_read::<V>(addr).map_err(RangeError::Memory)?;
+            self.try_write(addr, val.swap_bytes())
+                .map_err(RangeError::Memory)?;
+        }
+        Ok(())
+    }
+
+    /// Swaps the byte order of every `V` inside the given range in place.
+    ///
+    /// Panics if the method failed to access the range, or if the length of the range is not
+    /// a multiple of the size of `V`.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_swap_endianness` instead")
+    )]
+    fn swap_endianness<V: Value>(&mut self, range: Range<usize>) {
+        or_panic!(
+            self.try_swap_endianness::<V>(range.clone()),
+            "swap endianness of {:#x}..{:#x}",
+            range.start,
+            range.end
+        )
+    }
+
+    /// Tries to read a NUL-terminated UTF-16 string at the given address using little
+    /// endian format, like the wide strings of Windows and UEFI.
+    ///
+    /// At most `max_chars` code units are read, so the string is truncated if there is
+    /// no NUL terminator before. Unpaired surrogates are replaced with `U+FFFD`.
+    ///
+    /// Returns `Err(x)` if the method failed to read one of the code units.
+    #[cfg(feature = "alloc")]
+    fn try_read_utf16_str(
+        &self,
+        addr: usize,
+        max_chars: usize,
+    ) -> Result<alloc::string::String, Self::Error> {
+        utf16::read_str::<Self, LittleEndian>(self, addr, max_chars)
+    }
+
+    /// Reads a NUL-terminated UTF-16 string of at most `max_chars` code units at the
+    /// given address using little endian format.
+    ///
+    /// Panics if the method failed to read one of the code units.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_utf16_str` instead")
+    )]
+    fn read_utf16_str(&self, addr: usize, max_chars: usize) -> alloc::string::String {
+        or_panic!(
+            self.try_read_utf16_str(addr, max_chars),
+            "read UTF-16 string at {:#x}",
+            addr
+        )
+    }
+
+    /// Tries to read a NUL-terminated UTF-16 string at the given address using big
+    /// endian format.
+    ///
+    /// At most `max_chars` code units are read, so the string is truncated if there is
+    /// no NUL terminator before. Unpaired surrogates are replaced with `U+FFFD`.
+    ///
+    /// Returns `Err(x)` if the method failed to read one of the code units.
+    #[cfg(feature = "alloc")]
+    fn try_read_utf16_str_be(
+        &self,
+        addr: usize,
+        max_chars: usize,
+    ) -> Result<alloc::string::String, Self::Error> {
+        utf16::read_str::<Self, BigEndian>(self, addr, max_chars)
+    }
+
+    /// Reads a NUL-terminated UTF-16 string of at most `max_chars` code units at the
+    /// given address using big endian format.
+    ///
+    /// Panics if the method failed to read one of the code units.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_read_utf16_str_be` instead")
+    )]
+    fn read_utf16_str_be(&self, addr: usize, max_chars: usize) -> alloc::string::String {
+        or_panic!(
+            self.try_read_utf16_str_be(addr, max_chars),
+            "read UTF-16 string at {:#x}",
+            addr
+        )
+    }
+}
+
+/// A `MemoryStorage` whose bytes are stored in one contiguous slice, which allows
+/// handing out references to the stored bytes.
+///
+/// Implementors provide the slice and the error for indices outside of it, and get
+/// every other method for free.
+pub trait ContiguousMemory: MemoryStorage {
+    /// Returns the bytes of this memory, starting at address zero.
+    fn as_bytes(&self) -> &[u8];
+
+    /// Returns the bytes of this memory mutably, starting at address zero.
+    fn as_bytes_mut(&mut self) -> &mut [u8];
+
+    /// Returns the error that is reported for an access at `addr`, which is outside of
+    /// the memory.
+    fn out_of_bounds(&self, addr: usize) -> Self::Error;
+
+    /// Returns a reference to an element or subslice depending on the type of
+    /// index.
+    ///
+    /// Returns the [`out_of_bounds`](Self::out_of_bounds) error for the first byte after
+    /// the end of the memory if the index is out of bounds.
+    fn get<I>(&self, index: I) -> Result<&I::Output, Self::Error>
+    where
+        I: SliceIndex<[u8]>,
+    {
+        let slice = self.as_bytes();
+        match slice.get(index) {
+            Some(output) => Ok(output),
+            None => Err(self.out_of_bounds(slice.len())),
+        }
+    }
+
+    /// Returns a mutable reference to an element or subslice depending on the type of
+    /// index.
+    ///
+    /// Returns the [`out_of_bounds`](Self::out_of_bounds) error for the first byte after
+    /// the end of the memory if the index is out of bounds.
+    fn get_mut<I>(&mut self, index: I) -> Result<&mut I::Output, Self::Error>
+    where
+        I: SliceIndex<[u8]>,
+    {
+        // The error has to be created up front, because the borrow checker does not
+        // allow borrowing `self` again after the lookup failed.
+        let err = self.out_of_bounds(self.as_bytes().len());
+        self.as_bytes_mut().get_mut(index).ok_or(err)
+    }
+
+    /// Tries to split the given range into slices of `size` bytes, without copying them.
+    ///
+    /// The last slice is shorter than `size` bytes, if the length of the range is not a
+    /// multiple of `size`.
+    ///
+    /// Returns `Err(x)` if the method failed to access the range.
+    /// Panics if `size` is zero.
+    fn try_chunks_ref(
+        &self,
+        range: Range<usize>,
+        size: usize,
+    ) -> Result<core::slice::Chunks<'_, u8>, Self::Error> {
+        Ok(self.get(range)?.chunks(size))
+    }
+
+    /// Returns the host pointer to the first byte of this memory, together with its length,
+    /// so generated code can access the memory directly.
+    ///
+    /// The region starts at address zero. This method does not access the memory, but the
+    /// returned pointer is only useful in unsafe code, which has to uphold the following rules.
+    ///
+    /// # Safety
+    ///
+    /// The pointer is invalidated if the memory is moved or dropped, or if a method that
+    /// may reallocate the memory is called. The bytes must not be accessed through the
+    /// pointer while a reference into the memory exists, e.g. one that was returned by
+    /// [`get`](Self::get), and accesses through the pointer must not race with each other.
+    #[cfg(not(feature = "forbid-unsafe"))]
+    unsafe fn host_region(&mut self) -> HostRegion {
+        let slice = self.as_bytes_mut();
+        HostRegion {
+            base: 0,
+            host: slice.as_mut_ptr(),
+            len: slice.len(),
+        }
+    }
+
+    /// Tries to overwrite the given range with zeros, in a way that is guaranteed
+    /// to not be optimized away by the compiler.
+    ///
+    /// Use this to wipe key material or other secrets stored inside the memory.
+    ///
+    /// Returns `Err(x)` if the method failed to access the range.
+    #[cfg(feature = "zeroize")]
+    fn try_secure_clear(&mut self, range: Range<usize>) -> Result<(), Self::Error> {
+        use zeroize::Zeroize;
+
+        self.get_mut(range)?.zeroize();
+        Ok(())
+    }
+
+    /// Overwrites the given range with zeros, in a way that is guaranteed
+    /// to not be optimized away by the compiler.
+    ///
+    /// Panics if the method failed to access the range.
+    #[cfg(feature = "zeroize")]
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_secure_clear` instead")
+    )]
+    fn secure_clear(&mut self, range: Range<usize>) {
+        or_panic!(
+            self.try_secure_clear(range.clone()),
+            "clear {:#x}..{:#x}",
+            range.start,
+            range.end
+        )
     }
 }
 
+/// Returns the range of `len` bytes starting at `addr`, or `None` if the range overflows.
+fn slice_range(addr: usize, len: usize) -> Option<Range<usize>> {
+    Some(addr..addr.checked_add(len)?)
+}
+
+/// Emits an atomic fence with the given ordering, unless the ordering is `Relaxed`.
+#[cfg(not(feature = "forbid-unsafe"))]
+fn atomic_fence(order: Ordering) {
+    if order != Ordering::Relaxed {
+        core::sync::atomic::fence(order);
+    }
+}
+
+/// Panics with a message that describes a failed access and its error.
+#[cold]
+#[track_caller]
+fn access_failed(access: core::fmt::Arguments<'_>, err: &dyn core::fmt::Debug) -> ! {
+    panic!("failed to {}: {:?}", access, err)
+}
+
+/// Returns the address and number of bytes that contain the given range of bits, together
+/// with the shift and mask that extract the bits out of these bytes.
+///
+/// Returns `Err(RangeError::Invalid)` if the range is reversed, wider than 64 bits, or
+/// starts after the end of the address space.
+fn bit_field<E>(
+    addr: usize,
+    bits: Range<usize>,
+) -> Result<(usize, usize, usize, u128), RangeError<E>> {
+    let invalid = RangeError::Invalid {
+        start: bits.start,
+        end: bits.end,
+    };
+    if bits.start > bits.end || bits.end - bits.start > 64 {
+        return Err(invalid);
+    }
+    let addr = addr.checked_add(bits.start / 8).ok_or(invalid)?;
+    let nbytes = bits.end.div_ceil(8) - bits.start / 8;
+    let mask = (1u128 << (bits.end - bits.start)) - 1;
+    Ok((addr, nbytes, bits.start % 8, mask))
+}
+
 macro_rules! impl_trait {
     ($($ty:path),*) => {
         $(
@@ -235,6 +1825,28 @@ macro_rules! impl_trait {
                 fn to_be(self) -> Self {
                     self.to_be()
                 }
+
+                fn swap_bytes(self) -> Self {
+                    self.swap_bytes()
+                }
+
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    let bytes = bytes.try_into().expect("slice length must match the value size");
+                    Self::from_le_bytes(bytes)
+                }
+
+                fn from_be_slice(bytes: &[u8]) -> Self {
+                    let bytes = bytes.try_into().expect("slice length must match the value size");
+                    Self::from_be_bytes(bytes)
+                }
+
+                fn write_le_bytes(self, out: &mut [u8]) {
+                    out.copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn write_be_bytes(self, out: &mut [u8]) {
+                    out.copy_from_slice(&self.to_be_bytes());
+                }
             }
         )*
     };
@@ -248,10 +1860,85 @@ pub trait Value: private::Sealed + Sized + Copy {
 
     /// Converts `self` to big endian format.
     fn to_be(self) -> Self;
+
+    /// Reverses the byte order of `self`.
+    fn swap_bytes(self) -> Self;
+
+    /// Creates a value from its little endian representation.
+    ///
+    /// Panics if the length of `bytes` is not equal to the size of `Self`.
+    fn from_le_slice(bytes: &[u8]) -> Self;
+
+    /// Creates a value from its big endian representation.
+    ///
+    /// Panics if the length of `bytes` is not equal to the size of `Self`.
+    fn from_be_slice(bytes: &[u8]) -> Self;
+
+    /// Writes the little endian representation of `self` into `out`.
+    ///
+    /// Panics if the length of `out` is not equal to the size of `Self`.
+    fn write_le_bytes(self, out: &mut [u8]);
+
+    /// Writes the big endian representation of `self` into `out`.
+    ///
+    /// Panics if the length of `out` is not equal to the size of `Self`.
+    fn write_be_bytes(self, out: &mut [u8]);
 }
 
 impl_trait!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
 
+/// A marker trait that is implemented for the non-zero integer types, like [`NonZeroU32`].
+///
+/// Non-zero values are read using [`MemoryStorage::try_read_nonzero`], and written by
+/// writing the underlying [`Value`].
+///
+/// [`NonZeroU32`]: core::num::NonZeroU32
+pub trait NonZeroValue: private::Sealed + Sized + Copy {
+    /// The underlying number type.
+    type Value: Value;
+
+    /// Creates a non-zero value, or returns `None` if `val` is zero.
+    fn new(val: Self::Value) -> Option<Self>;
+
+    /// Returns the underlying number.
+    fn get(self) -> Self::Value;
+}
+
+macro_rules! impl_nonzero {
+    ($($ty:ident => $val:ty),*) => {
+        $(
+            impl NonZeroValue for core::num::$ty {
+                type Value = $val;
+
+                fn new(val: $val) -> Option<Self> {
+                    Self::new(val)
+                }
+
+                fn get(self) -> $val {
+                    self.get()
+                }
+            }
+        )*
+    };
+}
+
+impl_nonzero!(
+    NonZeroU8 => u8, NonZeroI8 => i8, NonZeroU16 => u16, NonZeroI16 => i16,
+    NonZeroU32 => u32, NonZeroI32 => i32, NonZeroU64 => u64, NonZeroI64 => i64,
+    NonZeroU128 => u128, NonZeroI128 => i128
+);
+
+/// A marker trait that is implemented for the signed number types.
+///
+/// Signed values are read and sign-extended using [`MemoryStorage::try_read_signed`].
+pub trait SignedValue: Value {}
+
+impl SignedValue for i8 {}
+impl SignedValue for i16 {}
+impl SignedValue for i32 {}
+impl SignedValue for i64 {}
+impl SignedValue for i128 {}
+
 mod private {
     pub trait Sealed {}
 
@@ -262,4 +1949,19 @@ mod private {
     }
 
     impl_trait!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
+    impl_trait!(crate::LittleEndian, crate::BigEndian);
+
+    use core::num::*;
+    impl_trait!(
+        NonZeroU8,
+        NonZeroI8,
+        NonZeroU16,
+        NonZeroI16,
+        NonZeroU32,
+        NonZeroI32,
+        NonZeroU64,
+        NonZeroI64,
+        NonZeroU128,
+        NonZeroI128
+    );
 }
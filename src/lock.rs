@@ -0,0 +1,177 @@
+use crate::{LockError, MemoryStorage};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// The ranges that are currently locked, which are shared with every [`RegionGuard`].
+type Locks = Rc<RefCell<Vec<Range<usize>>>>;
+
+/// A wrapper that allows locking ranges of the inner memory, e.g. while a DMA transfer
+/// is in flight or a buffer is owned by a device.
+///
+/// A range is locked by [`lock_range`](Self::lock_range), which returns a
+/// [`RegionGuard`] that keeps the range locked until it is dropped. Accesses through this
+/// wrapper that touch a locked range fail with [`LockError::Locked`], and are not
+/// forwarded to the inner memory. The owner of the lock accesses the range using
+/// [`inner_mut`](Self::inner_mut).
+pub struct LockableMemory<M> {
+    inner: M,
+    locks: Locks,
+}
+
+impl<M: MemoryStorage> LockableMemory<M> {
+    /// Creates a new `LockableMemory` without any locked ranges.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            locks: Rc::default(),
+        }
+    }
+
+    /// Tries to lock the given range until the returned guard is dropped.
+    ///
+    /// Returns `None` if the range overlaps a range that is already locked.
+    pub fn try_lock_range(&self, range: Range<usize>) -> Option<RegionGuard> {
+        if self.is_locked(range.clone()) {
+            return None;
+        }
+
+        self.locks.borrow_mut().push(range.clone());
+        Some(RegionGuard {
+            locks: Rc::clone(&self.locks),
+            range,
+        })
+    }
+
+    /// Locks the given range until the returned guard is dropped.
+    ///
+    /// Panics if the range overlaps a range that is already locked.
+    #[cfg_attr(
+        feature = "no-panic",
+        deprecated(note = "use `try_lock_range` instead")
+    )]
+    pub fn lock_range(&self, range: Range<usize>) -> RegionGuard {
+        match self.try_lock_range(range.clone()) {
+            Some(guard) => guard,
+            None => panic!("range {:#x?} is already locked", range),
+        }
+    }
+
+    /// Returns `true` if the given range overlaps a locked range.
+    pub fn is_locked(&self, range: Range<usize>) -> bool {
+        self.locks
+            .borrow()
+            .iter()
+            .any(|locked| range.start < locked.end && locked.start < range.end)
+    }
+
+    /// Returns a reference to the inner memory, which ignores the locked ranges.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory, which ignores the locked ranges.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `LockableMemory` and returns the inner memory.
+    ///
+    /// Guards that are still alive do not lock anything anymore.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Returns an error if the access of `len` bytes at `addr` touches a locked range.
+    fn check(&self, addr: usize, len: usize) -> Result<(), LockError<M::Error>> {
+        let end = addr.saturating_add(len.max(1));
+        if self.is_locked(addr..end) {
+            return Err(LockError::Locked { addr });
+        }
+        Ok(())
+    }
+}
+
+impl<M: MemoryStorage> MemoryStorage for LockableMemory<M> {
+    type Error = LockError<M::Error>;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.check(addr, 1)?;
+        self.inner.try_read_byte(addr).map_err(LockError::Memory)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.check(addr, 1)?;
+        self.inner
+            .try_write_byte(addr, byte)
+            .map_err(LockError::Memory)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+        self.inner
+            .try_read_into(addr, buf)
+            .map_err(LockError::Memory)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+        self.inner
+            .try_write_from(addr, buf)
+            .map_err(LockError::Memory)
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M> core::fmt::Debug for LockableMemory<M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LockableMemory")
+            .field("locks", &self.locks.borrow())
+            .finish()
+    }
+}
+
+/// A guard that keeps a range of a [`LockableMemory`] locked until it is dropped.
+///
+/// Created by [`LockableMemory::lock_range`].
+pub struct RegionGuard {
+    locks: Locks,
+    range: Range<usize>,
+}
+
+impl RegionGuard {
+    /// Returns the range that is locked by this guard.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+impl Drop for RegionGuard {
+    fn drop(&mut self) {
+        let mut locks = self.locks.borrow_mut();
+        if let Some(idx) = locks.iter().position(|range| *range == self.range) {
+            locks.swap_remove(idx);
+        }
+    }
+}
+
+impl core::fmt::Debug for RegionGuard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RegionGuard")
+            .field("range", &self.range)
+            .finish()
+    }
+}
@@ -0,0 +1,285 @@
+//! Game Boy memory bank controllers (MBCs).
+
+use crate::{MemoryStorage, OutOfBounds};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::ops::Range;
+
+const ROM_BANK_SIZE: usize = 0x4000;
+const RAM_BANK_SIZE: usize = 0x2000;
+const MBC2_RAM_SIZE: usize = 0x200;
+
+/// The type of a memory bank controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MbcKind {
+    /// The MBC1, which supports up to 2 MiB ROM and 32 KiB RAM.
+    Mbc1,
+    /// The MBC2, which supports up to 256 KiB ROM and has 512 half-bytes of built-in RAM.
+    Mbc2,
+    /// The MBC3, which supports up to 2 MiB ROM, 32 KiB RAM and a real time clock.
+    Mbc3,
+    /// The MBC5, which supports up to 8 MiB ROM and 128 KiB RAM.
+    Mbc5,
+}
+
+impl MbcKind {
+    /// Returns the controller type from the cartridge type byte at `0x147` of the ROM header,
+    /// or `None` if the cartridge does not use one of the supported controllers.
+    pub fn from_cartridge_type(ty: u8) -> Option<Self> {
+        match ty {
+            0x01..=0x03 => Some(MbcKind::Mbc1),
+            0x05..=0x06 => Some(MbcKind::Mbc2),
+            0x0F..=0x13 => Some(MbcKind::Mbc3),
+            0x19..=0x1E => Some(MbcKind::Mbc5),
+            _ => None,
+        }
+    }
+}
+
+/// A Game Boy cartridge with a memory bank controller.
+///
+/// The memory covers the cartridge part of the address space: the ROM at `0x0000..0x8000`,
+/// whose writes select the banks, and the external RAM at `0xA000..0xC000`. Every other
+/// address is out of bounds. Reading disabled or missing RAM returns `0xFF`.
+///
+/// The real time clock of the MBC3 does not advance by itself. The emulator sets the live
+/// clock registers using [`set_rtc`](Self::set_rtc).
+#[derive(Clone)]
+pub struct Mbc {
+    kind: MbcKind,
+    rom: Box<[u8]>,
+    ram: Box<[u8]>,
+    ram_enabled: bool,
+    /// The low ROM bank register (`0x2000..0x4000`).
+    bank1: usize,
+    /// The RAM bank, upper ROM bank or RTC register select (`0x4000..0x6000`).
+    bank2: usize,
+    /// The MBC1 banking mode, or the MBC3 latch register.
+    mode: u8,
+    rtc: [u8; 5],
+    latched_rtc: [u8; 5],
+}
+
+impl Mbc {
+    /// Creates a new controller of the given type, with `ram_size` bytes of external RAM.
+    ///
+    /// The MBC2 always uses its 512 bytes of built-in RAM, so `ram_size` is ignored.
+    pub fn new(kind: MbcKind, rom: Vec<u8>, ram_size: usize) -> Self {
+        let ram_size = match kind {
+            MbcKind::Mbc2 => MBC2_RAM_SIZE,
+            _ => ram_size,
+        };
+
+        Self {
+            kind,
+            rom: rom.into_boxed_slice(),
+            ram: vec![0; ram_size].into_boxed_slice(),
+            ram_enabled: false,
+            bank1: 1,
+            bank2: 0,
+            mode: 0,
+            rtc: [0; 5],
+            latched_rtc: [0; 5],
+        }
+    }
+
+    /// Creates a new controller, whose type and RAM size are read from the ROM header.
+    ///
+    /// Returns `None` if the ROM is too short to contain a header, or if it does not use
+    /// one of the supported controllers.
+    pub fn from_rom(rom: Vec<u8>) -> Option<Self> {
+        let kind = MbcKind::from_cartridge_type(*rom.get(0x147)?)?;
+        let ram_size = match rom.get(0x149)? {
+            0x02 => 0x2000,
+            0x03 => 0x8000,
+            0x04 => 0x20000,
+            0x05 => 0x10000,
+            _ => 0,
+        };
+        Some(Self::new(kind, rom, ram_size))
+    }
+
+    /// Returns the type of this controller.
+    pub fn kind(&self) -> MbcKind {
+        self.kind
+    }
+
+    /// Returns the ROM of the cartridge.
+    pub fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    /// Returns the external RAM of the cartridge, e.g. for writing a battery save.
+    ///
+    /// The RAM of the MBC2 stores one half-byte per byte.
+    pub fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    /// Returns the external RAM of the cartridge mutably, e.g. for loading a battery save.
+    pub fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    /// Returns the ROM bank that is currently mapped to `0x4000..0x8000`.
+    pub fn rom_bank(&self) -> usize {
+        self.high_rom_bank() % self.rom_banks()
+    }
+
+    /// Sets the live MBC3 clock registers: seconds, minutes, hours, the low 8 bits of the
+    /// day counter, and the day counter high / halt / carry flags.
+    pub fn set_rtc(&mut self, registers: [u8; 5]) {
+        self.rtc = registers;
+    }
+
+    /// Returns the live MBC3 clock registers.
+    pub fn rtc(&self) -> [u8; 5] {
+        self.rtc
+    }
+
+    fn rom_banks(&self) -> usize {
+        (self.rom.len() / ROM_BANK_SIZE).max(1)
+    }
+
+    fn low_rom_bank(&self) -> usize {
+        match self.kind {
+            MbcKind::Mbc1 if self.mode & 1 != 0 => self.bank2 << 5,
+            _ => 0,
+        }
+    }
+
+    fn high_rom_bank(&self) -> usize {
+        match self.kind {
+            MbcKind::Mbc1 => self.bank2 << 5 | self.bank1,
+            _ => self.bank1,
+        }
+    }
+
+    fn read_rom(&self, bank: usize, offset: usize) -> u8 {
+        let bank = bank % self.rom_banks();
+        self.rom
+            .get(bank * ROM_BANK_SIZE + offset)
+            .copied()
+            .unwrap_or(0xFF)
+    }
+
+    /// Returns the index into the RAM for the given offset into `0xA000..0xC000`.
+    fn ram_index(&self, offset: usize) -> Option<usize> {
+        if !self.ram_enabled || self.ram.is_empty() {
+            return None;
+        }
+
+        let addr = match self.kind {
+            MbcKind::Mbc1 if self.mode & 1 == 0 => offset,
+            MbcKind::Mbc2 => offset,
+            MbcKind::Mbc3 if self.bank2 >= 0x08 => return None,
+            _ => self.bank2 * RAM_BANK_SIZE + offset,
+        };
+        Some(addr % self.ram.len())
+    }
+
+    fn rtc_register(&self) -> Option<usize> {
+        match (self.kind, self.bank2) {
+            (MbcKind::Mbc3, reg @ 0x08..=0x0C) if self.ram_enabled => Some(reg - 0x08),
+            _ => None,
+        }
+    }
+
+    fn write_register(&mut self, addr: usize, byte: u8) {
+        let byte = byte as usize;
+        match (self.kind, addr) {
+            (MbcKind::Mbc2, 0x0000..=0x3FFF) if addr & 0x100 == 0 => {
+                self.ram_enabled = byte & 0x0F == 0x0A;
+            }
+            (MbcKind::Mbc2, 0x0000..=0x3FFF) => self.bank1 = (byte & 0x0F).max(1),
+            (_, 0x0000..=0x1FFF) => self.ram_enabled = byte & 0x0F == 0x0A,
+
+            (MbcKind::Mbc1, 0x2000..=0x3FFF) => self.bank1 = (byte & 0x1F).max(1),
+            (MbcKind::Mbc3, 0x2000..=0x3FFF) => self.bank1 = (byte & 0x7F).max(1),
+            (MbcKind::Mbc5, 0x2000..=0x2FFF) => self.bank1 = self.bank1 & 0x100 | byte,
+            (MbcKind::Mbc5, 0x3000..=0x3FFF) => self.bank1 = (byte & 1) << 8 | self.bank1 & 0xFF,
+
+            (MbcKind::Mbc1, 0x4000..=0x5FFF) => self.bank2 = byte & 0x03,
+            (MbcKind::Mbc3, 0x4000..=0x5FFF) => self.bank2 = byte & 0x0F,
+            (MbcKind::Mbc5, 0x4000..=0x5FFF) => self.bank2 = byte & 0x0F,
+
+            (MbcKind::Mbc1, 0x6000..=0x7FFF) => self.mode = byte as u8 & 1,
+            (MbcKind::Mbc3, 0x6000..=0x7FFF) => {
+                // Writing 0 and then 1 latches the current time.
+                if self.mode == 0 && byte == 1 {
+                    self.latched_rtc = self.rtc;
+                }
+                self.mode = byte as u8;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl MemoryStorage for Mbc {
+    type Error = OutOfBounds;
+
+    /// The ROM and the external RAM, without the hole at `0x8000..0xA000`.
+    fn size(&self) -> Option<usize> {
+        Some(0xA000)
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        Some(0x0000..0xC000)
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        match addr {
+            0x0000..=0x3FFF => Ok(self.read_rom(self.low_rom_bank(), addr)),
+            0x4000..=0x7FFF => Ok(self.read_rom(self.high_rom_bank(), addr - 0x4000)),
+            0xA000..=0xBFFF => {
+                if let Some(reg) = self.rtc_register() {
+                    return Ok(self.latched_rtc[reg]);
+                }
+
+                let byte = match self.ram_index(addr - 0xA000) {
+                    Some(idx) => self.ram[idx],
+                    None => return Ok(0xFF),
+                };
+
+                // The MBC2 only stores the lower half of every byte.
+                match self.kind {
+                    MbcKind::Mbc2 => Ok(byte | 0xF0),
+                    _ => Ok(byte),
+                }
+            }
+            _ => Err(OutOfBounds { addr }),
+        }
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        match addr {
+            0x0000..=0x7FFF => {
+                self.write_register(addr, byte);
+                trace_event!(debug, addr, byte, rom_bank = self.rom_bank(), "bank switch");
+            }
+            0xA000..=0xBFFF => {
+                if let Some(reg) = self.rtc_register() {
+                    self.rtc[reg] = byte;
+                } else if let Some(idx) = self.ram_index(addr - 0xA000) {
+                    self.ram[idx] = match self.kind {
+                        MbcKind::Mbc2 => byte & 0x0F,
+                        _ => byte,
+                    };
+                }
+            }
+            _ => return Err(OutOfBounds { addr }),
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for Mbc {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Mbc")
+            .field("kind", &self.kind)
+            .field("rom_len", &self.rom.len())
+            .field("ram_len", &self.ram.len())
+            .field("rom_bank", &self.rom_bank())
+            .finish()
+    }
+}
@@ -0,0 +1,7 @@
+//! Ready-made cartridge mappers of popular consoles.
+//!
+//! Every mapper implements [`MemoryStorage`](crate::MemoryStorage) over the address range
+//! the console maps the cartridge to, and handles writes to its bank-select registers.
+
+pub mod gb;
+pub mod nes;
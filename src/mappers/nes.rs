@@ -0,0 +1,453 @@
+//! NES cartridge mappers.
+
+use crate::{MemoryStorage, OutOfBounds};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::ops::Range;
+
+const PRG_RAM_SIZE: usize = 0x2000;
+const CHR_RAM_SIZE: usize = 0x2000;
+
+/// The nametable arrangement of a cartridge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mirroring {
+    /// The two nametables are arranged on top of each other, so `0x2000` mirrors `0x2400`.
+    Horizontal,
+    /// The two nametables are arranged side by side, so `0x2000` mirrors `0x2800`.
+    Vertical,
+    /// Every nametable mirrors the first nametable.
+    SingleScreenLow,
+    /// Every nametable mirrors the second nametable.
+    SingleScreenHigh,
+    /// The cartridge provides additional memory for four separate nametables.
+    FourScreen,
+}
+
+impl Mirroring {
+    /// Returns the offset into the nametable memory, which is 2 KiB large, or 4 KiB
+    /// for four screen mirroring, for the PPU address in `0x2000..0x3F00`.
+    pub fn nametable_offset(self, addr: usize) -> usize {
+        let addr = addr & 0x0FFF;
+        match self {
+            Mirroring::Horizontal => (addr >> 1) & 0x400 | addr & 0x3FF,
+            Mirroring::Vertical => addr & 0x7FF,
+            Mirroring::SingleScreenLow => addr & 0x3FF,
+            Mirroring::SingleScreenHigh => 0x400 | addr & 0x3FF,
+            Mirroring::FourScreen => addr,
+        }
+    }
+}
+
+/// The supported mappers, named after their iNES mapper number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapperKind {
+    /// Mapper 0, which has no bank switching.
+    Nrom,
+    /// Mapper 1, the MMC1 with a serial bank-select register.
+    Mmc1,
+    /// Mapper 2, which switches the 16 KiB PRG bank at `0x8000`.
+    Uxrom,
+    /// Mapper 4, the MMC3 with fine grained banking and a scanline counter.
+    Mmc3,
+}
+
+impl MapperKind {
+    /// Returns the mapper with the given iNES mapper number, or `None` if it is not supported.
+    pub fn from_ines(number: u8) -> Option<Self> {
+        match number {
+            0 => Some(MapperKind::Nrom),
+            1 => Some(MapperKind::Mmc1),
+            2 => Some(MapperKind::Uxrom),
+            4 => Some(MapperKind::Mmc3),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum State {
+    Nrom,
+    Mmc1 {
+        shift: u8,
+        writes: u8,
+        control: u8,
+        chr: [usize; 2],
+        prg: usize,
+    },
+    Uxrom {
+        prg: usize,
+    },
+    Mmc3 {
+        select: u8,
+        regs: [usize; 8],
+        irq_latch: u8,
+        irq_counter: u8,
+        irq_reload: bool,
+        irq_enabled: bool,
+        irq_pending: bool,
+    },
+}
+
+/// A NES cartridge with one of the common mappers.
+///
+/// The cartridge itself covers the CPU side of the address space: the PRG RAM at
+/// `0x6000..0x8000`, and the PRG ROM at `0x8000..0x10000`, whose writes select the banks.
+/// Every other address is out of bounds.
+///
+/// The PPU side is accessed through [`chr`](Self::chr), and the nametables are arranged
+/// according to [`mirroring`](Self::mirroring), which may be changed by the mapper.
+#[derive(Clone)]
+pub struct Cartridge {
+    prg_rom: Box<[u8]>,
+    prg_ram: Box<[u8]>,
+    chr: Box<[u8]>,
+    chr_is_ram: bool,
+    mirroring: Mirroring,
+    state: State,
+}
+
+impl Cartridge {
+    /// Creates a new cartridge with the given mapper and ROMs.
+    ///
+    /// If `chr_rom` is empty, the cartridge uses 8 KiB of CHR RAM instead.
+    pub fn new(kind: MapperKind, prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+        let chr_is_ram = chr_rom.is_empty();
+        let chr = if chr_is_ram {
+            vec![0; CHR_RAM_SIZE]
+        } else {
+            chr_rom
+        };
+
+        let state = match kind {
+            MapperKind::Nrom => State::Nrom,
+            MapperKind::Mmc1 => State::Mmc1 {
+                shift: 0,
+                writes: 0,
+                control: 0x0C,
+                chr: [0; 2],
+                prg: 0,
+            },
+            MapperKind::Uxrom => State::Uxrom { prg: 0 },
+            MapperKind::Mmc3 => State::Mmc3 {
+                select: 0,
+                regs: [0, 2, 4, 5, 6, 7, 0, 1],
+                irq_latch: 0,
+                irq_counter: 0,
+                irq_reload: false,
+                irq_enabled: false,
+                irq_pending: false,
+            },
+        };
+
+        Self {
+            prg_rom: prg_rom.into_boxed_slice(),
+            prg_ram: vec![0; PRG_RAM_SIZE].into_boxed_slice(),
+            chr: chr.into_boxed_slice(),
+            chr_is_ram,
+            mirroring,
+            state,
+        }
+    }
+
+    /// Creates a new cartridge from an iNES file.
+    ///
+    /// Returns `None` if the file is not a valid iNES file, or if it uses an
+    /// unsupported mapper.
+    pub fn from_ines(data: &[u8]) -> Option<Self> {
+        let header = data.get(..16)?;
+        if &header[..4] != b"NES\x1A" {
+            return None;
+        }
+
+        let kind = MapperKind::from_ines(header[6] >> 4 | header[7] & 0xF0)?;
+        let mirroring = match header[6] {
+            flags if flags & 0x08 != 0 => Mirroring::FourScreen,
+            flags if flags & 0x01 != 0 => Mirroring::Vertical,
+            _ => Mirroring::Horizontal,
+        };
+
+        let prg_start = if header[6] & 0x04 != 0 { 16 + 512 } else { 16 };
+        let prg_end = prg_start + header[4] as usize * 0x4000;
+        let chr_end = prg_end + header[5] as usize * 0x2000;
+        let prg_rom = data.get(prg_start..prg_end)?.to_vec();
+        let chr_rom = data.get(prg_end..chr_end)?.to_vec();
+        Some(Self::new(kind, prg_rom, chr_rom, mirroring))
+    }
+
+    /// Returns the current nametable arrangement.
+    pub fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    /// Returns the PRG RAM, e.g. for writing a battery save.
+    pub fn prg_ram(&self) -> &[u8] {
+        &self.prg_ram
+    }
+
+    /// Returns the PRG RAM mutably, e.g. for loading a battery save.
+    pub fn prg_ram_mut(&mut self) -> &mut [u8] {
+        &mut self.prg_ram
+    }
+
+    /// Returns the PPU-visible pattern tables at `0x0000..0x2000`, which are banked by
+    /// the mapper.
+    pub fn chr(&mut self) -> ChrMemory<'_> {
+        ChrMemory { cart: self }
+    }
+
+    /// Clocks the scanline counter of the MMC3.
+    ///
+    /// This must be called once per visible scanline, when the PPU fetches the sprite
+    /// patterns while rendering is enabled.
+    pub fn clock_scanline(&mut self) {
+        if let State::Mmc3 {
+            irq_latch,
+            irq_counter,
+            irq_reload,
+            irq_enabled,
+            irq_pending,
+            ..
+        } = &mut self.state
+        {
+            if *irq_counter == 0 || *irq_reload {
+                *irq_counter = *irq_latch;
+                *irq_reload = false;
+            } else {
+                *irq_counter -= 1;
+            }
+
+            if *irq_counter == 0 && *irq_enabled {
+                *irq_pending = true;
+            }
+        }
+    }
+
+    /// Returns `true` if the mapper asserts the IRQ line of the CPU.
+    pub fn irq(&self) -> bool {
+        matches!(
+            self.state,
+            State::Mmc3 {
+                irq_pending: true,
+                ..
+            }
+        )
+    }
+
+    fn prg_index(&self, addr: usize) -> usize {
+        let banks_16k = (self.prg_rom.len() / 0x4000).max(1);
+        let banks_8k = (self.prg_rom.len() / 0x2000).max(1);
+        let offset = addr & 0x3FFF;
+        let upper = addr >= 0xC000;
+
+        match &self.state {
+            State::Nrom => addr - 0x8000,
+            State::Uxrom { prg } => {
+                let bank = if upper { banks_16k - 1 } else { *prg };
+                bank * 0x4000 + offset
+            }
+            State::Mmc1 { control, prg, .. } => {
+                let bank = match (control >> 2) & 0x03 {
+                    0 | 1 => (prg & !1) | upper as usize,
+                    2 if upper => *prg,
+                    2 => 0,
+                    _ if upper => banks_16k - 1,
+                    _ => *prg,
+                };
+                bank * 0x4000 + offset
+            }
+            State::Mmc3 { select, regs, .. } => {
+                let swap = select & 0x40 != 0;
+                let bank = match (addr >> 13) & 0x03 {
+                    0 if swap => banks_8k - 2,
+                    0 => regs[6],
+                    1 => regs[7],
+                    2 if swap => regs[6],
+                    2 => banks_8k - 2,
+                    _ => banks_8k - 1,
+                };
+                bank * 0x2000 + (addr & 0x1FFF)
+            }
+        }
+    }
+
+    fn chr_index(&self, addr: usize) -> usize {
+        match &self.state {
+            State::Nrom | State::Uxrom { .. } => addr,
+            State::Mmc1 { control, chr, .. } => {
+                if control & 0x10 == 0 {
+                    (chr[0] & !1) * 0x1000 + addr
+                } else {
+                    chr[addr / 0x1000] * 0x1000 + (addr & 0x0FFF)
+                }
+            }
+            State::Mmc3 { select, regs, .. } => {
+                let addr = if select & 0x80 != 0 {
+                    addr ^ 0x1000
+                } else {
+                    addr
+                };
+                let bank = match addr / 0x400 {
+                    slot @ 0..=3 => (regs[slot / 2] & !1) + slot % 2,
+                    slot => regs[slot - 2],
+                };
+                bank * 0x400 + (addr & 0x3FF)
+            }
+        }
+    }
+
+    fn write_register(&mut self, addr: usize, byte: u8) {
+        let mirroring = &mut self.mirroring;
+        match &mut self.state {
+            State::Nrom => {}
+            State::Uxrom { prg } => *prg = byte as usize,
+            State::Mmc1 {
+                shift,
+                writes,
+                control,
+                chr,
+                prg,
+            } => {
+                if byte & 0x80 != 0 {
+                    *shift = 0;
+                    *writes = 0;
+                    *control |= 0x0C;
+                    return;
+                }
+
+                *shift |= (byte & 1) << *writes;
+                *writes += 1;
+                if *writes < 5 {
+                    return;
+                }
+
+                let value = *shift;
+                *shift = 0;
+                *writes = 0;
+                match (addr >> 13) & 0x03 {
+                    0 => {
+                        *control = value;
+                        *mirroring = match value & 0x03 {
+                            0 => Mirroring::SingleScreenLow,
+                            1 => Mirroring::SingleScreenHigh,
+                            2 => Mirroring::Vertical,
+                            _ => Mirroring::Horizontal,
+                        };
+                    }
+                    1 => chr[0] = value as usize,
+                    2 => chr[1] = value as usize,
+                    _ => *prg = (value & 0x0F) as usize,
+                }
+            }
+            State::Mmc3 {
+                select,
+                regs,
+                irq_latch,
+                irq_reload,
+                irq_enabled,
+                irq_pending,
+                ..
+            } => match (addr & 0xE000, addr & 1 == 0) {
+                (0x8000, true) => *select = byte,
+                (0x8000, false) => regs[(*select & 0x07) as usize] = byte as usize,
+                (0xA000, true) if *mirroring != Mirroring::FourScreen => {
+                    *mirroring = if byte & 1 == 0 {
+                        Mirroring::Vertical
+                    } else {
+                        Mirroring::Horizontal
+                    };
+                }
+                (0xC000, true) => *irq_latch = byte,
+                (0xC000, false) => *irq_reload = true,
+                (0xE000, true) => {
+                    *irq_enabled = false;
+                    *irq_pending = false;
+                }
+                (0xE000, false) => *irq_enabled = true,
+                _ => {}
+            },
+        }
+    }
+}
+
+impl MemoryStorage for Cartridge {
+    type Error = OutOfBounds;
+
+    /// The PRG RAM and ROM, without the hole below `0x6000`.
+    fn size(&self) -> Option<usize> {
+        Some(0xA000)
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        Some(0x6000..0x10000)
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        match addr {
+            0x6000..=0x7FFF => Ok(self.prg_ram[addr - 0x6000]),
+            0x8000..=0xFFFF if self.prg_rom.is_empty() => Ok(0xFF),
+            0x8000..=0xFFFF => Ok(self.prg_rom[self.prg_index(addr) % self.prg_rom.len()]),
+            _ => Err(OutOfBounds { addr }),
+        }
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        match addr {
+            0x6000..=0x7FFF => self.prg_ram[addr - 0x6000] = byte,
+            0x8000..=0xFFFF => {
+                self.write_register(addr, byte);
+                trace_event!(debug, addr, byte, "bank switch");
+            }
+            _ => return Err(OutOfBounds { addr }),
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for Cartridge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Cartridge")
+            .field("prg_rom_len", &self.prg_rom.len())
+            .field("chr_len", &self.chr.len())
+            .field("chr_is_ram", &self.chr_is_ram)
+            .field("mirroring", &self.mirroring)
+            .finish()
+    }
+}
+
+/// The pattern tables of a [`Cartridge`], as seen by the PPU at `0x0000..0x2000`.
+///
+/// Writes are ignored, unless the cartridge uses CHR RAM.
+#[derive(Debug)]
+pub struct ChrMemory<'a> {
+    cart: &'a mut Cartridge,
+}
+
+impl MemoryStorage for ChrMemory<'_> {
+    type Error = OutOfBounds;
+
+    fn size(&self) -> Option<usize> {
+        Some(0x2000)
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        if addr >= 0x2000 {
+            return Err(OutOfBounds { addr });
+        }
+
+        let chr = &self.cart.chr;
+        Ok(chr[self.cart.chr_index(addr) % chr.len()])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        if addr >= 0x2000 {
+            return Err(OutOfBounds { addr });
+        }
+
+        if self.cart.chr_is_ram {
+            let idx = self.cart.chr_index(addr) % self.cart.chr.len();
+            self.cart.chr[idx] = byte;
+        } else {
+            log_warn!("write to CHR ROM at {:#x} ignored", addr);
+        }
+        Ok(())
+    }
+}
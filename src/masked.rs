@@ -0,0 +1,107 @@
+use crate::MemoryStorage;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// A wrapper that masks every address to the lower `BITS` bits before accessing
+/// the inner memory, like a CPU with an address bus that is only `BITS` wide.
+///
+/// This models the wrap-around of narrow address buses, e.g. the 24-bit bus of the
+/// 68000, or the 16-bit bus of the 6502. Accesses that cross the end of the address
+/// space wrap around to address zero, like on the real hardware.
+///
+/// If `BITS` is at least the width of `usize`, addresses are passed through unchanged.
+pub struct MaskedMemory<M, const BITS: u32> {
+    inner: M,
+}
+
+impl<M: MemoryStorage, const BITS: u32> MaskedMemory<M, BITS> {
+    /// The mask that is applied to every address.
+    pub const MASK: usize = if BITS >= usize::BITS {
+        usize::MAX
+    } else {
+        (1 << BITS) - 1
+    };
+
+    /// Creates a new `MaskedMemory` around the given memory.
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the address that an access to `addr` is forwarded to.
+    pub fn mask(addr: usize) -> usize {
+        addr & Self::MASK
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `MaskedMemory` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Splits an access of `len` bytes at `addr` into the number of bytes before the
+    /// end of the address space, and the masked start address.
+    fn split(addr: usize, len: usize) -> (usize, usize) {
+        let addr = Self::mask(addr);
+        let before_wrap = (Self::MASK - addr).saturating_add(1);
+        (addr, len.min(before_wrap))
+    }
+}
+
+impl<M: MemoryStorage, const BITS: u32> MemoryStorage for MaskedMemory<M, BITS> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.inner.try_read_byte(Self::mask(addr))
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.inner.try_write_byte(Self::mask(addr), byte)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let mut done = 0;
+        while done < buf.len() {
+            let (addr, len) = Self::split(addr.wrapping_add(done), buf.len() - done);
+            self.inner.try_read_into(addr, &mut buf[done..done + len])?;
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let mut done = 0;
+        while done < buf.len() {
+            let (addr, len) = Self::split(addr.wrapping_add(done), buf.len() - done);
+            self.inner.try_write_from(addr, &buf[done..done + len])?;
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M, const BITS: u32> core::fmt::Debug for MaskedMemory<M, BITS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MaskedMemory").field("bits", &BITS).finish()
+    }
+}
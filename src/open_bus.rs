@@ -0,0 +1,168 @@
+use crate::MemoryStorage;
+use core::cell::Cell;
+use core::convert::Infallible;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// Describes what reads of unmapped addresses return inside an [`OpenBus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenBusMode {
+    /// Unmapped reads return the last value that was driven on the bus,
+    /// which is the behavior of a real open bus.
+    LastValue,
+    /// Unmapped reads always return the given value.
+    Constant(u8),
+}
+
+/// A wrapper that turns failed accesses of the inner memory into open bus accesses.
+///
+/// Reads of addresses where the inner memory returns an error are served according to the
+/// [`OpenBusMode`], and writes to such addresses are ignored. Every successful read,
+/// and every write, updates the value that was last driven on the bus.
+///
+/// Bulk accesses are forwarded as a whole if they are inside the
+/// [`addr_range`](MemoryStorage::addr_range) of the inner memory, and are split into
+/// single bytes otherwise, so no byte of the inner memory is accessed twice. If a bulk
+/// access inside the range still fails, e.g. because the range contains a hole, the whole
+/// access is served by the open bus.
+///
+/// Several consoles have games that depend on the exact open bus behavior.
+pub struct OpenBus<M> {
+    inner: M,
+    mode: OpenBusMode,
+    last: Cell<u8>,
+}
+
+impl<M: MemoryStorage> OpenBus<M> {
+    /// Creates a new `OpenBus` that returns the last value driven on the bus for
+    /// unmapped reads.
+    pub fn new(inner: M) -> Self {
+        Self::with_mode(inner, OpenBusMode::LastValue)
+    }
+
+    /// Creates a new `OpenBus` that uses the given mode for unmapped reads.
+    pub fn with_mode(inner: M, mode: OpenBusMode) -> Self {
+        Self {
+            inner,
+            mode,
+            last: Cell::new(0),
+        }
+    }
+
+    /// Returns the value that was last driven on the bus.
+    pub fn last_value(&self) -> u8 {
+        self.last.get()
+    }
+
+    /// Overrides the value that was last driven on the bus, e.g. by a DMA transfer
+    /// that does not go through this memory.
+    pub fn set_last_value(&self, value: u8) {
+        self.last.set(value);
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `OpenBus` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Returns `true` if the access is inside the address range of the inner memory, so
+    /// it can be forwarded as a whole.
+    fn is_mapped(&self, addr: usize, len: usize) -> bool {
+        match (self.inner.addr_range(), addr.checked_add(len)) {
+            (Some(range), Some(end)) => range.start <= addr && end <= range.end,
+            _ => false,
+        }
+    }
+
+    fn unmapped(&self) -> u8 {
+        match self.mode {
+            OpenBusMode::LastValue => self.last.get(),
+            OpenBusMode::Constant(value) => value,
+        }
+    }
+}
+
+impl<M: MemoryStorage> MemoryStorage for OpenBus<M> {
+    type Error = Infallible;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let byte = self.inner.try_read_byte(addr).unwrap_or_else(|_| {
+            log_warn!("read of unmapped address {:#x} served by open bus", addr);
+            self.unmapped()
+        });
+        self.last.set(byte);
+        Ok(byte)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        if self.inner.try_write_byte(addr, byte).is_err() {
+            log_warn!("write to unmapped address {:#x} ignored", addr);
+        }
+        self.last.set(byte);
+        Ok(())
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        if !self.is_mapped(addr, buf.len()) {
+            // Only a part of the range may be unmapped, so read single bytes.
+            for (offset, byte) in buf.iter_mut().enumerate() {
+                *byte = match addr.checked_add(offset) {
+                    Some(addr) => self.try_read_byte(addr)?,
+                    None => self.unmapped(),
+                };
+            }
+            return Ok(());
+        }
+
+        if self.inner.try_read_into(addr, buf).is_err() {
+            log_warn!("read of unmapped range at {:#x} served by open bus", addr);
+            buf.fill(self.unmapped());
+        }
+        if let Some(&byte) = buf.last() {
+            self.last.set(byte);
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        if !self.is_mapped(addr, buf.len()) {
+            for (offset, &byte) in buf.iter().enumerate() {
+                match addr.checked_add(offset) {
+                    Some(addr) => self.try_write_byte(addr, byte)?,
+                    None => self.last.set(byte),
+                }
+            }
+            return Ok(());
+        }
+
+        if self.inner.try_write_from(addr, buf).is_err() {
+            log_warn!("write to unmapped range at {:#x} ignored", addr);
+        }
+        if let Some(&byte) = buf.last() {
+            self.last.set(byte);
+        }
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
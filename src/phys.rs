@@ -0,0 +1,142 @@
+use crate::volatile::{read_volatile, write_volatile};
+use crate::{MemoryStorage, OutOfBounds};
+use core::sync::atomic::Ordering;
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/// A memory that maps a range of physical memory through `/dev/mem`, or the memory of
+/// a device through a UIO device, like `/dev/uio0`.
+///
+/// This allows using the same register and memory abstractions against real hardware,
+/// e.g. during board bring-up, and against emulation. Accesses of 1, 2, 4 or 8 bytes to
+/// naturally aligned addresses are done using a single volatile access of that width,
+/// which is what most device registers require. All other accesses are done bytewise.
+#[derive(Debug)]
+pub struct PhysMemory {
+    map: *mut u8,
+    map_len: usize,
+    /// The offset of the first mapped byte inside the page aligned mapping.
+    offset: usize,
+    len: usize,
+}
+
+// Safety: the mapping is owned by the memory, and only accessed through it.
+unsafe impl Send for PhysMemory {}
+
+impl PhysMemory {
+    /// Maps `len` bytes at offset `addr` of the given device, like `/dev/mem`, where the
+    /// offset is the physical address. The offset does not need to be page aligned.
+    ///
+    /// # Safety
+    ///
+    /// Writing to physical memory or device registers can corrupt the state of the whole
+    /// system, including the memory of this process. The caller must ensure that the
+    /// range only contains memory or registers that are not used by anything else.
+    pub unsafe fn map<P: AsRef<Path>>(path: P, addr: u64, len: usize) -> io::Result<Self> {
+        let path = CString::new(path.as_ref().as_os_str().as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains a NUL"))?;
+        let page = libc::sysconf(libc::_SC_PAGESIZE) as u64;
+        let offset = (addr % page) as usize;
+        let map_len = len
+            .checked_add(offset)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "range is too large"))?
+            .max(1);
+
+        let fd = libc::open(path.as_ptr(), libc::O_RDWR | libc::O_SYNC | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let map = libc::mmap(
+            core::ptr::null_mut(),
+            map_len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            (addr - offset as u64) as libc::off_t,
+        );
+        let err = io::Error::last_os_error();
+        libc::close(fd);
+
+        if map == libc::MAP_FAILED {
+            return Err(err);
+        }
+
+        trace_event!(debug, addr, len, "mapped physical memory");
+        Ok(Self {
+            map: map as *mut u8,
+            map_len,
+            offset,
+            len,
+        })
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a pointer to the given range, if it is inside the mapping.
+    fn ptr(&self, addr: usize, len: usize) -> Result<*mut u8, OutOfBounds> {
+        match addr.checked_add(len) {
+            // Safety: the range is inside the mapping.
+            Some(end) if end <= self.len => Ok(unsafe { self.map.add(self.offset + addr) }),
+            _ => Err(OutOfBounds {
+                addr: addr.max(self.len),
+            }),
+        }
+    }
+}
+
+impl MemoryStorage for PhysMemory {
+    type Error = OutOfBounds;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let ptr = self.ptr(addr, 1)?;
+        // Safety: the pointer is inside the mapping.
+        Ok(unsafe { ptr.read_volatile() })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let ptr = self.ptr(addr, 1)?;
+        // Safety: the pointer is inside the mapping.
+        unsafe { ptr.write_volatile(byte) };
+        Ok(())
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let ptr = self.ptr(addr, buf.len())?;
+        // Safety: the range is inside the mapping.
+        unsafe { read_volatile(ptr, buf) };
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let ptr = self.ptr(addr, buf.len())?;
+        // Safety: the range is inside the mapping.
+        unsafe { write_volatile(ptr, buf) };
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        crate::atomic_fence(order)
+    }
+}
+
+impl Drop for PhysMemory {
+    fn drop(&mut self) {
+        // Safety: the mapping was created by `map` and is not used anymore.
+        unsafe { libc::munmap(self.map as *mut libc::c_void, self.map_len) };
+        trace_event!(debug, len = self.len, "unmapped physical memory");
+    }
+}
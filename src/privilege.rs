@@ -0,0 +1,178 @@
+use crate::{MemoryStorage, PrivilegeError};
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// The privilege level that accesses to a [`PrivilegedMemory`] are made with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Privilege {
+    /// The unprivileged mode that applications run in.
+    User,
+    /// The privileged mode that the kernel runs in.
+    Supervisor,
+}
+
+/// A wrapper that checks the privilege level of every access to the inner memory.
+///
+/// The privilege level is part of the state of the wrapper and is set by the CPU
+/// emulator using [`set_privilege`](Self::set_privilege), e.g. when it takes a trap or
+/// returns from one. Accesses fail with [`PrivilegeError::Denied`] if they touch
+///
+/// - a supervisor range while running in user mode, or
+/// - a user range while running in supervisor mode and user access is disabled, which
+///   emulates SMAP on x86 and a cleared `SUM` bit on RISC-V.
+///
+/// Failed accesses are not forwarded to the inner memory.
+pub struct PrivilegedMemory<M> {
+    inner: M,
+    privilege: Privilege,
+    supervisor: Vec<Range<usize>>,
+    user: Vec<Range<usize>>,
+    user_access: bool,
+}
+
+impl<M: MemoryStorage> PrivilegedMemory<M> {
+    /// Creates a new `PrivilegedMemory` that runs in supervisor mode, without any
+    /// restricted ranges.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            privilege: Privilege::Supervisor,
+            supervisor: Vec::new(),
+            user: Vec::new(),
+            user_access: true,
+        }
+    }
+
+    /// Returns the privilege level that accesses are currently made with.
+    pub fn privilege(&self) -> Privilege {
+        self.privilege
+    }
+
+    /// Sets the privilege level that following accesses are made with.
+    pub fn set_privilege(&mut self, privilege: Privilege) {
+        self.privilege = privilege;
+    }
+
+    /// Restricts the given range to supervisor mode.
+    pub fn add_supervisor_range(&mut self, range: Range<usize>) {
+        self.supervisor.push(range);
+    }
+
+    /// Marks the given range as user memory, which can not be accessed from supervisor
+    /// mode while user access is disabled.
+    pub fn add_user_range(&mut self, range: Range<usize>) {
+        self.user.push(range);
+    }
+
+    /// Removes every supervisor and user range.
+    pub fn clear_ranges(&mut self) {
+        self.supervisor.clear();
+        self.user.clear();
+    }
+
+    /// Returns `true` if accesses from supervisor mode to user ranges are allowed.
+    pub fn user_access(&self) -> bool {
+        self.user_access
+    }
+
+    /// Allows or denies accesses from supervisor mode to user ranges, like the `SUM` bit
+    /// on RISC-V or the `AC` flag on x86 with SMAP enabled.
+    pub fn set_user_access(&mut self, allowed: bool) {
+        self.user_access = allowed;
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `PrivilegedMemory` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Returns an error if the access of `len` bytes at `addr` is not allowed with the
+    /// current privilege level.
+    fn check(&self, addr: usize, len: usize) -> Result<(), PrivilegeError<M::Error>> {
+        let end = addr.saturating_add(len.max(1));
+        let touches = |ranges: &[Range<usize>]| {
+            ranges
+                .iter()
+                .any(|range| addr < range.end && range.start < end)
+        };
+
+        let denied = match self.privilege {
+            Privilege::User => touches(&self.supervisor),
+            Privilege::Supervisor => !self.user_access && touches(&self.user),
+        };
+        if denied {
+            return Err(PrivilegeError::Denied {
+                addr,
+                privilege: self.privilege,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<M: MemoryStorage> MemoryStorage for PrivilegedMemory<M> {
+    type Error = PrivilegeError<M::Error>;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.check(addr, 1)?;
+        self.inner
+            .try_read_byte(addr)
+            .map_err(PrivilegeError::Memory)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.check(addr, 1)?;
+        self.inner
+            .try_write_byte(addr, byte)
+            .map_err(PrivilegeError::Memory)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+        self.inner
+            .try_read_into(addr, buf)
+            .map_err(PrivilegeError::Memory)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+        self.inner
+            .try_write_from(addr, buf)
+            .map_err(PrivilegeError::Memory)
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M> core::fmt::Debug for PrivilegedMemory<M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrivilegedMemory")
+            .field("privilege", &self.privilege)
+            .field("supervisor", &self.supervisor)
+            .field("user", &self.user)
+            .field("user_access", &self.user_access)
+            .finish()
+    }
+}
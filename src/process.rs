@@ -0,0 +1,98 @@
+use crate::MemoryStorage;
+use std::io;
+
+/// A memory that accesses the address space of another process on Linux, using
+/// `process_vm_readv` and `process_vm_writev`.
+///
+/// Address `0` of this memory is mapped to the address `base` inside the other process.
+/// Accessing another process requires the same permissions as attaching to it with
+/// `ptrace`. The other process keeps running, so its memory may change at any time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessMemory {
+    pid: libc::pid_t,
+    base: usize,
+}
+
+impl ProcessMemory {
+    /// Creates a new `ProcessMemory` for the process with the given id, where address `0`
+    /// is mapped to `base` inside the process.
+    pub fn new(pid: u32, base: usize) -> Self {
+        Self {
+            pid: pid as libc::pid_t,
+            base,
+        }
+    }
+
+    /// Returns the id of the process.
+    pub fn pid(&self) -> u32 {
+        self.pid as u32
+    }
+
+    /// Returns the address inside the process that address `0` is mapped to.
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    fn remote(&self, addr: usize, len: usize) -> io::Result<libc::iovec> {
+        let addr = self
+            .base
+            .checked_add(addr)
+            .filter(|addr| addr.checked_add(len).is_some())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "address overflows"))?;
+        Ok(libc::iovec {
+            iov_base: addr as *mut libc::c_void,
+            iov_len: len,
+        })
+    }
+}
+
+impl MemoryStorage for ProcessMemory {
+    type Error = io::Error;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let local = libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+            iov_len: buf.len(),
+        };
+        let remote = self.remote(addr, buf.len())?;
+        // Safety: `local` describes the buffer, and the kernel checks the remote range.
+        let read = unsafe { libc::process_vm_readv(self.pid, &local, 1, &remote, 1, 0) };
+        check(read, buf.len())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let local = libc::iovec {
+            iov_base: buf.as_ptr() as *mut libc::c_void,
+            iov_len: buf.len(),
+        };
+        let remote = self.remote(addr, buf.len())?;
+        // Safety: `local` describes the buffer, which is only read, and the kernel checks
+        // the remote range.
+        let written = unsafe { libc::process_vm_writev(self.pid, &local, 1, &remote, 1, 0) };
+        check(written, buf.len())
+    }
+}
+
+/// Turns the result of a transfer into an error, if it failed or was partial.
+fn check(result: isize, len: usize) -> io::Result<()> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else if result as usize != len {
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "only a part of the range was transferred",
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use crate::MemoryStorage;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// A named address range of a [`ProfileMemory`], which counts the accesses that touch it.
+#[derive(Debug)]
+pub struct ProfileRegion {
+    name: &'static str,
+    range: Range<usize>,
+    fetches: Cell<u64>,
+    loads: Cell<u64>,
+    stores: Cell<u64>,
+}
+
+impl ProfileRegion {
+    /// Returns the name of this region.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the address range of this region.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the number of instruction fetches that touched this region.
+    pub fn fetches(&self) -> u64 {
+        self.fetches.get()
+    }
+
+    /// Returns the number of data reads that touched this region.
+    pub fn loads(&self) -> u64 {
+        self.loads.get()
+    }
+
+    /// Returns the number of data writes that touched this region.
+    pub fn stores(&self) -> u64 {
+        self.stores.get()
+    }
+
+    fn reset(&self) {
+        self.fetches.set(0);
+        self.loads.set(0);
+        self.stores.set(0);
+    }
+}
+
+/// A wrapper that counts the instruction fetches, loads and stores to named regions of
+/// the inner memory, e.g. to find the regions that are worth compiling by a JIT.
+///
+/// The `MemoryStorage` methods of the wrapper count reads as loads and writes as stores.
+/// Instruction fetches are made through the port that is returned by
+/// [`fetch_port`](Self::fetch_port), so the same memory can be used by the fetch and
+/// the data side of a CPU.
+///
+/// Every successful call to the `MemoryStorage` methods is counted once in every region
+/// it touches. Failed accesses are not counted.
+pub struct ProfileMemory<M> {
+    inner: M,
+    regions: Vec<ProfileRegion>,
+}
+
+impl<M: MemoryStorage> ProfileMemory<M> {
+    /// Creates a new `ProfileMemory` without any regions.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            regions: Vec::new(),
+        }
+    }
+
+    /// Adds a region with the given name, whose accesses are counted.
+    pub fn add_region(&mut self, name: &'static str, range: Range<usize>) {
+        self.regions.push(ProfileRegion {
+            name,
+            range,
+            fetches: Cell::new(0),
+            loads: Cell::new(0),
+            stores: Cell::new(0),
+        });
+    }
+
+    /// Returns the regions of this memory, in the order they were added.
+    pub fn regions(&self) -> &[ProfileRegion] {
+        &self.regions
+    }
+
+    /// Returns the region with the given name.
+    pub fn region(&self, name: &str) -> Option<&ProfileRegion> {
+        self.regions.iter().find(|region| region.name == name)
+    }
+
+    /// Resets the counters of every region to zero.
+    pub fn reset(&mut self) {
+        self.regions.iter().for_each(ProfileRegion::reset);
+    }
+
+    /// Returns a port whose reads are counted as instruction fetches.
+    ///
+    /// Writes through the port are counted as stores.
+    pub fn fetch_port(&mut self) -> FetchPort<'_, M> {
+        FetchPort { mem: self }
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory, whose accesses are not counted.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `ProfileMemory` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Increments the selected counter of every region that overlaps the access.
+    fn count(&self, addr: usize, len: usize, counter: fn(&ProfileRegion) -> &Cell<u64>) {
+        let end = addr.saturating_add(len.max(1));
+        for region in &self.regions {
+            if addr < region.range.end && region.range.start < end {
+                let counter = counter(region);
+                counter.set(counter.get() + 1);
+            }
+        }
+    }
+}
+
+impl<M: MemoryStorage> MemoryStorage for ProfileMemory<M> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let byte = self.inner.try_read_byte(addr)?;
+        self.count(addr, 1, |region| &region.loads);
+        Ok(byte)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.inner.try_write_byte(addr, byte)?;
+        self.count(addr, 1, |region| &region.stores);
+        Ok(())
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.try_read_into(addr, buf)?;
+        self.count(addr, buf.len(), |region| &region.loads);
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.inner.try_write_from(addr, buf)?;
+        self.count(addr, buf.len(), |region| &region.stores);
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M> core::fmt::Debug for ProfileMemory<M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ProfileMemory")
+            .field("regions", &self.regions)
+            .finish()
+    }
+}
+
+/// The instruction fetch side of a [`ProfileMemory`], which is returned by
+/// [`ProfileMemory::fetch_port`].
+pub struct FetchPort<'a, M> {
+    mem: &'a mut ProfileMemory<M>,
+}
+
+impl<M: MemoryStorage> MemoryStorage for FetchPort<'_, M> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.mem.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.mem.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let byte = self.mem.inner.try_read_byte(addr)?;
+        self.mem.count(addr, 1, |region| &region.fetches);
+        Ok(byte)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.mem.try_write_byte(addr, byte)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.mem.inner.try_read_into(addr, buf)?;
+        self.mem.count(addr, buf.len(), |region| &region.fetches);
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.mem.try_write_from(addr, buf)
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.mem.fence(order)
+    }
+}
+
+impl<M> core::fmt::Debug for FetchPort<'_, M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FetchPort")
+            .field("regions", &self.mem.regions)
+            .finish()
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{ContiguousMemory, MemoryStorage, OutOfBounds};
+use core::ptr::NonNull;
+
+/// A memory that is backed by a raw pointer to memory which is owned by foreign code,
+/// e.g. the RAM of an emulator core written in C, or a buffer of a device driver.
+///
+/// The memory is accessed in place, without copying it, and is never freed by this type.
+#[derive(Debug)]
+pub struct PtrMemory {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+impl PtrMemory {
+    /// Creates a new `PtrMemory` over the `len` bytes starting at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// For as long as the returned `PtrMemory` exists:
+    ///
+    /// - `ptr` must be valid for reads and writes of `len` initialized bytes, and the
+    ///   memory must not be freed or unmapped.
+    /// - `len` must be at most `isize::MAX`.
+    /// - The memory must not be accessed through any other pointer while this memory is
+    ///   being accessed, or while a reference that was returned by it is alive. Foreign
+    ///   code may only access the memory in between, e.g. while the emulator core runs
+    ///   and this memory is not used.
+    pub unsafe fn new(ptr: NonNull<u8>, len: usize) -> Self {
+        Self { ptr, len }
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the pointer to the first byte of this memory.
+    pub fn as_ptr(&self) -> NonNull<u8> {
+        self.ptr
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Safety: guaranteed by the caller of `new`.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // Safety: guaranteed by the caller of `new`.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl MemoryStorage for PtrMemory {
+    type Error = OutOfBounds;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.bytes().get(addr).copied().ok_or(OutOfBounds { addr })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let cell = self.bytes_mut().get_mut(addr).ok_or(OutOfBounds { addr })?;
+        *cell = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self.bytes())
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(self.bytes_mut())
+    }
+}
+
+impl ContiguousMemory for PtrMemory {
+    fn as_bytes(&self) -> &[u8] {
+        self.bytes()
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.bytes_mut()
+    }
+
+    fn out_of_bounds(&self, addr: usize) -> Self::Error {
+        OutOfBounds { addr }
+    }
+}
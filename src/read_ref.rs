@@ -0,0 +1,50 @@
+use crate::{ContiguousMemory, MemoryStorage};
+use core::ops::Range;
+
+/// A `MemoryStorage` that can hand out the bytes of a range without copying them,
+/// if the memory layout allows it.
+///
+/// This is implemented for every [`ContiguousMemory`], which borrows the bytes directly.
+/// Memories that are not contiguous gather the bytes into an owned buffer instead.
+pub trait ReadRef: MemoryStorage {
+    /// The bytes that are returned by [`try_read_ref`](Self::try_read_ref).
+    type Bytes<'a>: AsRef<[u8]>
+    where
+        Self: 'a;
+
+    /// Tries to read the bytes inside the given range, borrowing them if possible.
+    ///
+    /// Returns `Err(x)` if the method failed to read one of the bytes.
+    fn try_read_ref(&self, range: Range<usize>) -> Result<Self::Bytes<'_>, Self::Error>;
+
+    /// Reads the bytes inside the given range, borrowing them if possible.
+    ///
+    /// Panics if the method failed to read one of the bytes.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_read_ref` instead"))]
+    fn read_ref(&self, range: Range<usize>) -> Self::Bytes<'_> {
+        let (start, end) = (range.start, range.end);
+        or_panic!(self.try_read_ref(range), "read {:#x}..{:#x}", start, end)
+    }
+}
+
+impl<M: ContiguousMemory> ReadRef for M {
+    type Bytes<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    fn try_read_ref(&self, range: Range<usize>) -> Result<Self::Bytes<'_>, Self::Error> {
+        self.get(range)
+    }
+}
+
+/// Reads the bytes of `range` into a new `Vec`.
+#[cfg(feature = "alloc")]
+pub(crate) fn gather<M: MemoryStorage + ?Sized>(
+    mem: &M,
+    range: Range<usize>,
+) -> Result<alloc::vec::Vec<u8>, M::Error> {
+    let mut bytes = alloc::vec![0u8; range.len()];
+    mem.try_read_into(range.start, &mut bytes)?;
+    Ok(bytes)
+}
@@ -0,0 +1,262 @@
+use crate::{MemoryStorage, ReplayError};
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// Describes whether an [`Access`] read or wrote memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AccessKind {
+    /// The bytes were read from the memory.
+    Read,
+    /// The bytes were written to the memory.
+    Write,
+}
+
+/// A single access that was recorded by a [`RecordMemory`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Access {
+    /// Whether the access was a read or a write.
+    pub kind: AccessKind,
+    /// The address of the first byte that was accessed.
+    pub addr: usize,
+    /// The bytes that were returned by the read, or passed to the write.
+    pub data: Vec<u8>,
+}
+
+/// A wrapper that records every successful access to the inner memory, e.g. to the
+/// registers of a device model.
+///
+/// The recorded trace can be served back by a [`ReplayMemory`], so regression tests of
+/// a CPU core can run deterministically without the real device models. Every call
+/// to the `MemoryStorage` methods is recorded as one access, and failed accesses are
+/// not recorded.
+pub struct RecordMemory<M> {
+    inner: M,
+    trace: RefCell<Vec<Access>>,
+}
+
+impl<M: MemoryStorage> RecordMemory<M> {
+    /// Creates a new `RecordMemory` with an empty trace.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            trace: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory, whose accesses are not recorded.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Returns the accesses that were recorded so far.
+    pub fn trace(&mut self) -> &[Access] {
+        self.trace.get_mut()
+    }
+
+    /// Returns the accesses that were recorded so far, and starts a new trace.
+    pub fn take_trace(&mut self) -> Vec<Access> {
+        core::mem::take(self.trace.get_mut())
+    }
+
+    /// Consumes this `RecordMemory` and returns the inner memory and the trace.
+    pub fn into_parts(self) -> (M, Vec<Access>) {
+        (self.inner, self.trace.into_inner())
+    }
+
+    fn record(&self, kind: AccessKind, addr: usize, data: &[u8]) {
+        self.trace.borrow_mut().push(Access {
+            kind,
+            addr,
+            data: data.into(),
+        });
+    }
+}
+
+impl<M: MemoryStorage> MemoryStorage for RecordMemory<M> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let byte = self.inner.try_read_byte(addr)?;
+        self.record(AccessKind::Read, addr, &[byte]);
+        Ok(byte)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.inner.try_write_byte(addr, byte)?;
+        self.record(AccessKind::Write, addr, &[byte]);
+        Ok(())
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.inner.try_read_into(addr, buf)?;
+        self.record(AccessKind::Read, addr, buf);
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.inner.try_write_from(addr, buf)?;
+        self.record(AccessKind::Write, addr, buf);
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+/// A memory that serves the accesses of a trace, which was recorded by a [`RecordMemory`].
+///
+/// Every access must match the next access of the trace. Reads return the recorded
+/// bytes, and writes must pass the recorded bytes. Any other access fails with a
+/// [`ReplayError`], which points to the first place where the run diverged.
+#[derive(Debug, Clone)]
+pub struct ReplayMemory {
+    trace: Vec<Access>,
+    next: Cell<usize>,
+}
+
+impl ReplayMemory {
+    /// Creates a new `ReplayMemory` that serves the given trace from the start.
+    pub fn new(trace: Vec<Access>) -> Self {
+        Self {
+            trace,
+            next: Cell::new(0),
+        }
+    }
+
+    /// Returns the index of the next access inside the trace.
+    pub fn position(&self) -> usize {
+        self.next.get()
+    }
+
+    /// Returns `true` if every access of the trace was replayed.
+    pub fn is_finished(&self) -> bool {
+        self.next.get() == self.trace.len()
+    }
+
+    /// Returns the accesses of the trace that were not replayed yet.
+    pub fn remaining(&self) -> &[Access] {
+        &self.trace[self.next.get()..]
+    }
+
+    /// Consumes this `ReplayMemory` and returns the trace.
+    pub fn into_trace(self) -> Vec<Access> {
+        self.trace
+    }
+
+    /// Returns the next access, if it has the given kind, address and length.
+    fn expect(&self, kind: AccessKind, addr: usize, len: usize) -> Result<&Access, ReplayError> {
+        let index = self.next.get();
+        let access = self
+            .trace
+            .get(index)
+            .ok_or(ReplayError::Exhausted { addr })?;
+        if access.kind != kind || access.addr != addr || access.data.len() != len {
+            return Err(ReplayError::Diverged { index, addr });
+        }
+        Ok(access)
+    }
+}
+
+impl MemoryStorage for ReplayMemory {
+    type Error = ReplayError;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let access = self.expect(AccessKind::Read, addr, buf.len())?;
+        buf.copy_from_slice(&access.data);
+        self.next.set(self.next.get() + 1);
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let index = self.next.get();
+        if self.expect(AccessKind::Write, addr, buf.len())?.data != buf {
+            return Err(ReplayError::Diverged { index, addr });
+        }
+        self.next.set(index + 1);
+        Ok(())
+    }
+}
+
+/// The first place where two traces differ, as found by [`compare_traces`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Divergence {
+    /// The index of the first access that differs.
+    pub index: usize,
+    /// The access of the left trace, or `None` if the left trace ended first.
+    pub left: Option<Access>,
+    /// The access of the right trace, or `None` if the right trace ended first.
+    pub right: Option<Access>,
+    /// The matching accesses right before the divergence, oldest first.
+    pub context: Vec<Access>,
+}
+
+/// Compares two access traces, e.g. of an emulator and a reference emulator or a logic
+/// analyzer capture, and returns the first access where they differ.
+///
+/// Up to `context` of the matching accesses before the divergence are returned with it,
+/// to make it easier to find the place in the program that caused it.
+///
+/// Returns `None` if both traces contain the same accesses.
+pub fn compare_traces<L, R>(left: L, right: R, context: usize) -> Option<Divergence>
+where
+    L: IntoIterator<Item = Access>,
+    R: IntoIterator<Item = Access>,
+{
+    let mut left = left.into_iter();
+    let mut right = right.into_iter();
+    let mut recent = VecDeque::with_capacity(context);
+
+    let mut index = 0;
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return None,
+            (Some(l), Some(r)) if l == r => {
+                if context > 0 {
+                    if recent.len() == context {
+                        recent.pop_front();
+                    }
+                    recent.push_back(l);
+                }
+                index += 1;
+            }
+            (left, right) => {
+                return Some(Divergence {
+                    index,
+                    left,
+                    right,
+                    context: recent.into(),
+                })
+            }
+        }
+    }
+}
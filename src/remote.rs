@@ -0,0 +1,197 @@
+//! A simple protocol for accessing a memory over a byte stream, like a TCP connection.
+//!
+//! Every request starts with a byte that selects the operation, followed by the address
+//! as a little endian `u64` and the length as a little endian `u32`. A write request
+//! is followed by the bytes to write. Every response starts with a status byte, which
+//! is zero on success, followed by the bytes that were read for a successful read.
+//!
+//! A single request may access at most [`MAX_ACCESS_LEN`] bytes, so the server never
+//! allocates more than that for a request. Larger accesses are split by the client.
+
+use crate::{MemoryStorage, RemoteError};
+use core::cell::RefCell;
+use core::convert::{TryFrom, TryInto};
+use core::ops::Range;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::vec;
+
+const OP_READ: u8 = 0;
+const OP_WRITE: u8 = 1;
+
+const STATUS_OK: u8 = 0;
+const STATUS_FAILED: u8 = 1;
+
+/// The maximum number of bytes that are accessed by a single request.
+pub const MAX_ACCESS_LEN: usize = 1 << 20;
+
+/// A memory that forwards every access to a memory on another machine, which is
+/// served by [`serve`].
+///
+/// The stream is stored inside a [`RefCell`], because reads send requests too.
+/// Addresses must fit into a `u64`, and accesses that are larger than
+/// [`MAX_ACCESS_LEN`] bytes are sent as multiple requests.
+#[derive(Debug)]
+pub struct RemoteMemory<T = TcpStream> {
+    stream: RefCell<T>,
+}
+
+impl RemoteMemory<TcpStream> {
+    /// Connects to a memory that is served at the given address.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream))
+    }
+}
+
+impl<T: Read + Write> RemoteMemory<T> {
+    /// Creates a new `RemoteMemory` that sends its requests over the given stream.
+    pub fn new(stream: T) -> Self {
+        Self {
+            stream: RefCell::new(stream),
+        }
+    }
+
+    /// Consumes this `RemoteMemory` and returns the stream.
+    pub fn into_inner(self) -> T {
+        self.stream.into_inner()
+    }
+}
+
+impl<T: Read + Write> MemoryStorage for RemoteMemory<T> {
+    type Error = RemoteError;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let mut stream = self.stream.borrow_mut();
+        for (idx, chunk) in buf.chunks_mut(MAX_ACCESS_LEN).enumerate() {
+            let addr = addr + idx * MAX_ACCESS_LEN;
+            write_header(&mut *stream, OP_READ, addr, chunk.len())?;
+            stream.flush()?;
+
+            if !read_status(&mut *stream)? {
+                return Err(RemoteError::Rejected { addr });
+            }
+            stream.read_exact(chunk)?;
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let stream = self.stream.get_mut();
+        for (idx, chunk) in buf.chunks(MAX_ACCESS_LEN).enumerate() {
+            let addr = addr + idx * MAX_ACCESS_LEN;
+            write_header(&mut *stream, OP_WRITE, addr, chunk.len())?;
+            stream.write_all(chunk)?;
+            stream.flush()?;
+
+            if !read_status(&mut *stream)? {
+                return Err(RemoteError::Rejected { addr });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serves the given memory to a single [`RemoteMemory`] client, until the client
+/// closes the stream.
+///
+/// Failed accesses are reported to the client and do not stop serving. Accesses that
+/// are larger than [`MAX_ACCESS_LEN`] bytes, or do not fit into the memory, are
+/// rejected before any buffer is allocated for them.
+///
+/// Returns `Err(x)` if the stream failed, or the client sent an invalid request.
+pub fn serve<M, T>(mem: &mut M, mut stream: T) -> io::Result<()>
+where
+    M: MemoryStorage + ?Sized,
+    T: Read + Write,
+{
+    loop {
+        let mut header = [0u8; 13];
+        match stream.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(err) => return Err(err),
+        }
+
+        let op = header[0];
+        let addr = u64::from_le_bytes(header[1..9].try_into().unwrap());
+        let len = u32::from_le_bytes(header[9..13].try_into().unwrap()) as usize;
+        let addr = usize::try_from(addr).map_err(|_| invalid("address does not fit a usize"))?;
+        if op != OP_READ && op != OP_WRITE {
+            return Err(invalid("unknown operation"));
+        }
+
+        if len > MAX_ACCESS_LEN || !fits(mem.addr_range(), addr, len) {
+            if op == OP_WRITE {
+                io::copy(&mut (&mut stream).take(len as u64), &mut io::sink())?;
+            }
+            stream.write_all(&[STATUS_FAILED])?;
+            stream.flush()?;
+            continue;
+        }
+
+        let mut data = vec![0u8; len];
+        if op == OP_READ {
+            match mem.try_read_into(addr, &mut data) {
+                Ok(()) => {
+                    stream.write_all(&[STATUS_OK])?;
+                    stream.write_all(&data)?;
+                }
+                Err(_) => stream.write_all(&[STATUS_FAILED])?,
+            }
+        } else {
+            stream.read_exact(&mut data)?;
+            let status = match mem.try_write_from(addr, &data) {
+                Ok(()) => STATUS_OK,
+                Err(_) => STATUS_FAILED,
+            };
+            stream.write_all(&[status])?;
+        }
+        stream.flush()?;
+    }
+}
+
+/// Returns `true` if an access of `len` bytes at `addr` lies inside the given range of
+/// addresses, or the range is not known.
+fn fits(range: Option<Range<usize>>, addr: usize, len: usize) -> bool {
+    match (addr.checked_add(len), range) {
+        (Some(end), Some(range)) => range.start <= addr && end <= range.end,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+fn write_header<T: Write>(stream: &mut T, op: u8, addr: usize, len: usize) -> io::Result<()> {
+    let len = u32::try_from(len).map_err(|_| invalid("access is larger than u32::MAX bytes"))?;
+
+    let mut header = [0u8; 13];
+    header[0] = op;
+    header[1..9].copy_from_slice(&(addr as u64).to_le_bytes());
+    header[9..13].copy_from_slice(&len.to_le_bytes());
+    stream.write_all(&header)
+}
+
+fn read_status<T: Read>(stream: &mut T) -> io::Result<bool> {
+    let mut status = [0u8];
+    stream.read_exact(&mut status)?;
+    match status[0] {
+        STATUS_OK => Ok(true),
+        STATUS_FAILED => Ok(false),
+        _ => Err(invalid("invalid response status")),
+    }
+}
+
+pub(crate) fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,69 @@
+use crate::{MemoryStorage, ResizeError};
+
+/// A memory whose size can be changed at runtime, while keeping its contents.
+///
+/// This is used to emulate systems with expandable RAM, or Wasm-style linear memories
+/// that grow in pages. Implementations must report their current size through
+/// [`MemoryStorage::size`].
+pub trait ResizableMemory: MemoryStorage {
+    /// Tries to resize this memory to `new_size` bytes.
+    ///
+    /// All bytes below the new size keep their contents, and bytes that are added are
+    /// zero initialized.
+    ///
+    /// Returns `Err(x)` if the memory could not be resized, in which case it is left
+    /// unchanged.
+    fn try_resize(&mut self, new_size: usize) -> Result<(), ResizeError>;
+
+    /// Grows this memory to `new_size` bytes, keeping its contents.
+    ///
+    /// Panics if `new_size` is smaller than the current size, or the memory could not
+    /// be resized.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_resize` instead"))]
+    fn grow(&mut self, new_size: usize) {
+        assert!(
+            new_size >= current_size(self),
+            "new size is smaller than the current size"
+        );
+        or_panic!(
+            self.try_resize(new_size),
+            "grow memory to {} bytes",
+            new_size
+        )
+    }
+
+    /// Shrinks this memory to `new_size` bytes, keeping the contents below the new size.
+    ///
+    /// Panics if `new_size` is larger than the current size, or the memory could not
+    /// be resized.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_resize` instead"))]
+    fn shrink(&mut self, new_size: usize) {
+        assert!(
+            new_size <= current_size(self),
+            "new size is larger than the current size"
+        );
+        or_panic!(
+            self.try_resize(new_size),
+            "shrink memory to {} bytes",
+            new_size
+        )
+    }
+
+    /// Tries to grow this memory by `additional` bytes, and returns the previous size,
+    /// like the `memory.grow` instruction of Wasm.
+    ///
+    /// Returns `Err(x)` if the memory could not be resized, or the new size overflows.
+    fn try_grow_by(&mut self, additional: usize) -> Result<usize, ResizeError> {
+        let size = current_size(self);
+        let new_size = size
+            .checked_add(additional)
+            .ok_or(ResizeError::AllocFailed { size: usize::MAX })?;
+        self.try_resize(new_size)?;
+        Ok(size)
+    }
+}
+
+fn current_size<M: ResizableMemory + ?Sized>(mem: &M) -> usize {
+    mem.size()
+        .expect("resizable memory does not report its size")
+}
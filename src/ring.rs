@@ -0,0 +1,185 @@
+use crate::{MemoryStorage, Value};
+use core::marker::PhantomData;
+use core::ops::Range;
+
+/// Describes what happens if a value is pushed to a full [`RingRegion`] or [`MemFifo`].
+///
+/// [`MemFifo`]: crate::MemFifo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// The pushed value is dropped, like the receive FIFO of most UARTs.
+    Reject,
+    /// The oldest value is dropped to make room for the pushed value.
+    DropOldest,
+    /// The pushed value replaces the value that was pushed last.
+    OverwriteNewest,
+}
+
+/// A ring buffer of `V`s that is stored inside a memory.
+///
+/// The entries live inside the data range, while the head (next entry to pop) and tail
+/// (next entry to push) indices are stored as little endian `u32`s at configurable addresses.
+/// This matches the layout of the shared rings used by DMA engines or network cards,
+/// so the guest and the host can both operate on the same ring.
+///
+/// One entry is always kept free to distinguish a full from an empty ring,
+/// so a ring with `n` entries can hold up to `n - 1` values. Pushing to a full ring
+/// drops a value according to the [`OverflowPolicy`], which is
+/// [`Reject`](OverflowPolicy::Reject) by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RingRegion<V> {
+    base: usize,
+    entries: usize,
+    head_addr: usize,
+    tail_addr: usize,
+    policy: OverflowPolicy,
+    _value: PhantomData<V>,
+}
+
+impl<V: Value> RingRegion<V> {
+    /// Creates a new `RingRegion` whose entries are stored inside `data`, and whose
+    /// head and tail indices are stored at `head_addr` and `tail_addr`.
+    ///
+    /// Panics if `data` can not hold at least two entries.
+    pub fn new(data: Range<usize>, head_addr: usize, tail_addr: usize) -> Self {
+        let entries = data.len() / core::mem::size_of::<V>();
+        assert!(entries >= 2, "a ring must have at least two entries");
+
+        Self {
+            base: data.start,
+            entries,
+            head_addr,
+            tail_addr,
+            policy: OverflowPolicy::Reject,
+            _value: PhantomData,
+        }
+    }
+
+    /// Sets what happens if a value is pushed to a full ring.
+    pub fn overflow(mut self, policy: OverflowPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the maximum number of values that can be stored inside this ring.
+    pub fn capacity(&self) -> usize {
+        self.entries - 1
+    }
+
+    /// Tries to reset the head and tail indices, which makes the ring empty.
+    ///
+    /// Returns `Err(x)` if the method failed to write the indices.
+    pub fn try_reset<M: MemoryStorage>(&self, mem: &mut M) -> Result<(), M::Error> {
+        mem.try_write::<u32>(self.head_addr, 0)?;
+        mem.try_write::<u32>(self.tail_addr, 0)
+    }
+
+    /// Tries to get the number of values that are currently stored inside the ring.
+    ///
+    /// Returns `Err(x)` if the method failed to read the indices.
+    pub fn try_len<M: MemoryStorage>(&self, mem: &M) -> Result<usize, M::Error> {
+        let (head, tail) = self.indices(mem)?;
+        Ok((tail + self.entries - head) % self.entries)
+    }
+
+    /// Tries to push a value to the tail of the ring.
+    ///
+    /// Returns `Ok(false)` if the ring was full, and a value was dropped according to
+    /// the overflow policy, and `Err(x)` if the method failed to access the memory.
+    pub fn try_push<M: MemoryStorage>(&self, mem: &mut M, val: V) -> Result<bool, M::Error> {
+        let (head, tail) = self.indices(mem)?;
+        let next = (tail + 1) % self.entries;
+        if next != head {
+            mem.try_write(self.entry_addr(tail), val)?;
+            mem.try_write::<u32>(self.tail_addr, next as u32)?;
+            return Ok(true);
+        }
+
+        match self.policy {
+            OverflowPolicy::Reject => {}
+            OverflowPolicy::DropOldest => {
+                mem.try_write::<u32>(self.head_addr, ((head + 1) % self.entries) as u32)?;
+                mem.try_write(self.entry_addr(tail), val)?;
+                mem.try_write::<u32>(self.tail_addr, next as u32)?;
+            }
+            OverflowPolicy::OverwriteNewest => {
+                let newest = (tail + self.entries - 1) % self.entries;
+                mem.try_write(self.entry_addr(newest), val)?;
+            }
+        }
+        Ok(false)
+    }
+
+    /// Pushes a value to the tail of the ring.
+    ///
+    /// Returns `false` if the ring was full, and a value was dropped according to the
+    /// overflow policy.
+    /// Panics if the method failed to access the memory.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_push` instead"))]
+    pub fn push<M: MemoryStorage>(&self, mem: &mut M, val: V) -> bool {
+        or_panic!(self.try_push(mem, val), "push to ring at {:#x}", self.base)
+    }
+
+    /// Tries to pop a value from the head of the ring.
+    ///
+    /// Returns `Ok(None)` if the ring is empty, and `Err(x)` if the method failed to
+    /// access the memory.
+    pub fn try_pop<M: MemoryStorage>(&self, mem: &mut M) -> Result<Option<V>, M::Error> {
+        let (head, tail) = self.indices(mem)?;
+        if head == tail {
+            return Ok(None);
+        }
+
+        let val = mem.try_read(self.entry_addr(head))?;
+        mem.try_write::<u32>(self.head_addr, ((head + 1) % self.entries) as u32)?;
+        Ok(Some(val))
+    }
+
+    /// Pops a value from the head of the ring.
+    ///
+    /// Returns `None` if the ring is empty.
+    /// Panics if the method failed to access the memory.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_pop` instead"))]
+    pub fn pop<M: MemoryStorage>(&self, mem: &mut M) -> Option<V> {
+        or_panic!(self.try_pop(mem), "pop from ring at {:#x}", self.base)
+    }
+
+    /// Tries to read the value at the head of the ring, without removing it.
+    ///
+    /// Returns `Ok(None)` if the ring is empty, and `Err(x)` if the method failed to
+    /// access the memory.
+    pub fn try_peek<M: MemoryStorage>(&self, mem: &M) -> Result<Option<V>, M::Error> {
+        let (head, tail) = self.indices(mem)?;
+        if head == tail {
+            return Ok(None);
+        }
+        mem.try_read(self.entry_addr(head)).map(Some)
+    }
+
+    /// Reads the value at the head of the ring, without removing it.
+    ///
+    /// Returns `None` if the ring is empty.
+    /// Panics if the method failed to access the memory.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_peek` instead"))]
+    pub fn peek<M: MemoryStorage>(&self, mem: &M) -> Option<V> {
+        or_panic!(self.try_peek(mem), "peek into ring at {:#x}", self.base)
+    }
+
+    fn indices<M: MemoryStorage>(&self, mem: &M) -> Result<(usize, usize), M::Error> {
+        // Indices that are out of range (e.g. written by a misbehaving guest) are wrapped
+        // into the ring, instead of accessing memory outside of it.
+        let head = mem.try_read::<u32>(self.head_addr)? as usize % self.entries;
+        let tail = mem.try_read::<u32>(self.tail_addr)? as usize % self.entries;
+        Ok((head, tail))
+    }
+
+    /// Returns the address of the head index, which a [`MemFifo`](crate::MemFifo) uses as
+    /// the start of its region.
+    pub(crate) fn head_addr(&self) -> usize {
+        self.head_addr
+    }
+
+    fn entry_addr(&self, index: usize) -> usize {
+        self.base + index * core::mem::size_of::<V>()
+    }
+}
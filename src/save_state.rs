@@ -0,0 +1,389 @@
+//! A small, versioned binary format for saving and restoring the content of a memory.
+//!
+//! # Format
+//!
+//! All integers are stored in little endian format.
+//!
+//! | Field        | Size | Description                                           |
+//! |--------------|------|-------------------------------------------------------|
+//! | magic        | 4    | Always `b"MEMS"`                                      |
+//! | major        | 2    | Major version, currently [`MAJOR_VERSION`]            |
+//! | minor        | 2    | Minor version, currently [`MINOR_VERSION`]            |
+//! | flags        | 4    | Bit 0 is set if pages may be compressed, bit 1 if the |
+//! |              |      | state is a delta                                      |
+//! | page size    | 4    | Number of bytes per page                              |
+//! | start        | 8    | Address of the first saved byte                       |
+//! | length       | 8    | Number of saved bytes                                 |
+//!
+//! The header is followed by chunks, each consisting of a 4 byte tag, a 4 byte payload
+//! length and the payload. A `PAGE` chunk stores the page index as `u64`, followed by
+//! an encoding byte and the encoded page data. The last chunk is always an `END\0` chunk.
+//!
+//! # Deltas
+//!
+//! A delta, created by [`save_delta`], only contains the pages that differ from a baseline.
+//! It uses the same format, so it is applied by [`load`]ing it into a memory that
+//! already contains the baseline.
+//!
+//! # Compatibility
+//!
+//! States with a different major version are rejected. Newer minor versions may add new
+//! chunk types, which are skipped by older readers using the payload length. New page
+//! encodings always require a new major version.
+
+use crate::MemoryStorage;
+use core::convert::TryFrom;
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::ops::Range;
+
+/// The major version of the format that is written by [`save`].
+pub const MAJOR_VERSION: u16 = 1;
+
+/// The minor version of the format that is written by [`save`].
+pub const MINOR_VERSION: u16 = 1;
+
+const MAGIC: &[u8; 4] = b"MEMS";
+const HEADER_LEN: usize = 32;
+const FLAG_COMPRESSED: u32 = 1;
+const FLAG_DELTA: u32 = 2;
+
+const TAG_PAGE: &[u8; 4] = b"PAGE";
+const TAG_END: &[u8; 4] = b"END\0";
+
+const ENCODING_RAW: u8 = 0;
+const ENCODING_ZERO: u8 = 1;
+const ENCODING_RLE: u8 = 2;
+
+/// Options that control how a memory is saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveOptions {
+    /// The number of bytes per page.
+    pub page_size: u32,
+    /// If `true`, pages are run-length encoded if that makes them smaller.
+    pub compress: bool,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            page_size: 4096,
+            compress: false,
+        }
+    }
+}
+
+/// The error that is returned if a save state could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError<E> {
+    /// The data does not start with the magic bytes.
+    InvalidMagic,
+    /// The major version of the save state is not supported.
+    UnsupportedVersion {
+        /// The major version of the save state.
+        major: u16,
+        /// The minor version of the save state.
+        minor: u16,
+    },
+    /// The data ended before the `END` chunk.
+    Truncated,
+    /// A chunk or the header contains invalid data.
+    InvalidChunk,
+    /// Writing the restored data into the memory failed.
+    Memory(E),
+}
+
+impl<E: fmt::Debug> fmt::Display for LoadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::InvalidMagic => f.write_str("not a memory save state"),
+            LoadError::UnsupportedVersion { major, minor } => {
+                write!(f, "unsupported save state version {}.{}", major, minor)
+            }
+            LoadError::Truncated => f.write_str("save state is truncated"),
+            LoadError::InvalidChunk => f.write_str("save state contains an invalid chunk"),
+            LoadError::Memory(err) => write!(f, "failed to write memory: {:?}", err),
+        }
+    }
+}
+
+/// The error that is returned if a save state could not be created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveError<E> {
+    /// The page size of the options is zero.
+    InvalidPageSize,
+    /// The baseline of a delta does not have the same length as the saved range.
+    BaselineLength {
+        /// The length of the saved range.
+        expected: usize,
+        /// The length of the baseline.
+        found: usize,
+    },
+    /// Reading the saved range from the memory failed.
+    Memory(E),
+}
+
+impl<E: fmt::Debug> fmt::Display for SaveError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::InvalidPageSize => f.write_str("page size must be non-zero"),
+            SaveError::BaselineLength { expected, found } => write!(
+                f,
+                "baseline has {} bytes, but the range has {} bytes",
+                found, expected
+            ),
+            SaveError::Memory(err) => write!(f, "failed to read memory: {:?}", err),
+        }
+    }
+}
+
+/// Saves the bytes inside the given range of the memory.
+///
+/// Returns `Err(x)` if the page size is zero, or the method failed to read the range.
+#[cfg(feature = "alloc")]
+pub fn save<M: MemoryStorage + ?Sized>(
+    mem: &M,
+    range: Range<usize>,
+    options: SaveOptions,
+) -> Result<alloc::vec::Vec<u8>, SaveError<M::Error>> {
+    save_pages(mem, range, options, None)
+}
+
+/// Saves only the pages inside the given range of the memory, that differ from `baseline`.
+///
+/// `baseline` contains the previous bytes of the range, so `baseline[0]` is the byte at
+/// `range.start`. Loading the delta into a memory that contains the baseline restores
+/// the current content of the range.
+///
+/// Returns `Err(x)` if the page size is zero, `baseline` is not as long as the range, or
+/// the method failed to read the range.
+#[cfg(feature = "alloc")]
+pub fn save_delta<M: MemoryStorage + ?Sized>(
+    mem: &M,
+    range: Range<usize>,
+    baseline: &[u8],
+    options: SaveOptions,
+) -> Result<alloc::vec::Vec<u8>, SaveError<M::Error>> {
+    if baseline.len() != range.len() {
+        return Err(SaveError::BaselineLength {
+            expected: range.len(),
+            found: baseline.len(),
+        });
+    }
+    save_pages(mem, range, options, Some(baseline))
+}
+
+/// Returns `true` if the save state was created using [`save_delta`].
+///
+/// Returns `false` if the data is not a valid save state header.
+pub fn is_delta(data: &[u8]) -> bool {
+    data.len() >= HEADER_LEN
+        && &data[..4] == MAGIC
+        && u32::from_le_bytes([data[8], data[9], data[10], data[11]]) & FLAG_DELTA != 0
+}
+
+#[cfg(feature = "alloc")]
+fn save_pages<M: MemoryStorage + ?Sized>(
+    mem: &M,
+    range: Range<usize>,
+    options: SaveOptions,
+    baseline: Option<&[u8]>,
+) -> Result<alloc::vec::Vec<u8>, SaveError<M::Error>> {
+    use alloc::vec::Vec;
+
+    if options.page_size == 0 {
+        return Err(SaveError::InvalidPageSize);
+    }
+    let page_size = options.page_size as usize;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + range.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&MAJOR_VERSION.to_le_bytes());
+    out.extend_from_slice(&MINOR_VERSION.to_le_bytes());
+    let mut flags = if options.compress { FLAG_COMPRESSED } else { 0 };
+    if baseline.is_some() {
+        flags |= FLAG_DELTA;
+    }
+    out.extend_from_slice(&flags.to_le_bytes());
+    out.extend_from_slice(&options.page_size.to_le_bytes());
+    out.extend_from_slice(&(range.start as u64).to_le_bytes());
+    out.extend_from_slice(&(range.len() as u64).to_le_bytes());
+
+    let mut page = alloc::vec![0u8; page_size];
+    let mut encoded = Vec::new();
+    for (index, start) in range.clone().step_by(page_size).enumerate() {
+        let page = &mut page[..page_size.min(range.end - start)];
+        mem.try_read_into(start, page).map_err(SaveError::Memory)?;
+
+        let offset = start - range.start;
+        if let Some(baseline) = baseline {
+            if baseline[offset..offset + page.len()] == *page {
+                continue;
+            }
+        }
+
+        encoded.clear();
+        let encoding = if page.iter().all(|&b| b == 0) {
+            ENCODING_ZERO
+        } else if options.compress && rle_encode(page, &mut encoded) {
+            ENCODING_RLE
+        } else {
+            encoded.clear();
+            encoded.extend_from_slice(page);
+            ENCODING_RAW
+        };
+
+        out.extend_from_slice(TAG_PAGE);
+        out.extend_from_slice(&(9 + encoded.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(index as u64).to_le_bytes());
+        out.push(encoding);
+        out.extend_from_slice(&encoded);
+    }
+
+    out.extend_from_slice(TAG_END);
+    out.extend_from_slice(&0u32.to_le_bytes());
+    Ok(out)
+}
+
+/// Restores a save state, that was created using [`save`], into the memory.
+///
+/// The bytes are written to the same addresses they were saved from. If the state
+/// is a delta, only the changed pages are written.
+pub fn load<M: MemoryStorage + ?Sized>(
+    mem: &mut M,
+    data: &[u8],
+) -> Result<(), LoadError<M::Error>> {
+    let mut reader = Reader(data);
+    if reader.take(4)? != MAGIC {
+        return Err(LoadError::InvalidMagic);
+    }
+
+    let major = reader.u16()?;
+    let minor = reader.u16()?;
+    if major != MAJOR_VERSION {
+        return Err(LoadError::UnsupportedVersion { major, minor });
+    }
+
+    let flags = reader.u32()?;
+    let page_size = reader.u32()? as usize;
+    let start = to_usize(reader.u64()?)?;
+    let len = to_usize(reader.u64()?)?;
+    if page_size == 0 {
+        return Err(LoadError::InvalidChunk);
+    }
+
+    loop {
+        let tag = reader.take(4)?;
+        let payload_len = reader.u32()? as usize;
+        let mut payload = Reader(reader.take(payload_len)?);
+
+        match tag {
+            t if t == TAG_END => return Ok(()),
+            t if t == TAG_PAGE => {
+                let index = to_usize(payload.u64()?)?;
+                let offset = index.checked_mul(page_size).filter(|&o| o < len);
+                let offset = offset.ok_or(LoadError::InvalidChunk)?;
+                let page_len = page_size.min(len - offset);
+                let addr = start.checked_add(offset).ok_or(LoadError::InvalidChunk)?;
+                let end = addr.checked_add(page_len).ok_or(LoadError::InvalidChunk)?;
+
+                match payload.take(1)?[0] {
+                    ENCODING_RAW if payload.0.len() == page_len => mem
+                        .try_write_from(addr, payload.0)
+                        .map_err(LoadError::Memory)?,
+                    ENCODING_ZERO => mem.try_fill(addr..end, 0).map_err(LoadError::Memory)?,
+                    ENCODING_RLE if flags & FLAG_COMPRESSED != 0 => {
+                        rle_decode(mem, addr, page_len, payload.0)?
+                    }
+                    _ => return Err(LoadError::InvalidChunk),
+                }
+            }
+            // Chunks that were added in a newer minor version are skipped.
+            _ => {}
+        }
+    }
+}
+
+/// Run-length encodes `page` as pairs of run length and byte into `out`.
+///
+/// Returns `false` if the encoded page would not be smaller than the raw page.
+#[cfg(feature = "alloc")]
+fn rle_encode(page: &[u8], out: &mut alloc::vec::Vec<u8>) -> bool {
+    let mut rest = page;
+    while let Some(&byte) = rest.first() {
+        let run = rest.iter().take(255).take_while(|&&b| b == byte).count();
+        out.push(run as u8);
+        out.push(byte);
+        rest = &rest[run..];
+
+        if out.len() >= page.len() {
+            return false;
+        }
+    }
+    true
+}
+
+fn rle_decode<M: MemoryStorage + ?Sized>(
+    mem: &mut M,
+    addr: usize,
+    page_len: usize,
+    data: &[u8],
+) -> Result<(), LoadError<M::Error>> {
+    if !data.len().is_multiple_of(2) {
+        return Err(LoadError::InvalidChunk);
+    }
+
+    let mut offset = 0;
+    for pair in data.chunks_exact(2) {
+        let run = pair[0] as usize;
+        if run == 0 || offset + run > page_len {
+            return Err(LoadError::InvalidChunk);
+        }
+        let start = addr.checked_add(offset).ok_or(LoadError::InvalidChunk)?;
+        let end = start.checked_add(run).ok_or(LoadError::InvalidChunk)?;
+        mem.try_fill(start..end, pair[1])
+            .map_err(LoadError::Memory)?;
+        offset += run;
+    }
+
+    if offset == page_len {
+        Ok(())
+    } else {
+        Err(LoadError::InvalidChunk)
+    }
+}
+
+fn to_usize<E>(val: u64) -> Result<usize, LoadError<E>> {
+    usize::try_from(val).map_err(|_| LoadError::InvalidChunk)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take<E>(&mut self, len: usize) -> Result<&'a [u8], LoadError<E>> {
+        if self.0.len() < len {
+            return Err(LoadError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u16<E>(&mut self) -> Result<u16, LoadError<E>> {
+        let mut bytes = [0u8; 2];
+        bytes.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    fn u32<E>(&mut self) -> Result<u32, LoadError<E>> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn u64<E>(&mut self) -> Result<u64, LoadError<E>> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
@@ -0,0 +1,437 @@
+use crate::{MemoryStorage, OutOfBounds};
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::ffi::CString;
+use std::io;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+
+/// Marks a mapping whose header was completely written by its creator.
+const MAGIC: u64 = u64::from_le_bytes(*b"MEMSHM01");
+
+/// The size of the header at the start of every mapping, which stores the magic and the
+/// size of the memory. It is a page, so the memory itself stays page aligned.
+const HEADER: usize = 4096;
+
+/// The access permissions of a [`SharedView`], which are enforced by the MMU of the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Protection {
+    /// If `true`, the bytes can be read.
+    pub read: bool,
+    /// If `true`, the bytes can be written.
+    pub write: bool,
+    /// If `true`, the bytes can be executed, e.g. for code that was generated by a JIT.
+    pub exec: bool,
+}
+
+impl Protection {
+    /// Every access traps.
+    pub const NONE: Self = Self {
+        read: false,
+        write: false,
+        exec: false,
+    };
+    /// Only reads are allowed.
+    pub const READ: Self = Self {
+        read: true,
+        write: false,
+        exec: false,
+    };
+    /// Reads and writes are allowed.
+    pub const READ_WRITE: Self = Self {
+        read: true,
+        write: true,
+        exec: false,
+    };
+
+    pub(crate) fn bits(self) -> libc::c_int {
+        let mut prot = libc::PROT_NONE;
+        if self.read {
+            prot |= libc::PROT_READ;
+        }
+        if self.write {
+            prot |= libc::PROT_WRITE;
+        }
+        if self.exec {
+            prot |= libc::PROT_EXEC;
+        }
+        prot
+    }
+}
+
+/// A memory that lives inside a POSIX shared memory object, so multiple processes can
+/// access the same bytes, e.g. an emulator core and its UI, or a fuzzer and its target.
+///
+/// The creator stores the size of the memory and a magic value inside a header in front
+/// of the memory. The magic is written last, so [`open`](Self::open) only succeeds after
+/// the memory was completely set up, and always sees the correct size.
+///
+/// Other processes may modify the memory at any time, so this memory never hands out
+/// references to its content, and synchronizing the accesses is up to the user.
+#[derive(Debug)]
+pub struct SharedMemory {
+    fd: OwnedFd,
+    ptr: *mut u8,
+    len: usize,
+    map_len: usize,
+    name: Option<CString>,
+}
+
+// Safety: the mapping is owned by the memory, and only accessed through it.
+unsafe impl Send for SharedMemory {}
+
+impl SharedMemory {
+    /// Creates a new, zeroed shared memory object with the given name and size.
+    ///
+    /// The name must start with a `/` and contain no other `/`. The name is removed once
+    /// the returned memory is dropped, but processes that already opened it stay connected.
+    ///
+    /// Returns `Err(x)` if the name is invalid or already exists, or the memory could not
+    /// be created.
+    pub fn create(name: &str, size: usize) -> io::Result<Self> {
+        let name = shm_name(name)?;
+        let flags = libc::O_RDWR | libc::O_CREAT | libc::O_EXCL;
+        // Safety: `name` is a valid C string.
+        let fd = unsafe { libc::shm_open(name.as_ptr(), flags, 0o600) };
+        let fd = owned_fd(fd)?;
+
+        match Self::init(fd, size) {
+            Ok(mut mem) => {
+                mem.name = Some(name);
+                Ok(mem)
+            }
+            Err(err) => {
+                // Safety: `name` is a valid C string.
+                unsafe { libc::shm_unlink(name.as_ptr()) };
+                Err(err)
+            }
+        }
+    }
+
+    /// Opens a shared memory object that was created by [`create`](Self::create).
+    ///
+    /// Returns `Err(x)` if the name is invalid or does not exist, or the memory was not
+    /// created by this crate.
+    pub fn open(name: &str) -> io::Result<Self> {
+        let name = shm_name(name)?;
+        // Safety: `name` is a valid C string.
+        let fd = unsafe { libc::shm_open(name.as_ptr(), libc::O_RDWR, 0) };
+        Self::from_fd(owned_fd(fd)?)
+    }
+
+    /// Creates a new, zeroed anonymous shared memory object of the given size using
+    /// `memfd_create`.
+    ///
+    /// The file descriptor can be passed to another process, which opens it using
+    /// [`from_fd`](Self::from_fd).
+    #[cfg(target_os = "linux")]
+    pub fn anonymous(size: usize) -> io::Result<Self> {
+        // Safety: the name is a valid C string.
+        let name = b"mem_storage\0".as_ptr() as *const libc::c_char;
+        let fd = unsafe { libc::memfd_create(name, libc::MFD_CLOEXEC) };
+        Self::init(owned_fd(fd)?, size)
+    }
+
+    /// Opens a shared memory object from a file descriptor, which refers to a memory
+    /// created by [`create`](Self::create) or [`anonymous`](Self::anonymous).
+    ///
+    /// Returns `Err(x)` if the memory was not created by this crate.
+    pub fn from_fd(fd: OwnedFd) -> io::Result<Self> {
+        // Safety: `stat` is a plain C struct, which is valid if zeroed.
+        let mut stat = unsafe { core::mem::zeroed::<libc::stat>() };
+        // Safety: `fd` is a valid file descriptor and `stat` a valid pointer.
+        if unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let file_len = stat.st_size as usize;
+        if file_len < HEADER {
+            return Err(not_shared());
+        }
+        let ptr = map(&fd, file_len)?;
+        let mut mem = Self {
+            fd,
+            ptr,
+            len: 0,
+            map_len: file_len,
+            name: None,
+        };
+
+        let (magic, size) = mem.header();
+        let size = size.load(Ordering::Relaxed);
+        if magic.load(Ordering::Acquire) != MAGIC || size > (file_len - HEADER) as u64 {
+            return Err(not_shared());
+        }
+        mem.len = size as usize;
+        Ok(mem)
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Maps the bytes of this memory a second time, with the given protection.
+    ///
+    /// The view shares its bytes with this memory, but has its own permissions, which
+    /// can be changed per page using [`SharedView::protect`]. This way, the raw pointers
+    /// that are used by JIT compiled code trap on illegal accesses, while the
+    /// `MemoryStorage` methods keep using the unprotected mapping.
+    ///
+    /// Returns `Err(x)` if the view could not be mapped.
+    pub fn map_view(&self, prot: Protection) -> io::Result<SharedView> {
+        let ptr = self.map_data(core::ptr::null_mut(), 0, prot)?;
+        Ok(SharedView { ptr, len: self.len })
+    }
+
+    /// Maps the bytes of this memory at the given address, which is only a hint unless
+    /// `flags` contains `MAP_FIXED`.
+    pub(crate) fn map_data(
+        &self,
+        addr: *mut u8,
+        flags: libc::c_int,
+        prot: Protection,
+    ) -> io::Result<*mut u8> {
+        // Safety: a new shared mapping is created, which does not alias any Rust memory.
+        // Callers that pass `MAP_FIXED` own the address range that is replaced.
+        let ptr = unsafe {
+            libc::mmap(
+                addr as *mut libc::c_void,
+                self.len.max(1),
+                prot.bits(),
+                libc::MAP_SHARED | flags,
+                self.fd.as_raw_fd(),
+                HEADER as libc::off_t,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(ptr as *mut u8)
+    }
+
+    /// Resizes a new shared memory object, maps it and writes the header.
+    fn init(fd: OwnedFd, size: usize) -> io::Result<Self> {
+        let file_len = size
+            .checked_add(HEADER)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "size is too large"))?;
+        // Safety: `fd` is a valid file descriptor.
+        if unsafe { libc::ftruncate(fd.as_raw_fd(), file_len as libc::off_t) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let ptr = map(&fd, file_len)?;
+        let mem = Self {
+            fd,
+            ptr,
+            len: size,
+            map_len: file_len,
+            name: None,
+        };
+        let (magic, len) = mem.header();
+        len.store(size as u64, Ordering::Relaxed);
+        magic.store(MAGIC, Ordering::Release);
+        Ok(mem)
+    }
+
+    fn header(&self) -> (&AtomicU64, &AtomicU64) {
+        // Safety: the mapping is page aligned and at least `HEADER` bytes long, and the
+        // header is only accessed atomically.
+        unsafe {
+            let header = self.ptr.sub(HEADER) as *const AtomicU64;
+            (&*header, &*header.add(1))
+        }
+    }
+
+    fn check(&self, addr: usize, len: usize) -> Result<(), OutOfBounds> {
+        match addr.checked_add(len) {
+            Some(end) if end <= self.len => Ok(()),
+            _ => Err(OutOfBounds {
+                addr: addr.max(self.len),
+            }),
+        }
+    }
+}
+
+impl MemoryStorage for SharedMemory {
+    type Error = OutOfBounds;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+        // Safety: the range was checked to be inside the mapping.
+        unsafe { core::ptr::copy_nonoverlapping(self.ptr.add(addr), buf.as_mut_ptr(), buf.len()) };
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.check(addr, buf.len())?;
+        // Safety: the range was checked to be inside the mapping.
+        unsafe { core::ptr::copy_nonoverlapping(buf.as_ptr(), self.ptr.add(addr), buf.len()) };
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        crate::atomic_fence(order)
+    }
+}
+
+impl AsFd for SharedMemory {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl Drop for SharedMemory {
+    fn drop(&mut self) {
+        // Safety: the mapping was created by `map` and is not used anymore.
+        unsafe { libc::munmap(self.ptr.sub(HEADER) as *mut libc::c_void, self.map_len) };
+        if let Some(name) = &self.name {
+            // Safety: `name` is a valid C string.
+            unsafe { libc::shm_unlink(name.as_ptr()) };
+        }
+    }
+}
+
+/// A second mapping of a [`SharedMemory`] with its own protection, which is created by
+/// [`SharedMemory::map_view`].
+///
+/// The view only hands out a raw pointer, so accesses that violate the protection trap
+/// in hardware with a `SIGSEGV`, instead of being checked in software.
+#[derive(Debug)]
+pub struct SharedView {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// Safety: the mapping is owned by the view, which never accesses it.
+unsafe impl Send for SharedView {}
+// Safety: shared references only allow reading the pointer.
+unsafe impl Sync for SharedView {}
+
+impl SharedView {
+    /// Returns a pointer to the first byte of the view.
+    ///
+    /// The bytes can be modified by other mappings of the memory at any time, and
+    /// accesses that are not allowed by the protection of their page trap.
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Returns the number of bytes inside this view.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this view has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Changes the protection of every page that overlaps the given range.
+    ///
+    /// Returns `Err(x)` if the range is outside of the view, or the protection could not
+    /// be changed.
+    pub fn protect(&mut self, range: core::ops::Range<usize>, prot: Protection) -> io::Result<()> {
+        if range.start > range.end || range.end > self.len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "range is outside of the view",
+            ));
+        }
+        if range.start == range.end {
+            return Ok(());
+        }
+        protect(self.ptr, range, prot)
+    }
+}
+
+impl Drop for SharedView {
+    fn drop(&mut self) {
+        // Safety: the mapping was created by `map_view` and is not used anymore.
+        unsafe { libc::munmap(self.ptr as *mut libc::c_void, self.len.max(1)) };
+    }
+}
+
+/// Changes the protection of every page that overlaps `range`, relative to `base`.
+pub(crate) fn protect(
+    base: *mut u8,
+    range: core::ops::Range<usize>,
+    prot: Protection,
+) -> io::Result<()> {
+    // Safety: `sysconf` has no preconditions.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let start = range.start / page_size * page_size;
+    let end = range.end.div_ceil(page_size) * page_size;
+    // Safety: the pages belong to a mapping that is owned by the caller, and only
+    // accessed through raw pointers.
+    if unsafe {
+        libc::mprotect(
+            base.add(start) as *mut libc::c_void,
+            end - start,
+            prot.bits(),
+        )
+    } != 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Maps the whole file and returns the pointer to the first byte after the header.
+fn map(fd: &OwnedFd, len: usize) -> io::Result<*mut u8> {
+    // Safety: a new shared mapping is created, which does not alias any Rust memory.
+    let ptr = unsafe {
+        libc::mmap(
+            core::ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd.as_raw_fd(),
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        return Err(io::Error::last_os_error());
+    }
+    // Safety: the mapping is at least `HEADER` bytes long.
+    Ok(unsafe { (ptr as *mut u8).add(HEADER) })
+}
+
+pub(crate) fn owned_fd(fd: libc::c_int) -> io::Result<OwnedFd> {
+    if fd < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        // Safety: `fd` is a new file descriptor that is owned by nobody else.
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+}
+
+fn shm_name(name: &str) -> io::Result<CString> {
+    let valid = name.len() > 1 && name.starts_with('/') && !name[1..].contains('/');
+    let name = CString::new(name).ok().filter(|_| valid);
+    name.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid shared memory name"))
+}
+
+fn not_shared() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "not a shared memory of this crate",
+    )
+}
@@ -0,0 +1,328 @@
+use crate::{FillPolicy, MemoryStorage, MemoryUsage, OutOfBounds, ReportUsage};
+use alloc::{collections::BTreeMap, sync::Arc, vec, vec::Vec};
+use core::convert::TryFrom;
+use core::ops::Range;
+
+/// A heap allocated memory that spans the whole address space, but only allocates the
+/// pages that were written to.
+///
+/// Pages that were never written read as zero, or as the [`FillPolicy`] that was set
+/// using [`fill_policy`](Self::fill_policy). The pages are stored inside a [`BTreeMap`],
+/// so the populated parts of the address space can be walked in address order using
+/// [`mapped_ranges`](Self::mapped_ranges) and [`next_mapped`](Self::next_mapped), which is
+/// useful for tools that inspect huge guest address spaces.
+///
+/// Pages that only contain their initial content again can be freed using
+/// [`compact`](Self::compact), either manually or automatically after a number of pages
+/// were allocated.
+///
+/// The pages are reference counted, so [`snapshot`](Self::snapshot) and `clone` only copy
+/// the page table, and share every page until one side modifies it. Keeping many rewind
+/// states therefore only costs memory for the pages that changed in between.
+///
+/// The memory is divided into pages of `PAGE_SIZE` bytes, where page `n` starts
+/// at address `n * PAGE_SIZE`. Accesses only fail if they end after `usize::MAX`.
+///
+/// The pages are addressed using `u64`, so a 64-bit guest address space can be
+/// emulated on hosts with a 32-bit `usize`, like wasm. Addresses above `usize::MAX`
+/// are accessed using [`try_read_into_u64`](Self::try_read_into_u64) and
+/// [`try_write_from_u64`](Self::try_write_from_u64).
+#[derive(Clone, Default)]
+pub struct SparseMemory<const PAGE_SIZE: usize = 4096> {
+    pages: BTreeMap<u64, Arc<[u8]>>,
+    compact_after: usize,
+    allocated: usize,
+    fill: FillPolicy,
+}
+
+impl<const PAGE_SIZE: usize> SparseMemory<PAGE_SIZE> {
+    /// Creates a new `SparseMemory` without any allocated pages.
+    pub fn new() -> Self {
+        const { assert!(PAGE_SIZE > 0, "page size must be non-zero") };
+        Self {
+            pages: BTreeMap::new(),
+            compact_after: 0,
+            allocated: 0,
+            fill: FillPolicy::Zero,
+        }
+    }
+
+    /// Sets the content of pages that were never written, which also applies to pages
+    /// that are allocated afterwards.
+    pub fn fill_policy(mut self, fill: FillPolicy) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Runs [`compact`](Self::compact) automatically every time `pages` new pages were
+    /// allocated, where zero disables the automatic compaction.
+    pub fn compact_after(mut self, pages: usize) -> Self {
+        self.compact_after = pages;
+        self
+    }
+
+    /// Frees every page that only contains its initial content, which is zero unless
+    /// another [`FillPolicy`] was set, and returns the number of freed pages.
+    ///
+    /// This keeps the memory usage from growing over time, if the guest clears memory
+    /// that it no longer uses.
+    pub fn compact(&mut self) -> usize {
+        let before = self.pages.len();
+        let fill = self.fill;
+        let mut initial = vec![0u8; PAGE_SIZE];
+        self.pages.retain(|&page, data| {
+            fill.fill_at(page * PAGE_SIZE as u64, &mut initial);
+            **data != initial[..]
+        });
+        self.allocated = 0;
+        before - self.pages.len()
+    }
+
+    /// Returns the number of pages that are currently allocated.
+    pub fn resident_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns `true` if the page that contains `addr` is allocated.
+    pub fn is_mapped(&self, addr: u64) -> bool {
+        self.pages.contains_key(&(addr / PAGE_SIZE as u64))
+    }
+
+    /// Returns the first address at or after `addr` that lies inside an allocated page.
+    pub fn next_mapped(&self, addr: u64) -> Option<u64> {
+        let (&page, _) = self.pages.range(addr / PAGE_SIZE as u64..).next()?;
+        Some(addr.max(page * PAGE_SIZE as u64))
+    }
+
+    /// Returns an iterator over the ranges of allocated pages in ascending address order,
+    /// where adjacent pages are merged into a single range.
+    pub fn mapped_ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        let mut pages = self.pages.keys().copied().peekable();
+        core::iter::from_fn(move || {
+            let first = pages.next()?;
+            let mut last = first;
+            while pages.peek() == Some(&(last + 1)) {
+                last = pages.next()?;
+            }
+            Some(page_range::<PAGE_SIZE>(first).start..page_range::<PAGE_SIZE>(last).end)
+        })
+    }
+
+    /// Resets the given range to its initial content, and frees every page that lies completely inside it.
+    pub fn unmap(&mut self, range: Range<u64>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        let first = range.start / PAGE_SIZE as u64;
+        let last = (range.end - 1) / PAGE_SIZE as u64;
+        let mut freed = Vec::new();
+        for (&page, data) in self.pages.range_mut(first..=last) {
+            let bytes = page_range::<PAGE_SIZE>(page);
+            let start = (range.start.max(bytes.start) - bytes.start) as usize;
+            let end = (range.end.min(bytes.end) - bytes.start) as usize;
+            if start == 0 && end == data.len() {
+                freed.push(page);
+            } else {
+                let dst = &mut Arc::make_mut(data)[start..end];
+                self.fill.fill_at(range.start.max(bytes.start), dst);
+            }
+        }
+
+        for page in freed {
+            self.pages.remove(&page);
+        }
+    }
+
+    /// Frees every page.
+    pub fn clear(&mut self) {
+        self.pages.clear();
+    }
+
+    /// Takes a snapshot of the current content, which shares every page with this memory.
+    pub fn snapshot(&self) -> SparseSnapshot<PAGE_SIZE> {
+        SparseSnapshot {
+            pages: self.pages.clone(),
+        }
+    }
+
+    /// Resets the content of this memory to the given snapshot, which shares every page
+    /// with the snapshot afterwards.
+    pub fn restore(&mut self, snapshot: &SparseSnapshot<PAGE_SIZE>) {
+        self.pages = snapshot.pages.clone();
+    }
+
+    /// Returns the number of allocated pages that are shared with a snapshot or a clone
+    /// of this memory, and thus do not use any additional memory.
+    pub fn shared_pages(&self) -> usize {
+        self.pages
+            .values()
+            .filter(|data| Arc::strong_count(data) > 1)
+            .count()
+    }
+
+    /// Tries to read `buf.len()` bytes starting at the 64-bit address `addr`.
+    ///
+    /// Returns `Err(x)` if the access ends after `u64::MAX`.
+    pub fn try_read_into_u64(&self, addr: u64, buf: &mut [u8]) -> Result<(), OutOfBounds> {
+        let pages = &self.pages;
+        Self::split(addr, buf.len(), |page, offset, range| {
+            let len = range.len();
+            match pages.get(&page) {
+                Some(data) => buf[range].copy_from_slice(&data[offset..offset + len]),
+                None => self
+                    .fill
+                    .fill_at(page * PAGE_SIZE as u64 + offset as u64, &mut buf[range]),
+            }
+        })
+    }
+
+    /// Tries to write the bytes of `buf` starting at the 64-bit address `addr`.
+    ///
+    /// Returns `Err(x)` if the access ends after `u64::MAX`.
+    pub fn try_write_from_u64(&mut self, addr: u64, buf: &[u8]) -> Result<(), OutOfBounds> {
+        let pages = &mut self.pages;
+        let allocated = &mut self.allocated;
+        let fill = self.fill;
+        Self::split(addr, buf.len(), |page, offset, range| {
+            let data = pages.entry(page).or_insert_with(|| {
+                *allocated += 1;
+                let mut data = vec![0u8; PAGE_SIZE];
+                fill.fill_at(page * PAGE_SIZE as u64, &mut data);
+                Arc::from(data)
+            });
+            Arc::make_mut(data)[offset..offset + range.len()].copy_from_slice(&buf[range]);
+        })?;
+
+        if self.compact_after != 0 && self.allocated >= self.compact_after {
+            self.compact();
+        }
+        Ok(())
+    }
+
+    /// Calls `f` with the page index, the offset inside the page and the range of the
+    /// buffer for every page that is touched by an access of `len` bytes at `addr`.
+    fn split<F>(addr: u64, len: usize, mut f: F) -> Result<(), OutOfBounds>
+    where
+        F: FnMut(u64, usize, Range<usize>),
+    {
+        // The last accessed byte may be `u64::MAX`, so the end is inclusive.
+        if len != 0 && addr.checked_add(len as u64 - 1).is_none() {
+            return Err(OutOfBounds {
+                addr: usize::try_from(addr).unwrap_or(usize::MAX),
+            });
+        }
+        let mut cur = addr;
+        let mut done = 0;
+        while done < len {
+            let offset = (cur % PAGE_SIZE as u64) as usize;
+            let n = (PAGE_SIZE - offset).min(len - done);
+            f(cur / PAGE_SIZE as u64, offset, done..done + n);
+            // Wraps only after the last byte of the address space was accessed.
+            cur = cur.wrapping_add(n as u64);
+            done += n;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the addresses that are covered by the given page, where the end of the last
+/// page of the address space is clamped to `u64::MAX`.
+fn page_range<const PAGE_SIZE: usize>(page: u64) -> Range<u64> {
+    let start = page * PAGE_SIZE as u64;
+    start..start.saturating_add(PAGE_SIZE as u64)
+}
+
+impl<const PAGE_SIZE: usize> MemoryStorage for SparseMemory<PAGE_SIZE> {
+    type Error = OutOfBounds;
+
+    /// Every address can be accessed, so the size is `usize::MAX`.
+    fn size(&self) -> Option<usize> {
+        Some(usize::MAX)
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.try_read_into_u64(addr as u64, buf)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.try_write_from_u64(addr as u64, buf)
+    }
+}
+
+impl<const PAGE_SIZE: usize> core::fmt::Debug for SparseMemory<PAGE_SIZE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SparseMemory")
+            .field("resident_pages", &self.pages.len())
+            .finish()
+    }
+}
+
+/// Wipes every page that is not shared with a [`SparseSnapshot`], and frees all pages
+/// afterwards, so the whole memory reads as its initial content again.
+#[cfg(feature = "zeroize")]
+impl<const PAGE_SIZE: usize> zeroize::Zeroize for SparseMemory<PAGE_SIZE> {
+    fn zeroize(&mut self) {
+        self.pages
+            .values_mut()
+            .filter_map(Arc::get_mut)
+            .for_each(zeroize::Zeroize::zeroize);
+        self.pages.clear();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const PAGE_SIZE: usize> Drop for SparseMemory<PAGE_SIZE> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const PAGE_SIZE: usize> zeroize::ZeroizeOnDrop for SparseMemory<PAGE_SIZE> {}
+
+/// An immutable snapshot of a [`SparseMemory`], that is created by
+/// [`SparseMemory::snapshot`].
+///
+/// The snapshot shares its pages with the memory, so it only costs memory once the
+/// memory modifies them.
+#[derive(Clone, Default)]
+pub struct SparseSnapshot<const PAGE_SIZE: usize = 4096> {
+    pages: BTreeMap<u64, Arc<[u8]>>,
+}
+
+impl<const PAGE_SIZE: usize> SparseSnapshot<PAGE_SIZE> {
+    /// Returns the number of pages that were allocated when the snapshot was taken.
+    pub fn resident_pages(&self) -> usize {
+        self.pages.len()
+    }
+}
+
+impl<const PAGE_SIZE: usize> core::fmt::Debug for SparseSnapshot<PAGE_SIZE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SparseSnapshot")
+            .field("resident_pages", &self.pages.len())
+            .finish()
+    }
+}
+
+impl<const PAGE_SIZE: usize> ReportUsage for SparseMemory<PAGE_SIZE> {
+    fn usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            addressable: usize::MAX,
+            allocated: self.pages.len() * PAGE_SIZE,
+            resident_pages: self.pages.len(),
+            dirty_pages: 0,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{ContiguousMemory, MemoryStorage, OutOfBounds};
+
+/// A memory that is backed by a caller-provided buffer, which lives for the whole program,
+/// e.g. a buffer that was placed by the linker on an embedded target.
+///
+/// This does not allocate at all, so it can be used by bootloaders and kernels for their
+/// scratch regions, even without the `alloc` feature.
+#[derive(Debug)]
+pub struct StaticMemory {
+    data: &'static mut [u8],
+}
+
+impl StaticMemory {
+    /// Creates a new `StaticMemory` that uses the given buffer, without modifying it.
+    pub fn new(data: &'static mut [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Consumes this `StaticMemory` and returns the buffer.
+    pub fn into_inner(self) -> &'static mut [u8] {
+        self.data
+    }
+}
+
+impl MemoryStorage for StaticMemory {
+    type Error = OutOfBounds;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.data.get(addr).copied().ok_or(OutOfBounds { addr })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let cell = self.data.get_mut(addr).ok_or(OutOfBounds { addr })?;
+        *cell = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self.data)
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(self.data)
+    }
+}
+
+impl ContiguousMemory for StaticMemory {
+    fn as_bytes(&self) -> &[u8] {
+        self.data
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    fn out_of_bounds(&self, addr: usize) -> Self::Error {
+        OutOfBounds { addr }
+    }
+}
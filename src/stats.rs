@@ -0,0 +1,188 @@
+//! Exporting the counters of the statistics types, like [`CacheStats`], to JSON or CSV,
+//! so they can be consumed by external analysis scripts and dashboards.
+//!
+//! ```
+//! # use mem_storage::{stats::StatsExport, CacheStats, TierStats};
+//! let cache = CacheStats { hits: 10, misses: 2, writebacks: 1 };
+//! let tiers = TierStats { fast: 7, slow: 3, migrations: 1 };
+//!
+//! let mut csv = Vec::new();
+//! StatsExport::new()
+//!     .region("rom cache", &cache)
+//!     .region("ram", &tiers)
+//!     .write_csv(&mut csv)
+//!     .unwrap();
+//! assert!(csv.starts_with(b"region,counter,value\nrom cache,hits,10\n"));
+//! ```
+
+use crate::{
+    CacheStats, InitiatorStats, MemoryStorage, MemoryUsage, ProfileRegion, TierStats, TieredMemory,
+    WearReport,
+};
+use std::format;
+use std::io::{self, Write};
+use std::vec::Vec;
+
+/// A set of named counters, which can be exported using [`StatsExport`].
+pub trait Counters {
+    /// Calls `f` with the name and the value of every counter.
+    fn for_each_counter(&self, f: &mut dyn FnMut(&str, u64));
+}
+
+impl Counters for CacheStats {
+    fn for_each_counter(&self, f: &mut dyn FnMut(&str, u64)) {
+        f("hits", self.hits);
+        f("misses", self.misses);
+        f("writebacks", self.writebacks);
+    }
+}
+
+impl Counters for TierStats {
+    fn for_each_counter(&self, f: &mut dyn FnMut(&str, u64)) {
+        f("fast", self.fast);
+        f("slow", self.slow);
+        f("migrations", self.migrations);
+    }
+}
+
+impl Counters for InitiatorStats {
+    fn for_each_counter(&self, f: &mut dyn FnMut(&str, u64)) {
+        f("accesses", self.accesses);
+        f("stall_cycles", self.stall_cycles);
+    }
+}
+
+impl Counters for ProfileRegion {
+    fn for_each_counter(&self, f: &mut dyn FnMut(&str, u64)) {
+        f("fetches", self.fetches());
+        f("loads", self.loads());
+        f("stores", self.stores());
+    }
+}
+
+impl Counters for MemoryUsage {
+    fn for_each_counter(&self, f: &mut dyn FnMut(&str, u64)) {
+        f("addressable", self.addressable as u64);
+        f("allocated", self.allocated as u64);
+        f("resident_pages", self.resident_pages as u64);
+        f("dirty_pages", self.dirty_pages as u64);
+    }
+}
+
+impl Counters for WearReport {
+    fn for_each_counter(&self, f: &mut dyn FnMut(&str, u64)) {
+        f("blocks", self.blocks as u64);
+        f("total", self.total);
+        f("min", u64::from(self.min));
+        f("max", u64::from(self.max));
+        if let Some(block) = self.most_worn {
+            f("most_worn", block as u64);
+        }
+    }
+}
+
+/// Exports the [`TierStats`] of the memory, followed by the heat of every page as
+/// `page_<n>` counters.
+impl<F, S, const PAGE_SIZE: usize> Counters for TieredMemory<F, S, PAGE_SIZE>
+where
+    F: MemoryStorage,
+    S: MemoryStorage,
+{
+    fn for_each_counter(&self, f: &mut dyn FnMut(&str, u64)) {
+        self.stats().for_each_counter(f);
+        for page in 0..self.len() / PAGE_SIZE {
+            f(&format!("page_{}", page), u64::from(self.heat(page)));
+        }
+    }
+}
+
+/// A list of named regions and their counters, which is written as JSON or CSV.
+#[derive(Default)]
+pub struct StatsExport<'a> {
+    regions: Vec<(&'a str, &'a dyn Counters)>,
+}
+
+impl<'a> StatsExport<'a> {
+    /// Creates a new `StatsExport` without any regions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the counters of a region with the given name.
+    pub fn region(mut self, name: &'a str, counters: &'a dyn Counters) -> Self {
+        self.regions.push((name, counters));
+        self
+    }
+
+    /// Writes the counters as a JSON object, which maps every region name to an object
+    /// that maps the counter names to their values.
+    pub fn write_json<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(b"{")?;
+        for (idx, (name, counters)) in self.regions.iter().enumerate() {
+            if idx > 0 {
+                out.write_all(b",")?;
+            }
+            write_json_string(&mut out, name)?;
+            out.write_all(b":{")?;
+
+            let mut result = Ok(());
+            let mut first = true;
+            counters.for_each_counter(&mut |counter, value| {
+                if result.is_ok() {
+                    let sep = if first { "" } else { "," };
+                    first = false;
+                    result = write!(out, "{}\"{}\":{}", sep, counter, value);
+                }
+            });
+            result?;
+            out.write_all(b"}")?;
+        }
+        out.write_all(b"}")?;
+        out.flush()
+    }
+
+    /// Writes the counters as CSV, with a `region,counter,value` header and one row
+    /// for every counter.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(b"region,counter,value\n")?;
+        for (name, counters) in &self.regions {
+            let mut result = Ok(());
+            counters.for_each_counter(&mut |counter, value| {
+                if result.is_ok() {
+                    result = write_csv_field(&mut out, name)
+                        .and_then(|_| writeln!(out, ",{},{}", counter, value));
+                }
+            });
+            result?;
+        }
+        out.flush()
+    }
+}
+
+impl core::fmt::Debug for StatsExport<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let names = self.regions.iter().map(|(name, _)| name);
+        f.debug_list().entries(names).finish()
+    }
+}
+
+fn write_json_string<W: Write>(out: &mut W, s: &str) -> io::Result<()> {
+    out.write_all(b"\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_all(b"\\\"")?,
+            '\\' => out.write_all(b"\\\\")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => write!(out, "{}", c)?,
+        }
+    }
+    out.write_all(b"\"")
+}
+
+fn write_csv_field<W: Write>(out: &mut W, s: &str) -> io::Result<()> {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        write!(out, "\"{}\"", s.replace('"', "\"\""))
+    } else {
+        out.write_all(s.as_bytes())
+    }
+}
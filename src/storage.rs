@@ -0,0 +1,195 @@
+use crate::{MemoryStorage, StorageError};
+use core::cell::RefCell;
+use core::convert::TryFrom;
+use embedded_storage::nor_flash::{self, NorFlashError, NorFlashErrorKind};
+use embedded_storage::{ReadStorage, Storage};
+
+/// An adapter that exposes a memory as an `embedded-storage` device, so embedded HALs
+/// and bootloaders can use it as their storage in simulation.
+///
+/// The storage offsets are used as addresses of the memory.
+#[derive(Debug, Clone)]
+pub struct StorageDevice<M> {
+    mem: M,
+    capacity: usize,
+}
+
+impl<M: MemoryStorage> StorageDevice<M> {
+    /// Creates a new `StorageDevice` that reports the given capacity in bytes.
+    pub fn new(mem: M, capacity: usize) -> Self {
+        Self { mem, capacity }
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.mem
+    }
+
+    /// Consumes this `StorageDevice` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.mem
+    }
+}
+
+impl<M: MemoryStorage> ReadStorage for StorageDevice<M> {
+    type Error = M::Error;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.mem.try_read_into(offset as usize, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<M: MemoryStorage> Storage for StorageDevice<M> {
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.mem.try_write_from(offset as usize, bytes)
+    }
+}
+
+/// An adapter that exposes an `embedded-storage` device as a memory.
+///
+/// Since `ReadStorage` requires a mutable reference for reading, the device is
+/// stored inside a [`RefCell`].
+///
+/// Accesses fail with [`StorageError::OutOfRange`] if they do not fit into the `u32`
+/// offsets of the device.
+#[derive(Debug)]
+pub struct StorageMemory<S> {
+    storage: RefCell<S>,
+}
+
+impl<S: ReadStorage> StorageMemory<S> {
+    /// Creates a new `StorageMemory` that accesses the given device.
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage: RefCell::new(storage),
+        }
+    }
+
+    /// Returns the capacity of the device in bytes.
+    pub fn capacity(&self) -> usize {
+        self.storage.borrow().capacity()
+    }
+
+    /// Consumes this `StorageMemory` and returns the device.
+    pub fn into_inner(self) -> S {
+        self.storage.into_inner()
+    }
+
+    /// Reads `buf.len()` bytes starting at `addr` from the device.
+    fn read(&self, addr: usize, buf: &mut [u8]) -> Result<(), StorageError<S::Error>> {
+        let offset = offset(addr, buf.len())?;
+        self.storage
+            .borrow_mut()
+            .read(offset, buf)
+            .map_err(StorageError::Storage)
+    }
+}
+
+impl<S> MemoryStorage for StorageMemory<S>
+where
+    S: Storage,
+    S::Error: core::fmt::Debug,
+{
+    type Error = StorageError<S::Error>;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.read(addr, buf)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let offset = offset(addr, buf.len())?;
+        self.storage
+            .get_mut()
+            .write(offset, buf)
+            .map_err(StorageError::Storage)
+    }
+}
+
+/// Converts the address of an access of `len` bytes into a storage offset.
+fn offset<E>(addr: usize, len: usize) -> Result<u32, StorageError<E>> {
+    let end = addr.checked_add(len).map(|end| end as u64);
+    match u32::try_from(addr) {
+        Ok(offset) if end.is_some_and(|end| end <= 1 << 32) => Ok(offset),
+        _ => Err(StorageError::OutOfRange { addr }),
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ReadStorage for crate::CellMemory {
+    type Error = crate::OutOfBounds;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        MemoryStorage::try_read_into(self, offset as usize, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        self.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Storage for crate::CellMemory {
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        MemoryStorage::try_write_from(self, offset as usize, bytes)
+    }
+}
+
+impl NorFlashError for crate::FlashError {
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            crate::FlashError::OutOfBounds { .. } => NorFlashErrorKind::OutOfBounds,
+            crate::FlashError::NotAligned { .. } => NorFlashErrorKind::NotAligned,
+            crate::FlashError::NotErased { .. } => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const BLOCK_SIZE: usize> nor_flash::ErrorType for crate::NorFlashMemory<BLOCK_SIZE> {
+    type Error = crate::FlashError;
+}
+
+#[cfg(feature = "alloc")]
+impl<const BLOCK_SIZE: usize> nor_flash::ReadNorFlash for crate::NorFlashMemory<BLOCK_SIZE> {
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.try_read_into(offset as usize, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        self.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const BLOCK_SIZE: usize> nor_flash::NorFlash for crate::NorFlashMemory<BLOCK_SIZE> {
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = BLOCK_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        self.try_erase(from as usize..to as usize)
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.try_write_from(offset as usize, bytes)
+    }
+}
+
+/// Bits can be cleared by multiple writes without erasing in between.
+#[cfg(feature = "alloc")]
+impl<const BLOCK_SIZE: usize> nor_flash::MultiwriteNorFlash for crate::NorFlashMemory<BLOCK_SIZE> {}
@@ -0,0 +1,228 @@
+use crate::frames::{Frame, Frames};
+use crate::{MemoryStorage, MemoryUsage, ReportUsage, SwapError};
+use alloc::{collections::BTreeSet, vec};
+use core::cell::RefCell;
+use core::ops::Range;
+
+/// The store that holds the pages of a [`SwapMemory`] which are not resident.
+///
+/// With the `std` feature, this is implemented for every type that implements
+/// `Read`, `Write` and `Seek`, like a temporary `File`, where page `n` is stored at
+/// offset `n * PAGE_SIZE`.
+pub trait SwapStore {
+    /// The error that is returned if a page can not be loaded or stored.
+    type Error: core::fmt::Debug;
+
+    /// Fills `data` with the page with the given index, which was stored before.
+    fn load_page(&mut self, page: usize, data: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Stores the page with the given index.
+    fn store_page(&mut self, page: usize, data: &[u8]) -> Result<(), Self::Error>;
+}
+
+#[cfg(feature = "std")]
+impl<T> SwapStore for T
+where
+    T: std::io::Read + std::io::Write + std::io::Seek,
+{
+    type Error = std::io::Error;
+
+    fn load_page(&mut self, page: usize, data: &mut [u8]) -> Result<(), Self::Error> {
+        self.seek(std::io::SeekFrom::Start((page * data.len()) as u64))?;
+        self.read_exact(data)
+    }
+
+    fn store_page(&mut self, page: usize, data: &[u8]) -> Result<(), Self::Error> {
+        self.seek(std::io::SeekFrom::Start((page * data.len()) as u64))?;
+        self.write_all(data)
+    }
+}
+
+/// A memory that keeps at most a fixed number of pages resident on the heap, and swaps
+/// the other pages out to a [`SwapStore`].
+///
+/// Pages are swapped in transparently on access, replacing the least recently used
+/// page, which is only written to the store if it was modified. Pages that were never
+/// swapped out read as zero, so the size of the memory can be much larger than the
+/// memory available to the host. Reads swap pages too, so the memory stores the pages
+/// and the store inside a [`RefCell`].
+///
+/// The memory is divided into pages of `PAGE_SIZE` bytes, where page `n` starts
+/// at address `n * PAGE_SIZE`.
+pub struct SwapMemory<S, const PAGE_SIZE: usize = 4096> {
+    store: RefCell<S>,
+    state: RefCell<State>,
+    pages: usize,
+}
+
+struct State {
+    frames: Frames,
+    swapped: BTreeSet<usize>,
+}
+
+impl<S: SwapStore, const PAGE_SIZE: usize> SwapMemory<S, PAGE_SIZE> {
+    /// Creates a new, zeroed `SwapMemory` with the given number of pages, where at most
+    /// `resident` pages are kept on the heap.
+    ///
+    /// Panics if `resident` is zero.
+    pub fn new(store: S, pages: usize, resident: usize) -> Self {
+        const { assert!(PAGE_SIZE > 0, "page size must be non-zero") };
+        Self {
+            store: RefCell::new(store),
+            state: RefCell::new(State {
+                frames: Frames::new(resident),
+                swapped: BTreeSet::new(),
+            }),
+            pages,
+        }
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.pages * PAGE_SIZE
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.pages == 0
+    }
+
+    /// Returns the maximum number of pages that are kept on the heap.
+    pub fn budget(&self) -> usize {
+        self.state.borrow().frames.capacity()
+    }
+
+    /// Returns the number of pages that are currently kept on the heap.
+    pub fn resident_pages(&self) -> usize {
+        self.state.borrow().frames.len()
+    }
+
+    /// Returns the number of pages that were written to the store.
+    pub fn swapped_pages(&self) -> usize {
+        self.state.borrow().swapped.len()
+    }
+
+    /// Returns the indices of the pages that are covered by the given access.
+    fn page_range(&self, addr: usize, len: usize) -> Result<Range<usize>, SwapError<S::Error>> {
+        let size = self.len();
+        match addr.checked_add(len) {
+            Some(end) if end <= size => Ok(addr / PAGE_SIZE..end.div_ceil(PAGE_SIZE)),
+            _ => Err(SwapError::OutOfBounds {
+                addr: addr.max(size),
+            }),
+        }
+    }
+
+    /// Returns the frame of the given page, and swaps it in first if necessary.
+    fn resident<'a>(
+        &self,
+        state: &'a mut State,
+        page: usize,
+    ) -> Result<&'a mut Frame, SwapError<S::Error>> {
+        if state.frames.get(page).is_some() {
+            return Ok(state.frames.get(page).unwrap());
+        }
+
+        let mut store = self.store.borrow_mut();
+        let mut data = if state.frames.is_full() {
+            // The victim stays resident if it can not be written to the store.
+            let victim = state.frames.lru().unwrap();
+            if victim.dirty {
+                store
+                    .store_page(victim.page, &victim.data)
+                    .map_err(|error| SwapError::Store {
+                        page: victim.page,
+                        error,
+                    })?;
+                victim.dirty = false;
+                state.swapped.insert(victim.page);
+            }
+            state.frames.evict().unwrap().data
+        } else {
+            vec![0u8; PAGE_SIZE].into_boxed_slice()
+        };
+
+        if state.swapped.contains(&page) {
+            store
+                .load_page(page, &mut data)
+                .map_err(|error| SwapError::Store { page, error })?;
+        } else {
+            data.fill(0);
+        }
+        Ok(state.frames.insert(page, data))
+    }
+}
+
+impl<S: SwapStore, const PAGE_SIZE: usize> MemoryStorage for SwapMemory<S, PAGE_SIZE> {
+    type Error = SwapError<S::Error>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.page_range(addr, buf.len())?;
+        let mut state = self.state.borrow_mut();
+
+        let mut done = 0;
+        while done < buf.len() {
+            let (page, offset) = ((addr + done) / PAGE_SIZE, (addr + done) % PAGE_SIZE);
+            let len = (PAGE_SIZE - offset).min(buf.len() - done);
+
+            let frame = self.resident(&mut state, page)?;
+            buf[done..done + len].copy_from_slice(&frame.data[offset..offset + len]);
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.page_range(addr, buf.len())?;
+        let mut state = self.state.borrow_mut();
+
+        let mut done = 0;
+        while done < buf.len() {
+            let (page, offset) = ((addr + done) / PAGE_SIZE, (addr + done) % PAGE_SIZE);
+            let len = (PAGE_SIZE - offset).min(buf.len() - done);
+
+            let frame = self.resident(&mut state, page)?;
+            frame.data[offset..offset + len].copy_from_slice(&buf[done..done + len]);
+            frame.dirty = true;
+            done += len;
+        }
+        Ok(())
+    }
+}
+
+impl<S, const PAGE_SIZE: usize> core::fmt::Debug for SwapMemory<S, PAGE_SIZE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let state = self.state.borrow();
+        f.debug_struct("SwapMemory")
+            .field("pages", &self.pages)
+            .field("resident", &state.frames.len())
+            .field("swapped", &state.swapped.len())
+            .finish()
+    }
+}
+
+impl<S: SwapStore, const PAGE_SIZE: usize> ReportUsage for SwapMemory<S, PAGE_SIZE> {
+    fn usage(&self) -> MemoryUsage {
+        let state = self.state.borrow();
+        MemoryUsage {
+            addressable: self.len(),
+            allocated: state.frames.len() * PAGE_SIZE,
+            resident_pages: state.frames.len(),
+            dirty_pages: state.frames.dirty_pages(),
+        }
+    }
+}
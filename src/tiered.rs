@@ -0,0 +1,392 @@
+use crate::{MemoryStorage, TieredError};
+use alloc::{vec, vec::Vec};
+use core::cell::Cell;
+use core::sync::atomic::Ordering;
+
+/// The tier that stores a page of a [`TieredMemory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Tier {
+    /// The page is stored inside the fast tier.
+    Fast,
+    /// The page is stored inside the slow tier.
+    Slow,
+}
+
+/// The access counters of a [`TieredMemory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TierStats {
+    /// The number of page accesses that were served by the fast tier.
+    pub fast: u64,
+    /// The number of page accesses that were served by the slow tier.
+    pub slow: u64,
+    /// The number of pages that were moved between the tiers.
+    pub migrations: u64,
+}
+
+impl TierStats {
+    /// Returns the total cost of all accesses and migrations, if every access to the fast
+    /// tier costs `fast_cost`, every access to the slow tier costs `slow_cost`, and every
+    /// migration costs both.
+    ///
+    /// This can be used to model mixed DRAM and PMEM systems, or NUMA-like penalties.
+    pub fn cost(&self, fast_cost: u64, slow_cost: u64) -> u64 {
+        self.fast * fast_cost + self.slow * slow_cost + self.migrations * (fast_cost + slow_cost)
+    }
+}
+
+/// A memory that splits its pages across a small, fast tier, like RAM, and a large,
+/// slow tier, like a file, and migrates pages between them.
+///
+/// Every page has a home inside the slow tier at its own address, while the fast tier
+/// provides a fixed number of slots, where slot `n` starts at address `n * PAGE_SIZE`.
+/// Single pages are moved into the fast tier using [`promote`](Self::promote), or by
+/// heat using [`rebalance`](Self::rebalance), which moves the most accessed pages into
+/// the fast tier.
+///
+/// The memory is divided into pages of `PAGE_SIZE` bytes, where page `n` starts
+/// at address `n * PAGE_SIZE`.
+pub struct TieredMemory<F, S, const PAGE_SIZE: usize = 4096> {
+    fast: F,
+    slow: S,
+    /// The fast slot of every page.
+    table: Vec<Option<usize>>,
+    /// The page inside every fast slot.
+    slots: Vec<Option<usize>>,
+    heat: Vec<Cell<u32>>,
+    stats: Cell<TierStats>,
+}
+
+impl<F, S, const PAGE_SIZE: usize> TieredMemory<F, S, PAGE_SIZE>
+where
+    F: MemoryStorage,
+    S: MemoryStorage,
+{
+    /// Creates a new `TieredMemory` with `pages` pages inside the slow tier and
+    /// `fast_pages` slots inside the fast tier.
+    ///
+    /// All pages start inside the slow tier, and the content of the fast tier is ignored.
+    pub fn new(fast: F, fast_pages: usize, slow: S, pages: usize) -> Self {
+        const { assert!(PAGE_SIZE > 0, "page size must be non-zero") };
+        Self {
+            fast,
+            slow,
+            table: vec![None; pages],
+            slots: vec![None; fast_pages],
+            heat: vec![Cell::new(0); pages],
+            stats: Cell::new(TierStats::default()),
+        }
+    }
+
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.table.len() * PAGE_SIZE
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Returns the tier that currently stores the given page.
+    ///
+    /// Panics if the page is out of bounds.
+    pub fn tier_of(&self, page: usize) -> Tier {
+        match self.table[page] {
+            Some(_) => Tier::Fast,
+            None => Tier::Slow,
+        }
+    }
+
+    /// Returns how often the given page was accessed since the last
+    /// [`rebalance`](Self::rebalance).
+    ///
+    /// Panics if the page is out of bounds.
+    pub fn heat(&self, page: usize) -> u32 {
+        self.heat[page].get()
+    }
+
+    /// Returns the access counters of this memory.
+    pub fn stats(&self) -> TierStats {
+        self.stats.get()
+    }
+
+    /// Returns a reference to the fast tier.
+    pub fn fast(&self) -> &F {
+        &self.fast
+    }
+
+    /// Returns a reference to the slow tier.
+    ///
+    /// Pages that are stored inside the fast tier are outdated inside the slow tier.
+    pub fn slow(&self) -> &S {
+        &self.slow
+    }
+
+    /// Tries to move the given page into a free slot of the fast tier.
+    ///
+    /// Returns `Ok(false)` if the fast tier has no free slot, and `Ok(true)` if the page
+    /// was moved or is already stored inside the fast tier.
+    /// Returns `Err(x)` if the page could not be copied, or is out of bounds.
+    pub fn try_promote(&mut self, page: usize) -> Result<bool, TieredError<F::Error, S::Error>> {
+        self.check_page(page)?;
+        if self.table[page].is_some() {
+            return Ok(true);
+        }
+        let slot = match self.slots.iter().position(Option::is_none) {
+            Some(slot) => slot,
+            None => return Ok(false),
+        };
+
+        copy_page::<_, _, _, PAGE_SIZE>(
+            &self.slow,
+            page * PAGE_SIZE,
+            &mut self.fast,
+            slot * PAGE_SIZE,
+            TieredError::Slow,
+            TieredError::Fast,
+        )?;
+
+        self.table[page] = Some(slot);
+        self.slots[slot] = Some(page);
+        self.count_migration();
+        Ok(true)
+    }
+
+    /// Moves the given page into a free slot of the fast tier, and returns `false` if
+    /// the fast tier has no free slot.
+    ///
+    /// Panics if the page could not be copied, or is out of bounds.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_promote` instead"))]
+    pub fn promote(&mut self, page: usize) -> bool {
+        or_panic!(
+            self.try_promote(page),
+            "promote page {} at {:#x}",
+            page,
+            page.saturating_mul(PAGE_SIZE)
+        )
+    }
+
+    /// Tries to move the given page back into the slow tier, which frees its slot
+    /// inside the fast tier.
+    ///
+    /// Returns `Err(x)` if the page could not be copied, or is out of bounds.
+    pub fn try_demote(&mut self, page: usize) -> Result<(), TieredError<F::Error, S::Error>> {
+        self.check_page(page)?;
+        let slot = match self.table[page] {
+            Some(slot) => slot,
+            None => return Ok(()),
+        };
+
+        copy_page::<_, _, _, PAGE_SIZE>(
+            &self.fast,
+            slot * PAGE_SIZE,
+            &mut self.slow,
+            page * PAGE_SIZE,
+            TieredError::Fast,
+            TieredError::Slow,
+        )?;
+
+        self.table[page] = None;
+        self.slots[slot] = None;
+        self.count_migration();
+        Ok(())
+    }
+
+    /// Moves the given page back into the slow tier, which frees its slot
+    /// inside the fast tier.
+    ///
+    /// Panics if the page could not be copied, or is out of bounds.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_demote` instead"))]
+    pub fn demote(&mut self, page: usize) {
+        or_panic!(
+            self.try_demote(page),
+            "demote page {} at {:#x}",
+            page,
+            page.saturating_mul(PAGE_SIZE)
+        )
+    }
+
+    /// Tries to move the most accessed pages into the fast tier, and the other pages
+    /// into the slow tier. Pages that were never accessed are not promoted.
+    ///
+    /// Afterwards, the heat of every page is halved, so old accesses lose weight.
+    ///
+    /// Returns `Err(x)` if a page could not be copied.
+    pub fn try_rebalance(&mut self) -> Result<(), TieredError<F::Error, S::Error>> {
+        let mut hot = (0..self.table.len())
+            .filter(|&page| self.heat[page].get() > 0)
+            .collect::<Vec<_>>();
+        hot.sort_by_key(|&page| core::cmp::Reverse(self.heat[page].get()));
+        hot.truncate(self.slots.len());
+        hot.sort_unstable();
+
+        for page in 0..self.table.len() {
+            if self.table[page].is_some() && hot.binary_search(&page).is_err() {
+                self.try_demote(page)?;
+            }
+        }
+        for &page in &hot {
+            self.try_promote(page)?;
+        }
+
+        for heat in &self.heat {
+            heat.set(heat.get() / 2);
+        }
+        Ok(())
+    }
+
+    /// Moves the most accessed pages into the fast tier, and the other pages
+    /// into the slow tier. Pages that were never accessed are not promoted.
+    ///
+    /// Afterwards, the heat of every page is halved, so old accesses lose weight.
+    ///
+    /// Panics if a page could not be copied.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_rebalance` instead"))]
+    pub fn rebalance(&mut self) {
+        or_panic!(self.try_rebalance(), "rebalance {} pages", self.table.len())
+    }
+
+    fn check_page(&self, page: usize) -> Result<(), TieredError<F::Error, S::Error>> {
+        if page < self.table.len() {
+            Ok(())
+        } else {
+            Err(TieredError::OutOfBounds {
+                addr: page.saturating_mul(PAGE_SIZE).max(self.len()),
+            })
+        }
+    }
+
+    fn check_access(&self, addr: usize, len: usize) -> Result<(), TieredError<F::Error, S::Error>> {
+        match addr.checked_add(len) {
+            Some(end) if end <= self.len() => Ok(()),
+            _ => Err(TieredError::OutOfBounds {
+                addr: addr.max(self.len()),
+            }),
+        }
+    }
+
+    /// Records an access to the given page and returns its fast slot, if any.
+    fn touch(&self, page: usize) -> Option<usize> {
+        let heat = &self.heat[page];
+        heat.set(heat.get().saturating_add(1));
+
+        let mut stats = self.stats.get();
+        let slot = self.table[page];
+        match slot {
+            Some(_) => stats.fast += 1,
+            None => stats.slow += 1,
+        }
+        self.stats.set(stats);
+        slot
+    }
+
+    fn count_migration(&self) {
+        let mut stats = self.stats.get();
+        stats.migrations += 1;
+        self.stats.set(stats);
+    }
+}
+
+/// Copies a page from `src` at `from` to `dest` at `to` through a small buffer, so large
+/// pages do not need a page sized buffer on the stack.
+fn copy_page<A, B, E, const PAGE_SIZE: usize>(
+    src: &A,
+    from: usize,
+    dest: &mut B,
+    to: usize,
+    src_err: impl Fn(A::Error) -> E,
+    dest_err: impl Fn(B::Error) -> E,
+) -> Result<(), E>
+where
+    A: MemoryStorage,
+    B: MemoryStorage,
+{
+    let mut buf = [0u8; 256];
+    let mut done = 0;
+    while done < PAGE_SIZE {
+        let n = (PAGE_SIZE - done).min(buf.len());
+        src.try_read_into(from + done, &mut buf[..n])
+            .map_err(&src_err)?;
+        dest.try_write_from(to + done, &buf[..n])
+            .map_err(&dest_err)?;
+        done += n;
+    }
+    Ok(())
+}
+
+impl<F, S, const PAGE_SIZE: usize> MemoryStorage for TieredMemory<F, S, PAGE_SIZE>
+where
+    F: MemoryStorage,
+    S: MemoryStorage,
+{
+    type Error = TieredError<F::Error, S::Error>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.check_access(addr, buf.len())?;
+
+        let mut done = 0;
+        while done < buf.len() {
+            let (page, offset) = ((addr + done) / PAGE_SIZE, (addr + done) % PAGE_SIZE);
+            let len = (PAGE_SIZE - offset).min(buf.len() - done);
+            let buf = &mut buf[done..done + len];
+
+            match self.touch(page) {
+                Some(slot) => self
+                    .fast
+                    .try_read_into(slot * PAGE_SIZE + offset, buf)
+                    .map_err(TieredError::Fast)?,
+                None => self
+                    .slow
+                    .try_read_into(addr + done, buf)
+                    .map_err(TieredError::Slow)?,
+            }
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.check_access(addr, buf.len())?;
+
+        let mut done = 0;
+        while done < buf.len() {
+            let (page, offset) = ((addr + done) / PAGE_SIZE, (addr + done) % PAGE_SIZE);
+            let len = (PAGE_SIZE - offset).min(buf.len() - done);
+            let buf = &buf[done..done + len];
+
+            match self.touch(page) {
+                Some(slot) => self
+                    .fast
+                    .try_write_from(slot * PAGE_SIZE + offset, buf)
+                    .map_err(TieredError::Fast)?,
+                None => self
+                    .slow
+                    .try_write_from(addr + done, buf)
+                    .map_err(TieredError::Slow)?,
+            }
+            done += len;
+        }
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.fast.fence(order);
+        self.slow.fence(order);
+    }
+}
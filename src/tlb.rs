@@ -0,0 +1,143 @@
+use alloc::vec::Vec;
+
+/// A translation of a single virtual page, which is cached inside a [`Tlb`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TlbEntry {
+    /// The address-space ID of the translation, which is ignored for global entries.
+    pub asid: u16,
+    /// The address of the virtual page.
+    pub virt: usize,
+    /// The address of the physical page.
+    pub phys: usize,
+    /// If `true`, the translation is valid in every address space, like the kernel
+    /// mappings of a guest.
+    pub global: bool,
+}
+
+impl TlbEntry {
+    fn matches(&self, asid: u16, page: usize) -> bool {
+        self.virt == page && (self.global || self.asid == asid)
+    }
+}
+
+/// A translation lookaside buffer, which caches the translations of the page table
+/// walker of an emulated MMU.
+///
+/// Every entry is tagged with an address-space ID (ASID), so the translations of
+/// multiple guest processes can be cached at the same time, and a context switch does not
+/// require flushing the whole TLB. This matches how the TLBs of RISC-V and ARM behave.
+///
+/// The TLB is fully associative and replaces its entries in FIFO order. Addresses are
+/// split into pages of `PAGE_SIZE` bytes, which must be a power of two.
+#[derive(Debug, Clone)]
+pub struct Tlb<const PAGE_SIZE: usize = 4096> {
+    entries: Vec<TlbEntry>,
+    capacity: usize,
+    next: usize,
+}
+
+impl<const PAGE_SIZE: usize> Tlb<PAGE_SIZE> {
+    /// Creates a new, empty `Tlb` that can hold up to `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        const {
+            assert!(
+                PAGE_SIZE.is_power_of_two(),
+                "page size must be a power of two"
+            )
+        };
+        Self {
+            entries: Vec::with_capacity(capacity),
+            capacity,
+            next: 0,
+        }
+    }
+
+    /// Returns the number of entries this TLB can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of valid entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if this TLB does not contain any entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Caches the given translation, after rounding its addresses down to the start of
+    /// their pages.
+    ///
+    /// An existing translation of the same virtual page in the same address space is
+    /// replaced. Otherwise the oldest entry is evicted if the TLB is full.
+    pub fn insert(&mut self, mut entry: TlbEntry) {
+        entry.virt &= !(PAGE_SIZE - 1);
+        entry.phys &= !(PAGE_SIZE - 1);
+        if self.capacity == 0 {
+            return;
+        }
+
+        let existing = self.entries.iter().position(|old| {
+            old.virt == entry.virt && (old.global || entry.global || old.asid == entry.asid)
+        });
+        match existing {
+            Some(index) => self.entries[index] = entry,
+            None if self.entries.len() < self.capacity => self.entries.push(entry),
+            None => {
+                self.entries[self.next] = entry;
+                self.next = (self.next + 1) % self.capacity;
+            }
+        }
+    }
+
+    /// Returns the cached translation of the virtual address `addr` in the given
+    /// address space.
+    pub fn lookup(&self, asid: u16, addr: usize) -> Option<&TlbEntry> {
+        let page = addr & !(PAGE_SIZE - 1);
+        self.entries.iter().find(|entry| entry.matches(asid, page))
+    }
+
+    /// Translates the virtual address `addr` in the given address space into a physical
+    /// address.
+    ///
+    /// Returns `None` if the translation is not cached, and the page table must be walked.
+    pub fn translate(&self, asid: u16, addr: usize) -> Option<usize> {
+        self.lookup(asid, addr)
+            .map(|entry| entry.phys | (addr & (PAGE_SIZE - 1)))
+    }
+
+    /// Removes every entry.
+    pub fn flush(&mut self) {
+        self.entries.clear();
+        self.next = 0;
+    }
+
+    /// Removes every entry of the given address space, except for the global entries.
+    pub fn flush_asid(&mut self, asid: u16) {
+        self.retain(|entry| entry.global || entry.asid != asid);
+    }
+
+    /// Removes the entries of the page that contains `addr` in every address space,
+    /// including the global entry.
+    pub fn flush_page(&mut self, addr: usize) {
+        let page = addr & !(PAGE_SIZE - 1);
+        self.retain(|entry| entry.virt != page);
+    }
+
+    /// Removes the entries of the page that contains `addr` in the given address space,
+    /// except for the global entry.
+    pub fn flush_asid_page(&mut self, asid: u16, addr: usize) {
+        let page = addr & !(PAGE_SIZE - 1);
+        self.retain(|entry| entry.global || entry.asid != asid || entry.virt != page);
+    }
+
+    fn retain(&mut self, f: impl FnMut(&TlbEntry) -> bool) {
+        // Move the oldest entry to the start, so new entries are appended again.
+        self.entries.rotate_left(self.next);
+        self.next = 0;
+        self.entries.retain(f);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::MemoryStorage;
+use core::cell::Cell;
+use core::ops::Range;
+use core::sync::atomic::Ordering;
+
+/// A wrapper around a `MemoryStorage` that emits a `tracing` event for accesses to
+/// the inner memory.
+///
+/// Successful accesses are emitted at the `TRACE` level, optionally sampled to only
+/// emit every n-th access, because tracing every access is very expensive. Failed
+/// accesses are always emitted at the `DEBUG` level.
+pub struct TracedMemory<M> {
+    inner: M,
+    one_in: u32,
+    counter: Cell<u32>,
+}
+
+impl<M: MemoryStorage> TracedMemory<M> {
+    /// Creates a new `TracedMemory` that traces every access.
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            one_in: 1,
+            counter: Cell::new(0),
+        }
+    }
+
+    /// Only traces every `one_in`-th successful access, where zero disables the tracing
+    /// of successful accesses.
+    pub fn sample(mut self, one_in: u32) -> Self {
+        self.one_in = one_in;
+        self
+    }
+
+    /// Returns a reference to the inner memory.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner memory.
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+
+    /// Consumes this `TracedMemory` and returns the inner memory.
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Emits an event for an access of `len` bytes at `addr`.
+    fn trace<T>(&self, write: bool, addr: usize, len: usize, result: &Result<T, M::Error>) {
+        let kind = if write { "write" } else { "read" };
+        match result {
+            Ok(_) => {
+                if self.one_in == 0 {
+                    return;
+                }
+                let count = self.counter.get() + 1;
+                if count >= self.one_in {
+                    self.counter.set(0);
+                    tracing::trace!(kind, addr, len, "memory access");
+                } else {
+                    self.counter.set(count);
+                }
+            }
+            Err(err) => tracing::debug!(kind, addr, len, error = ?err, "memory access failed"),
+        }
+    }
+}
+
+impl<M: MemoryStorage> MemoryStorage for TracedMemory<M> {
+    type Error = M::Error;
+
+    fn size(&self) -> Option<usize> {
+        self.inner.size()
+    }
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        self.inner.addr_range()
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let result = self.inner.try_read_byte(addr);
+        self.trace(false, addr, 1, &result);
+        result
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let result = self.inner.try_write_byte(addr, byte);
+        self.trace(true, addr, 1, &result);
+        result
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let result = self.inner.try_read_into(addr, buf);
+        self.trace(false, addr, buf.len(), &result);
+        result
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let result = self.inner.try_write_from(addr, buf);
+        self.trace(true, addr, buf.len(), &result);
+        result
+    }
+
+    fn fence(&self, order: Ordering) {
+        self.inner.fence(order)
+    }
+}
+
+impl<M> core::fmt::Debug for TracedMemory<M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TracedMemory")
+            .field("one_in", &self.one_in)
+            .finish()
+    }
+}
@@ -0,0 +1,527 @@
+use crate::{LazyError, MemoryStorage, MemoryUsage, PageSource, ReportUsage};
+use core::ops::Range;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::JoinHandle;
+use std::vec::Vec;
+
+const UFFD_API: u64 = 0xAA;
+const UFFD_USER_MODE_ONLY: libc::c_int = 1;
+const UFFD_EVENT_PAGEFAULT: u8 = 0x12;
+const UFFDIO_REGISTER_MODE_MISSING: u64 = 1;
+
+const UFFDIO_API: libc::c_ulong = iowr(0x3F, core::mem::size_of::<UffdioApi>());
+const UFFDIO_REGISTER: libc::c_ulong = iowr(0x00, core::mem::size_of::<UffdioRegister>());
+const UFFDIO_COPY: libc::c_ulong = iowr(0x03, core::mem::size_of::<UffdioCopy>());
+const UFFDIO_ZEROPAGE: libc::c_ulong = iowr(0x04, core::mem::size_of::<UffdioZeropage>());
+
+/// Encodes the number of an `ioctl` of userfaultfd, which reads and writes its argument.
+const fn iowr(nr: u32, size: usize) -> libc::c_ulong {
+    #[cfg(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc",
+        target_arch = "sparc64"
+    ))]
+    const DIR: u32 = (2 | 4) << 29;
+    #[cfg(not(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc",
+        target_arch = "sparc64"
+    )))]
+    const DIR: u32 = (2 | 1) << 30;
+    (DIR | ((size as u32) << 16) | ((UFFD_API as u32) << 8) | nr) as libc::c_ulong
+}
+
+#[repr(C)]
+struct UffdioApi {
+    api: u64,
+    features: u64,
+    ioctls: u64,
+}
+
+#[repr(C)]
+struct UffdioRange {
+    start: u64,
+    len: u64,
+}
+
+#[repr(C)]
+struct UffdioRegister {
+    range: UffdioRange,
+    mode: u64,
+    ioctls: u64,
+}
+
+#[repr(C)]
+struct UffdioCopy {
+    dst: u64,
+    src: u64,
+    len: u64,
+    mode: u64,
+    copy: i64,
+}
+
+#[repr(C)]
+struct UffdioZeropage {
+    range: UffdioRange,
+    mode: u64,
+    zeropage: i64,
+}
+
+#[repr(C)]
+struct UffdMsg {
+    event: u8,
+    reserved: [u8; 7],
+    arg: [u64; 3],
+}
+
+/// A memory whose pages are fetched from a [`PageSource`] the first time they are
+/// accessed, even by code that accesses the host memory directly, e.g. the guest of a
+/// virtual machine or the code generated by a JIT.
+///
+/// The memory is a private, anonymous mapping that is registered with `userfaultfd`. A
+/// handler thread resolves the page faults of missing pages by fetching them from the
+/// source, which allows restoring big guests lazily, or loading them post-copy style
+/// from another host. The `MemoryStorage` methods fetch the pages they access up front,
+/// so failed fetches are reported as errors.
+///
+/// Pages have the size of the pages of the system, see [`page_size`](Self::page_size),
+/// and page `n` starts at address `n * page_size`.
+pub struct UffdMemory<S: PageSource> {
+    ptr: *mut u8,
+    len: usize,
+    map_len: usize,
+    shared: Arc<Shared<S>>,
+    stop: OwnedFd,
+    handler: Option<JoinHandle<()>>,
+}
+
+// Safety: the mapping is owned by the memory, and only written by the handler thread
+// while a page is missing, which is synchronized by the kernel.
+unsafe impl<S: PageSource + Send> Send for UffdMemory<S> where S::Error: Send {}
+// Safety: shared references only allow reading the mapping, and fetching pages through
+// the synchronized source.
+unsafe impl<S: PageSource + Send> Sync for UffdMemory<S> where S::Error: Send {}
+
+/// The state that is shared with the handler thread.
+struct Shared<S: PageSource> {
+    uffd: OwnedFd,
+    base: usize,
+    page_size: usize,
+    source: Mutex<S>,
+    resident: Vec<AtomicBool>,
+    error: Mutex<Option<LazyError<S::Error>>>,
+}
+
+impl<S> UffdMemory<S>
+where
+    S: PageSource + Send + 'static,
+    S::Error: Send + 'static,
+{
+    /// Creates a new `UffdMemory` with `size` bytes, whose pages are all fetched from
+    /// `source` on their first access.
+    ///
+    /// Returns `Err(x)` if `userfaultfd` is not available, e.g. because unprivileged
+    /// processes are not allowed to use it, or the mapping could not be created.
+    pub fn new(source: S, size: usize) -> io::Result<Self> {
+        let page_size = page_size();
+        let map_len = size
+            .max(1)
+            .checked_next_multiple_of(page_size)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "size is too large"))?;
+
+        let uffd = open_uffd()?;
+        let mut api = UffdioApi {
+            api: UFFD_API,
+            features: 0,
+            ioctls: 0,
+        };
+        ioctl(&uffd, UFFDIO_API, &mut api)?;
+        let stop = eventfd()?;
+
+        // Safety: a new, anonymous mapping is created, which does not alias any memory.
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                map_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        let mut mem = Self {
+            ptr: ptr as *mut u8,
+            len: size,
+            map_len,
+            shared: Arc::new(Shared {
+                uffd,
+                base: ptr as usize,
+                page_size,
+                source: Mutex::new(source),
+                resident: (0..map_len / page_size)
+                    .map(|_| AtomicBool::new(false))
+                    .collect(),
+                error: Mutex::new(None),
+            }),
+            stop,
+            handler: None,
+        };
+
+        let mut register = UffdioRegister {
+            range: UffdioRange {
+                start: ptr as u64,
+                len: map_len as u64,
+            },
+            mode: UFFDIO_REGISTER_MODE_MISSING,
+            ioctls: 0,
+        };
+        ioctl(&mem.shared.uffd, UFFDIO_REGISTER, &mut register)?;
+
+        let shared = Arc::clone(&mem.shared);
+        let stop = mem.stop.as_raw_fd();
+        mem.handler = Some(
+            std::thread::Builder::new()
+                .name("uffd-handler".into())
+                .spawn(move || shared.handle_faults(stop))?,
+        );
+        Ok(mem)
+    }
+}
+
+impl<S: PageSource> UffdMemory<S> {
+    /// Returns the number of bytes inside this memory.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes per page.
+    pub fn page_size(&self) -> usize {
+        self.shared.page_size
+    }
+
+    /// Returns the pointer to the first byte of this memory.
+    ///
+    /// Accessing a missing page through the pointer blocks, until the handler thread
+    /// fetched the page. If the page could not be fetched, it is filled with zeros
+    /// instead, and the error is returned by [`take_error`](Self::take_error).
+    ///
+    /// The pointer stays valid until the memory is dropped, but writes through it must
+    /// not race with the `MemoryStorage` methods.
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Returns `true` if the page with the given index was already fetched.
+    ///
+    /// Panics if the page is out of bounds.
+    pub fn is_resident(&self, page: usize) -> bool {
+        self.shared.resident[page].load(Ordering::Acquire)
+    }
+
+    /// Returns the number of pages that were already fetched.
+    pub fn resident_pages(&self) -> usize {
+        self.shared
+            .resident
+            .iter()
+            .filter(|page| page.load(Ordering::Acquire))
+            .count()
+    }
+
+    /// Returns the first error of a fetch that was caused by an access through
+    /// [`as_ptr`](Self::as_ptr), and clears it.
+    pub fn take_error(&self) -> Option<LazyError<S::Error>> {
+        self.shared
+            .error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    /// Tries to fetch every page that overlaps the given range, which have not been
+    /// fetched yet.
+    ///
+    /// Returns `Err(x)` if the range is out of bounds, or a page could not be fetched.
+    pub fn try_populate(&self, range: Range<usize>) -> Result<(), LazyError<S::Error>> {
+        if range.is_empty() {
+            return Ok(());
+        }
+        self.page_range(range.start, range.end - range.start)?
+            .try_for_each(|page| self.shared.populate(page))
+    }
+
+    /// Fetches every page that overlaps the given range, which have not been
+    /// fetched yet.
+    ///
+    /// Panics if the range is out of bounds, or a page could not be fetched.
+    #[cfg_attr(feature = "no-panic", deprecated(note = "use `try_populate` instead"))]
+    pub fn populate(&self, range: Range<usize>) {
+        or_panic!(
+            self.try_populate(range.clone()),
+            "populate {:#x}..{:#x}",
+            range.start,
+            range.end
+        )
+    }
+
+    /// Returns the indices of the pages that are covered by the given access.
+    fn page_range(&self, addr: usize, len: usize) -> Result<Range<usize>, LazyError<S::Error>> {
+        let page_size = self.shared.page_size;
+        match addr.checked_add(len) {
+            Some(end) if end <= self.len => Ok(addr / page_size..end.div_ceil(page_size)),
+            _ => Err(LazyError::OutOfBounds {
+                addr: addr.max(self.len),
+            }),
+        }
+    }
+}
+
+impl<S: PageSource> Shared<S> {
+    /// Resolves page faults until the `stop` file descriptor becomes readable.
+    fn handle_faults(&self, stop: libc::c_int) {
+        let mut fds = [
+            libc::pollfd {
+                fd: self.uffd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+            libc::pollfd {
+                fd: stop,
+                events: libc::POLLIN,
+                revents: 0,
+            },
+        ];
+        loop {
+            // Safety: `fds` is a valid array of two `pollfd`s.
+            if unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) } < 0 {
+                continue;
+            }
+            if fds[1].revents != 0 || fds[0].revents & libc::POLLIN == 0 {
+                return;
+            }
+
+            let mut msg = UffdMsg {
+                event: 0,
+                reserved: [0; 7],
+                arg: [0; 3],
+            };
+            // Safety: `msg` is a writable buffer of the size of a message.
+            let read = unsafe {
+                libc::read(
+                    self.uffd.as_raw_fd(),
+                    &mut msg as *mut UffdMsg as *mut libc::c_void,
+                    core::mem::size_of::<UffdMsg>(),
+                )
+            };
+            if read as usize != core::mem::size_of::<UffdMsg>() || msg.event != UFFD_EVENT_PAGEFAULT
+            {
+                continue;
+            }
+
+            let page = (msg.arg[1] as usize - self.base) / self.page_size;
+            // The lock is held until the page is marked as resident, because resolving
+            // the fault already wakes up the faulting thread.
+            let mut source = self.source.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Err(err) = self.populate_locked(&mut source, page) {
+                log_warn!("failed to fetch page {}, mapping zeros instead", page);
+                self.error
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get_or_insert(err);
+                let _ = self.zero_page(page);
+                self.resident[page].store(true, Ordering::Release);
+            }
+        }
+    }
+
+    /// Fetches the given page from the source and places it into the mapping, if it is
+    /// still missing.
+    fn populate(&self, page: usize) -> Result<(), LazyError<S::Error>> {
+        if self.resident[page].load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let mut source = self.source.lock().unwrap_or_else(PoisonError::into_inner);
+        self.populate_locked(&mut source, page)
+    }
+
+    /// Like [`populate`](Self::populate), but the source is already locked.
+    fn populate_locked(&self, source: &mut S, page: usize) -> Result<(), LazyError<S::Error>> {
+        if self.resident[page].load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let mut data = std::vec![0u8; self.page_size];
+        source
+            .fetch_page(page, &mut data)
+            .map_err(|error| LazyError::Fetch { page, error })?;
+        let mut copy = UffdioCopy {
+            dst: (self.base + page * self.page_size) as u64,
+            src: data.as_ptr() as u64,
+            len: self.page_size as u64,
+            mode: 0,
+            copy: 0,
+        };
+        resolve(page, ioctl(&self.uffd, UFFDIO_COPY, &mut copy))?;
+        self.resident[page].store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Maps a page of zeros at the given page, if it is still missing.
+    fn zero_page(&self, page: usize) -> Result<(), LazyError<S::Error>> {
+        let mut zero = UffdioZeropage {
+            range: UffdioRange {
+                start: (self.base + page * self.page_size) as u64,
+                len: self.page_size as u64,
+            },
+            mode: 0,
+            zeropage: 0,
+        };
+        resolve(page, ioctl(&self.uffd, UFFDIO_ZEROPAGE, &mut zero))
+    }
+}
+
+impl<S: PageSource> MemoryStorage for UffdMemory<S> {
+    type Error = LazyError<S::Error>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len)
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let mut byte = [0u8];
+        self.try_read_into(addr, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.try_write_from(addr, &[byte])
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.page_range(addr, buf.len())?
+            .try_for_each(|page| self.shared.populate(page))?;
+        // Safety: the range is inside the mapping, and every page of it is resident.
+        let src = unsafe { core::slice::from_raw_parts(self.ptr.add(addr), buf.len()) };
+        buf.copy_from_slice(src);
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.page_range(addr, buf.len())?
+            .try_for_each(|page| self.shared.populate(page))?;
+        // Safety: the range is inside the mapping, which is uniquely borrowed, and every
+        // page of it is resident.
+        let dst = unsafe { core::slice::from_raw_parts_mut(self.ptr.add(addr), buf.len()) };
+        dst.copy_from_slice(buf);
+        Ok(())
+    }
+}
+
+impl<S: PageSource> ReportUsage for UffdMemory<S> {
+    fn usage(&self) -> MemoryUsage {
+        let resident = self.resident_pages();
+        MemoryUsage {
+            addressable: self.len,
+            allocated: resident * self.shared.page_size,
+            resident_pages: resident,
+            dirty_pages: 0,
+        }
+    }
+}
+
+impl<S: PageSource> core::fmt::Debug for UffdMemory<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UffdMemory")
+            .field("len", &self.len)
+            .field("page_size", &self.shared.page_size)
+            .field("resident", &self.resident_pages())
+            .finish()
+    }
+}
+
+impl<S: PageSource> Drop for UffdMemory<S> {
+    fn drop(&mut self) {
+        if let Some(handler) = self.handler.take() {
+            let one = 1u64;
+            // Safety: the buffer is a readable `u64`, as required by an eventfd.
+            unsafe {
+                libc::write(
+                    self.stop.as_raw_fd(),
+                    &one as *const u64 as *const libc::c_void,
+                    8,
+                )
+            };
+            let _ = handler.join();
+        }
+        // Safety: the mapping was created by `new`, and the handler thread has stopped.
+        unsafe { libc::munmap(self.ptr as *mut libc::c_void, self.map_len) };
+    }
+}
+
+/// Opens a new userfaultfd, that only handles faults of user mode accesses if the kernel
+/// supports it, which does not require any privileges.
+fn open_uffd() -> io::Result<OwnedFd> {
+    let flags = libc::O_CLOEXEC | libc::O_NONBLOCK;
+    // Safety: `userfaultfd` has no preconditions.
+    let fd = unsafe { libc::syscall(libc::SYS_userfaultfd, flags | UFFD_USER_MODE_ONLY) };
+    if fd >= 0 {
+        return owned_fd(fd as libc::c_int);
+    }
+    // Safety: see above.
+    owned_fd(unsafe { libc::syscall(libc::SYS_userfaultfd, flags) } as libc::c_int)
+}
+
+fn eventfd() -> io::Result<OwnedFd> {
+    // Safety: `eventfd` has no preconditions.
+    owned_fd(unsafe { libc::eventfd(0, libc::EFD_CLOEXEC) })
+}
+
+fn owned_fd(fd: libc::c_int) -> io::Result<OwnedFd> {
+    if fd < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        // Safety: `fd` is a new file descriptor that is owned by nobody else.
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+}
+
+fn ioctl<T>(fd: &OwnedFd, request: libc::c_ulong, arg: &mut T) -> io::Result<()> {
+    // Safety: `arg` is the argument structure that belongs to the request.
+    if unsafe { libc::ioctl(fd.as_raw_fd(), request as _, arg as *mut T) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Turns the result of resolving a page fault into an error, where a page that was
+/// resolved concurrently counts as success.
+fn resolve<E>(page: usize, result: io::Result<()>) -> Result<(), LazyError<E>> {
+    match result {
+        Err(err) if err.raw_os_error() != Some(libc::EEXIST) => Err(LazyError::Resolve {
+            page,
+            errno: err.raw_os_error().unwrap_or(0),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn page_size() -> usize {
+    // Safety: `sysconf` has no preconditions.
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
@@ -0,0 +1,35 @@
+/// How much host memory a memory uses, compared to the number of bytes it can address.
+///
+/// Returned by [`ReportUsage::usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MemoryUsage {
+    /// The number of bytes that can be addressed.
+    pub addressable: usize,
+    /// The number of bytes that are currently allocated on the host.
+    pub allocated: usize,
+    /// The number of pages that are currently allocated on the host, or zero if the
+    /// memory is not divided into pages.
+    pub resident_pages: usize,
+    /// The number of resident pages that were modified, but not written back yet.
+    pub dirty_pages: usize,
+}
+
+impl MemoryUsage {
+    /// Returns the ratio of allocated to addressable bytes, between `0.0` and `1.0` for
+    /// memories that allocate on demand.
+    pub fn occupancy(&self) -> f64 {
+        if self.addressable == 0 {
+            0.0
+        } else {
+            self.allocated as f64 / self.addressable as f64
+        }
+    }
+}
+
+/// A memory that can report how much host memory it uses, so emulator frontends can
+/// show the real memory overhead.
+pub trait ReportUsage {
+    /// Returns the current memory usage.
+    fn usage(&self) -> MemoryUsage;
+}
@@ -0,0 +1,29 @@
+use crate::{Endianness, MemoryStorage};
+use alloc::string::String;
+
+/// Reads a NUL-terminated UTF-16 string of at most `max_chars` code units, using the
+/// byte order `E`, and replaces unpaired surrogates with `U+FFFD`.
+pub(crate) fn read_str<M, E>(mem: &M, addr: usize, max_chars: usize) -> Result<String, M::Error>
+where
+    M: MemoryStorage + ?Sized,
+    E: Endianness,
+{
+    let mut units = (0..max_chars).map(|idx| mem.try_read_with::<u16, E>(addr + idx * 2));
+    let mut err = None;
+    let units = core::iter::from_fn(|| match units.next()? {
+        Ok(0) => None,
+        Ok(unit) => Some(unit),
+        Err(e) => {
+            err = Some(e);
+            None
+        }
+    });
+
+    let string = char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    match err {
+        Some(err) => Err(err),
+        None => Ok(string),
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{MemoryStorage, OutOfBounds};
+use core::sync::atomic::Ordering;
+
+/// A region of memory mapped I/O at a fixed base address, like the registers of a
+/// peripheral on a microcontroller.
+///
+/// Every access is a volatile access through a raw pointer, and no reference to the
+/// region is ever created, so it can be used on real hardware without an operating
+/// system. Accesses of 1, 2, 4 or 8 bytes to naturally aligned addresses are done using
+/// a single volatile access of that width, which is what most device registers require.
+/// All other accesses are done bytewise.
+#[derive(Debug)]
+pub struct VolatileRegion {
+    base: usize,
+    len: usize,
+}
+
+impl VolatileRegion {
+    /// Creates a new `VolatileRegion` over the `len` bytes starting at address `base`.
+    ///
+    /// # Safety
+    ///
+    /// The range must be valid for volatile reads and writes for as long as the region
+    /// exists, and must not be accessed through a reference by anything else. Accessing
+    /// device registers can have arbitrary side effects, which the caller must account for.
+    pub const unsafe fn new(base: usize, len: usize) -> Self {
+        Self { base, len }
+    }
+
+    /// Returns the address of the first byte of this region.
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Returns the number of bytes inside this region.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this region has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a pointer to the given range, if it is inside the region.
+    fn ptr(&self, addr: usize, len: usize) -> Result<*mut u8, OutOfBounds> {
+        match addr.checked_add(len) {
+            Some(end) if end <= self.len => Ok((self.base + addr) as *mut u8),
+            _ => Err(OutOfBounds {
+                addr: addr.max(self.len),
+            }),
+        }
+    }
+}
+
+impl MemoryStorage for VolatileRegion {
+    type Error = OutOfBounds;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let ptr = self.ptr(addr, 1)?;
+        // Safety: the pointer is inside the region.
+        Ok(unsafe { ptr.read_volatile() })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let ptr = self.ptr(addr, 1)?;
+        // Safety: the pointer is inside the region.
+        unsafe { ptr.write_volatile(byte) };
+        Ok(())
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let ptr = self.ptr(addr, buf.len())?;
+        // Safety: the range is inside the region.
+        unsafe { read_volatile(ptr, buf) };
+        Ok(())
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let ptr = self.ptr(addr, buf.len())?;
+        // Safety: the range is inside the region.
+        unsafe { write_volatile(ptr, buf) };
+        Ok(())
+    }
+
+    fn fence(&self, order: Ordering) {
+        crate::atomic_fence(order)
+    }
+}
+
+/// Reads `buf.len()` bytes starting at `ptr` using volatile reads, where naturally aligned
+/// accesses of 2, 4 or 8 bytes are done using a single read of that width.
+///
+/// # Safety
+///
+/// `ptr` must be valid for volatile reads of `buf.len()` bytes.
+pub(crate) unsafe fn read_volatile(ptr: *const u8, buf: &mut [u8]) {
+    match (buf.len(), (ptr as usize).is_multiple_of(buf.len().max(1))) {
+        (2, true) => buf.copy_from_slice(&(ptr as *const u16).read_volatile().to_ne_bytes()),
+        (4, true) => buf.copy_from_slice(&(ptr as *const u32).read_volatile().to_ne_bytes()),
+        (8, true) => buf.copy_from_slice(&(ptr as *const u64).read_volatile().to_ne_bytes()),
+        _ => {
+            for (idx, byte) in buf.iter_mut().enumerate() {
+                *byte = ptr.add(idx).read_volatile();
+            }
+        }
+    }
+}
+
+/// Writes `buf` starting at `ptr` using volatile writes, where naturally aligned accesses
+/// of 2, 4 or 8 bytes are done using a single write of that width.
+///
+/// # Safety
+///
+/// `ptr` must be valid for volatile writes of `buf.len()` bytes.
+pub(crate) unsafe fn write_volatile(ptr: *mut u8, buf: &[u8]) {
+    match (buf.len(), (ptr as usize).is_multiple_of(buf.len().max(1))) {
+        (2, true) => (ptr as *mut u16).write_volatile(u16::from_ne_bytes([buf[0], buf[1]])),
+        (4, true) => {
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(buf);
+            (ptr as *mut u32).write_volatile(u32::from_ne_bytes(bytes))
+        }
+        (8, true) => {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(buf);
+            (ptr as *mut u64).write_volatile(u64::from_ne_bytes(bytes))
+        }
+        _ => {
+            for (idx, &byte) in buf.iter().enumerate() {
+                ptr.add(idx).write_volatile(byte);
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{ContiguousMemory, MemoryStorage, OutOfBounds, ResizableMemory, ResizeError};
+use wasmtime::{Memory, StoreContextMut};
+
+/// The size of a Wasm page in bytes.
+const WASM_PAGE_SIZE: usize = 0x10000;
+
+/// A linear memory of a wasmtime instance, together with the store that owns it.
+///
+/// This allows using the typed accessors, search and all other helpers of this crate
+/// on the memory of a Wasm guest, e.g. from inside a host function.
+///
+/// ```ignore
+/// let memory = instance.get_memory(&mut store, "memory").unwrap();
+/// let mut mem = WasmMemory::new(&mut store, memory);
+/// let magic = mem.read::<u32>(0x100);
+/// ```
+pub struct WasmMemory<'a, T: 'static> {
+    store: StoreContextMut<'a, T>,
+    memory: Memory,
+}
+
+impl<'a, T: 'static> WasmMemory<'a, T> {
+    /// Creates a new `WasmMemory` from a linear memory and the store that owns it.
+    ///
+    /// Accesses panic if the memory does not belong to the store.
+    pub fn new(store: impl Into<StoreContextMut<'a, T>>, memory: Memory) -> Self {
+        Self {
+            store: store.into(),
+            memory,
+        }
+    }
+
+    /// Returns the linear memory.
+    pub fn memory(&self) -> Memory {
+        self.memory
+    }
+
+    /// Returns the number of bytes inside the linear memory.
+    pub fn len(&self) -> usize {
+        self.memory.data_size(&self.store)
+    }
+
+    /// Returns `true` if the linear memory has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Tries to grow the linear memory by the given number of Wasm pages, and returns
+    /// the previous number of pages.
+    ///
+    /// Returns `Err(x)` if the memory can not grow, e.g. because its maximum is reached.
+    pub fn try_grow(&mut self, pages: u64) -> wasmtime::Result<u64> {
+        self.memory.grow(&mut self.store, pages)
+    }
+
+    /// Consumes this `WasmMemory` and returns the store.
+    pub fn into_store(self) -> StoreContextMut<'a, T> {
+        self.store
+    }
+}
+
+impl<T: 'static> MemoryStorage for WasmMemory<'_, T> {
+    type Error = OutOfBounds;
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let data = self.memory.data(&self.store);
+        data.get(addr).copied().ok_or(OutOfBounds { addr })
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let data = self.memory.data_mut(&mut self.store);
+        let cell = data.get_mut(addr).ok_or(OutOfBounds { addr })?;
+        *cell = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self.memory.data(&self.store))
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(self.memory.data_mut(&mut self.store))
+    }
+}
+
+/// Linear memories can only grow, in whole Wasm pages of 64 KiB.
+impl<T: 'static> ResizableMemory for WasmMemory<'_, T> {
+    fn try_resize(&mut self, new_size: usize) -> Result<(), ResizeError> {
+        let additional = new_size
+            .checked_sub(self.len())
+            .filter(|additional| additional.is_multiple_of(WASM_PAGE_SIZE))
+            .ok_or(ResizeError::Unsupported { size: new_size })?;
+
+        self.try_grow((additional / WASM_PAGE_SIZE) as u64)
+            .map(drop)
+            .map_err(|_| ResizeError::AllocFailed { size: new_size })
+    }
+}
+
+impl<T: 'static> ContiguousMemory for WasmMemory<'_, T> {
+    fn as_bytes(&self) -> &[u8] {
+        self.memory.data(&self.store)
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.memory.data_mut(&mut self.store)
+    }
+
+    fn out_of_bounds(&self, addr: usize) -> Self::Error {
+        OutOfBounds { addr }
+    }
+}
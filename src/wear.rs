@@ -0,0 +1,75 @@
+use core::fmt;
+
+/// A summary of how evenly writes or erases are spread over the blocks of a memory.
+///
+/// This is used to verify that a wear-leveling algorithm actually spreads the wear,
+/// before running it on real parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WearReport {
+    /// The number of blocks.
+    pub blocks: usize,
+    /// The sum of the counters of all blocks.
+    pub total: u64,
+    /// The smallest counter of all blocks.
+    pub min: u32,
+    /// The largest counter of all blocks.
+    pub max: u32,
+    /// The index of the first block with the largest counter, or `None` if there are no blocks.
+    pub most_worn: Option<usize>,
+}
+
+impl WearReport {
+    /// Creates a report from the counter of every block.
+    pub fn from_counts<I: IntoIterator<Item = u32>>(counts: I) -> Self {
+        let mut report = Self {
+            min: u32::MAX,
+            ..Self::default()
+        };
+
+        for (block, count) in counts.into_iter().enumerate() {
+            report.blocks += 1;
+            report.total += u64::from(count);
+            report.min = report.min.min(count);
+            if report.most_worn.is_none() || count > report.max {
+                report.max = count;
+                report.most_worn = Some(block);
+            }
+        }
+
+        if report.blocks == 0 {
+            report.min = 0;
+        }
+        report
+    }
+
+    /// Returns the average counter of all blocks.
+    pub fn mean(&self) -> f64 {
+        if self.blocks == 0 {
+            0.0
+        } else {
+            self.total as f64 / self.blocks as f64
+        }
+    }
+
+    /// Returns the difference between the most and least worn block.
+    ///
+    /// A good wear-leveling algorithm keeps this small.
+    pub fn spread(&self) -> u32 {
+        self.max - self.min
+    }
+}
+
+impl fmt::Display for WearReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} blocks, {} total, min {}, max {}, mean {:.1}",
+            self.blocks,
+            self.total,
+            self.min,
+            self.max,
+            self.mean()
+        )
+    }
+}
@@ -0,0 +1,38 @@
+use mem_storage::Addr;
+
+#[test]
+fn test_alignment() {
+    let addr = Addr::new(0x1234);
+    assert!(addr.is_aligned(4));
+    assert!(!addr.is_aligned(8));
+    assert_eq!(addr.align_down(0x1000), Addr(0x1000));
+    assert_eq!(addr.align_up(0x1000), Some(Addr(0x2000)));
+    assert_eq!(Addr(0x2000).align_up(0x1000), Some(Addr(0x2000)));
+    assert_eq!(Addr(usize::MAX).align_up(2), None);
+}
+
+#[test]
+#[should_panic(expected = "alignment must be a power of two")]
+fn test_invalid_alignment() {
+    Addr(0).align_down(3);
+}
+
+#[test]
+fn test_arithmetic() {
+    assert_eq!(Addr(0x10).checked_add(0x10), Some(Addr(0x20)));
+    assert_eq!(Addr(usize::MAX).checked_add(1), None);
+    assert_eq!(Addr(0x10).checked_sub(0x11), None);
+    assert_eq!(Addr(0x1010).offset_from(Addr(0x1000)), Some(0x10));
+    assert_eq!(Addr(0x1000).offset_from(Addr(0x1010)), None);
+}
+
+#[test]
+fn test_pages() {
+    let addr = Addr::from(0x12345);
+    assert_eq!(addr.page(0x1000), 0x12);
+    assert_eq!(addr.page_offset(0x1000), 0x345);
+    assert_eq!(addr.split(1), (0x12345, 0));
+    assert_eq!(usize::from(addr), 0x12345);
+    assert_eq!(addr.to_string(), "0x12345");
+    assert_eq!(format!("{:08X}", addr), "00012345");
+}
@@ -0,0 +1,47 @@
+#![cfg(all(feature = "shm", unix, not(feature = "forbid-unsafe")))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{AflSharedMap, MemoryStorage, OutOfBounds, AFL_MAP_SIZE, AFL_SHM_ENV};
+
+/// Creates a new private System V segment, like the fuzzer does.
+fn create_segment() -> i32 {
+    let id = unsafe { libc::shmget(libc::IPC_PRIVATE, AFL_MAP_SIZE, libc::IPC_CREAT | 0o600) };
+    assert!(id >= 0);
+    id
+}
+
+fn remove_segment(id: i32) {
+    unsafe { libc::shmctl(id, libc::IPC_RMID, core::ptr::null_mut()) };
+}
+
+#[test]
+fn test_shared_map() {
+    let id = create_segment();
+    let mut target = AflSharedMap::attach(id).unwrap();
+    let fuzzer = AflSharedMap::attach(id).unwrap();
+    remove_segment(id);
+    assert_eq!(target.len(), AFL_MAP_SIZE);
+
+    let mut coverage = target.coverage();
+    coverage.hit(0x4242);
+    coverage.hit(0x4242);
+    target.write::<u8>(7, 1);
+    assert_eq!(fuzzer.read::<u8>(0x4242), 2);
+    assert_eq!(fuzzer.read::<u8>(7), 1);
+    assert_eq!(
+        fuzzer.try_read::<u8>(AFL_MAP_SIZE),
+        Err(OutOfBounds { addr: AFL_MAP_SIZE })
+    );
+}
+
+#[test]
+fn test_from_env() {
+    let id = create_segment();
+    std::env::set_var(AFL_SHM_ENV, id.to_string());
+    let map = AflSharedMap::from_env();
+    std::env::remove_var(AFL_SHM_ENV);
+    remove_segment(id);
+    assert_eq!(map.unwrap().len(), AFL_MAP_SIZE);
+
+    assert!(AflSharedMap::from_env().is_err());
+}
@@ -0,0 +1,96 @@
+#![cfg(all(feature = "alloc", not(feature = "forbid-unsafe")))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use core::alloc::Layout;
+use core::cell::{Cell, UnsafeCell};
+use core::ptr::NonNull;
+use mem_storage::{
+    AlignedMemory, ContiguousMemory, FillPolicy, MemoryStorage, OutOfBounds, RawAllocator,
+    PAGE_ALIGN,
+};
+
+#[test]
+fn test_alignment() {
+    for &align in &[1, 64, PAGE_ALIGN, 1 << 16] {
+        let mem = AlignedMemory::new(100, align);
+        assert_eq!(mem.align(), align);
+        assert_eq!(mem.as_slice().unwrap().as_ptr() as usize % align, 0);
+        assert_eq!(mem.get(..).unwrap(), &[0; 100][..]);
+    }
+
+    let mem = AlignedMemory::page_aligned(0);
+    assert!(mem.is_empty());
+    assert_eq!(mem.as_slice().unwrap().as_ptr() as usize % PAGE_ALIGN, 0);
+
+    assert!(AlignedMemory::try_new(16, 3).is_none());
+    assert!(AlignedMemory::try_new(usize::MAX - 64, 64).is_none());
+}
+
+#[test]
+fn test_access() {
+    let mut mem = AlignedMemory::new(8, 64);
+    mem.write::<u64>(0, 0x0102_0304_0506_0708);
+    assert_eq!(mem.read_be::<u32>(4), 0x0403_0201);
+    assert_eq!(mem.try_read::<u8>(8), Err(OutOfBounds { addr: 8 }));
+
+    let copy = mem.clone();
+    mem.write::<u8>(0, 0);
+    assert_eq!(copy.read::<u8>(0), 0x08);
+}
+
+#[test]
+fn test_with_fill() {
+    let mem = AlignedMemory::with_fill(6, 64, FillPolicy::Pattern(&[1, 2, 3, 4]));
+    assert_eq!(mem.align(), 64);
+    assert_eq!(mem.get(..).unwrap(), &[1, 2, 3, 4, 1, 2][..]);
+
+    let mem = AlignedMemory::with_fill(4, 1, FillPolicy::Byte(0xCC));
+    assert_eq!(mem.get(..).unwrap(), &[0xCC; 4][..]);
+}
+
+/// Allocates memory out of a fixed buffer, without ever freeing it.
+struct Arena {
+    buf: UnsafeCell<[u8; 4096]>,
+    used: Cell<usize>,
+    live: Cell<usize>,
+}
+
+unsafe impl RawAllocator for Arena {
+    fn allocate_zeroed(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let base = self.buf.get() as *mut u8;
+        let start = (base as usize + self.used.get()).next_multiple_of(layout.align());
+        let end = start - base as usize + layout.size();
+        if end > 4096 {
+            return None;
+        }
+        self.used.set(end);
+        self.live.set(self.live.get() + 1);
+        NonNull::new(start as *mut u8)
+    }
+
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {
+        self.live.set(self.live.get() - 1);
+    }
+}
+
+#[test]
+fn test_custom_allocator() {
+    let arena = Arena {
+        buf: UnsafeCell::new([0; 4096]),
+        used: Cell::new(0),
+        live: Cell::new(0),
+    };
+
+    let mut a = AlignedMemory::new_in(100, 64, &arena);
+    let b = AlignedMemory::new_in(1000, 256, &arena);
+    a.write::<u32>(0, 0xdead_beef);
+    assert_eq!(a.as_slice().unwrap().as_ptr() as usize % 64, 0);
+    assert_eq!(b.as_slice().unwrap().as_ptr() as usize % 256, 0);
+    assert_eq!(b.get(..).unwrap(), &[0; 1000][..]);
+    assert_eq!(arena.live.get(), 2);
+
+    assert!(AlignedMemory::try_new_in(4096, 1, &arena).is_none());
+    drop(a);
+    drop(b);
+    assert_eq!(arena.live.get(), 0);
+}
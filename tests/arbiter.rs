@@ -0,0 +1,59 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{BusArbiter, InitiatorStats, MemoryStorage};
+
+#[test]
+fn test_stall_accounting() {
+    let mut bus = BusArbiter::new(TestMemory::new([0u8; 16])).access_cycles(4);
+    let dma = bus.add_initiator(0);
+    let cpu = bus.add_initiator(1);
+    let video = bus.add_initiator(2);
+
+    bus.port(dma).unwrap().write::<u16>(0, 0xAABB);
+    // The CPU wins the bus, so the transfer of the DMA is delayed.
+    assert_eq!(bus.port(cpu).unwrap().read::<u16>(0), 0xAABB);
+
+    bus.tick(8);
+    bus.port(cpu).unwrap().read::<u8>(4);
+    bus.port(dma).unwrap().read::<u8>(5);
+    bus.port(video).unwrap().read::<u8>(6);
+
+    let stats = |initiator| bus.stats(initiator).unwrap();
+    assert_eq!(
+        stats(cpu),
+        InitiatorStats {
+            accesses: 2,
+            stall_cycles: 0,
+        }
+    );
+    assert_eq!(
+        stats(dma),
+        InitiatorStats {
+            accesses: 2,
+            stall_cycles: 12,
+        }
+    );
+    assert_eq!(stats(video).stall_cycles, 0);
+    assert_eq!(bus.now(), 8);
+
+    bus.reset_stats();
+    assert_eq!(bus.stats(dma), Some(InitiatorStats::default()));
+}
+
+#[test]
+fn test_unknown_initiator() {
+    let mut other = BusArbiter::new(TestMemory::new([0u8; 4]));
+    let initiator = other.add_initiator(0);
+
+    let mut bus = BusArbiter::new(TestMemory::new([0u8; 4]));
+    assert!(bus.port(initiator).is_none());
+    assert_eq!(bus.stats(initiator), None);
+
+    let cpu = bus.add_initiator(0);
+    assert_eq!(bus.port(cpu).unwrap().try_read::<u8>(4), Err(()));
+    assert_eq!(bus.stats(cpu).unwrap().accesses, 1);
+}
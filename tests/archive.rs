@@ -0,0 +1,45 @@
+#![cfg(feature = "rkyv")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{CellMemory, FillPolicy, LengthMismatch, MemoryStorage};
+use rkyv::rancor::Error;
+
+#[test]
+fn test_rkyv_restore() {
+    let mem = CellMemory::with_fill(1024, FillPolicy::Pattern(&[1, 2, 3]));
+    (&mem).write::<u32>(100, 0xDEADBEEF);
+    let archive = mem.to_rkyv::<Error>().unwrap();
+
+    let archived = CellMemory::access_rkyv::<Error>(&archive).unwrap();
+    assert_eq!(archived.len(), 1024);
+    assert_eq!(archived[100..104], [0xEF, 0xBE, 0xAD, 0xDE]);
+
+    let mut restored = CellMemory::new(1024);
+    restored.restore_archived(archived);
+    assert_eq!(restored.to_vec(), mem.to_vec());
+
+    let deserialized = rkyv::deserialize::<CellMemory, Error>(archived).unwrap();
+    assert_eq!(deserialized.to_vec(), mem.to_vec());
+}
+
+#[test]
+fn test_rkyv_rejects_invalid_archive() {
+    assert!(CellMemory::access_rkyv::<Error>(&[0xFF; 3]).is_err());
+}
+
+#[test]
+fn test_rkyv_restore_length_mismatch() {
+    let mem = CellMemory::with_fill(16, FillPolicy::Pattern(&[0xAA]));
+    let archive = mem.to_rkyv::<Error>().unwrap();
+    let archived = CellMemory::access_rkyv::<Error>(&archive).unwrap();
+
+    let mut restored = CellMemory::new(8);
+    assert_eq!(
+        restored.try_restore_archived(archived),
+        Err(LengthMismatch {
+            expected: 8,
+            found: 16
+        })
+    );
+    assert_eq!(restored.to_vec(), [0; 8]);
+}
@@ -1,44 +1,14 @@
-use mem_storage::MemoryStorage;
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
 
-struct TestMemory {
-    ram: Vec<u8>,
-}
-
-impl TestMemory {
-    fn new<S: AsRef<[u8]>>(slice: S) -> Self {
-        Self {
-            ram: slice.as_ref().into(),
-        }
-    }
-}
-
-impl MemoryStorage for TestMemory {
-    type Error = ();
-
-    fn get<I>(&self, index: I) -> Result<&I::Output, Self::Error>
-    where
-        I: std::slice::SliceIndex<[u8]>,
-    {
-        self.ram.get(index).ok_or(())
-    }
+mod common;
 
-    fn get_mut<I>(&mut self, index: I) -> Result<&mut I::Output, Self::Error>
-    where
-        I: std::slice::SliceIndex<[u8]>,
-    {
-        self.ram.get_mut(index).ok_or(())
-    }
-
-    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
-        self.get(addr).map(Clone::clone)
-    }
-
-    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
-        let entry = self.get_mut(addr)?;
-        *entry = byte;
-        Ok(())
-    }
-}
+use common::TestMemory;
+use core::cell::Cell;
+use core::sync::atomic::Ordering;
+use mem_storage::{
+    BigEndian, ContiguousMemory, Endianness, LittleEndian, MemoryStorage, NativeEndian, OpenBus,
+    RangeError,
+};
 
 #[test]
 fn test_read_le() {
@@ -81,3 +51,325 @@ fn test_write_be() {
 
     assert_eq!(mem.read_be::<u32>(4), 0xDDFFEEAAu32);
 }
+
+#[test]
+fn test_swap_endianness() {
+    let mut mem = TestMemory::new([0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99]);
+
+    mem.swap_endianness::<u16>(0..4);
+    assert_eq!(mem.get(0..4).unwrap(), &[0x22, 0x11, 0x44, 0x33]);
+
+    mem.swap_endianness::<u32>(1..9);
+    assert_eq!(
+        mem.get(..).unwrap(),
+        &[0x22, 0x55, 0x33, 0x44, 0x11, 0x99, 0x88, 0x77, 0x66]
+    );
+}
+
+#[test]
+fn test_swap_endianness_uneven_range() {
+    let mut mem = TestMemory::new([0u8; 8]);
+    assert_eq!(
+        mem.try_swap_endianness::<u32>(0..6),
+        Err(RangeError::Invalid { start: 0, end: 6 })
+    );
+    assert_eq!(
+        mem.try_swap_endianness::<u16>(6..10),
+        Err(RangeError::Memory(()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "failed to read 4 bytes at 0x6: ()")]
+fn test_panic_message() {
+    let mem = TestMemory::new([0u8; 8]);
+    mem.read::<u32>(6);
+}
+
+#[test]
+#[should_panic(expected = "failed to fill 0x4..0x10: ()")]
+fn test_panic_message_range() {
+    let mut mem = TestMemory::new([0u8; 8]);
+    mem.fill(4..16, 0);
+}
+
+/// A memory that mirrors 4 bytes of storage over the whole address space,
+/// and thus only implements the byte accessors.
+struct MirroredMemory {
+    ram: [u8; 4],
+}
+
+impl MemoryStorage for MirroredMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        Ok(self.ram[addr % 4])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.ram[addr % 4] = byte;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_byte_accessors_only() {
+    let mut mem = MirroredMemory { ram: [0; 4] };
+
+    mem.write::<u16>(0x1003, 0xAABB);
+    assert_eq!(mem.ram, [0xAA, 0, 0, 0xBB]);
+    assert_eq!(mem.read::<u32>(0x2000), 0xBB0000AA);
+    assert_eq!(mem.read_be::<u16>(7), 0xBBAA);
+
+    mem.swap_endianness::<u16>(0..4);
+    assert_eq!(mem.ram, [0, 0xAA, 0xBB, 0]);
+}
+
+#[test]
+fn test_size_and_addr_range() {
+    let mem = TestMemory::new([0; 16]);
+    assert_eq!(mem.size(), Some(16));
+    assert_eq!(mem.addr_range(), Some(0..16));
+
+    let mem = MirroredMemory { ram: [0; 4] };
+    assert_eq!(mem.size(), None);
+    assert_eq!(mem.addr_range(), None);
+}
+
+#[test]
+fn test_contiguous_defaults() {
+    let mut mem = TestMemory::new([1, 2, 3, 4]);
+
+    assert_eq!(mem.get(1..3), Ok(&[2u8, 3][..]));
+    assert_eq!(mem.get(2..5), Err(()));
+
+    *mem.get_mut(0).unwrap() = 9;
+    assert!(mem.get_mut(4..).is_ok());
+    assert!(mem.get_mut(5..).is_err());
+    assert_eq!(mem.read::<u16>(0), 0x0209);
+    assert_eq!(mem.try_read::<u16>(3), Err(()));
+}
+
+macro_rules! assert_roundtrip {
+    ($($ty:ty = $val:expr),*) => {
+        $({
+            let val: $ty = $val;
+            let size = core::mem::size_of::<$ty>();
+            let mut mem = TestMemory::new([0u8; 32]);
+
+            mem.write::<$ty>(3, val);
+            assert_eq!(mem.get(3..3 + size).unwrap(), &val.to_le_bytes()[..]);
+            assert_eq!(mem.read::<$ty>(3), val);
+
+            mem.write_be::<$ty>(3, val);
+            assert_eq!(mem.get(3..3 + size).unwrap(), &val.to_be_bytes()[..]);
+            assert_eq!(mem.read_be::<$ty>(3), val);
+
+            // Reading with the other byte order must return the swapped value.
+            assert_eq!(mem.read::<$ty>(3), val.swap_bytes());
+        })*
+    };
+}
+
+#[test]
+fn test_endianness_roundtrip() {
+    assert_roundtrip!(
+        u8 = 0xAB,
+        i8 = -0x12,
+        u16 = 0x1234,
+        i16 = -0x1234,
+        u32 = 0x1234_5678,
+        i32 = -0x1234_5678,
+        u64 = 0x0102_0304_0506_0708,
+        i64 = -0x0102_0304_0506_0708,
+        u128 = 0x0102_0304_0506_0708_090A_0B0C_0D0E_0F10,
+        i128 = -0x0102_0304_0506_0708_090A_0B0C_0D0E_0F10
+    );
+}
+
+#[test]
+fn test_read_nonzero() {
+    use core::num::{NonZeroI8, NonZeroU16, NonZeroU32};
+
+    let mem = TestMemory::new([0x00, 0x00, 0x12, 0x34, 0xFF]);
+    assert_eq!(mem.read_nonzero::<NonZeroU16>(0), None);
+    assert_eq!(mem.read_nonzero::<NonZeroU16>(2), NonZeroU16::new(0x3412));
+    assert_eq!(
+        mem.read_nonzero_be::<NonZeroU16>(2),
+        NonZeroU16::new(0x1234)
+    );
+    assert_eq!(mem.read_nonzero::<NonZeroI8>(4), NonZeroI8::new(-1));
+    assert_eq!(mem.try_read_nonzero::<NonZeroU32>(2), Err(()));
+}
+
+/// Reads a 16 bit instruction the way a bi-endian CPU core would.
+#[test]
+fn test_read_signed() {
+    let mem = TestMemory::new([0x80, 0xFF, 0x7F, 0x00]);
+    assert_eq!(mem.read_signed::<i8, i32>(0), -128);
+    assert_eq!(mem.read_signed::<i8, i64>(2), 127);
+    assert_eq!(mem.read_signed::<i16, i32>(0), -128);
+    assert_eq!(mem.read_signed_be::<i16, i32>(0), -32513);
+    assert_eq!(mem.read_signed::<i16, i32>(1) as u32, 0x7FFF);
+    assert_eq!(mem.read_signed::<i8, i16>(1) as u32, 0xFFFF_FFFF);
+    assert_eq!(mem.try_read_signed::<i16, i64>(3), Err(()));
+}
+
+#[test]
+fn test_bits() {
+    let mut mem = TestMemory::new([0u8; 12]);
+    mem.write_bit(0, 3, true);
+    mem.write_bit(0, 9, true);
+    assert_eq!(mem.get(..2).unwrap(), &[0x08, 0x02]);
+    assert!(mem.read_bit(1, 1));
+    assert!(!mem.read_bit(0, 2));
+    mem.write_bit(0, 3, false);
+    assert_eq!(mem.read::<u8>(0), 0);
+
+    // Bit fields can cross byte boundaries, and keep the surrounding bits.
+    mem.write::<u16>(2, 0xFFFF);
+    mem.write_bits(2, 4..12, 0x1A5);
+    assert_eq!(mem.read::<u16>(2), 0xFA5F);
+    assert_eq!(mem.read_bits(2, 4..12), 0xA5);
+    assert_eq!(mem.read_bits(2, 0..0), 0);
+
+    mem.write_bits(3, 7..71, u64::MAX);
+    assert_eq!(mem.read_bits(3, 7..71), u64::MAX);
+    assert_eq!(mem.read_bits(3, 0..8), 0xFA);
+    assert!(!mem.read_bit(3, 71));
+    assert_eq!(mem.try_write_bit(11, 8, true), Err(()));
+}
+
+#[test]
+fn test_invalid_bits() {
+    let mut mem = TestMemory::new([0u8; 12]);
+    assert_eq!(
+        mem.try_read_bits(0, 0..65),
+        Err(RangeError::Invalid { start: 0, end: 65 })
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 8..4;
+    assert_eq!(
+        mem.try_write_bits(0, reversed, 0),
+        Err(RangeError::Invalid { start: 8, end: 4 })
+    );
+    assert_eq!(
+        mem.try_write_bits(11, 4..12, 0),
+        Err(RangeError::Memory(()))
+    );
+    assert_eq!(mem.get(..).unwrap(), &[0; 12][..]);
+}
+
+#[test]
+#[should_panic(expected = "failed to read bits 0..65 at 0x0: Invalid")]
+fn test_bits_too_wide() {
+    TestMemory::new([0u8; 12]).read_bits(0, 0..65);
+}
+
+#[test]
+fn test_bcd() {
+    let mut mem = TestMemory::new([0u8; 8]);
+    mem.write_bcd::<3>(0, 123_456);
+    assert_eq!(mem.get(..3).unwrap(), &[0x56, 0x34, 0x12]);
+    assert_eq!(mem.read_bcd::<3>(0), Some(123_456));
+    assert_eq!(mem.read_bcd_be::<3>(0), Some(563_412));
+
+    // Only the lowest digits are written.
+    mem.write_bcd_be::<2>(4, 98_765);
+    assert_eq!(mem.get(4..6).unwrap(), &[0x87, 0x65]);
+    assert_eq!(mem.read_bcd_be::<2>(4), Some(8765));
+
+    mem.write::<u8>(7, 0x1A);
+    assert_eq!(mem.read_bcd::<1>(7), None);
+    assert_eq!(mem.try_read_bcd::<2>(7), Err(()));
+}
+
+#[test]
+fn test_increment_bcd() {
+    let mut mem = TestMemory::new([0x99, 0x19, 0x00, 0x59, 0x99]);
+    assert!(!mem.increment_bcd::<2>(0));
+    assert_eq!(mem.read_bcd::<2>(0), Some(2000));
+
+    assert!(!mem.increment_bcd_be::<2>(3));
+    assert_eq!(mem.get(3..5).unwrap(), &[0x60, 0x00]);
+
+    mem.write_bcd::<2>(0, 9999);
+    assert!(mem.increment_bcd::<2>(0));
+    assert_eq!(mem.read_bcd::<2>(0), Some(0));
+}
+
+#[test]
+fn test_uint() {
+    let mut mem = TestMemory::new([0u8; 20]);
+    mem.write_uint::<3>(1, 0xAB_CDEF);
+    assert_eq!(mem.get(..5).unwrap(), &[0x00, 0xEF, 0xCD, 0xAB, 0x00]);
+    assert_eq!(mem.read_uint::<3>(1), 0xAB_CDEF);
+    assert_eq!(mem.read_uint_be::<3>(1), 0xEF_CDAB);
+
+    // Only the lower bytes of the value are written.
+    mem.write_uint_be::<6>(8, 0xFFFF_1122_3344_5566);
+    assert_eq!(
+        mem.get(8..14).unwrap(),
+        &[0x11, 0x22, 0x33, 0x44, 0x55, 0x66]
+    );
+    assert_eq!(mem.read_uint_be::<6>(8), 0x1122_3344_5566);
+
+    mem.write_uint::<16>(4, u128::MAX);
+    assert_eq!(mem.read_uint::<16>(4), u128::MAX);
+    assert_eq!(mem.read_uint::<0>(4), 0);
+    assert_eq!(mem.try_read_uint::<3>(18), Err(()));
+}
+
+fn fetch<E: Endianness, M: MemoryStorage>(mem: &M, pc: usize) -> u16 {
+    mem.read_with::<u16, E>(pc)
+}
+
+#[test]
+fn test_generic_endianness() {
+    let mut mem = TestMemory::new([0u8; 8]);
+    mem.write_with::<u32, BigEndian>(0, 0x1234_5678);
+    assert_eq!(mem.get(..4).unwrap(), &[0x12, 0x34, 0x56, 0x78]);
+    mem.write_with::<u32, LittleEndian>(4, 0x1234_5678);
+    assert_eq!(mem.get(4..).unwrap(), &[0x78, 0x56, 0x34, 0x12]);
+
+    assert_eq!(fetch::<BigEndian, _>(&mem, 0), 0x1234);
+    assert_eq!(fetch::<LittleEndian, _>(&mem, 0), 0x3412);
+    assert_eq!(
+        mem.read_with::<u32, NativeEndian>(4),
+        u32::from_ne_bytes([0x78, 0x56, 0x34, 0x12])
+    );
+    assert_eq!(mem.try_read_with::<u32, BigEndian>(6), Err(()));
+}
+
+struct FenceMemory {
+    fences: Cell<usize>,
+}
+
+impl MemoryStorage for FenceMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, _: usize) -> Result<u8, Self::Error> {
+        Err(())
+    }
+
+    fn try_write_byte(&mut self, _: usize, _: u8) -> Result<(), Self::Error> {
+        Err(())
+    }
+
+    fn fence(&self, _: Ordering) {
+        self.fences.set(self.fences.get() + 1);
+    }
+}
+
+#[test]
+fn test_fence() {
+    // The default implementation does nothing.
+    TestMemory::new([0; 4]).fence(Ordering::SeqCst);
+
+    let mem = OpenBus::new(FenceMemory {
+        fences: Cell::new(0),
+    });
+    mem.fence(Ordering::Acquire);
+    mem.fence(Ordering::Relaxed);
+    assert_eq!(mem.inner().fences.get(), 2);
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "binrw")]
+
+mod common;
+
+use binrw::io::{Seek, SeekFrom};
+use binrw::{binrw, BinRead, BinWrite};
+use common::TestMemory;
+// Needed by the `count` attribute on edition 2018.
+use core::convert::TryFrom;
+use mem_storage::{ContiguousMemory, MemoryCursor};
+
+#[binrw]
+#[derive(Debug, PartialEq)]
+#[brw(big)]
+struct Entry {
+    kind: u8,
+    #[br(temp)]
+    #[bw(calc = name.len() as u16)]
+    len: u16,
+    #[br(count = len)]
+    name: Vec<u8>,
+}
+
+#[test]
+fn test_binrw_roundtrip() {
+    let mut mem = TestMemory::new([0u8; 64]);
+    let entry = Entry {
+        kind: 7,
+        name: b"boot".to_vec(),
+    };
+
+    let mut cursor = MemoryCursor::new(&mut mem, 0x10);
+    entry.write(&mut cursor).unwrap();
+    assert_eq!(cursor.position(), 0x17);
+    assert_eq!(mem.get(0x10..0x17).unwrap(), b"\x07\x00\x04boot");
+
+    let mut cursor = MemoryCursor::new(&mem, 0x10);
+    assert_eq!(Entry::read(&mut cursor).unwrap(), entry);
+    assert_eq!(cursor.seek(SeekFrom::Current(-7)).unwrap(), 0x10);
+    assert!(cursor.seek(SeekFrom::End(0)).is_err());
+}
+
+#[test]
+fn test_binrw_out_of_bounds() {
+    let mem = TestMemory::new([0u8; 4]);
+    assert!(u32::read_le(&mut MemoryCursor::new(&mem, 2)).is_err());
+    assert!(u32::read_le(&mut MemoryCursor::new(&mem, 0)).is_ok());
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{assert_accesses_within, MemoryStorage};
+
+#[test]
+fn test_accesses_within() {
+    let mut mem = TestMemory::new([0; 16]);
+    let value = assert_accesses_within(&mut mem, 4..12, |mem| {
+        assert_eq!(mem.allowed(), 4..12);
+        mem.write::<u32>(4, 0xDEAD_BEEF);
+        mem.inner().read::<u8>(0);
+        mem.read::<u64>(4)
+    });
+    assert_eq!(value, 0xDEAD_BEEF);
+    assert_eq!(mem.read::<u32>(4), 0xDEAD_BEEF);
+}
+
+#[test]
+#[should_panic(
+    expected = "2 accesses outside of 0x4..0xc:\n  read of 0xa..0xe\n  write of 0x3..0x4"
+)]
+fn test_accesses_outside() {
+    let mut mem = TestMemory::new([0; 16]);
+    assert_accesses_within(&mut mem, 4..12, |mem| {
+        mem.read::<u32>(10);
+        mem.write::<u8>(3, 1);
+        mem.read::<u8>(11);
+    });
+}
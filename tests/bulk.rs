@@ -0,0 +1,94 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{ContiguousMemory, MemoryStorage};
+
+/// Hides the slice of a `TestMemory`, so the byte accessor fallbacks are used.
+struct ByteMemory(TestMemory);
+
+impl MemoryStorage for ByteMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.0.try_read_byte(addr)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.0.try_write_byte(addr, byte)
+    }
+}
+
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn test_fill() {
+    let mut fast = TestMemory::new(pattern(1000));
+    let mut slow = ByteMemory(TestMemory::new(pattern(1000)));
+
+    fast.fill(3..700, 0xAA);
+    slow.fill(3..700, 0xAA);
+    assert_eq!(fast.get(..).unwrap(), slow.0.get(..).unwrap());
+    assert_eq!(fast.get(2..4).unwrap(), &[2, 0xAA]);
+    assert_eq!(fast.get(699..701).unwrap(), &[0xAA, 198]);
+
+    assert_eq!(fast.try_fill(990..1001, 0), Err(()));
+    assert_eq!(slow.try_fill(990..1001, 0), Err(()));
+}
+
+#[test]
+fn test_copy_within() {
+    for (src, dest) in [(0..600, 100), (100..700, 0), (10..20, 500), (0..0, 999)] {
+        let mut fast = TestMemory::new(pattern(1000));
+        let mut slow = ByteMemory(TestMemory::new(pattern(1000)));
+
+        fast.copy_within(src.clone(), dest);
+        slow.copy_within(src.clone(), dest);
+
+        let mut expected = pattern(1000);
+        expected.copy_within(src, dest);
+        assert_eq!(fast.get(..).unwrap(), &expected[..]);
+        assert_eq!(slow.0.get(..).unwrap(), &expected[..]);
+    }
+
+    let mut mem = TestMemory::new(pattern(1000));
+    assert_eq!(mem.try_copy_within(0..10, 995), Err(()));
+    assert_eq!(mem.try_copy_within(995..1005, 0), Err(()));
+
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 20..10;
+    let mut mem = TestMemory::new(pattern(1000));
+    let mut slow = ByteMemory(TestMemory::new(pattern(1000)));
+    assert_eq!(mem.try_copy_within(reversed.clone(), 0), Ok(()));
+    assert_eq!(slow.try_copy_within(reversed, 0), Ok(()));
+    assert_eq!(mem.get(..).unwrap(), &pattern(1000)[..]);
+    assert_eq!(slow.0.get(..).unwrap(), &pattern(1000)[..]);
+}
+
+#[test]
+fn test_find() {
+    let mut data = vec![0u8; 1000];
+    let mut fast = TestMemory::new(data.clone());
+    assert_eq!(fast.find(0..1000, 1), None);
+
+    // Cover every position inside and around a vector of bytes.
+    for pos in (0..40).chain(990..1000) {
+        data.fill(0);
+        data[pos] = 0xEE;
+        data[999] = 0xEE;
+        fast = TestMemory::new(data.clone());
+        let slow = ByteMemory(TestMemory::new(data.clone()));
+
+        assert_eq!(fast.find(0..1000, 0xEE), Some(pos));
+        assert_eq!(slow.find(0..1000, 0xEE), Some(pos));
+        assert_eq!(fast.find(pos + 1..999, 0xEE), None);
+    }
+
+    let mem = TestMemory::new(pattern(300));
+    assert_eq!(mem.find(5..300, 3), Some(254));
+    assert_eq!(mem.try_find(290..301, 0xFF), Err(()));
+    assert_eq!(ByteMemory(mem).try_find(290..301, 0xFF), Err(()));
+}
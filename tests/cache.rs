@@ -0,0 +1,77 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{CacheStats, MemoryStorage, PageCache};
+
+#[test]
+fn test_hits_and_misses() {
+    let mem = TestMemory::new([0u8; 18]);
+    let mut cache = PageCache::<_, 4>::new(mem, 18, 2);
+
+    assert_eq!(cache.read::<u16>(0), 0);
+    assert_eq!(cache.read::<u16>(2), 0);
+    cache.write::<u32>(6, 0x1122_3344);
+    assert_eq!(
+        cache.stats(),
+        CacheStats {
+            hits: 1,
+            misses: 3,
+            writebacks: 0
+        }
+    );
+    assert_eq!(cache.cached_pages(), 2);
+    assert_eq!(cache.stats().hit_rate(), 0.25);
+
+    cache.reset_stats();
+    assert_eq!(cache.stats(), CacheStats::default());
+}
+
+#[test]
+fn test_write_back() {
+    let mem = TestMemory::new([0u8; 18]);
+    let mut cache = PageCache::<_, 4>::new(mem, 18, 1);
+
+    cache.write::<u16>(16, 0xBEEF);
+    cache.write::<u8>(1, 0xAA);
+    assert_eq!(cache.stats().writebacks, 1);
+
+    cache.flush();
+    assert_eq!(cache.stats().writebacks, 2);
+    assert_eq!(cache.read::<u16>(16), 0xBEEF);
+
+    let mem = cache.into_inner();
+    assert_eq!(mem.read::<u8>(1), 0xAA);
+    assert_eq!(mem.read::<u16>(16), 0xBEEF);
+}
+
+#[test]
+fn test_uncached_range() {
+    let mem = TestMemory::new([1u8; 8]);
+    let mut cache = PageCache::<_, 4>::new(mem, 4, 1);
+
+    assert_eq!(cache.read::<u8>(6), 1);
+    assert_eq!(cache.try_read::<u8>(8), Err(()));
+    cache.write::<u8>(7, 2);
+    assert_eq!(cache.stats(), CacheStats::default());
+    assert_eq!(cache.into_inner().read::<u8>(7), 2);
+}
+
+#[test]
+fn test_access_across_cached_range() {
+    let mem = TestMemory::new([1u8; 8]);
+    let mut cache = PageCache::<_, 4>::new(mem, 6, 2);
+
+    cache.write::<u8>(5, 0xAA);
+    assert_eq!(cache.read::<u32>(4), 0x0101_AA01);
+
+    cache.write::<u32>(4, 0x2233_4455);
+    assert_eq!(cache.read::<u32>(4), 0x2233_4455);
+    assert_eq!(cache.read::<u8>(5), 0x44);
+
+    let mem = cache.into_inner();
+    assert_eq!(mem.read::<u16>(4), 0x0101);
+    assert_eq!(mem.read::<u16>(6), 0x2233);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{CellMemory, FillPolicy, MemoryStorage, OutOfBounds};
+
+#[test]
+fn test_cell_write_through_shared_ref() {
+    let mem = CellMemory::new(16);
+    let mut alias = &mem;
+
+    (&mem).write::<u32>(0, 0xDDFFEEAA);
+    alias.write_be::<u16>(4, 0x1234);
+
+    assert_eq!(alias.read::<u32>(0), 0xDDFFEEAA);
+    assert_eq!(mem.read_byte(4), 0x12);
+    assert_eq!(mem.read_be::<u16>(4), 0x1234);
+    assert_eq!(&mem.to_vec()[..6], &[0xAA, 0xEE, 0xFF, 0xDD, 0x12, 0x34]);
+}
+
+#[test]
+fn test_cell_out_of_bounds() {
+    let mem = CellMemory::from(vec![1, 2, 3, 4]);
+
+    assert_eq!(mem.try_read::<u16>(3), Err(OutOfBounds { addr: 3 }));
+    assert_eq!((&mem).try_write_byte(4, 0), Err(OutOfBounds { addr: 4 }));
+    assert_eq!(
+        mem.try_read::<u32>(usize::MAX),
+        Err(OutOfBounds { addr: usize::MAX })
+    );
+}
+
+#[test]
+fn test_cell_as_memory_storage() {
+    fn fill<M: MemoryStorage>(mem: &mut M) {
+        mem.write::<u16>(0, 0xBEEF);
+        mem.write_be::<u16>(2, 0xBEEF);
+    }
+
+    let mut mem = CellMemory::new(4);
+    fill(&mut mem);
+    assert_eq!(mem.to_vec(), [0xEF, 0xBE, 0xBE, 0xEF]);
+    assert_eq!(
+        MemoryStorage::try_read::<u64>(&mem, 0),
+        Err(OutOfBounds { addr: 0 })
+    );
+}
+
+#[test]
+fn test_cell_fill_policy() {
+    let mem = CellMemory::with_fill(6, FillPolicy::Byte(0xCC));
+    assert_eq!(mem.to_vec(), [0xCC; 6]);
+
+    let mem = CellMemory::with_fill(10, FillPolicy::Pattern(&[0xDE, 0xAD, 0xBE, 0xEF]));
+    assert_eq!(mem.read_be::<u32>(4), 0xDEADBEEF);
+    assert_eq!(mem.to_vec()[8..], [0xDE, 0xAD]);
+
+    let mem = CellMemory::with_fill(4, FillPolicy::default());
+    assert_eq!(mem.to_vec(), [0; 4]);
+}
+
+#[test]
+fn test_cell_try_new() {
+    let mem = CellMemory::try_new(16).unwrap();
+    assert_eq!(mem.to_vec(), [0; 16]);
+    (&mem).write::<u32>(12, 0xAABBCCDD);
+    assert_eq!(mem.read::<u32>(12), 0xAABBCCDD);
+
+    assert!(CellMemory::try_new(0).unwrap().is_empty());
+    assert!(CellMemory::try_new(usize::MAX).is_none());
+}
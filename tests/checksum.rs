@@ -0,0 +1,62 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{ChecksumError, ChecksumMemory, MemoryStorage};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_writes_update_checksums() {
+    let inner = TestMemory::new([0u8; 40]);
+    let mut mem = ChecksumMemory::<_, 16>::new(inner, 36).unwrap();
+    assert_eq!(mem.len(), 36);
+
+    mem.write::<u32>(14, 0xAABB_CCDD);
+    mem.fill(20..36, 0xFF);
+    mem.write::<u8>(38, 1);
+    mem.verify();
+    assert_eq!(mem.read::<u32>(14), 0xAABB_CCDD);
+    assert_eq!(mem.try_write::<u8>(40, 0), Err(ChecksumError::Memory(())));
+}
+
+/// A memory whose bytes can be modified without going through the wrapper.
+struct SharedMemory(Rc<RefCell<Vec<u8>>>);
+
+impl MemoryStorage for SharedMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.0.borrow().get(addr).copied().ok_or(())
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        *self.0.borrow_mut().get_mut(addr).ok_or(())? = byte;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_detects_corruption() {
+    let bytes = Rc::new(RefCell::new(vec![0u8; 48]));
+    let mut mem = ChecksumMemory::<_, 16>::new(SharedMemory(bytes.clone()), 48).unwrap();
+    mem.write::<u16>(0, 0x1234);
+
+    bytes.borrow_mut()[20] = 0x55;
+    assert_eq!(mem.try_verify(), Err(ChecksumError::Corrupted { page: 1 }));
+    // Reads are only verified in paranoid mode, but writes always are.
+    assert_eq!(mem.read::<u8>(20), 0x55);
+    assert_eq!(
+        mem.try_write::<u8>(31, 0),
+        Err(ChecksumError::Corrupted { page: 1 })
+    );
+
+    let mem = mem.paranoid();
+    assert_eq!(mem.read::<u8>(0), 0x34);
+    assert_eq!(
+        mem.try_read::<u32>(14),
+        Err(ChecksumError::Corrupted { page: 1 })
+    );
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use core::cell::{Cell, RefCell};
+use mem_storage::{CmpLogEntry, CmpLogMemory, MemoryStorage};
+
+#[test]
+fn test_typed_reads_are_logged() {
+    let inner = TestMemory::new([0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88]);
+    let mut mem = CmpLogMemory::new(inner, RefCell::new(Vec::<CmpLogEntry>::new()));
+
+    assert_eq!(mem.read::<u8>(0), 0x11);
+    assert_eq!(mem.read::<u16>(0), 0x2211);
+    assert_eq!(mem.read_be::<u32>(4), 0x5566_7788);
+    assert!(mem.try_read::<u64>(4).is_err());
+    mem.write::<u16>(0, 0xFFFF);
+
+    let (_, log) = mem.into_parts();
+    let log = log.into_inner();
+    assert_eq!(log.len(), 2);
+
+    assert_eq!(log[0].addr, 0);
+    assert_eq!(log[0].width(), 2);
+    assert_eq!(log[0].value_le(), 0x2211);
+
+    assert_eq!(log[1].addr, 4);
+    assert_eq!(log[1].bytes(), &[0x55, 0x66, 0x77, 0x88]);
+    assert_eq!(log[1].value_be(), 0x5566_7788);
+    assert_eq!(log[1].value_le(), 0x8877_6655);
+}
+
+#[test]
+fn test_closure_log() {
+    let count = Cell::new(0);
+    let mem = CmpLogMemory::new(TestMemory::new([0; 16]), |entry: CmpLogEntry| {
+        assert_eq!(entry.width(), 16);
+        count.set(count.get() + 1);
+    });
+
+    assert_eq!(mem.read::<u128>(0), 0);
+    assert_eq!(count.get(), 1);
+}
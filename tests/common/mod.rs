@@ -0,0 +1,47 @@
+use mem_storage::{ContiguousMemory, MemoryStorage};
+
+pub struct TestMemory {
+    ram: Vec<u8>,
+}
+
+impl TestMemory {
+    pub fn new<S: AsRef<[u8]>>(slice: S) -> Self {
+        Self {
+            ram: slice.as_ref().into(),
+        }
+    }
+}
+
+impl MemoryStorage for TestMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.ram.get(addr).copied().ok_or(())
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        let entry = self.ram.get_mut(addr).ok_or(())?;
+        *entry = byte;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(&self.ram)
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(&mut self.ram)
+    }
+}
+
+impl ContiguousMemory for TestMemory {
+    fn as_bytes(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn out_of_bounds(&self, _addr: usize) -> Self::Error {}
+}
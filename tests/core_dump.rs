@@ -0,0 +1,68 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::core_dump::{NT_PRSTATUS, PF_R, PF_W, PF_X};
+use mem_storage::{CoreDump, CoreDumpError};
+use std::convert::TryInto;
+
+fn u16_at(file: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(file[offset..offset + 2].try_into().unwrap())
+}
+
+fn u32_at(file: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(file[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(file: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(file[offset..offset + 8].try_into().unwrap())
+}
+
+#[test]
+fn test_core_file() {
+    let mem = TestMemory::new((0..64).collect::<Vec<u8>>());
+    let dump = CoreDump::new(243)
+        .region(0, 16, PF_R | PF_X)
+        .region(32, 8, PF_R | PF_W)
+        .note("CORE", NT_PRSTATUS, &[0xAA; 6]);
+
+    let mut file = Vec::new();
+    dump.write(&mem, &mut file).unwrap();
+
+    assert_eq!(&file[..6], b"\x7fELF\x02\x01");
+    assert_eq!(u16_at(&file, 16), 4);
+    assert_eq!(u16_at(&file, 18), 243);
+    assert_eq!(u64_at(&file, 32), 64);
+    assert_eq!(u16_at(&file, 56), 3);
+
+    // The note segment, with the name and the content padded to 4 bytes.
+    assert_eq!(u32_at(&file, 64), 4);
+    let notes = u64_at(&file, 64 + 8) as usize;
+    assert_eq!(u64_at(&file, 64 + 32), 4 + 4 + 4 + 8 + 8);
+    assert_eq!(u32_at(&file, notes), 5);
+    assert_eq!(u32_at(&file, notes + 4), 6);
+    assert_eq!(u32_at(&file, notes + 8), NT_PRSTATUS);
+    assert_eq!(&file[notes + 12..notes + 17], b"CORE\0");
+    assert_eq!(&file[notes + 20..notes + 26], &[0xAA; 6]);
+
+    // The second load segment.
+    let phdr = 64 + 2 * 56;
+    assert_eq!(u32_at(&file, phdr), 1);
+    assert_eq!(u32_at(&file, phdr + 4), PF_R | PF_W);
+    assert_eq!(u64_at(&file, phdr + 16), 32);
+    assert_eq!(u64_at(&file, phdr + 32), 8);
+    let offset = u64_at(&file, phdr + 8) as usize;
+    assert_eq!(&file[offset..offset + 8], &[32, 33, 34, 35, 36, 37, 38, 39]);
+    assert_eq!(file.len(), offset + 8);
+}
+
+#[test]
+fn test_unreadable_region() {
+    let mem = TestMemory::new([0; 16]);
+    let dump = CoreDump::new(62).region(8, 16, PF_R);
+    match dump.write(&mem, Vec::new()) {
+        Err(CoreDumpError::Memory(())) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,39 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{ContiguousMemory, CoverageMap, MemoryStorage, OutOfBounds, AFL_MAP_SIZE};
+
+#[test]
+fn test_coverage_map() {
+    let mut map = CoverageMap::new([0u8; 8]);
+    map.hit(1);
+    map.hit(9);
+    map.hit(3);
+    assert_eq!(map.counter(1), 2);
+    assert_eq!(map.count(), 2);
+
+    for _ in 0..300 {
+        map.hit(5);
+    }
+    assert_eq!(map.counter(5), 255);
+
+    map.reset();
+    assert_eq!(map.count(), 0);
+    assert_eq!(map.into_inner(), [0; 8]);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_guest_access() {
+    let mut map = CoverageMap::with_size(AFL_MAP_SIZE);
+    assert_eq!(map.len(), AFL_MAP_SIZE);
+
+    map.write::<u8>(0x1234, 7);
+    map.hit(0x1234);
+    assert_eq!(map.read::<u8>(0x1234), 8);
+    assert_eq!(map.get(0x1234..0x1235).unwrap(), &[8]);
+    assert_eq!(map.as_bytes()[0x1234], 8);
+    assert_eq!(
+        map.try_read::<u8>(AFL_MAP_SIZE),
+        Err(OutOfBounds { addr: AFL_MAP_SIZE })
+    );
+}
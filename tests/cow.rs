@@ -0,0 +1,65 @@
+#![cfg(all(feature = "shm", target_os = "linux", not(feature = "forbid-unsafe")))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{ContiguousMemory, CowMemory, MapOptions, MemoryStorage, OutOfBounds};
+
+#[test]
+fn test_restore() {
+    let mut mem = CowMemory::from_bytes(&[1, 2, 3, 4]).unwrap();
+    assert_eq!(mem.len(), 4);
+    assert_eq!(mem.get(..).unwrap(), &[1, 2, 3, 4]);
+
+    mem.write::<u16>(1, 0xAABB);
+    assert_eq!(mem.get(..).unwrap(), &[1, 0xBB, 0xAA, 4]);
+    mem.restore().unwrap();
+    assert_eq!(mem.get(..).unwrap(), &[1, 2, 3, 4]);
+
+    mem.write::<u8>(3, 9);
+    mem.snapshot().unwrap();
+    mem.write::<u8>(0, 7);
+    mem.restore().unwrap();
+    assert_eq!(mem.get(..).unwrap(), &[1, 2, 3, 9]);
+    assert_eq!(mem.try_read::<u8>(4), Err(OutOfBounds { addr: 4 }));
+}
+
+#[test]
+fn test_large_and_empty() {
+    let mut mem = CowMemory::new(1 << 20).unwrap();
+    mem.fill(0x1000..0x3000, 0xFF);
+    assert_eq!(mem.read::<u8>(0x2FFF), 0xFF);
+    mem.restore().unwrap();
+    assert_eq!(mem.find(0..1 << 20, 0xFF), None);
+
+    let mut mem = CowMemory::new(0).unwrap();
+    assert!(mem.is_empty());
+    mem.snapshot().unwrap();
+    assert_eq!(mem.try_read::<u8>(0), Err(OutOfBounds { addr: 0 }));
+}
+
+#[test]
+fn test_discard() {
+    let mut mem = CowMemory::from_bytes(&[0xAA; 0x4000]).unwrap();
+    mem.fill(0..0x4000, 0);
+    mem.discard(0x800..0x2800).unwrap();
+    assert_eq!(mem.find(0x800..0x2800, 0), None);
+    assert_eq!(mem.read::<u8>(0x7FF), 0);
+    assert_eq!(mem.read::<u8>(0x2800), 0);
+
+    mem.discard(0x3001..0x3002).unwrap();
+    assert_eq!(mem.get(0x3000..0x3003).unwrap(), &[0, 0xAA, 0]);
+    mem.discard(0x3000..0x3000).unwrap();
+    assert!(mem.discard(0x3000..0x4001).is_err());
+}
+
+#[test]
+fn test_map_options() {
+    let options = MapOptions {
+        populate: true,
+        huge_pages: true,
+    };
+    let mut mem = CowMemory::with_options(1 << 21, options).unwrap();
+    mem.write::<u32>(0x1000, 0xDEAD_BEEF);
+    assert_eq!(mem.read::<u32>(0x1000), 0xDEAD_BEEF);
+    mem.restore().unwrap();
+    assert_eq!(mem.read::<u32>(0x1000), 0);
+}
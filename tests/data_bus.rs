@@ -0,0 +1,121 @@
+mod common;
+
+use common::TestMemory;
+use mem_storage::{DataBus, DataBusError, MemoryStorage, MisalignedPolicy, WidePolicy, Width};
+use std::cell::RefCell;
+
+/// A memory that records the address and length of every access.
+struct LogMemory {
+    mem: TestMemory,
+    log: RefCell<Vec<(usize, usize)>>,
+}
+
+impl LogMemory {
+    fn new(len: usize) -> Self {
+        Self {
+            mem: TestMemory::new(vec![0; len]),
+            log: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl MemoryStorage for LogMemory {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        self.log.borrow_mut().push((addr, 1));
+        self.mem.try_read_byte(addr)
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        self.log.borrow_mut().push((addr, 1));
+        self.mem.try_write_byte(addr, byte)
+    }
+
+    fn try_read_into(&self, addr: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.log.borrow_mut().push((addr, buf.len()));
+        self.mem.try_read_into(addr, buf)
+    }
+
+    fn try_write_from(&mut self, addr: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.log.borrow_mut().push((addr, buf.len()));
+        self.mem.try_write_from(addr, buf)
+    }
+}
+
+#[test]
+fn test_split_wide_accesses() {
+    let mut bus = DataBus::new(LogMemory::new(16), Width::W16);
+
+    bus.try_write(4, 0x1122_3344u32).unwrap();
+    assert_eq!(bus.try_read::<u32>(4), Ok(0x1122_3344));
+    assert_eq!(bus.try_read::<u16>(6), Ok(0x1122));
+    assert_eq!(
+        *bus.inner().log.borrow(),
+        [(4, 2), (6, 2), (4, 2), (6, 2), (6, 2)]
+    );
+}
+
+#[test]
+fn test_reject_wide_accesses() {
+    let mut bus = DataBus::new(LogMemory::new(16), Width::W16).wide_policy(WidePolicy::Reject);
+
+    assert_eq!(
+        bus.try_write(8, 1u64),
+        Err(DataBusError::TooWide { addr: 8, len: 8 })
+    );
+    assert_eq!(bus.try_write(8, 1u16), Ok(()));
+    assert_eq!(*bus.inner().log.borrow(), [(8, 2)]);
+}
+
+#[test]
+fn test_misaligned() {
+    let bus = DataBus::new(LogMemory::new(16), Width::W32);
+
+    assert_eq!(
+        bus.try_read::<u32>(2),
+        Err(DataBusError::Misaligned { addr: 2, len: 4 })
+    );
+    assert_eq!(bus.try_read::<u16>(2), Ok(0));
+    assert!(bus.try_read::<u8>(3).is_ok());
+}
+
+#[test]
+fn test_bulk_access() {
+    let mut bus = DataBus::new(LogMemory::new(16), Width::W32);
+
+    bus.try_write_from(3, &[1, 2, 3, 4, 5, 6]).unwrap();
+    assert_eq!(*bus.inner().log.borrow(), [(3, 1), (4, 4), (8, 1)]);
+    assert_eq!(
+        bus.try_read_into(0, &mut [0; 32]),
+        Err(DataBusError::Memory(()))
+    );
+}
+
+#[test]
+fn test_split_misaligned() {
+    let mut bus =
+        DataBus::new(LogMemory::new(16), Width::W32).misaligned_policy(MisalignedPolicy::Split);
+
+    bus.try_write(2, 0x1122_3344u32).unwrap();
+    assert_eq!(bus.try_read::<u32>(2), Ok(0x1122_3344));
+    assert_eq!(bus.try_read::<u16>(7), Ok(0));
+    assert_eq!(
+        *bus.inner().log.borrow(),
+        [(2, 2), (4, 2), (2, 2), (4, 2), (7, 1), (8, 1)]
+    );
+    assert_eq!(bus.cycles(), 6);
+}
+
+#[test]
+fn test_cycles() {
+    let mut bus = DataBus::new(LogMemory::new(16), Width::W16);
+
+    bus.try_write_byte(0, 1).unwrap();
+    bus.try_write(0, 1u64).unwrap();
+    assert_eq!(bus.cycles(), 5);
+
+    bus.reset_cycles();
+    assert_eq!(bus.try_read::<u16>(0), Ok(1));
+    assert_eq!(bus.cycles(), 1);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "defmt")]
+
+use mem_storage::{
+    Access, CacheStats, ChecksumError, CmpLogEntry, Divergence, EepromError, FaultError,
+    FlashError, GuestMemoryError, LazyError, OutOfBounds, ReplayError, SwapError, TierStats,
+    TieredError, WearReport, Width,
+};
+
+fn assert_format<T: defmt::Format>() {}
+
+#[test]
+fn test_errors_implement_format() {
+    assert_format::<OutOfBounds>();
+    assert_format::<FlashError>();
+    assert_format::<EepromError>();
+    assert_format::<GuestMemoryError>();
+    assert_format::<ReplayError>();
+    assert_format::<LazyError<OutOfBounds>>();
+    assert_format::<SwapError<OutOfBounds>>();
+    assert_format::<TieredError<OutOfBounds, OutOfBounds>>();
+    assert_format::<ChecksumError<OutOfBounds>>();
+    assert_format::<FaultError<OutOfBounds>>();
+}
+
+#[test]
+fn test_events_implement_format() {
+    assert_format::<Width>();
+    assert_format::<CmpLogEntry>();
+    assert_format::<CacheStats>();
+    assert_format::<TierStats>();
+    assert_format::<WearReport>();
+    assert_format::<Access>();
+    assert_format::<Divergence>();
+}
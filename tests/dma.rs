@@ -0,0 +1,87 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{CellMemory, DmaDescriptor, DmaEngine, DmaError, MemoryStorage};
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn test_copy_and_fill() {
+    let src = TestMemory::new([1, 2, 3, 4]);
+    let mut dst = TestMemory::new([0; 8]);
+
+    let mut dma = DmaEngine::new();
+    dma.submit(DmaDescriptor::copy(0, 4, 4));
+    dma.submit(DmaDescriptor::fill(0, 0xFF, 2));
+    assert_eq!(dma.run(&src, &mut dst), 6);
+    assert!(dma.is_idle());
+    assert_eq!(dst.read::<u64>(0), 0x0403_0201_0000_FFFF);
+}
+
+#[test]
+fn test_strides() {
+    let src = TestMemory::new([1, 2, 3, 4]);
+    let mut dst = TestMemory::new([0; 8]);
+
+    let mut dma = DmaEngine::new();
+    dma.submit(DmaDescriptor::copy(3, 0, 4).src_stride(-1).dst_stride(2));
+    dma.submit(DmaDescriptor::copy(0, 7, 3).dst_stride(0));
+    dma.run(&src, &mut dst);
+    assert_eq!(dst.read_be::<u64>(0), 0x0400_0300_0200_0103);
+}
+
+#[test]
+fn test_cycle_budget() {
+    let completed = Rc::new(Cell::new(None));
+    let src = TestMemory::new([0xAA; 4]);
+    let mut dst = TestMemory::new([0; 4]);
+
+    let mut dma = DmaEngine::new().cycles_per_byte(2).setup_cycles(4);
+    let flag = completed.clone();
+    dma.submit_with(DmaDescriptor::copy(0, 0, 4), move |desc, cycles| {
+        flag.set(Some((desc.len, cycles)))
+    });
+
+    assert_eq!(dma.try_step(&src, &mut dst, 7), Ok(8));
+    assert_eq!(dst.read::<u32>(0), 0x0000_AAAA);
+    assert_eq!(completed.get(), None);
+
+    assert_eq!(dma.try_step(&src, &mut dst, 100), Ok(4));
+    assert_eq!(completed.get(), Some((4, 12)));
+    assert_eq!(dma.cycles(), 12);
+    assert!(dma.is_idle());
+}
+
+#[test]
+fn test_in_single_memory() {
+    let mut mem = CellMemory::new(8);
+    mem.write::<u32>(0, 0xDEAD_BEEF);
+
+    let mut dma = DmaEngine::new();
+    dma.submit(DmaDescriptor::copy(0, 4, 4));
+    dma.try_step_in(&mut mem, u64::MAX).unwrap();
+    assert_eq!(mem.read::<u32>(4), 0xDEAD_BEEF);
+}
+
+#[test]
+fn test_errors() {
+    let src = TestMemory::new([0; 4]);
+    let mut dst = TestMemory::new([0; 4]);
+
+    let mut dma = DmaEngine::new();
+    dma.submit(DmaDescriptor::copy(2, 0, 4));
+    dma.submit(DmaDescriptor::fill(2, 1, 4));
+    assert_eq!(
+        dma.try_run(&src, &mut dst),
+        Err(DmaError::Source { addr: 4, error: () })
+    );
+    assert_eq!(dma.pending(), 1);
+    assert_eq!(
+        dma.try_run(&src, &mut dst),
+        Err(DmaError::Destination { addr: 4, error: () })
+    );
+    assert!(dma.is_idle());
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{EepromError, EepromMemory, MemoryStorage, WearOut, WearReport};
+
+#[test]
+fn test_write_counts() {
+    let mut eeprom = EepromMemory::new(8);
+    assert_eq!(eeprom.read::<u16>(0), 0xFFFF);
+
+    eeprom.write::<u16>(0, 0x1234);
+    eeprom.write::<u8>(1, 0x56);
+    assert_eq!(eeprom.read::<u16>(0), 0x5634);
+    assert_eq!(eeprom.write_counts(), &[1, 2, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(
+        eeprom.try_write::<u16>(7, 0),
+        Err(EepromError::OutOfBounds { addr: 8 })
+    );
+}
+
+#[test]
+fn test_endurance() {
+    let mut eeprom = EepromMemory::new(4).with_endurance(2, WearOut::Fail);
+    eeprom.write::<u8>(1, 1);
+    eeprom.write::<u8>(1, 2);
+    assert_eq!(
+        eeprom.try_write::<u16>(0, 0),
+        Err(EepromError::WornOut { addr: 1 })
+    );
+    assert_eq!(eeprom.as_bytes(), &[0xFF, 2, 0xFF, 0xFF]);
+    assert_eq!(eeprom.write_count(0), 0);
+
+    let mut eeprom = EepromMemory::new(4).with_endurance(1, WearOut::Corrupt);
+    eeprom.write::<u8>(0, 0xF0);
+    eeprom.write::<u8>(0, 0x3C);
+    assert_eq!(eeprom.read::<u8>(0), 0x30);
+    assert_eq!(eeprom.write_count(0), 2);
+}
+
+#[test]
+fn test_write_latency() {
+    let mut eeprom = EepromMemory::new(4).with_write_latency(10);
+    eeprom.write::<u32>(0, 0xAABBCCDD);
+    assert!(eeprom.is_busy());
+    assert_eq!(eeprom.try_read::<u8>(0), Err(EepromError::Busy));
+    assert_eq!(eeprom.try_write::<u8>(0, 0), Err(EepromError::Busy));
+
+    eeprom.tick(9);
+    assert!(eeprom.is_busy());
+    eeprom.tick(1);
+    assert!(!eeprom.is_busy());
+    assert_eq!(eeprom.read::<u32>(0), 0xAABBCCDD);
+}
+
+#[test]
+fn test_wear_report() {
+    let mut eeprom = EepromMemory::new(8);
+    for _ in 0..3 {
+        eeprom.write::<u8>(5, 0);
+    }
+    eeprom.try_write_from(3, &[0, 0, 0]).unwrap();
+
+    assert_eq!(eeprom.block_wear(4).collect::<Vec<_>>(), [1, 4]);
+    let report = eeprom.wear_report(4);
+    assert_eq!(report.most_worn, Some(1));
+    assert_eq!(
+        report.to_string(),
+        "2 blocks, 5 total, min 1, max 4, mean 2.5"
+    );
+    assert_eq!(WearReport::from_counts([]), WearReport::default());
+}
@@ -0,0 +1,70 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{ContiguousMemory, EncryptedMemory, MemoryStorage, PageCipher};
+
+/// A (very insecure) cipher that xors every byte with a key derived from the page index.
+struct XorCipher(u8);
+
+impl PageCipher for XorCipher {
+    fn encrypt_page(&self, page: usize, data: &mut [u8]) {
+        data.iter_mut().for_each(|b| *b ^= self.0 ^ page as u8);
+    }
+
+    fn decrypt_page(&self, page: usize, data: &mut [u8]) {
+        self.encrypt_page(page, data)
+    }
+}
+
+#[test]
+fn test_encrypted_roundtrip() {
+    let inner = TestMemory::new([0u8; 64]);
+    let mut mem = EncryptedMemory::<_, _, 16>::from_plaintext(inner, XorCipher(0x5A), 4).unwrap();
+
+    assert_eq!(mem.try_read::<u32>(0), Ok(0));
+
+    // Crosses the border between the first and the second page.
+    mem.try_write::<u64>(12, 0x1122334455667788).unwrap();
+    assert_eq!(mem.try_read::<u64>(12), Ok(0x1122334455667788));
+    assert_eq!(mem.try_read_byte(16), Ok(0x44));
+
+    let raw = mem.inner().get(12..20).unwrap();
+    assert_eq!(
+        raw,
+        &[
+            0x88 ^ 0x5A,
+            0x77 ^ 0x5A,
+            0x66 ^ 0x5A,
+            0x55 ^ 0x5A,
+            0x44 ^ 0x5B,
+            0x33 ^ 0x5B,
+            0x22 ^ 0x5B,
+            0x11 ^ 0x5B
+        ]
+    );
+}
+
+#[test]
+fn test_encrypted_out_of_bounds() {
+    let inner = TestMemory::new([0u8; 32]);
+    let mut mem = EncryptedMemory::<_, _, 16>::new(inner, XorCipher(1));
+
+    assert_eq!(mem.try_read::<u16>(31), Err(()));
+    assert_eq!(mem.try_write_byte(32, 0), Err(()));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_encrypted_read_ref() {
+    use mem_storage::ReadRef;
+
+    let inner = TestMemory::new([0u8; 32]);
+    let mut mem = EncryptedMemory::<_, _, 16>::from_plaintext(inner, XorCipher(0x5A), 2).unwrap();
+    mem.write::<u32>(14, 0xAABB_CCDD);
+
+    let bytes = mem.read_ref(13..19);
+    assert_eq!(*bytes, [0, 0xDD, 0xCC, 0xBB, 0xAA, 0]);
+    assert!(mem.try_read_ref(30..34).is_err());
+}
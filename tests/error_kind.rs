@@ -0,0 +1,60 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{
+    CellMemory, ErrorKind, FlashError, GuestMemoryError, GuestMemoryMap, GuestRegion, MemoryError,
+    MemoryStorage, NorFlashMemory, Privilege, PrivilegeError, PrivilegedMemory, ResizeError,
+};
+
+fn kind_of<E: MemoryError>(err: E) -> ErrorKind {
+    err.kind()
+}
+
+#[test]
+fn test_built_in_kinds() {
+    let mem = CellMemory::new(4);
+    assert_eq!(
+        kind_of(mem.try_read::<u32>(2).unwrap_err()),
+        ErrorKind::OutOfBounds
+    );
+
+    let mut flash = NorFlashMemory::<16>::new(1);
+    flash.write::<u8>(0, 0x0F);
+    let err = flash.try_write::<u8>(0, 0xF0).unwrap_err();
+    assert_eq!(err, FlashError::NotErased { addr: 0 });
+    assert_eq!(err.kind(), ErrorKind::WriteToRom);
+
+    let guest =
+        GuestMemoryMap::new(vec![GuestRegion::new(0x1000, TestMemory::new([0u8; 0x10]))]).unwrap();
+    let err = guest.try_read::<u8>(0x2000).unwrap_err();
+    assert!(matches!(err, GuestMemoryError::Unmapped { .. }));
+    assert_eq!(err.kind(), ErrorKind::Unmapped);
+
+    assert_eq!(
+        ResizeError::AllocFailed { size: 16 }.kind(),
+        ErrorKind::OutOfMemory
+    );
+    assert_eq!(
+        ErrorKind::WriteToRom.to_string(),
+        "write to read-only memory"
+    );
+}
+
+#[test]
+fn test_wrapped_errors_forward_kind() {
+    let mut mem = PrivilegedMemory::new(CellMemory::new(0x100));
+    mem.add_supervisor_range(0x80..0x100);
+    mem.set_privilege(Privilege::User);
+
+    let err = mem.try_read::<u8>(0x80).unwrap_err();
+    assert!(matches!(err, PrivilegeError::Denied { .. }));
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+    // Errors of the inner memory keep their own kind.
+    let err = mem.try_read::<u8>(0x100).unwrap_err();
+    assert!(matches!(err, PrivilegeError::Memory(_)));
+    assert_eq!(err.kind(), ErrorKind::OutOfBounds);
+}
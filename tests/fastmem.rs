@@ -0,0 +1,51 @@
+#![cfg(all(feature = "shm", target_os = "linux", not(feature = "forbid-unsafe")))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{
+    AccessKind, FastmemArena, FastmemFault, MemoryStorage, Protection, SharedMemory,
+};
+
+#[test]
+fn test_map_and_classify() {
+    let mut ram = SharedMemory::anonymous(0x2000).unwrap();
+    let rom = SharedMemory::anonymous(0x1000).unwrap();
+    let mut arena = FastmemArena::new(0x10_0000, 0x1_0000).unwrap();
+    assert_eq!(arena.map(0, &ram, Protection::READ_WRITE).unwrap(), 0);
+    assert_eq!(arena.map(0x8000, &rom, Protection::READ).unwrap(), 1);
+    assert_eq!(arena.regions(), &[0..0x2000, 0x8000..0x9000]);
+
+    ram.write::<u32>(0x1234, 0xCAFE_BABE);
+    // Safety: the guest address is inside the RAM region, which is readable and writable.
+    unsafe {
+        let ptr = arena.base().add(0x1234) as *mut u32;
+        assert_eq!(ptr.read(), 0xCAFE_BABE);
+        ptr.write(0x1122_3344);
+    }
+    assert_eq!(ram.read::<u32>(0x1234), 0x1122_3344);
+
+    let base = arena.base() as usize;
+    assert_eq!(
+        arena.classify(base + 0x8004, Some(AccessKind::Write)),
+        Some(FastmemFault {
+            guest_addr: 0x8004,
+            region: Some(1),
+            access: Some(AccessKind::Write),
+        })
+    );
+    assert_eq!(arena.classify(base + 0x4000, None).unwrap().region, None);
+    assert_eq!(arena.classify(base + 0x10_0000, None), None);
+    assert_eq!(arena.classify(base - 1, None), None);
+
+    arena.protect(0x1000..0x2000, Protection::NONE).unwrap();
+    assert!(arena.protect(0x1000..0x3000, Protection::NONE).is_err());
+}
+
+#[test]
+fn test_invalid_regions() {
+    let mem = SharedMemory::anonymous(0x2000).unwrap();
+    let mut arena = FastmemArena::new(0x4000, 0).unwrap();
+    assert!(arena.map(0x100, &mem, Protection::READ).is_err());
+    assert!(arena.map(0x3000, &mem, Protection::READ).is_err());
+    arena.map(0x2000, &mem, Protection::READ).unwrap();
+    assert!(arena.map(0x1000, &mem, Protection::READ).is_err());
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{FaultError, FaultMemory, MemoryStorage};
+
+#[test]
+fn test_fault_ranges() {
+    let mut mem = FaultMemory::new(TestMemory::new([0u8; 32]));
+    mem.add_range(8..12);
+
+    mem.write::<u32>(4, 0xAABB_CCDD);
+    assert_eq!(
+        mem.try_write::<u16>(7, 0),
+        Err(FaultError::Injected { addr: 7 })
+    );
+    assert_eq!(
+        mem.try_read::<u8>(11),
+        Err(FaultError::Injected { addr: 11 })
+    );
+    assert_eq!(mem.read::<u8>(12), 0);
+    assert_eq!(mem.try_read::<u8>(32), Err(FaultError::Memory(())));
+    assert_eq!(mem.injected(), 2);
+
+    mem.clear_ranges();
+    assert_eq!(mem.read::<u8>(8), 0);
+    assert_eq!(mem.inner().read::<u32>(4), 0xAABB_CCDD);
+}
+
+#[test]
+fn test_random_faults() {
+    let run = |seed| {
+        let mut mem = FaultMemory::new(TestMemory::new([0u8; 16]));
+        mem.set_random(4, seed);
+        (0..1000)
+            .map(|idx| mem.try_read::<u8>(idx % 16).is_err())
+            .collect::<Vec<_>>()
+    };
+
+    let faults = run(42);
+    let count = faults.iter().filter(|&&fault| fault).count();
+    assert!((150..350).contains(&count), "{} faults", count);
+    assert_eq!(run(42), faults);
+    assert_ne!(run(7), faults);
+}
@@ -0,0 +1,57 @@
+#![cfg(all(feature = "ffi", not(feature = "forbid-unsafe")))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::ffi::*;
+use std::ptr;
+
+#[test]
+fn test_cell_handle() {
+    let mem = mem_storage_new(16);
+    let mut buf = [0u8; 4];
+    unsafe {
+        assert_eq!(
+            mem_storage_write(mem, 2, [1, 2, 3].as_ptr(), 3),
+            MEM_STORAGE_OK
+        );
+        assert_eq!(mem_storage_fill(mem, 8, 12, 0xAA), MEM_STORAGE_OK);
+        assert_eq!(mem_storage_copy(mem, 2, 5, 3), MEM_STORAGE_OK);
+
+        assert_eq!(
+            mem_storage_read(mem, 2, buf.as_mut_ptr(), 4),
+            MEM_STORAGE_OK
+        );
+        assert_eq!(buf, [1, 1, 2, 3]);
+        assert_eq!(
+            mem_storage_read(mem, 8, buf.as_mut_ptr(), 4),
+            MEM_STORAGE_OK
+        );
+        assert_eq!(buf, [0xAA; 4]);
+
+        assert_eq!(
+            mem_storage_read(mem, 14, buf.as_mut_ptr(), 4),
+            MEM_STORAGE_ERR_ACCESS
+        );
+        assert_eq!(mem_storage_fill(mem, 4, 2, 0), MEM_STORAGE_ERR_RANGE);
+        assert_eq!(
+            mem_storage_read(mem, 0, ptr::null_mut(), 4),
+            MEM_STORAGE_ERR_NULL
+        );
+        assert_eq!(mem_storage_read(mem, 0, ptr::null_mut(), 0), MEM_STORAGE_OK);
+        mem_storage_free(mem);
+        mem_storage_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn test_custom_memory() {
+    let mem = into_handle(TestMemory::new([5u8; 4]));
+    let mut byte = 0u8;
+    unsafe {
+        assert_eq!(mem_storage_read(mem, 3, &mut byte, 1), MEM_STORAGE_OK);
+        assert_eq!(mem_storage_write(mem, 4, &byte, 1), MEM_STORAGE_ERR_ACCESS);
+        mem_storage_free(mem);
+    }
+    assert_eq!(byte, 5);
+}
@@ -0,0 +1,84 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{MemFifo, MemoryStorage, OverflowPolicy};
+
+#[test]
+fn test_fifo_push_pop() {
+    let mut mem = TestMemory::new([0u8; 32]);
+    let fifo = MemFifo::<u16>::new(4..20);
+    fifo.try_reset(&mut mem).unwrap();
+    assert_eq!(fifo.capacity(), 3);
+
+    assert!(fifo.push(&mut mem, 1));
+    assert!(fifo.push(&mut mem, 2));
+    assert!(fifo.push(&mut mem, 3));
+    assert_eq!(fifo.try_len(&mem), Ok(3));
+    assert_eq!(mem.read::<u16>(16), 3);
+
+    // The default policy drops the pushed value.
+    assert!(!fifo.push(&mut mem, 4));
+    assert_eq!(fifo.peek(&mem), Some(1));
+    assert_eq!(fifo.pop(&mut mem), Some(1));
+    assert!(fifo.push(&mut mem, 5));
+    assert_eq!(mem.read::<u32>(8), 0);
+
+    assert_eq!(fifo.pop(&mut mem), Some(2));
+    assert_eq!(fifo.pop(&mut mem), Some(3));
+    assert_eq!(fifo.pop(&mut mem), Some(5));
+    assert_eq!(fifo.pop(&mut mem), None);
+    assert_eq!(fifo.peek(&mem), None);
+}
+
+#[test]
+fn test_fifo_overflow_policies() {
+    let mut mem = TestMemory::new([0u8; 16]);
+    let fifo = MemFifo::<u8>::new(0..12).overflow(OverflowPolicy::DropOldest);
+    for val in 1..=4 {
+        fifo.push(&mut mem, val);
+    }
+    assert_eq!(fifo.try_len(&mem), Ok(3));
+    assert_eq!(fifo.pop(&mut mem), Some(2));
+
+    fifo.try_reset(&mut mem).unwrap();
+    let fifo = fifo.overflow(OverflowPolicy::OverwriteNewest);
+    for val in 1..=4 {
+        fifo.push(&mut mem, val);
+    }
+    assert_eq!(fifo.pop(&mut mem), Some(1));
+    assert_eq!(fifo.pop(&mut mem), Some(2));
+    assert_eq!(fifo.pop(&mut mem), Some(4));
+    assert_eq!(fifo.pop(&mut mem), None);
+}
+
+#[test]
+fn test_fifo_guest_indices() {
+    let mut mem = TestMemory::new([0u8; 16]);
+    let fifo = MemFifo::<u8>::new(0..12);
+
+    // The guest filled the FIFO by writing the entries and moving the tail.
+    mem.fill(8..11, 0xAA);
+    mem.write::<u32>(4, 3);
+    assert_eq!(fifo.try_len(&mem), Ok(3));
+    assert!(!fifo.push(&mut mem, 0));
+
+    // Indices that are out of range are wrapped into the FIFO.
+    mem.write::<u32>(0, 9);
+    assert_eq!(fifo.try_len(&mem), Ok(2));
+    assert_eq!(fifo.try_pop(&mut TestMemory::new([0u8; 4])), Err(()));
+}
+
+#[test]
+#[should_panic(expected = "a ring must have at least two entries")]
+fn test_fifo_too_small() {
+    MemFifo::<u32>::new(0..15);
+}
+
+#[test]
+#[should_panic(expected = "failed to pop from FIFO at 0x20: ()")]
+fn test_fifo_pop_out_of_bounds() {
+    let mut mem = TestMemory::new([0u8; 16]);
+    MemFifo::<u8>::new(32..48).pop(&mut mem);
+}
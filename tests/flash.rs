@@ -0,0 +1,73 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{FlashError, MemoryStorage, NorFlashMemory, ReadRef};
+
+#[test]
+fn test_writes_only_clear_bits() {
+    let mut flash = NorFlashMemory::<16>::new(4);
+    assert_eq!(flash.len(), 64);
+    assert_eq!(flash.read::<u32>(0), 0xFFFF_FFFF);
+
+    flash.write::<u16>(2, 0xF0F0);
+    flash.write::<u16>(2, 0x3030);
+    assert_eq!(flash.read::<u16>(2), 0x3030);
+
+    // Setting a bit fails without modifying any byte of the write.
+    assert_eq!(
+        flash.try_write_from(1, &[0x00, 0x30, 0x31]),
+        Err(FlashError::NotErased { addr: 3 })
+    );
+    assert_eq!(flash.read_ref(0..4), [0xFF, 0xFF, 0x30, 0x30]);
+    assert_eq!(
+        flash.try_write_byte(64, 0),
+        Err(FlashError::OutOfBounds { addr: 64 })
+    );
+}
+
+#[test]
+fn test_erase_blocks() {
+    let mut flash = NorFlashMemory::<16>::new(4);
+    flash.fill(0..64, 0x00);
+
+    flash.erase_block(1);
+    assert_eq!(flash.read_ref(15..17), [0x00, 0xFF]);
+    assert_eq!(flash.read_ref(31..33), [0xFF, 0x00]);
+
+    flash.erase(32..64);
+    assert!(flash.as_bytes()[32..].iter().all(|&b| b == 0xFF));
+    assert_eq!(flash.as_bytes()[0], 0x00);
+
+    assert_eq!(
+        flash.try_erase(8..16),
+        Err(FlashError::NotAligned { addr: 8 })
+    );
+    assert_eq!(
+        flash.try_erase(48..80),
+        Err(FlashError::OutOfBounds { addr: 64 })
+    );
+    assert_eq!(
+        flash.try_erase_block(4),
+        Err(FlashError::OutOfBounds { addr: 64 })
+    );
+}
+
+#[test]
+fn test_wear_counters() {
+    let mut flash = NorFlashMemory::<16>::new(4);
+    flash.try_write_from(14, &[0, 0, 0, 0]).unwrap();
+    flash.erase(0..32);
+    flash.erase_block(0);
+    flash.write_byte(0, 0);
+
+    assert_eq!(flash.write_counts(), &[2, 1, 0, 0]);
+    assert_eq!(flash.erase_counts(), &[2, 1, 0, 0]);
+
+    let report = flash.erase_report();
+    assert_eq!(report.blocks, 4);
+    assert_eq!(report.total, 3);
+    assert_eq!((report.min, report.max), (0, 2));
+    assert_eq!(report.most_worn, Some(0));
+    assert_eq!(report.spread(), 2);
+    assert_eq!(report.mean(), 0.75);
+}
@@ -0,0 +1,75 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{ContiguousMemory, Framebuffer, MemoryStorage, PixelError, PixelFormat};
+
+#[test]
+fn test_pixels() {
+    let mut vram = TestMemory::new([0u8; 64]);
+    let fb = Framebuffer::new(4, PixelFormat::Rgb565, 3, 2).stride(8);
+    assert_eq!(fb.pixel_addr(2, 1), Some(16));
+    assert_eq!(fb.row_range(1), Some(12..18));
+
+    fb.set_pixel(&mut vram, 2, 1, 0xF800);
+    assert_eq!(vram.read::<u16>(16), 0xF800);
+    assert_eq!(fb.get_pixel(&vram, 2, 1), 0xF800);
+    assert_eq!(fb.get_pixel(&vram, 1, 1), 0);
+
+    // Bits that do not fit into the pixel are ignored.
+    let fb = Framebuffer::new(32, PixelFormat::Rgb888, 2, 2).big_endian();
+    fb.set_pixel(&mut vram, 1, 0, 0xFF11_2233);
+    assert_eq!(vram.get(35..38).unwrap(), &[0x11, 0x22, 0x33]);
+    assert_eq!(fb.get_pixel(&vram, 1, 0), 0x11_2233);
+}
+
+#[test]
+fn test_rows() {
+    let mut vram = TestMemory::new([0u8; 16]);
+    let fb = Framebuffer::new(0, PixelFormat::Indexed8, 4, 4);
+    fb.row_mut(&mut vram, 2)
+        .unwrap()
+        .copy_from_slice(&[1, 2, 3, 4]);
+    assert_eq!(fb.row(&vram, 2).unwrap(), &[1, 2, 3, 4]);
+    assert_eq!(fb.get_pixel(&vram, 3, 2), 4);
+
+    // Rows that are not backed by the memory are not returned.
+    let fb = Framebuffer::new(0, PixelFormat::Xrgb8888, 4, 2);
+    assert_eq!(fb.row(&vram, 0).unwrap().len(), 16);
+    assert!(fb.row(&vram, 1).is_none());
+    assert!(fb.try_get_pixel(&vram, 0, 1).is_err());
+}
+
+#[test]
+fn test_outside() {
+    let mut vram = TestMemory::new([0u8; 16]);
+    let fb = Framebuffer::new(0, PixelFormat::Indexed8, 4, 4);
+    assert_eq!(fb.pixel_addr(4, 0), None);
+    assert_eq!(fb.row_range(4), None);
+    assert!(fb.row(&vram, 4).is_none());
+    assert_eq!(
+        fb.try_get_pixel(&vram, 4, 0),
+        Err(PixelError::Outside { x: 4, y: 0 })
+    );
+    assert_eq!(
+        fb.try_set_pixel(&mut vram, 0, 4, 1),
+        Err(PixelError::Outside { x: 0, y: 4 })
+    );
+
+    // Addresses that do not fit into a `usize` are outside too.
+    let fb = Framebuffer::new(usize::MAX - 4, PixelFormat::Xrgb8888, 4, 4);
+    assert_eq!(fb.pixel_addr(2, 0), None);
+    assert_eq!(fb.row_range(0), None);
+    assert_eq!(
+        fb.try_get_pixel(&vram, 2, 0),
+        Err(PixelError::Outside { x: 2, y: 0 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "Outside { x: 4, y: 0 }")]
+fn test_outside_panics() {
+    let vram = TestMemory::new([0u8; 16]);
+    Framebuffer::new(0, PixelFormat::Indexed8, 4, 4).get_pixel(&vram, 4, 0);
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "std")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{GdbMemory, MemoryStorage, RemoteError};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+/// A minimal GDB server stub that serves 16 bytes of memory.
+fn stub(stream: TcpStream) -> Vec<u8> {
+    let mut mem = vec![0u8; 16];
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut stream = stream;
+
+    loop {
+        let mut packet = Vec::new();
+        if reader.read_until(b'#', &mut packet).unwrap() == 0 {
+            return mem;
+        }
+        let mut checksum = [0u8; 2];
+        reader.read_exact(&mut checksum).unwrap();
+        stream.write_all(b"+").unwrap();
+
+        let packet = String::from_utf8(packet).unwrap();
+        let packet = packet.trim_start_matches('$').trim_end_matches('#');
+        let (cmd, args) = packet.split_at(1);
+        let (range, data) = args.split_once(':').unwrap_or((args, ""));
+        let (addr, len) = range.split_once(',').unwrap();
+        let addr = usize::from_str_radix(addr, 16).unwrap();
+        let len = usize::from_str_radix(len, 16).unwrap();
+
+        let reply = if addr + len > mem.len() {
+            "E01".to_string()
+        } else if cmd == "m" {
+            let hex = mem[addr..addr + len]
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>();
+            // Compress runs of zeros like a real server.
+            hex.replacen("000000", "0*\"", 1)
+        } else {
+            for (idx, byte) in mem[addr..addr + len].iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&data[idx * 2..idx * 2 + 2], 16).unwrap();
+            }
+            "OK".to_string()
+        };
+
+        let sum = reply.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+        write!(stream, "${}#{:02x}", reply, sum).unwrap();
+        let mut ack = [0u8];
+        reader.read_exact(&mut ack).unwrap();
+        assert_eq!(ack[0], b'+');
+    }
+}
+
+#[test]
+fn test_gdb_memory() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || stub(listener.accept().unwrap().0));
+
+    let mut mem = GdbMemory::connect(addr).unwrap().with_chunk_size(3);
+    assert_eq!(mem.read::<u64>(0), 0);
+    mem.write::<u32>(4, 0x1122_3344);
+    assert_eq!(mem.read::<u64>(2), 0x0000_1122_3344_0000);
+    assert!(matches!(
+        mem.try_read::<u16>(15),
+        Err(RemoteError::Rejected { addr: 15 })
+    ));
+    drop(mem);
+
+    let mem = server.join().unwrap();
+    assert_eq!(&mem[4..8], &[0x44, 0x33, 0x22, 0x11]);
+}
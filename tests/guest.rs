@@ -0,0 +1,97 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{
+    GuestMemory, GuestMemoryError, GuestMemoryMap, GuestRegion, MemoryStorage, ReadRef,
+};
+use std::borrow::Cow;
+
+fn guest() -> GuestMemoryMap<TestMemory> {
+    GuestMemoryMap::new(vec![
+        GuestRegion::new(0x2000, TestMemory::new([0u8; 0x100])),
+        GuestRegion::new(0x1000, TestMemory::new([0u8; 0x1000])),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn test_translate() {
+    let mut mem = guest();
+    assert_eq!(mem.regions()[0].base(), 0x1000);
+    assert_eq!(mem.find_region(0x20FF).unwrap().range(), 0x2000..0x2100);
+    assert!(mem.find_region(0x2100).is_none());
+
+    mem.translate_mut(0x1FFC, 4)
+        .unwrap()
+        .copy_from_slice(b"host");
+    assert_eq!(mem.translate(0x1FFC, 4), Ok(&b"host"[..]));
+    assert_eq!(
+        mem.translate(0x1FFC, 8),
+        Err(GuestMemoryError::CrossesRegion { addr: 0x2000 })
+    );
+    assert_eq!(
+        mem.translate(0x0FFF, 1),
+        Err(GuestMemoryError::Unmapped { addr: 0x0FFF })
+    );
+}
+
+#[test]
+fn test_access_across_regions() {
+    let mut mem = guest();
+    mem.write::<u64>(0x1FFC, 0x1122_3344_5566_7788);
+    assert_eq!(mem.read::<u64>(0x1FFC), 0x1122_3344_5566_7788);
+    assert_eq!(mem.translate(0x2000, 4), Ok(&[0x44, 0x33, 0x22, 0x11][..]));
+
+    assert!(matches!(mem.read_ref(0x1FFC..0x2004), Cow::Owned(_)));
+    assert!(matches!(mem.read_ref(0x2000..0x2004), Cow::Borrowed(_)));
+
+    assert_eq!(
+        mem.try_write::<u32>(0x20FE, 0),
+        Err(GuestMemoryError::Unmapped { addr: 0x2100 })
+    );
+    assert_eq!(mem.read::<u16>(0x20FE), 0);
+}
+
+#[test]
+fn test_overlapping_regions() {
+    let regions = vec![
+        GuestRegion::new(0x0, TestMemory::new([0u8; 0x10])),
+        GuestRegion::new(0x8, TestMemory::new([0u8; 0x10])),
+    ];
+    assert!(GuestMemoryMap::new(regions).is_none());
+}
+
+#[test]
+fn test_region_at() {
+    let mem = GuestMemoryMap::new(vec![
+        GuestRegion::new(0x1000, TestMemory::new([0u8; 0x1000])).named("ram"),
+        GuestRegion::new(0x8000, TestMemory::new([0u8; 0x100])),
+    ])
+    .unwrap();
+
+    let at = mem.region_at(0x1234).unwrap();
+    assert_eq!(at.index, 0);
+    assert_eq!(at.region.name(), Some("ram"));
+    assert_eq!(at.offset, 0x234);
+
+    let at = mem.region_at(0x80FF).unwrap();
+    assert_eq!(at.index, 1);
+    assert_eq!(at.region.name(), None);
+    assert_eq!(at.offset, 0xFF);
+
+    assert!(mem.region_at(0x2000).is_none());
+}
+
+#[test]
+fn test_size_and_addr_range() {
+    let mem = guest();
+    assert_eq!(mem.size(), Some(0x1100));
+    assert_eq!(mem.addr_range(), Some(0x1000..0x2100));
+
+    let empty = GuestMemoryMap::<TestMemory>::new(vec![]).unwrap();
+    assert_eq!(empty.size(), Some(0));
+    assert_eq!(empty.addr_range(), None);
+}
@@ -0,0 +1,42 @@
+#![cfg(not(feature = "forbid-unsafe"))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{ContiguousMemory, MemoryStorage};
+
+#[test]
+fn test_host_region() {
+    let mut mem = TestMemory::new([0u8; 16]);
+    let region = unsafe { mem.host_region() };
+    assert_eq!(region.range(), 0..16);
+
+    let ptr = region.host_ptr(4).unwrap();
+    assert_eq!(region.addr_of(ptr), Some(4));
+    assert_eq!(region.host_ptr(16), None);
+    assert_eq!(region.addr_of(region.host.wrapping_add(16)), None);
+
+    unsafe { ptr.write(0xAB) };
+    assert_eq!(mem.read::<u8>(4), 0xAB);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_guest_host_regions() {
+    use mem_storage::{GuestMemoryMap, GuestRegion};
+
+    let mut mem = GuestMemoryMap::new(vec![
+        GuestRegion::new(0x1000, TestMemory::new([0u8; 8])),
+        GuestRegion::new(0, TestMemory::new([0u8; 8])),
+    ])
+    .unwrap();
+
+    let regions = unsafe { mem.host_regions() };
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions[1].range(), 0x1000..0x1008);
+
+    let ptr = regions[1].host_ptr(0x1002).unwrap();
+    unsafe { ptr.write(0xCD) };
+    assert_eq!(mem.read::<u8>(0x1002), 0xCD);
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "std")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{image, ImageError, MemoryStorage};
+
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|idx| (idx % 251) as u8).collect()
+}
+
+#[test]
+fn test_raw_image() {
+    let src = TestMemory::new(pattern(200_000));
+    let mut file = Vec::new();
+    image::dump(&src, 100..150_100, &mut file).unwrap();
+    assert_eq!(file, pattern(200_000)[100..150_100]);
+
+    let mut dest = TestMemory::new(vec![0; 200_000]);
+    assert_eq!(image::load(&mut dest, 100, &file[..]).unwrap(), 150_000);
+    assert_eq!(dest.read::<u8>(99), 0);
+    assert_eq!(dest.read::<u8>(150_099), src.read::<u8>(150_099));
+
+    match image::load(&mut TestMemory::new([0; 16]), 8, &file[..]) {
+        Err(ImageError::Memory(())) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_gzip_image() {
+    let src = TestMemory::new(pattern(100_000));
+    let mut file = Vec::new();
+    image::dump_gzip(&src, 0..100_000, &mut file, 6).unwrap();
+    assert!(file.len() < 10_000);
+
+    let mut dest = TestMemory::new(vec![0; 100_000]);
+    assert_eq!(image::load_gzip(&mut dest, 0, &file[..]).unwrap(), 100_000);
+    assert_eq!(dest.read::<u64>(50_000), src.read::<u64>(50_000));
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd_image() {
+    let src = TestMemory::new(pattern(100_000));
+    let mut file = Vec::new();
+    image::dump_zstd(&src, 0..100_000, &mut file, 0).unwrap();
+    assert!(file.len() < 10_000);
+
+    let mut dest = TestMemory::new(vec![0; 100_000]);
+    assert_eq!(image::load_zstd(&mut dest, 0, &file[..]).unwrap(), 100_000);
+    assert_eq!(dest.read::<u64>(99_992), src.read::<u64>(99_992));
+
+    assert!(matches!(
+        image::load_zstd(&mut dest, 0, &b"not zstd"[..]),
+        Err(ImageError::Io(_))
+    ));
+}
@@ -0,0 +1,114 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{BigEndian, IoMemory, IoStorage, MemoryStorage, StorageIo, Width};
+
+/// A FIFO, whose data register pops a byte on every read.
+#[derive(Default)]
+struct Fifo {
+    data: Vec<u8>,
+    accesses: Vec<(usize, Width)>,
+}
+
+impl IoMemory for Fifo {
+    type Error = ();
+
+    fn io_read(&mut self, addr: usize, width: Width) -> Result<u64, Self::Error> {
+        self.accesses.push((addr, width));
+        match addr {
+            0 => Ok(if self.data.is_empty() {
+                0
+            } else {
+                self.data.remove(0) as u64
+            }),
+            4 => Ok(self.data.len() as u64),
+            _ => Err(()),
+        }
+    }
+
+    fn io_write(&mut self, addr: usize, width: Width, value: u64) -> Result<(), Self::Error> {
+        self.accesses.push((addr, width));
+        if addr != 0 {
+            return Err(());
+        }
+        self.data.push(value as u8);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_device_as_storage() {
+    let mut mem = IoStorage::<_>::new(Fifo::default());
+    mem.write::<u8>(0, 0xAA);
+    mem.write::<u32>(0, 0x1234_56BB);
+    assert_eq!(mem.read::<u32>(4), 2);
+    assert_eq!(mem.read::<u8>(0), 0xAA);
+    assert_eq!(mem.read::<u16>(0), 0xBB);
+    assert_eq!(mem.read::<u8>(0), 0);
+    assert_eq!(mem.try_read::<u8>(8), Err(()));
+
+    let fifo = mem.into_inner();
+    assert_eq!(fifo.accesses[1], (0, Width::W32));
+    assert_eq!(fifo.accesses[4], (0, Width::W16));
+}
+
+#[test]
+fn test_storage_as_device() {
+    let mut dev = StorageIo::<_, BigEndian>::new(TestMemory::new([0u8; 8]));
+    dev.io_write(0, Width::W32, 0xFFFF_1122_3344).unwrap();
+    dev.io_write(6, Width::W8, 0x1FF).unwrap();
+    assert_eq!(dev.io_read(0, Width::W16).unwrap(), 0x1122);
+    assert_eq!(dev.io_read(0, Width::W64).unwrap(), 0x1122_3344_0000_FF00);
+    assert_eq!(dev.io_read(8, Width::W8), Err(()));
+    assert_eq!(dev.inner().read_be::<u32>(0), 0x1122_3344);
+}
+
+/// A timer, whose counter register counts the elapsed cycles, and which raises its
+/// interrupt line once the counter reaches 100.
+#[derive(Default)]
+struct Timer {
+    cycles: u64,
+}
+
+impl IoMemory for Timer {
+    type Error = ();
+
+    fn io_read(&mut self, _: usize, _: Width) -> Result<u64, Self::Error> {
+        Ok(self.cycles)
+    }
+
+    fn io_write(&mut self, _: usize, _: Width, value: u64) -> Result<(), Self::Error> {
+        self.cycles = value;
+        Ok(())
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        self.cycles += cycles;
+    }
+
+    fn interrupt(&self) -> bool {
+        self.cycles >= 100
+    }
+}
+
+#[test]
+fn test_tick_and_interrupt() {
+    let mut mem = IoStorage::<_>::new(Timer::default());
+    mem.tick(90);
+    assert!(!mem.interrupt());
+    mem.tick(30);
+    assert!(mem.interrupt());
+    assert_eq!(mem.read::<u32>(0), 120);
+
+    // Acknowledge the interrupt by resetting the counter.
+    mem.write::<u32>(0, 0);
+    assert!(!mem.interrupt());
+
+    // The default implementations do nothing.
+    let mut fifo = Fifo::default();
+    fifo.tick(10);
+    assert!(fifo.accesses.is_empty());
+    assert!(!fifo.interrupt());
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::TestMemory;
+use mem_storage::{ContiguousMemory, MemoryStorage};
+
+#[test]
+fn test_values() {
+    let mem = TestMemory::new([0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04]);
+
+    let values = mem.values::<u16>(0..7).collect::<Result<Vec<_>, _>>();
+    assert_eq!(values, Ok(vec![1, 2, 3]));
+
+    let mut reader = mem.values::<u16>(2..6).big_endian();
+    assert_eq!(reader.next(), Some(Ok(0x0200)));
+    assert_eq!(reader.addr(), 4);
+    assert_eq!(reader.next(), Some(Ok(0x0300)));
+    assert_eq!(reader.next(), None);
+}
+
+#[test]
+fn test_values_stops_on_error() {
+    let mem = TestMemory::new([0u8; 6]);
+
+    let values = mem.values::<u32>(4..100).collect::<Vec<_>>();
+    assert_eq!(values, vec![Err(())]);
+}
+
+#[test]
+fn test_chunks_and_pages() {
+    let mem = TestMemory::new((0u8..10).collect::<Vec<_>>());
+
+    let chunks = mem
+        .chunks::<4>(1..10)
+        .map(|chunk| chunk.map(|chunk| (chunk.addr(), chunk.to_vec())))
+        .collect::<Result<Vec<_>, _>>();
+    assert_eq!(
+        chunks,
+        Ok(vec![
+            (1, vec![1, 2, 3, 4]),
+            (5, vec![5, 6, 7, 8]),
+            (9, vec![9])
+        ])
+    );
+
+    let pages = mem
+        .pages::<4>(1..10)
+        .map(|page| page.map(|page| page.to_vec()))
+        .collect::<Result<Vec<_>, _>>();
+    assert_eq!(pages, Ok(vec![vec![1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]));
+
+    let slices = mem.try_chunks_ref(2..9, 3).unwrap().collect::<Vec<_>>();
+    assert_eq!(slices, vec![&[2, 3, 4][..], &[5, 6, 7], &[8]]);
+
+    let errors = mem.chunks::<4>(8..16).collect::<Vec<_>>();
+    assert_eq!(errors, vec![Err(())]);
+}
@@ -0,0 +1,44 @@
+#![cfg(all(feature = "kvm", target_os = "linux", not(feature = "forbid-unsafe")))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{
+    ContiguousMemory, GuestMemoryMap, GuestRegion, KvmMemorySlot, KVM_MEM_LOG_DIRTY_PAGES,
+};
+
+#[test]
+fn test_kvm_slots() {
+    let mut mem = GuestMemoryMap::new(vec![
+        GuestRegion::new(0x10_0000, TestMemory::new([0u8; 0x2000])),
+        GuestRegion::new(0, TestMemory::new([0u8; 0x1000])),
+    ])
+    .unwrap();
+
+    let slots = mem.kvm_slots(true);
+    assert_eq!(slots.len(), 2);
+    assert_eq!(slots[1].slot, 1);
+    assert_eq!(slots[1].flags, KVM_MEM_LOG_DIRTY_PAGES);
+    assert_eq!(slots[1].guest_phys_addr, 0x10_0000);
+    assert_eq!(slots[1].memory_size, 0x2000);
+
+    let host = mem.regions()[1].inner().get(..).unwrap().as_ptr();
+    assert_eq!(slots[1].userspace_addr, host as u64);
+    assert_eq!(mem.kvm_slots(false)[0].flags, 0);
+}
+
+#[test]
+fn test_dirty_pages() {
+    let slot = KvmMemorySlot {
+        slot: 0,
+        flags: KVM_MEM_LOG_DIRTY_PAGES,
+        guest_phys_addr: 0x4000,
+        memory_size: 66 * 0x1000,
+        userspace_addr: 0,
+    };
+
+    // Bits past the end of the slot are ignored.
+    let bitmap = [0b101, 0b10 | 1 << 5];
+    let dirty = slot.dirty_pages(&bitmap, 0x1000).collect::<Vec<_>>();
+    assert_eq!(dirty, [0x4000, 0x6000, 0x4000 + 65 * 0x1000]);
+}
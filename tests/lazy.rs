@@ -0,0 +1,65 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{LazyError, LazyMemory, MemoryStorage};
+
+#[test]
+fn test_fetch_on_access() {
+    let mut fetched = Vec::new();
+    {
+        let mut mem = LazyMemory::<_, 4>::new(
+            |page: usize, data: &mut [u8]| -> Result<(), ()> {
+                fetched.push(page);
+                data.fill(page as u8);
+                Ok(())
+            },
+            4,
+        );
+        assert_eq!(mem.len(), 16);
+        assert_eq!(mem.resident_pages(), 0);
+
+        assert_eq!(mem.read::<u16>(3), 0x0100);
+        assert!(mem.is_resident(0) && mem.is_resident(1));
+
+        mem.write::<u8>(1, 0xAA);
+        assert_eq!(mem.read::<u8>(1), 0xAA);
+        mem.write::<u8>(14, 0xBB);
+        assert_eq!(mem.read::<u32>(12), 0x03BB_0303);
+        assert!(!mem.is_resident(2));
+
+        mem.populate(0..16);
+        assert_eq!(mem.resident_pages(), 4);
+    }
+    assert_eq!(fetched, [0, 1, 3, 2]);
+}
+
+#[test]
+fn test_errors() {
+    let mem = LazyMemory::<_, 4>::new(
+        |page: usize, _: &mut [u8]| if page == 1 { Err("offline") } else { Ok(()) },
+        2,
+    );
+
+    assert_eq!(
+        mem.try_read::<u8>(8),
+        Err(LazyError::OutOfBounds { addr: 8 })
+    );
+    assert_eq!(
+        mem.try_read::<u32>(2),
+        Err(LazyError::Fetch {
+            page: 1,
+            error: "offline"
+        })
+    );
+    assert!(!mem.is_resident(1));
+    assert_eq!(mem.try_read::<u16>(0), Ok(0));
+}
+
+#[test]
+#[should_panic(expected = "overflow the address space")]
+fn test_size_overflow() {
+    LazyMemory::<_, 4096>::new(
+        |_: usize, _: &mut [u8]| -> Result<(), ()> { Ok(()) },
+        usize::MAX,
+    );
+}
@@ -0,0 +1,61 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::MemoryStorage;
+
+#[test]
+fn test_read_uleb128() {
+    let mem = TestMemory::new([0xE5, 0x8E, 0x26, 0x7F, 0x80, 0x01]);
+    assert_eq!(mem.try_read_uleb128(0), Ok(Some((624_485, 3))));
+    assert_eq!(mem.read_uleb128(3), Some((127, 1)));
+    assert_eq!(mem.read_uleb128(4), Some((128, 2)));
+
+    let mem = TestMemory::new([0x80, 0x80]);
+    assert_eq!(mem.try_read_uleb128(0), Err(()));
+}
+
+#[test]
+fn test_read_sleb128() {
+    let mem = TestMemory::new([0xC0, 0xBB, 0x78, 0x7F, 0x3F, 0x80, 0x7F]);
+    assert_eq!(mem.try_read_sleb128(0), Ok(Some((-123_456, 3))));
+    assert_eq!(mem.read_sleb128(3), Some((-1, 1)));
+    assert_eq!(mem.read_sleb128(4), Some((63, 1)));
+    assert_eq!(mem.read_sleb128(5), Some((-128, 2)));
+}
+
+#[test]
+fn test_overflow() {
+    let mut bytes = [0xFF; 10];
+    bytes[9] = 0x01;
+    let mem = TestMemory::new(bytes);
+    assert_eq!(mem.read_uleb128(0), Some((u64::MAX, 10)));
+
+    bytes[9] = 0x02;
+    let mem = TestMemory::new(bytes);
+    assert_eq!(mem.read_uleb128(0), None);
+
+    let mem = TestMemory::new([0x80; 11]);
+    assert_eq!(mem.read_uleb128(0), None);
+    assert_eq!(mem.read_sleb128(0), None);
+}
+
+#[test]
+fn test_roundtrip() {
+    let mut mem = TestMemory::new([0u8; 16]);
+    for &val in &[0, 1, 127, 128, 0x3FFF, 0x4000, u64::MAX >> 1, u64::MAX] {
+        let len = mem.write_uleb128(0, val);
+        assert_eq!(mem.read_uleb128(0), Some((val, len)));
+    }
+    for &val in &[0, -1, 63, 64, -64, -65, i64::MIN, i64::MAX] {
+        let len = mem.write_sleb128(0, val);
+        assert_eq!(mem.read_sleb128(0), Some((val, len)));
+    }
+
+    assert_eq!(mem.try_write_uleb128(0, 624_485), Ok(3));
+    let mut buf = [0u8; 3];
+    mem.try_read_into(0, &mut buf).unwrap();
+    assert_eq!(buf, [0xE5, 0x8E, 0x26]);
+    assert_eq!(mem.try_write_sleb128(14, -123_456), Err(()));
+}
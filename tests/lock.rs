@@ -0,0 +1,53 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{LockError, LockableMemory, MemoryStorage};
+
+#[test]
+fn test_locked_accesses_fail() {
+    let mut mem = LockableMemory::new(TestMemory::new([0u8; 32]));
+    let guard = mem.lock_range(8..16);
+    assert_eq!(guard.range(), 8..16);
+    assert!(mem.is_locked(15..20));
+    assert!(!mem.is_locked(16..20));
+
+    assert_eq!(mem.try_read::<u8>(8), Err(LockError::Locked { addr: 8 }));
+    assert_eq!(
+        mem.try_write::<u32>(6, 0),
+        Err(LockError::Locked { addr: 6 })
+    );
+    mem.write::<u32>(4, 0xAABB_CCDD);
+    mem.write::<u8>(16, 1);
+
+    // The owner of the lock bypasses it.
+    mem.inner_mut().write::<u8>(8, 0xFF);
+    drop(guard);
+    assert!(!mem.is_locked(0..32));
+    assert_eq!(mem.read::<u8>(8), 0xFF);
+    assert_eq!(mem.try_read::<u8>(32), Err(LockError::Memory(())));
+}
+
+#[test]
+fn test_overlapping_locks() {
+    let mem = LockableMemory::new(TestMemory::new([0u8; 32]));
+    let first = mem.lock_range(0..8);
+    assert!(mem.try_lock_range(4..12).is_none());
+
+    let second = mem.lock_range(8..12);
+    drop(first);
+    assert!(mem.try_lock_range(4..8).is_some());
+    assert!(mem.try_lock_range(4..12).is_none());
+    drop(second);
+    assert!(mem.try_lock_range(4..12).is_some());
+}
+
+#[test]
+#[should_panic(expected = "range 0x4..0xc is already locked")]
+fn test_lock_conflict_panics() {
+    let mem = LockableMemory::new(TestMemory::new([0u8; 32]));
+    let _guard = mem.lock_range(0..8);
+    mem.lock_range(4..12);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "log")]
+
+mod common;
+
+use common::TestMemory;
+use log::{Log, Metadata, Record};
+use mem_storage::{MemoryStorage, OpenBus};
+use std::sync::{Mutex, Once};
+
+/// A logger that records every warning.
+struct Recorder(Mutex<Vec<String>>);
+
+impl Log for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::Level::Warn
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+/// Installs the recorder and returns every warning that was logged so far.
+fn warnings() -> Vec<String> {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&RECORDER).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+    });
+    RECORDER.0.lock().unwrap().clone()
+}
+
+#[test]
+fn test_open_bus_warnings() {
+    warnings();
+
+    let mut mem = OpenBus::new(TestMemory::new([0xAA; 4]));
+    assert_eq!(mem.try_read_byte(0x10), Ok(0));
+    mem.try_write_byte(0x20, 1).unwrap();
+    mem.try_write_byte(0, 1).unwrap();
+
+    let warnings = warnings();
+    assert!(warnings.contains(&"read of unmapped address 0x10 served by open bus".to_string()));
+    assert!(warnings.contains(&"write to unmapped address 0x20 ignored".to_string()));
+    assert!(!warnings.iter().any(|msg| msg.contains("address 0x0 ")));
+}
+
+#[cfg(feature = "mappers")]
+#[test]
+fn test_chr_rom_write_warning() {
+    use mem_storage::mappers::nes::{Cartridge, MapperKind, Mirroring};
+
+    warnings();
+
+    let mut cart = Cartridge::new(
+        MapperKind::Nrom,
+        vec![0; 0x4000],
+        vec![0; 0x2000],
+        Mirroring::Horizontal,
+    );
+    cart.chr().try_write_byte(0x123, 1).unwrap();
+
+    assert!(warnings().contains(&"write to CHR ROM at 0x123 ignored".to_string()));
+}
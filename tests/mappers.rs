@@ -0,0 +1,227 @@
+#![cfg(feature = "mappers")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::mappers::gb::{Mbc, MbcKind};
+use mem_storage::{MemoryStorage, OutOfBounds};
+
+/// Creates a ROM whose banks start with their own index.
+fn rom(banks: usize) -> Vec<u8> {
+    let mut rom = vec![0u8; banks * 0x4000];
+    for (bank, chunk) in rom.chunks_mut(0x4000).enumerate() {
+        chunk[0] = bank as u8;
+        chunk[1] = (bank >> 8) as u8;
+    }
+    rom
+}
+
+#[test]
+fn test_mbc1() {
+    let mut mbc = Mbc::new(MbcKind::Mbc1, rom(128), 0x8000);
+    assert_eq!(mbc.read::<u8>(0x4000), 1);
+
+    mbc.write::<u8>(0x2000, 0x00);
+    assert_eq!(mbc.rom_bank(), 1);
+    mbc.write::<u8>(0x2000, 0x05);
+    mbc.write::<u8>(0x4000, 0x02);
+    assert_eq!(mbc.read::<u8>(0x4000), 0x45);
+    assert_eq!(mbc.read::<u8>(0x0000), 0x00);
+
+    // Banking mode 1 also switches the lower ROM area and the RAM bank.
+    mbc.write::<u8>(0x6000, 0x01);
+    assert_eq!(mbc.read::<u8>(0x0000), 0x40);
+
+    assert_eq!(mbc.read::<u8>(0xA000), 0xFF);
+    mbc.write::<u8>(0x0000, 0x0A);
+    mbc.write::<u8>(0xA000, 0x12);
+    assert_eq!(mbc.ram()[2 * 0x2000], 0x12);
+    assert_eq!(mbc.try_read_byte(0x8000), Err(OutOfBounds { addr: 0x8000 }));
+}
+
+#[test]
+fn test_mbc2() {
+    let mut mbc = Mbc::new(MbcKind::Mbc2, rom(16), 0);
+    assert_eq!(mbc.ram().len(), 512);
+
+    mbc.write::<u8>(0x2100, 0x03);
+    assert_eq!(mbc.read::<u8>(0x4000), 3);
+    mbc.write::<u8>(0x0000, 0x0A);
+    mbc.write::<u8>(0xA001, 0xAB);
+    assert_eq!(mbc.read::<u8>(0xA001), 0xFB);
+    assert_eq!(mbc.read::<u8>(0xA201), 0xFB);
+}
+
+#[test]
+fn test_mbc3_rtc() {
+    let mut mbc = Mbc::new(MbcKind::Mbc3, rom(128), 0x8000);
+    mbc.write::<u8>(0x2000, 0x7F);
+    assert_eq!(mbc.read::<u8>(0x4000), 0x7F);
+
+    mbc.write::<u8>(0x0000, 0x0A);
+    mbc.set_rtc([30, 15, 10, 2, 0]);
+    mbc.write::<u8>(0x4000, 0x08);
+    assert_eq!(mbc.read::<u8>(0xA000), 0);
+
+    mbc.write::<u8>(0x6000, 0x00);
+    mbc.write::<u8>(0x6000, 0x01);
+    mbc.set_rtc([31, 15, 10, 2, 0]);
+    assert_eq!(mbc.read::<u8>(0xA000), 30);
+
+    mbc.write::<u8>(0x4000, 0x09);
+    mbc.write::<u8>(0xA000, 20);
+    assert_eq!(mbc.rtc()[1], 20);
+}
+
+#[test]
+fn test_mbc5_from_header() {
+    let mut rom = rom(512);
+    rom[0x147] = 0x1B;
+    rom[0x149] = 0x04;
+    let mut mbc = Mbc::from_rom(rom).unwrap();
+    assert_eq!(mbc.kind(), MbcKind::Mbc5);
+    assert_eq!(mbc.ram().len(), 0x20000);
+
+    mbc.write::<u8>(0x2000, 0x00);
+    assert_eq!(mbc.read::<u16>(0x4000), 0);
+    mbc.write::<u8>(0x2000, 0x23);
+    mbc.write::<u8>(0x3000, 0x01);
+    assert_eq!(mbc.read::<u16>(0x4000), 0x123);
+
+    mbc.write::<u8>(0x0000, 0x0A);
+    mbc.write::<u8>(0x4000, 0x0F);
+    mbc.write::<u8>(0xBFFF, 0x99);
+    assert_eq!(mbc.ram()[0x1FFFF], 0x99);
+
+    assert!(Mbc::from_rom(vec![0; 0x100]).is_none());
+}
+
+mod nes {
+    use mem_storage::mappers::nes::{Cartridge, MapperKind, Mirroring};
+    use mem_storage::MemoryStorage;
+
+    /// Creates a ROM whose banks of the given size start with their own index.
+    fn rom(len: usize, bank_size: usize) -> Vec<u8> {
+        let mut rom = vec![0u8; len];
+        for (bank, chunk) in rom.chunks_mut(bank_size).enumerate() {
+            chunk[0] = bank as u8;
+        }
+        rom
+    }
+
+    #[test]
+    fn test_nrom_from_ines() {
+        let mut file = b"NES\x1A\x01\x01\x01\x00".to_vec();
+        file.resize(16, 0);
+        file.extend(rom(0x4000, 0x4000));
+        file.extend(vec![0xAB; 0x2000]);
+
+        let mut cart = Cartridge::from_ines(&file).unwrap();
+        assert_eq!(cart.mirroring(), Mirroring::Vertical);
+        assert_eq!(cart.read::<u8>(0x8000), cart.read::<u8>(0xC000));
+        assert_eq!(cart.chr().read::<u8>(0x1FFF), 0xAB);
+
+        // CHR ROM can not be written.
+        cart.chr().write::<u8>(0, 0);
+        assert_eq!(cart.chr().read::<u8>(0), 0xAB);
+
+        cart.write::<u8>(0x6000, 0x42);
+        assert_eq!(cart.prg_ram()[0], 0x42);
+        assert!(cart.try_read_byte(0x5FFF).is_err());
+        assert!(Cartridge::from_ines(b"NES\x1A").is_none());
+    }
+
+    #[test]
+    fn test_uxrom() {
+        let mut cart = Cartridge::new(
+            MapperKind::Uxrom,
+            rom(8 * 0x4000, 0x4000),
+            vec![],
+            Mirroring::Horizontal,
+        );
+        assert_eq!(cart.read::<u8>(0xC000), 7);
+        cart.write::<u8>(0x8000, 3);
+        assert_eq!(cart.read::<u8>(0x8000), 3);
+
+        cart.chr().write::<u8>(0x10, 0x55);
+        assert_eq!(cart.chr().read::<u8>(0x10), 0x55);
+    }
+
+    #[test]
+    fn test_mmc1() {
+        let mut cart = Cartridge::new(
+            MapperKind::Mmc1,
+            rom(8 * 0x4000, 0x4000),
+            rom(8 * 0x1000, 0x1000),
+            Mirroring::Horizontal,
+        );
+        let mut write_serial = |addr: usize, value: u8| {
+            for bit in 0..5 {
+                cart.write::<u8>(addr, value >> bit & 1);
+            }
+        };
+
+        // Fix the last bank at 0xC000, switch 4 KiB CHR banks and vertical mirroring.
+        write_serial(0x8000, 0b11110);
+        write_serial(0xE000, 5);
+        write_serial(0xA000, 3);
+        write_serial(0xC000, 6);
+
+        assert_eq!(cart.mirroring(), Mirroring::Vertical);
+        assert_eq!(cart.read::<u8>(0x8000), 5);
+        assert_eq!(cart.read::<u8>(0xC000), 7);
+        assert_eq!(cart.chr().read::<u8>(0x0000), 3);
+        assert_eq!(cart.chr().read::<u8>(0x1000), 6);
+
+        // A reset fixes the last bank again.
+        cart.write::<u8>(0x8000, 0x80);
+        assert_eq!(cart.read::<u8>(0xC000), 7);
+    }
+
+    #[test]
+    fn test_mmc3() {
+        let mut cart = Cartridge::new(
+            MapperKind::Mmc3,
+            rom(16 * 0x2000, 0x2000),
+            rom(64 * 0x400, 0x400),
+            Mirroring::Vertical,
+        );
+        assert_eq!(cart.read::<u8>(0xE000), 15);
+
+        cart.write::<u8>(0x8000, 6);
+        cart.write::<u8>(0x8001, 3);
+        cart.write::<u8>(0x8000, 2);
+        cart.write::<u8>(0x8001, 40);
+        assert_eq!(cart.read::<u8>(0x8000), 3);
+        assert_eq!(cart.read::<u8>(0xC000), 14);
+        assert_eq!(cart.chr().read::<u8>(0x1000), 40);
+
+        // Swap the PRG and CHR halves.
+        cart.write::<u8>(0x8000, 0xC0);
+        assert_eq!(cart.read::<u8>(0xC000), 3);
+        assert_eq!(cart.read::<u8>(0x8000), 14);
+        assert_eq!(cart.chr().read::<u8>(0x0000), 40);
+
+        cart.write::<u8>(0xA000, 1);
+        assert_eq!(cart.mirroring(), Mirroring::Horizontal);
+
+        cart.write::<u8>(0xC000, 2);
+        cart.write::<u8>(0xC001, 0);
+        cart.write::<u8>(0xE001, 0);
+        cart.clock_scanline();
+        cart.clock_scanline();
+        assert!(!cart.irq());
+        cart.clock_scanline();
+        assert!(cart.irq());
+        cart.write::<u8>(0xE000, 0);
+        assert!(!cart.irq());
+    }
+
+    #[test]
+    fn test_nametable_mirroring() {
+        assert_eq!(Mirroring::Vertical.nametable_offset(0x2800), 0x000);
+        assert_eq!(Mirroring::Vertical.nametable_offset(0x2C10), 0x410);
+        assert_eq!(Mirroring::Horizontal.nametable_offset(0x2400), 0x000);
+        assert_eq!(Mirroring::Horizontal.nametable_offset(0x2810), 0x410);
+        assert_eq!(Mirroring::SingleScreenHigh.nametable_offset(0x2000), 0x400);
+        assert_eq!(Mirroring::FourScreen.nametable_offset(0x3C00), 0xC00);
+    }
+}
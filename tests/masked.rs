@@ -0,0 +1,49 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{MaskedMemory, MemoryStorage};
+
+#[test]
+fn test_addresses_are_masked() {
+    let mut mem = MaskedMemory::<_, 8>::new(TestMemory::new([0u8; 0x100]));
+    assert_eq!(MaskedMemory::<TestMemory, 8>::MASK, 0xFF);
+
+    mem.write::<u8>(0x1234, 0xAB);
+    assert_eq!(mem.read::<u8>(0x34), 0xAB);
+    assert_eq!(mem.read::<u8>(0xFF34), 0xAB);
+    assert_eq!(mem.inner().as_slice().unwrap()[0x34], 0xAB);
+}
+
+#[test]
+fn test_accesses_wrap_around() {
+    let mut mem = MaskedMemory::<_, 8>::new(TestMemory::new([0u8; 0x100]));
+    mem.write::<u32>(0xFE, 0x4433_2211);
+    assert_eq!(mem.inner().as_slice().unwrap()[0xFE..], [0x11, 0x22]);
+    assert_eq!(mem.inner().as_slice().unwrap()[..2], [0x33, 0x44]);
+    assert_eq!(mem.read::<u32>(0x1FE), 0x4433_2211);
+
+    // A buffer that is larger than the address space wraps more than once.
+    let mut buf = [0u8; 0x201];
+    mem.try_read_into(0, &mut buf).unwrap();
+    assert_eq!(buf[0x100..0x102], [0x33, 0x44]);
+    assert_eq!(buf[0x200], 0x33);
+}
+
+#[test]
+fn test_full_width_is_unchanged() {
+    let mut mem = MaskedMemory::<_, 64>::new(TestMemory::new([0u8; 4]));
+    assert_eq!(MaskedMemory::<TestMemory, 64>::MASK, usize::MAX);
+    mem.write::<u16>(2, 0xBEEF);
+    assert_eq!(mem.read::<u16>(2), 0xBEEF);
+    assert!(mem.try_read::<u8>(4).is_err());
+}
+
+#[test]
+fn test_forwards_errors() {
+    // A 16-bit bus in front of a memory that only stores 4 bytes.
+    let mem = MaskedMemory::<_, 16>::new(TestMemory::new([1, 2, 3, 4]));
+    assert_eq!(mem.read::<u8>(0x1_0003), 4);
+    assert_eq!(mem.try_read::<u8>(0x1_0004), Err(()));
+}
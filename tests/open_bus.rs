@@ -0,0 +1,78 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use core::cell::Cell;
+use core::ops::Range;
+use mem_storage::{MemoryStorage, OpenBus, OpenBusMode};
+
+#[test]
+fn test_open_bus_last_value() {
+    let mut mem = OpenBus::new(TestMemory::new([0x12, 0x34, 0x56, 0x78]));
+
+    assert_eq!(mem.read_byte(1), 0x34);
+    assert_eq!(mem.read_byte(0x100), 0x34);
+
+    mem.write_byte(0x200, 0xAB);
+    assert_eq!(mem.read_byte(0x100), 0xAB);
+
+    // Partially unmapped reads get the open bus value for the unmapped bytes.
+    assert_eq!(mem.read::<u32>(2), 0x78787856);
+    assert_eq!(mem.last_value(), 0x78);
+}
+
+#[test]
+fn test_open_bus_constant() {
+    let mem = OpenBus::with_mode(TestMemory::new([0x12]), OpenBusMode::Constant(0xFF));
+
+    assert_eq!(mem.read_byte(0), 0x12);
+    assert_eq!(mem.read::<u16>(0), 0xFF12);
+    assert_eq!(mem.read::<u16>(0x10), 0xFFFF);
+}
+
+/// A memory that counts how often each byte is accessed.
+struct CountingMemory {
+    bytes: [u8; 4],
+    reads: [Cell<u8>; 4],
+    writes: [u8; 4],
+}
+
+impl MemoryStorage for CountingMemory {
+    type Error = ();
+
+    fn addr_range(&self) -> Option<Range<usize>> {
+        Some(0..4)
+    }
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        let count = self.reads.get(addr).ok_or(())?;
+        count.set(count.get() + 1);
+        Ok(self.bytes[addr])
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        *self.writes.get_mut(addr).ok_or(())? += 1;
+        self.bytes[addr] = byte;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_open_bus_accesses_bytes_once() {
+    let mut mem = OpenBus::new(CountingMemory {
+        bytes: [0x12, 0x34, 0x56, 0x78],
+        reads: Default::default(),
+        writes: [0; 4],
+    });
+
+    assert_eq!(mem.read::<u32>(2), 0x78787856);
+    assert_eq!(mem.read::<u16>(0), 0x3412);
+    let reads = mem.inner().reads.each_ref().map(Cell::get);
+    assert_eq!(reads, [1, 1, 1, 1]);
+
+    mem.write::<u32>(2, 0xAABBCCDD);
+    mem.write::<u16>(0, 0x1122);
+    assert_eq!(mem.inner().writes, [1, 1, 1, 1]);
+    assert_eq!(mem.inner().bytes, [0x22, 0x11, 0xDD, 0xCC]);
+}
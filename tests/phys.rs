@@ -0,0 +1,31 @@
+#![cfg(all(feature = "devmem", unix, not(feature = "forbid-unsafe")))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{MemoryStorage, OutOfBounds, PhysMemory};
+use std::fs;
+
+#[test]
+fn test_map_file() {
+    // A regular file stands in for `/dev/mem`, which is not accessible during tests.
+    let path = std::env::temp_dir().join(format!("mem_storage_phys_{}", std::process::id()));
+    fs::write(&path, vec![0u8; 8192]).unwrap();
+
+    {
+        let mut mem = unsafe { PhysMemory::map(&path, 0x1002, 16) }.unwrap();
+        assert_eq!(mem.len(), 16);
+        mem.write::<u16>(2, 0xBEEF);
+        mem.write::<u32>(6, 0x1234_5678);
+        mem.write::<u64>(8, 0x0102_0304_0506_0708);
+        assert_eq!(mem.read::<u16>(2), 0xBEEF);
+        assert_eq!(mem.read::<u64>(8), 0x0102_0304_0506_0708);
+        assert_eq!(mem.try_read::<u8>(16), Err(OutOfBounds { addr: 16 }));
+    }
+
+    let data = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(&data[0x1004..0x1006], &0xBEEFu16.to_ne_bytes());
+    assert_eq!(
+        &data[0x100A..0x1012],
+        &0x0102_0304_0506_0708u64.to_ne_bytes()
+    );
+}
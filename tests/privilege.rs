@@ -0,0 +1,53 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{MemoryStorage, Privilege, PrivilegeError, PrivilegedMemory};
+
+#[test]
+fn test_supervisor_range() {
+    let mut mem = PrivilegedMemory::new(TestMemory::new([0; 16]));
+    mem.add_supervisor_range(8..16);
+
+    assert_eq!(mem.try_write(8, 1u32), Ok(()));
+
+    mem.set_privilege(Privilege::User);
+    assert_eq!(mem.try_read::<u8>(7), Ok(0));
+    assert_eq!(
+        mem.try_read::<u16>(7),
+        Err(PrivilegeError::Denied {
+            addr: 7,
+            privilege: Privilege::User
+        })
+    );
+    assert!(mem.try_write_byte(12, 1).is_err());
+    assert_eq!(mem.inner().try_read::<u32>(12), Ok(0));
+}
+
+#[test]
+fn test_user_access() {
+    let mut mem = PrivilegedMemory::new(TestMemory::new([0; 16]));
+    mem.add_user_range(0..8);
+    assert!(mem.user_access());
+    assert_eq!(mem.try_read::<u32>(0), Ok(0));
+
+    mem.set_user_access(false);
+    assert_eq!(
+        mem.try_read::<u32>(0),
+        Err(PrivilegeError::Denied {
+            addr: 0,
+            privilege: Privilege::Supervisor
+        })
+    );
+    assert_eq!(mem.try_read::<u32>(8), Ok(0));
+
+    mem.set_privilege(Privilege::User);
+    assert_eq!(mem.try_read::<u32>(0), Ok(0));
+}
+
+#[test]
+fn test_inner_error() {
+    let mem = PrivilegedMemory::new(TestMemory::new([0; 4]));
+    assert_eq!(mem.try_read::<u32>(2), Err(PrivilegeError::Memory(())));
+}
@@ -0,0 +1,27 @@
+#![cfg(all(
+    feature = "process",
+    target_os = "linux",
+    not(feature = "forbid-unsafe")
+))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{MemoryStorage, ProcessMemory};
+
+#[test]
+fn test_own_process() {
+    let mut data = [1u8, 2, 3, 4, 0, 0, 0, 0];
+    let base = data.as_mut_ptr() as usize;
+    let mut mem = ProcessMemory::new(std::process::id(), base);
+
+    assert_eq!(mem.read::<u32>(0), 0x0403_0201);
+    mem.write::<u32>(4, 0xAABB_CCDD);
+    assert_eq!(mem.read::<u32>(4), 0xAABB_CCDD);
+    assert_eq!(std::hint::black_box(&data)[4..], [0xDD, 0xCC, 0xBB, 0xAA]);
+}
+
+#[test]
+fn test_unmapped() {
+    let mem = ProcessMemory::new(std::process::id(), 0);
+    assert!(mem.try_read::<u8>(0).is_err());
+    assert!(mem.try_read::<u8>(usize::MAX).is_err());
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{MemoryStorage, ProfileMemory};
+
+#[test]
+fn test_counters() {
+    let mut mem = ProfileMemory::new(TestMemory::new([0u8; 32]));
+    mem.add_region("rom", 0..16);
+    mem.add_region("ram", 16..32);
+
+    let mut fetch = mem.fetch_port();
+    assert_eq!(fetch.read::<u32>(0), 0);
+    assert_eq!(fetch.read::<u32>(14), 0);
+    fetch.write::<u8>(20, 1);
+
+    mem.write::<u16>(16, 0xAABB);
+    assert_eq!(mem.read::<u8>(16), 0xBB);
+    assert_eq!(mem.try_read::<u8>(32), Err(()));
+
+    let rom = mem.region("rom").unwrap();
+    assert_eq!((rom.fetches(), rom.loads(), rom.stores()), (2, 0, 0));
+    let ram = mem.region("ram").unwrap();
+    assert_eq!((ram.fetches(), ram.loads(), ram.stores()), (1, 1, 2));
+    assert_eq!(ram.range(), 16..32);
+    assert!(mem.region("vram").is_none());
+
+    mem.reset();
+    assert!(mem.regions().iter().all(|region| region.fetches() == 0));
+    assert_eq!(mem.inner().read::<u8>(20), 1);
+}
@@ -0,0 +1,23 @@
+#![cfg(not(feature = "forbid-unsafe"))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use core::ptr::NonNull;
+use mem_storage::{ContiguousMemory, MemoryStorage, OutOfBounds, PtrMemory};
+
+#[test]
+fn test_ptr_memory() {
+    let mut buf = [0xAAu8; 8];
+    let ptr = NonNull::new(buf.as_mut_ptr()).unwrap();
+
+    let mut mem = unsafe { PtrMemory::new(ptr, buf.len()) };
+    assert_eq!(mem.len(), 8);
+    assert_eq!(mem.as_ptr(), ptr);
+    assert_eq!(mem.read::<u16>(6), 0xAAAA);
+
+    mem.write::<u32>(0, 0x1234_5678);
+    assert_eq!(mem.get(0..2).unwrap(), &[0x78, 0x56]);
+    assert_eq!(mem.try_read::<u16>(7), Err(OutOfBounds { addr: 8 }));
+    assert_eq!(mem.try_write::<u8>(8, 0), Err(OutOfBounds { addr: 8 }));
+
+    assert_eq!(buf[..4], [0x78, 0x56, 0x34, 0x12]);
+}
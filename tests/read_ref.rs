@@ -0,0 +1,33 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{ContiguousMemory, ReadRef};
+
+/// Parses a length prefixed string out of any memory.
+fn read_name<M: ReadRef>(mem: &M, addr: usize) -> String {
+    let len = mem.read_ref(addr..addr + 1).as_ref()[0] as usize;
+    let bytes = mem.read_ref(addr + 1..addr + 1 + len);
+    String::from_utf8(bytes.as_ref().to_vec()).unwrap()
+}
+
+#[test]
+fn test_read_ref_borrows_contiguous() {
+    let mem = TestMemory::new(b"\x05hello world");
+
+    let bytes = mem.read_ref(1..6);
+    assert_eq!(bytes, b"hello");
+    assert_eq!(bytes.as_ptr(), mem.get(1..).unwrap().as_ptr());
+    assert_eq!(read_name(&mem, 0), "hello");
+    assert_eq!(mem.try_read_ref(8..20), Err(()));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_read_ref_gathers() {
+    let mem = mem_storage::CellMemory::from(b"\x05hello world".to_vec());
+
+    assert_eq!(read_name(&mem, 0), "hello");
+    assert!(mem.try_read_ref(8..20).is_err());
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{
+    compare_traces, Access, AccessKind, MemoryStorage, RecordMemory, ReplayError, ReplayMemory,
+};
+
+/// A device whose status register changes on every read.
+struct Timer {
+    ticks: core::cell::Cell<u8>,
+    control: u8,
+}
+
+impl MemoryStorage for Timer {
+    type Error = ();
+
+    fn try_read_byte(&self, addr: usize) -> Result<u8, Self::Error> {
+        match addr {
+            0 => {
+                self.ticks.set(self.ticks.get().wrapping_add(3));
+                Ok(self.ticks.get())
+            }
+            1 => Ok(self.control),
+            _ => Err(()),
+        }
+    }
+
+    fn try_write_byte(&mut self, addr: usize, byte: u8) -> Result<(), Self::Error> {
+        if addr != 1 {
+            return Err(());
+        }
+        self.control = byte;
+        Ok(())
+    }
+}
+
+fn run<M: MemoryStorage>(mem: &mut M) -> Vec<u8> {
+    mem.write::<u8>(1, 0x80);
+    (0..3).map(|_| mem.read::<u8>(0)).collect()
+}
+
+#[test]
+fn test_record_and_replay() {
+    let mut mem = RecordMemory::new(Timer {
+        ticks: Default::default(),
+        control: 0,
+    });
+    let expected = run(&mut mem);
+    assert_eq!(expected, [3, 6, 9]);
+    assert!(mem.try_read_byte(2).is_err());
+    assert_eq!(
+        mem.trace()[0],
+        Access {
+            kind: AccessKind::Write,
+            addr: 1,
+            data: vec![0x80],
+        }
+    );
+
+    let (_, trace) = mem.into_parts();
+    assert_eq!(trace.len(), 4);
+
+    let mut replay = ReplayMemory::new(trace);
+    assert_eq!(run(&mut replay), expected);
+    assert!(replay.is_finished());
+    assert_eq!(
+        replay.try_read_byte(0),
+        Err(ReplayError::Exhausted { addr: 0 })
+    );
+}
+
+#[test]
+fn test_replay_divergence() {
+    let mut mem = RecordMemory::new(TestMemory::new([1, 2, 3, 4]));
+    assert_eq!(mem.read::<u16>(0), 0x0201);
+    mem.write::<u16>(2, 0xAABB);
+    let trace = mem.take_trace();
+    assert!(mem.trace().is_empty());
+
+    let mut replay = ReplayMemory::new(trace);
+    assert_eq!(
+        replay.try_read::<u8>(0),
+        Err(ReplayError::Diverged { index: 0, addr: 0 })
+    );
+    assert_eq!(replay.read::<u16>(0), 0x0201);
+    assert_eq!(
+        replay.try_write::<u16>(2, 0xAABC),
+        Err(ReplayError::Diverged { index: 1, addr: 2 })
+    );
+    assert_eq!(replay.position(), 1);
+    assert_eq!(replay.remaining().len(), 1);
+    replay.write::<u16>(2, 0xAABB);
+    assert!(replay.is_finished());
+}
+
+fn read(addr: usize, data: &[u8]) -> Access {
+    Access {
+        kind: AccessKind::Read,
+        addr,
+        data: data.into(),
+    }
+}
+
+#[test]
+fn test_compare_traces() {
+    let golden = vec![read(0, &[1]), read(1, &[2]), read(2, &[3]), read(3, &[4])];
+    assert_eq!(compare_traces(golden.clone(), golden.clone(), 2), None);
+
+    let mut trace = golden.clone();
+    trace[2].data[0] = 0xFF;
+    let divergence = compare_traces(golden.clone(), trace, 1).unwrap();
+    assert_eq!(divergence.index, 2);
+    assert_eq!(divergence.left, Some(read(2, &[3])));
+    assert_eq!(divergence.right, Some(read(2, &[0xFF])));
+    assert_eq!(divergence.context, [read(1, &[2])]);
+
+    let divergence = compare_traces(golden.clone(), golden[..3].to_vec(), 0).unwrap();
+    assert_eq!(divergence.index, 3);
+    assert_eq!(divergence.right, None);
+    assert!(divergence.context.is_empty());
+}
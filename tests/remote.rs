@@ -0,0 +1,116 @@
+#![cfg(feature = "std")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{remote, ContiguousMemory, MemoryStorage, RemoteError, RemoteMemory};
+use std::io::{self, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+#[test]
+fn test_remote_access() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let mut mem = TestMemory::new([0u8; 16]);
+        let (stream, _) = listener.accept().unwrap();
+        remote::serve(&mut mem, stream).unwrap();
+        mem
+    });
+
+    let mut mem = RemoteMemory::connect(addr).unwrap();
+    mem.write::<u32>(4, 0xDEAD_BEEF);
+    assert_eq!(mem.read::<u32>(4), 0xDEAD_BEEF);
+    assert_eq!(mem.read::<u16>(6), 0xDEAD);
+    assert!(matches!(
+        mem.try_read::<u32>(14),
+        Err(RemoteError::Rejected { addr: 14 })
+    ));
+    assert!(matches!(
+        mem.try_write::<u8>(16, 0),
+        Err(RemoteError::Rejected { addr: 16 })
+    ));
+    drop(mem);
+
+    let mem = server.join().unwrap();
+    assert_eq!(mem.read::<u32>(4), 0xDEAD_BEEF);
+}
+
+/// A stream that reads the requests from a buffer, and collects the responses.
+struct Duplex {
+    input: io::Cursor<Vec<u8>>,
+    output: Vec<u8>,
+}
+
+impl Read for Duplex {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for Duplex {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn request(op: u8, addr: u64, len: u32) -> Vec<u8> {
+    let mut req = vec![op];
+    req.extend_from_slice(&addr.to_le_bytes());
+    req.extend_from_slice(&len.to_le_bytes());
+    req
+}
+
+#[test]
+fn test_serve_rejects_before_allocating() {
+    let mut input = request(0, 0, u32::MAX);
+    input.extend(request(1, 8, 12));
+    input.extend_from_slice(&[0xAA; 12]);
+    input.extend(request(0, 8, 4));
+
+    let mut mem = TestMemory::new([0u8; 16]);
+    let mut stream = Duplex {
+        input: io::Cursor::new(input),
+        output: Vec::new(),
+    };
+    remote::serve(&mut mem, &mut stream).unwrap();
+    assert_eq!(stream.output, [1, 1, 0, 0, 0, 0, 0]);
+    assert_eq!(mem.get(..).unwrap(), &[0; 16][..]);
+
+    let mut stream = Duplex {
+        input: io::Cursor::new(request(7, 0, u32::MAX)),
+        output: Vec::new(),
+    };
+    assert!(remote::serve(&mut mem, &mut stream).is_err());
+    assert!(stream.output.is_empty());
+}
+
+#[test]
+fn test_large_access_is_split() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let len = remote::MAX_ACCESS_LEN + 10;
+
+    let server = thread::spawn(move || {
+        let mut mem = TestMemory::new(vec![0u8; len]);
+        let (stream, _) = listener.accept().unwrap();
+        remote::serve(&mut mem, stream).unwrap();
+    });
+
+    let mut mem = RemoteMemory::connect(addr).unwrap();
+    let data = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+    mem.try_write_from(0, &data).unwrap();
+    let mut buf = vec![0u8; len];
+    mem.try_read_into(0, &mut buf).unwrap();
+    assert_eq!(buf, data);
+    drop(mem);
+
+    server.join().unwrap();
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{CellMemory, MemoryStorage, ResizableMemory, ResizeError};
+
+#[test]
+fn test_cell_grow_shrink() {
+    let mut mem = CellMemory::new(4);
+    mem.write::<u32>(0, 0xAABBCCDD);
+
+    mem.grow(8);
+    assert_eq!(mem.size(), Some(8));
+    assert_eq!(mem.read::<u64>(0), 0xAABBCCDD);
+
+    mem.shrink(2);
+    assert_eq!(mem.to_vec(), [0xDD, 0xCC]);
+    assert_eq!(mem.try_grow_by(2), Ok(2));
+    assert_eq!(mem.to_vec(), [0xDD, 0xCC, 0, 0]);
+}
+
+#[test]
+#[cfg(not(feature = "forbid-unsafe"))]
+fn test_aligned_resize() {
+    let mut mem = mem_storage::AlignedMemory::new(0, 64);
+    mem.grow(0x100);
+    mem.write::<u16>(0xFE, 0x1234);
+    assert_eq!(mem.as_slice().unwrap().as_ptr() as usize % 64, 0);
+
+    mem.grow(0x200);
+    assert_eq!(mem.align(), 64);
+    assert_eq!(mem.read::<u16>(0xFE), 0x1234);
+    assert_eq!(mem.read::<u16>(0x100), 0);
+
+    mem.shrink(0);
+    assert!(mem.is_empty());
+}
+
+#[test]
+fn test_grow_by_overflow() {
+    let mut mem = CellMemory::new(4);
+    assert_eq!(
+        mem.try_grow_by(usize::MAX),
+        Err(ResizeError::AllocFailed { size: usize::MAX })
+    );
+    assert_eq!(mem.len(), 4);
+}
+
+#[test]
+#[should_panic(expected = "new size is smaller than the current size")]
+fn test_grow_smaller() {
+    CellMemory::new(4).grow(2);
+}
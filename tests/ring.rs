@@ -0,0 +1,69 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{MemoryStorage, OverflowPolicy, RingRegion};
+
+#[test]
+fn test_ring_push_pop() {
+    let mut mem = TestMemory::new([0u8; 32]);
+    let ring = RingRegion::<u16>::new(8..16, 0, 4);
+    ring.try_reset(&mut mem).unwrap();
+    assert_eq!(ring.capacity(), 3);
+
+    assert!(ring.push(&mut mem, 1));
+    assert!(ring.push(&mut mem, 2));
+    assert!(ring.push(&mut mem, 3));
+    assert!(!ring.push(&mut mem, 4));
+    assert_eq!(ring.try_len(&mem), Ok(3));
+    assert_eq!(mem.read::<u16>(10), 2);
+
+    assert_eq!(ring.pop(&mut mem), Some(1));
+    assert!(ring.push(&mut mem, 4));
+    assert_eq!(mem.read::<u32>(4), 0);
+
+    assert_eq!(ring.pop(&mut mem), Some(2));
+    assert_eq!(ring.pop(&mut mem), Some(3));
+    assert_eq!(ring.pop(&mut mem), Some(4));
+    assert_eq!(ring.pop(&mut mem), None);
+    assert_eq!(ring.try_len(&mem), Ok(0));
+}
+
+#[test]
+fn test_ring_guest_indices() {
+    let mut mem = TestMemory::new([0u8; 32]);
+    let ring = RingRegion::<u8>::new(16..24, 0, 4);
+
+    // The guest produced two entries by writing to the ring and bumping the tail.
+    mem.write::<u8>(16, 0xAA);
+    mem.write::<u8>(17, 0xBB);
+    mem.write::<u32>(4, 2);
+
+    assert_eq!(ring.pop(&mut mem), Some(0xAA));
+    assert_eq!(ring.pop(&mut mem), Some(0xBB));
+    assert_eq!(ring.pop(&mut mem), None);
+    assert_eq!(mem.read::<u32>(0), 2);
+}
+
+#[test]
+fn test_ring_overflow_policies() {
+    let mut mem = TestMemory::new([0u8; 16]);
+    let ring = RingRegion::<u8>::new(8..12, 0, 4).overflow(OverflowPolicy::DropOldest);
+    for val in 1..=4 {
+        ring.push(&mut mem, val);
+    }
+    assert_eq!(ring.try_len(&mem), Ok(3));
+    assert_eq!(ring.peek(&mem), Some(2));
+    assert_eq!(ring.pop(&mut mem), Some(2));
+
+    ring.try_reset(&mut mem).unwrap();
+    let ring = ring.overflow(OverflowPolicy::OverwriteNewest);
+    for val in 1..=4 {
+        ring.push(&mut mem, val);
+    }
+    assert_eq!(ring.pop(&mut mem), Some(1));
+    assert_eq!(ring.pop(&mut mem), Some(2));
+    assert_eq!(ring.pop(&mut mem), Some(4));
+    assert_eq!(ring.peek(&mem), None);
+}
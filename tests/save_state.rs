@@ -0,0 +1,160 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::save_state::{self, LoadError, SaveError, SaveOptions};
+use mem_storage::{ContiguousMemory, MemoryStorage};
+
+fn sample() -> TestMemory {
+    let mut mem = TestMemory::new([0u8; 100]);
+    mem.try_write_from(10, b"hello world").unwrap();
+    mem.get_mut(40..80).unwrap().fill(0xCC);
+    mem
+}
+
+#[test]
+fn test_save_load_roundtrip() {
+    let mem = sample();
+
+    for compress in [false, true] {
+        let options = SaveOptions {
+            page_size: 16,
+            compress,
+        };
+        let state = save_state::save(&mem, 4..100, options).unwrap();
+
+        let mut restored = TestMemory::new([0xFFu8; 100]);
+        save_state::load(&mut restored, &state).unwrap();
+        assert_eq!(restored.get(..4).unwrap(), &[0xFF; 4]);
+        assert_eq!(restored.get(4..).unwrap(), mem.get(4..).unwrap());
+    }
+}
+
+#[test]
+fn test_compression_shrinks_state() {
+    let mem = sample();
+    let options = SaveOptions {
+        page_size: 32,
+        compress: false,
+    };
+
+    let raw = save_state::save(&mem, 0..100, options).unwrap();
+    let compressed = save_state::save(
+        &mem,
+        0..100,
+        SaveOptions {
+            compress: true,
+            ..options
+        },
+    );
+    assert!(compressed.unwrap().len() < raw.len());
+}
+
+#[test]
+fn test_load_skips_unknown_chunks() {
+    let mem = sample();
+    let mut state = save_state::save(&mem, 0..100, SaveOptions::default()).unwrap();
+
+    // Pretend a newer minor version added a chunk before the end marker.
+    let end = state.len() - 8;
+    state.splice(end..end, b"NEW\0\x03\x00\x00\x00abc".iter().copied());
+    state[6] = 7;
+
+    let mut restored = TestMemory::new([0u8; 100]);
+    save_state::load(&mut restored, &state).unwrap();
+    assert_eq!(restored.get(..).unwrap(), mem.get(..).unwrap());
+}
+
+#[test]
+fn test_load_errors() {
+    let mem = sample();
+    let state = save_state::save(&mem, 0..100, SaveOptions::default()).unwrap();
+    let mut target = TestMemory::new([0u8; 100]);
+
+    assert_eq!(
+        save_state::load(&mut target, b"NOPE"),
+        Err(LoadError::InvalidMagic)
+    );
+    assert_eq!(
+        save_state::load(&mut target, &state[..state.len() - 1]),
+        Err(LoadError::Truncated)
+    );
+
+    let mut newer = state.clone();
+    newer[4] = 2;
+    assert_eq!(
+        save_state::load(&mut target, &newer),
+        Err(LoadError::UnsupportedVersion {
+            major: 2,
+            minor: save_state::MINOR_VERSION
+        })
+    );
+
+    let mut overflow = state.clone();
+    overflow[16..24].copy_from_slice(&(u64::MAX - 10).to_le_bytes());
+    assert_eq!(
+        save_state::load(&mut target, &overflow),
+        Err(LoadError::InvalidChunk)
+    );
+
+    let mut small = TestMemory::new([0u8; 50]);
+    assert_eq!(
+        save_state::load(&mut small, &state),
+        Err(LoadError::Memory(()))
+    );
+}
+
+#[test]
+fn test_delta_roundtrip() {
+    let baseline = sample();
+    let mut mem = sample();
+    mem.try_write_from(50, b"changed").unwrap();
+
+    let options = SaveOptions {
+        page_size: 16,
+        compress: false,
+    };
+    let full = save_state::save(&mem, 0..100, options).unwrap();
+    let delta = save_state::save_delta(&mem, 0..100, baseline.get(..).unwrap(), options).unwrap();
+    assert!(save_state::is_delta(&delta));
+    assert!(!save_state::is_delta(&full));
+    assert!(delta.len() < full.len());
+
+    let mut restored = sample();
+    save_state::load(&mut restored, &delta).unwrap();
+    assert_eq!(restored.get(..).unwrap(), mem.get(..).unwrap());
+
+    let empty = save_state::save_delta(&mem, 0..100, mem.get(..).unwrap(), options).unwrap();
+    // Only the header and the end chunk remain.
+    assert_eq!(empty.len(), 32 + 8);
+}
+
+#[test]
+fn test_delta_baseline_length() {
+    let mem = sample();
+    assert_eq!(
+        save_state::save_delta(&mem, 0..100, &[0; 99], SaveOptions::default()),
+        Err(SaveError::BaselineLength {
+            expected: 100,
+            found: 99
+        })
+    );
+}
+
+#[test]
+fn test_invalid_page_size() {
+    let mem = sample();
+    let options = SaveOptions {
+        page_size: 0,
+        compress: false,
+    };
+    assert_eq!(
+        save_state::save(&mem, 0..100, options),
+        Err(SaveError::InvalidPageSize)
+    );
+    assert_eq!(
+        save_state::save_delta(&mem, 0..100, &[0; 100], options),
+        Err(SaveError::InvalidPageSize)
+    );
+}
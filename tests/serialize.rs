@@ -0,0 +1,32 @@
+#![cfg(any(feature = "postcard", feature = "bincode"))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{CellMemory, FillPolicy, MemoryStorage};
+
+fn sample() -> CellMemory {
+    let mem = CellMemory::with_fill(300, FillPolicy::Byte(0xCC));
+    (&mem).write::<u64>(8, 0x1122334455667788);
+    mem
+}
+
+#[cfg(feature = "postcard")]
+#[test]
+fn test_postcard_roundtrip() {
+    let mem = sample();
+    let bytes = mem.to_postcard().unwrap();
+
+    let restored = CellMemory::from_postcard(&bytes).unwrap();
+    assert_eq!(restored.to_vec(), mem.to_vec());
+    assert!(CellMemory::from_postcard(&bytes[..10]).is_err());
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn test_bincode_roundtrip() {
+    let mem = sample();
+    let bytes = mem.to_bincode().unwrap();
+
+    let restored = CellMemory::from_bincode(&bytes).unwrap();
+    assert_eq!(restored.to_vec(), mem.to_vec());
+    assert!(CellMemory::from_bincode(&bytes[..10]).is_err());
+}
@@ -0,0 +1,106 @@
+#![cfg(all(feature = "shm", unix, not(feature = "forbid-unsafe")))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{MemoryStorage, OutOfBounds, Protection, SharedMemory};
+use std::io::ErrorKind;
+
+#[test]
+fn test_create_and_open() {
+    let name = format!("/mem_storage_test_{}", std::process::id());
+    let mut owner = SharedMemory::create(&name, 64).unwrap();
+    let mut other = SharedMemory::open(&name).unwrap();
+    assert_eq!(other.len(), 64);
+
+    owner.write::<u32>(60, 0xCAFE_BABE);
+    assert_eq!(other.read::<u32>(60), 0xCAFE_BABE);
+    other.write::<u8>(0, 0x42);
+    assert_eq!(owner.read::<u8>(0), 0x42);
+    assert_eq!(owner.try_read::<u16>(63), Err(OutOfBounds { addr: 64 }));
+
+    assert_eq!(
+        SharedMemory::create(&name, 64).unwrap_err().kind(),
+        ErrorKind::AlreadyExists
+    );
+    drop(owner);
+    assert_eq!(
+        SharedMemory::open(&name).unwrap_err().kind(),
+        ErrorKind::NotFound
+    );
+    assert_eq!(other.read::<u8>(0), 0x42);
+}
+
+#[test]
+fn test_invalid_name() {
+    for name in ["", "/", "no_slash", "/a/b"] {
+        assert_eq!(
+            SharedMemory::open(name).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_anonymous() {
+    use std::os::unix::io::AsFd;
+
+    let mut mem = SharedMemory::anonymous(16).unwrap();
+    mem.write::<u64>(8, u64::MAX);
+
+    let fd = mem.as_fd().try_clone_to_owned().unwrap();
+    let other = SharedMemory::from_fd(fd).unwrap();
+    assert_eq!(other.len(), 16);
+    assert_eq!(other.read::<u64>(8), u64::MAX);
+}
+
+/// Runs `f` in a forked child and returns the signal that killed it, if any.
+fn signal_of(f: impl FnOnce()) -> Option<i32> {
+    // Safety: the child only runs `f` and exits without returning to the test harness.
+    match unsafe { libc::fork() } {
+        0 => {
+            f();
+            unsafe { libc::_exit(0) }
+        }
+        pid => {
+            let mut status = 0;
+            assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+            libc::WIFSIGNALED(status).then(|| libc::WTERMSIG(status))
+        }
+    }
+}
+
+#[test]
+fn test_protected_view() {
+    let name = format!("/mem_storage_view_{}", std::process::id());
+    let mut mem = SharedMemory::create(&name, 0x2000).unwrap();
+    let mut view = mem.map_view(Protection::READ_WRITE).unwrap();
+    assert_eq!(view.len(), 0x2000);
+
+    mem.write::<u32>(0x1000, 0xDEAD_BEEF);
+    // Safety: the view is 0x2000 bytes long and readable.
+    unsafe {
+        assert_eq!(
+            (view.as_ptr().add(0x1000) as *const u32).read(),
+            0xDEAD_BEEF
+        );
+        view.as_ptr().write(0x42);
+    }
+    assert_eq!(mem.read::<u8>(0), 0x42);
+
+    view.protect(0x1000..0x1004, Protection::READ).unwrap();
+    let ptr = view.as_ptr() as usize;
+    // Safety: the page is mapped, but read-only, so the write traps.
+    let write = || unsafe { (ptr as *mut u8).add(0x1000).write(1) };
+    assert_eq!(signal_of(write), Some(libc::SIGSEGV));
+    // The memory itself is not protected.
+    mem.write::<u8>(0x1000, 1);
+    assert_eq!(mem.read::<u8>(0x1000), 1);
+
+    view.protect(0..0x2000, Protection::NONE).unwrap();
+    // Safety: the page is mapped, but not accessible, so the read traps.
+    let read = || {
+        unsafe { (ptr as *const u8).read_volatile() };
+    };
+    assert_eq!(signal_of(read), Some(libc::SIGSEGV));
+    assert!(view.protect(0..0x2001, Protection::READ).is_err());
+}
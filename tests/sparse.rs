@@ -0,0 +1,176 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{FillPolicy, MemoryStorage, OutOfBounds, SparseMemory};
+
+#[test]
+fn test_read_write() {
+    let mut mem = SparseMemory::<16>::new();
+    assert_eq!(mem.read::<u64>(0xFFFF_0000), 0);
+    assert_eq!(mem.resident_pages(), 0);
+
+    mem.write(0x1_0000_000E, 0x1122_3344u32);
+    assert_eq!(mem.read::<u32>(0x1_0000_000E), 0x1122_3344);
+    assert_eq!(mem.read::<u8>(0x1_0000_000D), 0);
+    assert_eq!(mem.resident_pages(), 2);
+
+    assert_eq!(
+        mem.try_write(usize::MAX - 1, 0u32),
+        Err(OutOfBounds {
+            addr: usize::MAX - 1
+        })
+    );
+}
+
+#[test]
+fn test_u64_addresses() {
+    let mut mem = SparseMemory::<16>::new();
+    let addr = 0x1234_0000_0000_0008u64;
+    mem.try_write_from_u64(addr, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+        .unwrap();
+
+    let mut buf = [0u8; 12];
+    mem.try_read_into_u64(addr - 1, &mut buf).unwrap();
+    assert_eq!(buf, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0]);
+    assert_eq!(mem.next_mapped(0), Some(addr - 8));
+    assert!(mem.is_mapped(addr + 16));
+
+    assert_eq!(
+        mem.try_read_into_u64(u64::MAX - 1, &mut buf),
+        Err(OutOfBounds {
+            addr: usize::MAX - 1
+        })
+    );
+
+    mem.try_write_from_u64(u64::MAX - 1, &[11, 12]).unwrap();
+    let mut buf = [0u8; 3];
+    mem.try_read_into_u64(u64::MAX - 2, &mut buf).unwrap();
+    assert_eq!(buf, [0, 11, 12]);
+}
+
+#[test]
+fn test_mapped_ranges() {
+    let mut mem = SparseMemory::<16>::new();
+    mem.write_byte(0x105, 1);
+    mem.write_byte(0x110, 1);
+    mem.write_byte(0x400, 1);
+
+    assert_eq!(
+        mem.mapped_ranges().collect::<Vec<_>>(),
+        [0x100..0x120, 0x400..0x410]
+    );
+    assert_eq!(mem.next_mapped(0), Some(0x100));
+    assert_eq!(mem.next_mapped(0x108), Some(0x108));
+    assert_eq!(mem.next_mapped(0x120), Some(0x400));
+    assert_eq!(mem.next_mapped(0x410), None);
+    assert!(mem.is_mapped(0x11F));
+}
+
+#[test]
+fn test_unmap() {
+    let mut mem = SparseMemory::<16>::new();
+    mem.fill(0x100..0x130, 0xFF);
+
+    mem.unmap(0x108..0x120);
+    assert_eq!(mem.resident_pages(), 2);
+    assert_eq!(mem.read::<u8>(0x107), 0xFF);
+    assert_eq!(mem.read::<u8>(0x108), 0);
+    assert_eq!(mem.read::<u8>(0x120), 0xFF);
+    assert_eq!(
+        mem.mapped_ranges().collect::<Vec<_>>(),
+        [0x100..0x110, 0x120..0x130]
+    );
+
+    mem.clear();
+    assert_eq!(mem.next_mapped(0), None);
+}
+
+#[test]
+fn test_compact() {
+    let mut mem = SparseMemory::<16>::new();
+    mem.write(0x100, 1u32);
+    mem.write(0x200, 1u32);
+    mem.write(0x200, 0u32);
+    assert_eq!(mem.resident_pages(), 2);
+
+    assert_eq!(mem.compact(), 1);
+    assert_eq!(mem.resident_pages(), 1);
+    assert_eq!(mem.read::<u32>(0x100), 1);
+    assert_eq!(mem.compact(), 0);
+}
+
+#[test]
+fn test_fill_policy() {
+    let pattern = FillPolicy::Pattern(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    let mut mem = SparseMemory::<16>::new().fill_policy(pattern);
+    assert_eq!(mem.read_be::<u32>(0x1000), 0xDEAD_BEEF);
+    assert_eq!(mem.read::<u8>(0x1001), 0xAD);
+
+    mem.write_byte(0x1002, 0);
+    assert_eq!(mem.read_be::<u32>(0x1000), 0xDEAD_00EF);
+    assert_eq!(mem.read_be::<u32>(0x100C), 0xDEAD_BEEF);
+
+    mem.unmap(0x1002..0x1003);
+    assert_eq!(mem.read_be::<u32>(0x1000), 0xDEAD_BEEF);
+    assert_eq!(mem.compact(), 1);
+    assert_eq!(mem.resident_pages(), 0);
+}
+
+#[test]
+fn test_compact_after() {
+    let mut mem = SparseMemory::<16>::new().compact_after(3);
+    mem.write_byte(0x00, 0);
+    mem.write_byte(0x10, 1);
+    assert_eq!(mem.resident_pages(), 2);
+
+    mem.write_byte(0x20, 0);
+    assert_eq!(mem.resident_pages(), 1);
+    assert!(mem.is_mapped(0x10));
+
+    mem.write_byte(0x10, 2);
+    mem.write_byte(0x30, 0);
+    assert_eq!(mem.resident_pages(), 2);
+}
+
+#[test]
+fn test_size() {
+    let mem = SparseMemory::<16>::new();
+    assert_eq!(mem.size(), Some(usize::MAX));
+    assert_eq!(mem.addr_range(), Some(0..usize::MAX));
+}
+
+#[test]
+fn test_snapshot_shares_pages() {
+    let mut mem = SparseMemory::<16>::new();
+    mem.write::<u32>(0x00, 1);
+    mem.write::<u32>(0x10, 2);
+    mem.write::<u32>(0x20, 3);
+
+    let snapshot = mem.snapshot();
+    assert_eq!(snapshot.resident_pages(), 3);
+    assert_eq!(mem.shared_pages(), 3);
+
+    mem.write::<u32>(0x14, 0xAA);
+    mem.unmap(0x20..0x30);
+    assert_eq!(mem.shared_pages(), 1);
+
+    mem.restore(&snapshot);
+    assert_eq!(mem.read::<u32>(0x10), 2);
+    assert_eq!(mem.read::<u32>(0x14), 0);
+    assert_eq!(mem.read::<u32>(0x20), 3);
+    assert_eq!(mem.shared_pages(), 3);
+
+    drop(snapshot);
+    assert_eq!(mem.shared_pages(), 0);
+}
+
+#[test]
+fn test_clone_is_copy_on_write() {
+    let mut mem = SparseMemory::<16>::new();
+    mem.write::<u8>(0, 1);
+
+    let mut copy = mem.clone();
+    copy.write::<u8>(0, 2);
+    assert_eq!(mem.read::<u8>(0), 1);
+    assert_eq!(copy.read::<u8>(0), 2);
+}
@@ -0,0 +1,29 @@
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{ContiguousMemory, MemoryStorage, OutOfBounds, StaticMemory};
+
+#[test]
+fn test_static_memory() {
+    let buf: &'static mut [u8] = Box::leak(Box::new([0xFFu8; 8]));
+    let mut mem = StaticMemory::new(buf);
+    assert_eq!(mem.len(), 8);
+    assert_eq!(mem.read::<u16>(0), 0xFFFF);
+
+    mem.write::<u32>(4, 0x1234_5678);
+    assert_eq!(mem.get(4..6).unwrap(), &[0x78, 0x56]);
+    assert_eq!(mem.try_read::<u16>(7), Err(OutOfBounds { addr: 8 }));
+    assert_eq!(mem.try_write::<u8>(8, 0), Err(OutOfBounds { addr: 8 }));
+
+    let buf = mem.into_inner();
+    assert_eq!(buf[7], 0x12);
+}
+
+#[test]
+fn test_static_memory_get_out_of_bounds() {
+    let buf: &'static mut [u8] = Box::leak(Box::new([0u8; 4]));
+    let mut mem = StaticMemory::new(buf);
+    assert_eq!(mem.as_bytes().len(), 4);
+    assert_eq!(mem.get(2..6), Err(OutOfBounds { addr: 4 }));
+    assert_eq!(mem.get_mut(4), Err(OutOfBounds { addr: 4 }));
+    assert_eq!(mem.out_of_bounds(9), OutOfBounds { addr: 9 });
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "std")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{
+    stats::StatsExport, BusArbiter, CacheStats, MemoryStorage, ProfileMemory, TierStats,
+    TieredMemory,
+};
+
+#[test]
+fn test_json() {
+    let cache = CacheStats {
+        hits: 10,
+        misses: 2,
+        writebacks: 1,
+    };
+    let tiers = TierStats {
+        fast: 7,
+        slow: 3,
+        migrations: 0,
+    };
+
+    let mut out = Vec::new();
+    StatsExport::new()
+        .region("rom \"cache\"", &cache)
+        .region("ram", &tiers)
+        .write_json(&mut out)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        r#"{"rom \"cache\"":{"hits":10,"misses":2,"writebacks":1},"ram":{"fast":7,"slow":3,"migrations":0}}"#
+    );
+}
+
+#[test]
+fn test_csv() {
+    let cache = CacheStats {
+        hits: 1,
+        misses: 2,
+        writebacks: 3,
+    };
+
+    let mut out = Vec::new();
+    StatsExport::new()
+        .region("vram, bank 0", &cache)
+        .write_csv(&mut out)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "region,counter,value\n\
+         \"vram, bank 0\",hits,1\n\
+         \"vram, bank 0\",misses,2\n\
+         \"vram, bank 0\",writebacks,3\n"
+    );
+}
+
+#[test]
+fn test_empty() {
+    let mut out = Vec::new();
+    StatsExport::new().write_json(&mut out).unwrap();
+    assert_eq!(out, b"{}");
+}
+
+#[test]
+fn test_heatmap() {
+    let fast = TestMemory::new([0; 4]);
+    let slow = TestMemory::new([0; 8]);
+    let mut mem = TieredMemory::<_, _, 4>::new(fast, 1, slow, 2);
+    mem.write(4, 1u8);
+    mem.read::<u8>(5);
+
+    let mut out = Vec::new();
+    StatsExport::new()
+        .region("ram", &mem)
+        .write_csv(&mut out)
+        .unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("ram,page_0,0\n"));
+    assert!(out.contains("ram,page_1,2\n"));
+}
+
+#[test]
+fn test_initiators() {
+    let mut bus = BusArbiter::new(TestMemory::new([0; 4]));
+    let cpu = bus.add_initiator(1);
+    let dma = bus.add_initiator(0);
+    bus.port(cpu).unwrap().read::<u8>(0);
+    bus.port(dma).unwrap().read::<u8>(1);
+
+    let cpu = bus.stats(cpu).unwrap();
+    let dma = bus.stats(dma).unwrap();
+    let mut out = Vec::new();
+    StatsExport::new()
+        .region("cpu", &cpu)
+        .region("dma", &dma)
+        .write_csv(&mut out)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "region,counter,value\ncpu,accesses,1\ncpu,stall_cycles,0\n\
+         dma,accesses,1\ndma,stall_cycles,1\n"
+    );
+}
+
+#[test]
+fn test_profile_regions() {
+    let mut mem = ProfileMemory::new(TestMemory::new([0; 8]));
+    mem.add_region("code", 0..4);
+    mem.add_region("data", 4..8);
+    mem.fetch_port().read::<u16>(0);
+    mem.write(4, 1u8);
+
+    let mut export = StatsExport::new();
+    for region in mem.regions() {
+        export = export.region(region.name(), region);
+    }
+    let mut out = Vec::new();
+    export.write_json(&mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        r#"{"code":{"fetches":1,"loads":0,"stores":0},"data":{"fetches":0,"loads":0,"stores":1}}"#
+    );
+}
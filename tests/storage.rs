@@ -0,0 +1,81 @@
+#![cfg(feature = "embedded-storage")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use embedded_storage::{ReadStorage, Storage};
+use mem_storage::{
+    CellMemory, ContiguousMemory, ErrorKind, MemoryStorage, OutOfBounds, StorageDevice,
+    StorageError, StorageMemory,
+};
+
+#[test]
+fn test_memory_as_storage() {
+    let mut device = StorageDevice::new(TestMemory::new([0u8; 16]), 16);
+    assert_eq!(device.capacity(), 16);
+
+    device.write(4, b"boot").unwrap();
+    let mut buf = [0u8; 4];
+    device.read(4, &mut buf).unwrap();
+    assert_eq!(&buf, b"boot");
+    assert_eq!(device.write(14, b"boot"), Err(()));
+    assert_eq!(device.inner().get(4..8).unwrap(), b"boot");
+}
+
+#[test]
+fn test_storage_as_memory() {
+    let mut mem = StorageMemory::new(CellMemory::new(16));
+    assert_eq!(mem.capacity(), 16);
+
+    mem.write::<u32>(0, 0xDEADBEEF);
+    assert_eq!(mem.read::<u16>(2), 0xDEAD);
+    assert_eq!(
+        mem.try_read::<u32>(14),
+        Err(StorageError::Storage(OutOfBounds { addr: 14 }))
+    );
+
+    let mut cell = mem.into_inner();
+    let mut buf = [0u8; 2];
+    ReadStorage::read(&mut cell, 0, &mut buf).unwrap();
+    assert_eq!(buf, [0xEF, 0xBE]);
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn test_offset_out_of_range() {
+    use mem_storage::MemoryError;
+
+    let mut mem = StorageMemory::new(CellMemory::new(16));
+    let err = mem.try_read::<u8>(1 << 32).unwrap_err();
+    assert_eq!(err, StorageError::OutOfRange { addr: 1 << 32 });
+    assert_eq!(err.kind(), ErrorKind::OutOfBounds);
+    assert_eq!(
+        mem.try_write::<u16>(u32::MAX as usize, 0),
+        Err(StorageError::OutOfRange {
+            addr: u32::MAX as usize
+        })
+    );
+}
+
+#[test]
+fn test_nor_flash() {
+    use embedded_storage::nor_flash::{NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash};
+    use mem_storage::NorFlashMemory;
+
+    let mut flash = NorFlashMemory::<16>::new(2);
+    assert_eq!(<NorFlashMemory<16> as NorFlash>::ERASE_SIZE, 16);
+
+    NorFlash::write(&mut flash, 4, &[0x12, 0x34]).unwrap();
+    let mut buf = [0u8; 2];
+    ReadNorFlash::read(&mut flash, 4, &mut buf).unwrap();
+    assert_eq!(buf, [0x12, 0x34]);
+
+    let err = NorFlash::write(&mut flash, 4, &[0xFF]).unwrap_err();
+    assert_eq!(err.kind(), NorFlashErrorKind::Other);
+    let err = NorFlash::erase(&mut flash, 0, 8).unwrap_err();
+    assert_eq!(err.kind(), NorFlashErrorKind::NotAligned);
+
+    NorFlash::erase(&mut flash, 0, 16).unwrap();
+    assert_eq!(flash.read::<u16>(4), 0xFFFF);
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{MemoryStorage, SwapError, SwapMemory, SwapStore};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct MapStore {
+    pages: HashMap<usize, Vec<u8>>,
+    fail: Rc<Cell<bool>>,
+}
+
+impl SwapStore for MapStore {
+    type Error = &'static str;
+
+    fn load_page(&mut self, page: usize, data: &mut [u8]) -> Result<(), Self::Error> {
+        data.copy_from_slice(&self.pages[&page]);
+        Ok(())
+    }
+
+    fn store_page(&mut self, page: usize, data: &[u8]) -> Result<(), Self::Error> {
+        if self.fail.get() {
+            return Err("disk full");
+        }
+        self.pages.insert(page, data.to_vec());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_swapping() {
+    let mut mem = SwapMemory::<_, 4>::new(MapStore::default(), 1 << 20, 2);
+    assert_eq!(mem.len(), 4 << 20);
+    assert_eq!(mem.read::<u32>(0x1000), 0);
+
+    mem.write::<u32>(0, 0x1122_3344);
+    mem.write::<u16>(0x3F_FFFE, 0x5566);
+    assert_eq!(mem.resident_pages(), 2);
+    assert_eq!(mem.swapped_pages(), 0);
+
+    // The clean page 0x400 was evicted without storing it, so the dirty page 0 is next.
+    mem.write::<u8>(8, 0x77);
+    assert_eq!(mem.swapped_pages(), 1);
+    assert_eq!(mem.read::<u32>(0), 0x1122_3344);
+    assert_eq!(mem.read::<u16>(0x3F_FFFE), 0x5566);
+    assert_eq!(mem.read::<u8>(8), 0x77);
+    assert_eq!(mem.budget(), 2);
+}
+
+#[test]
+fn test_store_failure() {
+    let store = MapStore::default();
+    let fail = store.fail.clone();
+    let mut mem = SwapMemory::<_, 4>::new(store, 4, 1);
+    mem.write::<u8>(0, 1);
+
+    fail.set(true);
+    assert_eq!(
+        mem.try_write::<u8>(4, 2),
+        Err(SwapError::Store {
+            page: 0,
+            error: "disk full"
+        })
+    );
+
+    // The dirty page stays resident if it can not be stored.
+    fail.set(false);
+    assert_eq!(mem.read::<u8>(0), 1);
+    mem.write::<u8>(4, 2);
+    assert_eq!(mem.read::<u8>(0), 1);
+    assert_eq!(mem.read::<u8>(4), 2);
+}
+
+#[test]
+fn test_out_of_bounds() {
+    let mem = SwapMemory::<_, 4>::new(MapStore::default(), 2, 1);
+    assert_eq!(
+        mem.try_read::<u16>(7),
+        Err(SwapError::OutOfBounds { addr: 8 })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_io_store() {
+    let mut mem = SwapMemory::<_, 8>::new(std::io::Cursor::new(Vec::new()), 16, 1);
+    for page in 0..16 {
+        mem.write::<u64>(page * 8, page as u64);
+    }
+    for page in 0..16 {
+        assert_eq!(mem.read::<u64>(page * 8), page as u64);
+    }
+}
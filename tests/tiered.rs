@@ -0,0 +1,78 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{MemoryStorage, Tier, TierStats, TieredError, TieredMemory};
+
+fn tiered() -> TieredMemory<TestMemory, TestMemory, 4> {
+    let slow = (0..16).collect::<Vec<u8>>();
+    TieredMemory::new(TestMemory::new([0u8; 8]), 2, TestMemory::new(slow), 4)
+}
+
+#[test]
+fn test_promote_and_demote() {
+    let mut mem = tiered();
+    assert_eq!(mem.read::<u32>(4), 0x0706_0504);
+
+    assert!(mem.promote(1));
+    assert_eq!(mem.tier_of(1), Tier::Fast);
+    assert_eq!(mem.read::<u32>(4), 0x0706_0504);
+    assert_eq!(mem.fast().read::<u32>(0), 0x0706_0504);
+
+    mem.write::<u16>(3, 0xAABB);
+    assert_eq!(mem.slow().read::<u8>(4), 4);
+    assert!(mem.promote(2));
+    assert!(!mem.promote(3));
+
+    mem.demote(1);
+    assert_eq!(mem.tier_of(1), Tier::Slow);
+    assert_eq!(mem.slow().read::<u16>(3), 0xAABB);
+    assert_eq!(mem.read::<u16>(3), 0xAABB);
+
+    assert_eq!(
+        mem.stats(),
+        TierStats {
+            fast: 2,
+            slow: 4,
+            migrations: 3
+        }
+    );
+    assert_eq!(mem.stats().cost(1, 10), 2 + 40 + 33);
+}
+
+#[test]
+fn test_rebalance() {
+    let mut mem = tiered();
+    for _ in 0..3 {
+        mem.read::<u8>(12);
+    }
+    mem.read::<u8>(0);
+    mem.read::<u16>(5);
+    mem.read::<u8>(6);
+
+    assert!(mem.promote(0));
+    assert!(mem.promote(2));
+    mem.rebalance();
+
+    assert_eq!(mem.tier_of(3), Tier::Fast);
+    assert_eq!(mem.tier_of(1), Tier::Fast);
+    assert_eq!(mem.tier_of(0), Tier::Slow);
+    assert_eq!(mem.tier_of(2), Tier::Slow);
+    assert_eq!(mem.heat(3), 1);
+    assert_eq!(mem.read::<u32>(12), 0x0F0E_0D0C);
+}
+
+#[test]
+fn test_out_of_bounds() {
+    let mut mem = tiered();
+    assert_eq!(
+        mem.try_read::<u16>(15),
+        Err(TieredError::OutOfBounds { addr: 16 })
+    );
+    assert_eq!(
+        mem.try_promote(4),
+        Err(TieredError::OutOfBounds { addr: 16 })
+    );
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "alloc")]
+
+use mem_storage::{Tlb, TlbEntry};
+
+fn entry(asid: u16, virt: usize, phys: usize, global: bool) -> TlbEntry {
+    TlbEntry {
+        asid,
+        virt,
+        phys,
+        global,
+    }
+}
+
+#[test]
+fn test_asid_tagging() {
+    let mut tlb = Tlb::<0x1000>::new(8);
+    tlb.insert(entry(1, 0x4000, 0x8000, false));
+    tlb.insert(entry(2, 0x4123, 0x9000, false));
+    tlb.insert(entry(0, 0xF000, 0x1000, true));
+
+    assert_eq!(tlb.translate(1, 0x4010), Some(0x8010));
+    assert_eq!(tlb.translate(2, 0x4010), Some(0x9010));
+    assert_eq!(tlb.translate(3, 0x4010), None);
+    assert_eq!(tlb.translate(3, 0xF00F), Some(0x100F));
+    assert_eq!(tlb.lookup(2, 0x4FFF).unwrap().virt, 0x4000);
+
+    tlb.flush_asid(1);
+    assert_eq!(tlb.translate(1, 0x4010), None);
+    assert_eq!(tlb.translate(1, 0xF000), Some(0x1000));
+    assert_eq!(tlb.translate(2, 0x4010), Some(0x9010));
+
+    tlb.flush_asid_page(2, 0xF000);
+    assert_eq!(tlb.translate(2, 0xF000), Some(0x1000));
+    tlb.flush_page(0xF000);
+    assert_eq!(tlb.translate(2, 0xF000), None);
+    assert_eq!(tlb.len(), 1);
+
+    tlb.flush();
+    assert!(tlb.is_empty());
+}
+
+#[test]
+fn test_replacement() {
+    let mut tlb = Tlb::<0x1000>::new(2);
+    tlb.insert(entry(1, 0x1000, 0x1000, false));
+    tlb.insert(entry(1, 0x1000, 0x5000, false));
+    assert_eq!(tlb.len(), 1);
+    assert_eq!(tlb.translate(1, 0x1000), Some(0x5000));
+
+    tlb.insert(entry(1, 0x2000, 0x2000, false));
+    tlb.insert(entry(1, 0x3000, 0x3000, false));
+    assert_eq!(tlb.len(), 2);
+    assert_eq!(tlb.translate(1, 0x1000), None);
+
+    // The entry of 0x2000 is now the oldest one.
+    tlb.flush_asid_page(1, 0x3000);
+    tlb.insert(entry(1, 0x4000, 0x4000, false));
+    tlb.insert(entry(1, 0x5000, 0x5000, false));
+    assert_eq!(tlb.translate(1, 0x2000), None);
+    assert_eq!(tlb.translate(1, 0x4000), Some(0x4000));
+    assert_eq!(tlb.capacity(), 2);
+
+    let mut tlb = Tlb::<0x1000>::new(0);
+    tlb.insert(entry(1, 0x1000, 0x1000, false));
+    assert!(tlb.is_empty());
+}
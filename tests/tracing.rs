@@ -0,0 +1,96 @@
+#![cfg(feature = "tracing")]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{FaultMemory, MemoryStorage, TracedMemory};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// A subscriber that records the message of every event.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Recorder {
+    fn messages(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+struct MessageVisitor<'a>(&'a mut String);
+
+impl Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            *self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut message = String::new();
+        event.record(&mut MessageVisitor(&mut message));
+        self.0.lock().unwrap().push(message);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn test_fault_event() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut mem = FaultMemory::new(TestMemory::new([0; 8]));
+        mem.add_range(4..8);
+        assert!(mem.try_read_byte(0).is_ok());
+        assert!(mem.try_read_byte(4).is_err());
+    });
+
+    assert_eq!(recorder.messages(), ["injected fault"]);
+}
+
+#[test]
+fn test_sampled_accesses() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut mem = TracedMemory::new(TestMemory::new([0; 8])).sample(2);
+        for addr in 0..4 {
+            mem.try_write_byte(addr, 1).unwrap();
+        }
+        assert!(mem.try_read_byte(8).is_err());
+    });
+
+    assert_eq!(
+        recorder.messages(),
+        ["memory access", "memory access", "memory access failed"]
+    );
+}
+
+#[test]
+fn test_disabled_sampling() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mem = TracedMemory::new(TestMemory::new([0; 8])).sample(0);
+        assert_eq!(mem.try_read_byte(0), Ok(0));
+    });
+
+    assert!(recorder.messages().is_empty());
+}
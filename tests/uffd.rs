@@ -0,0 +1,72 @@
+#![cfg(all(feature = "uffd", target_os = "linux", not(feature = "forbid-unsafe")))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{LazyError, MemoryStorage, UffdMemory};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_fetch_on_access() {
+    let fetched = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&fetched);
+    let mut mem = UffdMemory::new(
+        move |page: usize, data: &mut [u8]| -> Result<(), ()> {
+            log.lock().unwrap().push(page);
+            data.fill(page as u8 + 1);
+            Ok(())
+        },
+        4 * 4096,
+    )
+    .unwrap();
+    let page_size = mem.page_size();
+    assert_eq!(mem.len(), 4 * 4096);
+    assert_eq!(mem.resident_pages(), 0);
+
+    assert_eq!(mem.read::<u8>(0), 1);
+    mem.write::<u32>(page_size - 2, 0xAABB_CCDD);
+    assert_eq!(mem.read::<u32>(page_size - 4), 0xCCDD_0101);
+    assert!(mem.is_resident(0) && mem.is_resident(1));
+
+    // Direct accesses through the pointer are resolved by the handler thread.
+    let last = mem.len() - 1;
+    // Safety: the address is inside the memory, and no other access is running.
+    assert_eq!(unsafe { mem.as_ptr().add(last).read() }, 4);
+    assert!(mem.is_resident(3));
+
+    mem.populate(0..mem.len());
+    assert_eq!(*fetched.lock().unwrap(), [0, 1, 3, 2]);
+    assert!(mem.take_error().is_none());
+}
+
+#[test]
+fn test_errors() {
+    let mem = UffdMemory::new(
+        |page: usize, _: &mut [u8]| if page == 1 { Err("offline") } else { Ok(()) },
+        2 * 4096,
+    )
+    .unwrap();
+    let page_size = mem.page_size();
+
+    assert_eq!(
+        mem.try_read::<u8>(mem.len()),
+        Err(LazyError::OutOfBounds { addr: mem.len() })
+    );
+    assert_eq!(
+        mem.try_read::<u32>(page_size - 2),
+        Err(LazyError::Fetch {
+            page: 1,
+            error: "offline"
+        })
+    );
+    assert!(!mem.is_resident(1));
+
+    // Safety: the address is inside the memory, and no other access is running.
+    assert_eq!(unsafe { mem.as_ptr().add(page_size).read() }, 0);
+    assert_eq!(
+        mem.take_error(),
+        Some(LazyError::Fetch {
+            page: 1,
+            error: "offline"
+        })
+    );
+    assert_eq!(mem.try_read::<u8>(page_size), Ok(0));
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::{CellMemory, MemoryStorage, MemoryUsage, PageCache, ReportUsage, SparseMemory};
+
+#[test]
+fn test_flat_usage() {
+    let usage = CellMemory::new(64).usage();
+    assert_eq!(usage.addressable, 64);
+    assert_eq!(usage.allocated, 64);
+    assert_eq!(usage.occupancy(), 1.0);
+    assert_eq!(MemoryUsage::default().occupancy(), 0.0);
+}
+
+#[test]
+fn test_sparse_usage() {
+    let mut mem = SparseMemory::<16>::new();
+    mem.write_byte(0x1000, 1);
+    mem.write_byte(0x2000, 1);
+
+    let usage = mem.usage();
+    assert_eq!(usage.addressable, usize::MAX);
+    assert_eq!(usage.allocated, 32);
+    assert_eq!(usage.resident_pages, 2);
+}
+
+#[test]
+fn test_dirty_pages() {
+    let mut cache = PageCache::<_, 4>::new(TestMemory::new([0; 16]), 16, 4);
+    cache.read::<u8>(0);
+    cache.write::<u8>(4, 1);
+    cache.write::<u8>(8, 1);
+
+    let usage = cache.usage();
+    assert_eq!(usage.addressable, 16);
+    assert_eq!(usage.resident_pages, 3);
+    assert_eq!(usage.allocated, 12);
+    assert_eq!(usage.dirty_pages, 2);
+
+    cache.flush();
+    assert_eq!(cache.usage().dirty_pages, 0);
+}
+
+#[test]
+#[cfg(not(feature = "forbid-unsafe"))]
+fn test_guest_usage() {
+    use mem_storage::{AlignedMemory, GuestMemoryMap, GuestRegion};
+
+    let mem = GuestMemoryMap::new(vec![
+        GuestRegion::new(0x0, AlignedMemory::new(0x100, 16)),
+        GuestRegion::new(0x1000, AlignedMemory::new(0x40, 16)),
+    ])
+    .unwrap();
+
+    assert_eq!(mem.usage().addressable, 0x140);
+    assert_eq!(mem.regions()[1].inner().usage().allocated, 0x40);
+}
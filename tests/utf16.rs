@@ -0,0 +1,46 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::MemoryStorage;
+
+fn encode(s: &str, big_endian: bool) -> Vec<u8> {
+    s.encode_utf16()
+        .chain(Some(0))
+        .flat_map(|unit| {
+            if big_endian {
+                unit.to_be_bytes()
+            } else {
+                unit.to_le_bytes()
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_read_utf16_str() {
+    let mem = TestMemory::new(encode("EFI \u{1F600}", false));
+    assert_eq!(
+        mem.try_read_utf16_str(0, 64),
+        Ok("EFI \u{1F600}".to_string())
+    );
+    assert_eq!(mem.read_utf16_str(2, 2), "FI");
+
+    let mem = TestMemory::new(encode("Wide", true));
+    assert_eq!(mem.read_utf16_str_be(0, 64), "Wide");
+}
+
+#[test]
+fn test_unpaired_surrogate() {
+    let mem = TestMemory::new([0x3D, 0xD8, b'a', 0, 0, 0]);
+    assert_eq!(mem.read_utf16_str(0, 64), "\u{FFFD}a");
+}
+
+#[test]
+fn test_missing_terminator() {
+    let mem = TestMemory::new([b'a', 0, b'b', 0]);
+    assert_eq!(mem.try_read_utf16_str(0, 2), Ok("ab".to_string()));
+    assert_eq!(mem.try_read_utf16_str(0, 3), Err(()));
+}
@@ -0,0 +1,26 @@
+#![cfg(not(feature = "forbid-unsafe"))]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{MemoryStorage, OutOfBounds, VolatileRegion};
+
+#[test]
+fn test_volatile_region() {
+    let mut regs = [0u64; 2];
+    let base = regs.as_mut_ptr() as usize;
+
+    let mut region = unsafe { VolatileRegion::new(base, 16) };
+    assert_eq!(region.base(), base);
+    assert_eq!(region.len(), 16);
+
+    region.write::<u32>(0, 0x1234_5678);
+    region.write::<u64>(8, 0x0102_0304_0506_0708);
+    region.write::<u16>(5, 0xAABB);
+    assert_eq!(region.read::<u32>(0), 0x1234_5678);
+    assert_eq!(region.read::<u16>(5), 0xAABB);
+    assert_eq!(region.read::<u8>(8), 0x08);
+    assert_eq!(region.try_read::<u32>(14), Err(OutOfBounds { addr: 16 }));
+    assert_eq!(region.try_write::<u8>(16, 0), Err(OutOfBounds { addr: 16 }));
+
+    assert_eq!(regs[1], 0x0102_0304_0506_0708);
+    assert_eq!(regs[0].to_ne_bytes()[..4], 0x1234_5678u32.to_ne_bytes());
+}
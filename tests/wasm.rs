@@ -0,0 +1,45 @@
+#![cfg(feature = "wasmtime")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+use mem_storage::{MemoryStorage, OutOfBounds, ResizableMemory, ResizeError, WasmMemory};
+use wasmtime::{Engine, Memory, MemoryType, Store};
+
+#[test]
+fn test_linear_memory() {
+    let mut store = Store::new(&Engine::default(), ());
+    let memory = Memory::new(&mut store, MemoryType::new(1, Some(2))).unwrap();
+
+    let mut mem = WasmMemory::new(&mut store, memory);
+    assert_eq!(mem.len(), 0x10000);
+    mem.write::<u32>(0x100, 0xDEAD_BEEF);
+    assert_eq!(mem.read_be::<u32>(0x100), 0xEFBE_ADDE);
+    assert_eq!(mem.find(0..0x10000, 0xAD), Some(0x102));
+    assert_eq!(
+        mem.try_read::<u16>(0xFFFF),
+        Err(OutOfBounds { addr: 0x10000 })
+    );
+
+    assert_eq!(mem.try_grow(1).unwrap(), 1);
+    assert_eq!(mem.read::<u16>(0xFFFF), 0);
+    assert!(mem.try_grow(1).is_err());
+
+    assert_eq!(memory.data(&store)[0x100], 0xEF);
+}
+
+#[test]
+fn test_resize_in_pages() {
+    let mut store = Store::new(&Engine::default(), ());
+    let memory = Memory::new(&mut store, MemoryType::new(1, Some(2))).unwrap();
+
+    let mut mem = WasmMemory::new(&mut store, memory);
+    assert_eq!(
+        mem.try_resize(0x10001),
+        Err(ResizeError::Unsupported { size: 0x10001 })
+    );
+    assert_eq!(mem.try_resize(0), Err(ResizeError::Unsupported { size: 0 }));
+    assert_eq!(mem.try_grow_by(0x10000), Ok(0x10000));
+    assert_eq!(
+        mem.try_resize(0x30000),
+        Err(ResizeError::AllocFailed { size: 0x30000 })
+    );
+}
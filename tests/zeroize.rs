@@ -0,0 +1,59 @@
+#![cfg(feature = "zeroize")]
+#![cfg_attr(feature = "no-panic", allow(deprecated))]
+
+mod common;
+
+use common::TestMemory;
+use mem_storage::ContiguousMemory;
+
+#[test]
+fn test_secure_clear() {
+    let mut mem = TestMemory::new([0xAA; 16]);
+
+    mem.secure_clear(4..12);
+    assert_eq!(mem.get(0..4).unwrap(), &[0xAA; 4]);
+    assert_eq!(mem.get(4..12).unwrap(), &[0; 8]);
+    assert_eq!(mem.get(12..).unwrap(), &[0xAA; 4]);
+
+    assert!(mem.try_secure_clear(8..32).is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_owned_memories() {
+    use mem_storage::{EepromMemory, MemoryStorage, NorFlashMemory, SparseMemory};
+    use zeroize::Zeroize;
+
+    let mut flash = NorFlashMemory::<16>::new(2);
+    flash.zeroize();
+    assert_eq!(flash.read::<u32>(28), 0);
+
+    let mut eeprom = EepromMemory::new(8);
+    eeprom.write::<u8>(3, 0xAA);
+    eeprom.zeroize();
+    assert_eq!(eeprom.read::<u64>(0), 0);
+
+    // Pages that are shared with a snapshot are only freed, not wiped.
+    let mut sparse = SparseMemory::<16>::new();
+    sparse.write::<u32>(0x1000, 0xAABB_CCDD);
+    let snapshot = sparse.snapshot();
+    sparse.write::<u8>(0x2000, 0xEE);
+    sparse.zeroize();
+    assert_eq!(sparse.read::<u32>(0x1000), 0);
+    assert_eq!(sparse.read::<u8>(0x2000), 0);
+
+    sparse.restore(&snapshot);
+    assert_eq!(sparse.read::<u32>(0x1000), 0xAABB_CCDD);
+}
+
+#[cfg(all(feature = "alloc", not(feature = "forbid-unsafe")))]
+#[test]
+fn test_aligned_memory() {
+    use mem_storage::{AlignedMemory, MemoryStorage};
+    use zeroize::Zeroize;
+
+    let mut mem = AlignedMemory::new(64, 64);
+    mem.fill(0..64, 0xAA);
+    mem.zeroize();
+    assert_eq!(mem.read::<u128>(48), 0);
+}